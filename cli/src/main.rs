@@ -1,9 +1,45 @@
 //! Command-line interface for the FDB Simulation Visualizer.
 
+use clap::builder::{PossibleValue, PossibleValuesParser};
 use clap::Parser as ClapParser; // Alias clap's Parser
-use parser::{parser::parse_log_file, report::create_simulation_report}; // Use items from the parser library crate
-use std::{error::Error, path::PathBuf}; // Import std::process
+use parser::{
+    analysis::{churn::ChurnThreshold, churn::CoordinatorChurnPass, trigger::LikelyTriggerPass},
+    annotations::{Annotation, AnnotationStore},
+    ensemble::{ChaosCoverage, EnsembleComparison, EnsembleReport, FailureClustering},
+    events::Events,
+    export::{events::EventTable, trace_hash, ExportOptions, ExporterRegistry},
+    filter::Filter,
+    line_index::{parse_line_range, LineIndex},
+    number_format::NumberFormat,
+    parser::{
+        for_each_event, parse_byte_size, parse_log_directory_with_options,
+        parse_log_file_with_options, Event, ParseOptions,
+    },
+    record, remote,
+    report::{
+        self, create_simulation_report_from_file, create_simulation_report_with_stats, parse_tag,
+        SimulationReport,
+    },
+    sampling::Sampling,
+    split, state,
+    time_format::{TimeAlignment, TimeFormat, TimeFormatter},
+    timeline, triage,
+    watch::TraceWatcher,
+}; // Use items from the parser library crate
+use snapshot::{HtmlTheme, SnapshotFormat};
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fs,
+    io::Write,
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+    sync::Arc,
+    thread,
+    time::Duration,
+}; // Import std::process
 use thiserror::Error;
+use tracing_subscriber::layer::SubscriberExt;
 
 /// Enum defining the possible output formats for the report.
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, clap::ValueEnum, Debug)]
@@ -12,10 +48,125 @@ enum OutputFormat {
     Summary,
     /// Full report in JSON format
     Json,
+    /// Standalone HTML page with tables and an SVG timeline
+    Html,
+    /// GitHub-flavored Markdown, e.g. for pull request comments
+    Markdown,
     /// Display the report in an interactive TUI.
     Tui,
 }
 
+/// Output formats of the `query` subcommand.
+#[derive(Copy, Clone, PartialEq, Eq, clap::ValueEnum, Debug)]
+enum QueryFormat {
+    /// One JSON object per event
+    Jsonl,
+    /// Tab-separated columns with a header line
+    Text,
+    /// CSV with a header row
+    Csv,
+}
+
+/// Formats of the `report` subcommand, which never starts the TUI.
+#[derive(Copy, Clone, PartialEq, Eq, clap::ValueEnum, Debug)]
+enum ReportFormat {
+    /// Human-readable summary
+    Summary,
+    /// The serialized `SimulationReport`, with its `schema_version`
+    Json,
+    /// Standalone HTML page with tables and an SVG timeline
+    Html,
+    /// GitHub-flavored Markdown, e.g. for pull request comments
+    Markdown,
+}
+
+/// How simulated times are displayed in the report, TUI and exports.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, clap::ValueEnum, Debug)]
+enum TimeDisplay {
+    /// Simulated seconds since the start of the run
+    Seconds,
+    /// Humanized durations since the start of the run
+    Humanized,
+    /// Wall-clock date and time, anchored at the run's start
+    DateTime,
+}
+
+impl From<TimeDisplay> for TimeFormat {
+    fn from(display: TimeDisplay) -> Self {
+        match display {
+            TimeDisplay::Seconds => TimeFormat::Seconds,
+            TimeDisplay::Humanized => TimeFormat::Humanized,
+            TimeDisplay::DateTime => TimeFormat::DateTime,
+        }
+    }
+}
+
+/// How counts are displayed in the report tables.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, clap::ValueEnum, Debug)]
+enum NumberDisplay {
+    /// Digits only, e.g. 1234567
+    Plain,
+    /// Thousands separated by commas, e.g. 1,234,567
+    Grouped,
+}
+
+/// Origin of the time axis when runs are compared or bucketed together.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, clap::ValueEnum, Debug)]
+enum Alignment {
+    /// Absolute simulated time
+    SimTime,
+    /// Time since each run's first chaos event
+    ChaosOnset,
+}
+
+impl From<Alignment> for TimeAlignment {
+    fn from(alignment: Alignment) -> Self {
+        match alignment {
+            Alignment::SimTime => TimeAlignment::SimTime,
+            Alignment::ChaosOnset => TimeAlignment::ChaosOnset,
+        }
+    }
+}
+
+/// The formats of the `export` subcommand, as registered in the default
+/// `ExporterRegistry`.
+fn export_formats() -> PossibleValuesParser {
+    PossibleValuesParser::new(
+        ExporterRegistry::default()
+            .exporters()
+            .map(|exporter| PossibleValue::new(exporter.name()).help(exporter.description()))
+            .collect::<Vec<_>>(),
+    )
+}
+
+/// Event categories exported by `export --format csv`.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, clap::ValueEnum, Debug)]
+enum EventCategory {
+    /// CloggingPair events
+    CloggingPairs,
+    /// KillMachineProcess events
+    Kills,
+    /// SetDiskFailure events
+    DiskFailures,
+}
+
+impl From<EventCategory> for EventTable {
+    fn from(category: EventCategory) -> Self {
+        match category {
+            EventCategory::CloggingPairs => EventTable::CloggingPairs,
+            EventCategory::Kills => EventTable::Kills,
+            EventCategory::DiskFailures => EventTable::DiskFailures,
+        }
+    }
+}
+
+/// How the `split` subcommand partitions the events of a trace.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, clap::ValueEnum, Debug)]
+enum SplitBy {
+    /// One file per machine IP, with the events involving that machine
+    Machine,
+}
+
 #[derive(ClapParser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Cli {
@@ -37,66 +188,944 @@ enum CliError {
 }
 
 // Declare the tui module
+mod pager;
+mod progress;
+mod serve;
+mod snapshot;
+mod timing;
 mod tui;
 
 /// Command line arguments
 #[derive(ClapParser, Debug)]
 #[command(author, version, about = "A TUI for visualizing FDB simulation logs.", long_about = None)]
+#[command(subcommand_negates_reqs = true)]
 struct Args {
-    /// Path to the FDB simulation JSON log file
-    #[arg(short, long)]
-    log_file: PathBuf,
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Path to the FDB simulation JSON log file or trace directory, or its s3:// or https:// URL
+    #[arg(short, long, required = true)]
+    log_file: Option<PathBuf>,
 
     /// The desired output format for the simulation report.
     #[arg(long, value_enum, default_value_t = OutputFormat::Tui)]
     output_format: OutputFormat,
+
+    /// Render the TUI screens headlessly into this file instead of launching the TUI.
+    #[arg(long, value_name = "FILE")]
+    render_to: Option<PathBuf>,
+
+    /// Format of the headless rendering written by `--render-to`.
+    #[arg(long, value_enum, default_value_t = SnapshotFormat::Text)]
+    render_format: SnapshotFormat,
+
+    /// Color scheme of the HTML rendering written by `--render-to`.
+    #[arg(long, value_enum, default_value_t = HtmlTheme::Dark)]
+    render_theme: HtmlTheme,
+
+    /// Write only the body content of the HTML rendering, without the `<html>`
+    /// wrapper, to embed it in an existing page.
+    #[arg(long)]
+    render_fragment: bool,
+
+    /// Width of the virtual terminal used by `--render-to`.
+    #[arg(long, default_value_t = 160)]
+    render_width: u16,
+
+    /// Height of the virtual terminal used by `--render-to`.
+    #[arg(long, default_value_t = 50)]
+    render_height: u16,
+
+    /// Sample high-volume event types while parsing, e.g. `clog_interface=0.1`.
+    /// Kills, disk faults and other high-signal events are always kept.
+    #[arg(long, value_name = "TYPE=RATE", global = true)]
+    sample: Vec<Sampling>,
+
+    /// How simulated times are displayed (toggle with `t` in the TUI).
+    #[arg(long, value_enum, global = true, default_value_t = TimeDisplay::Seconds)]
+    time_format: TimeDisplay,
+
+    /// How counts and decimals are displayed in the summary tables.
+    #[arg(long, value_enum, global = true, default_value_t = NumberDisplay::Plain)]
+    number_format: NumberDisplay,
+
+    /// Number of decimals of durations in the summary tables, instead of each
+    /// column's own precision.
+    #[arg(long, value_name = "N", global = true)]
+    decimals: Option<usize>,
+
+    /// Approximate memory budget for raw events, e.g. `2GB`. Once exceeded, raw clog
+    /// events are dropped and only their summaries are kept; other events are kept.
+    #[arg(long, value_name = "SIZE", global = true, value_parser = parse_byte_size)]
+    max_memory: Option<usize>,
+
+    /// Replace invalid UTF-8 and NUL bytes in damaged traces instead of failing.
+    #[arg(long, global = true)]
+    lossy: bool,
+
+    /// Keep and list every Severity 30+ event instead of only the top offending types.
+    #[arg(long, global = true)]
+    full_errors: bool,
+
+    /// Keep events of types not otherwise parsed, e.g. in `split` output, instead of
+    /// dropping them.
+    #[arg(long, global = true)]
+    capture_other: bool,
+
+    /// Fail on events whose fields do not match their type instead of dropping them.
+    #[arg(long, global = true)]
+    strict: bool,
+
+    /// List the events dropped because their fields do not match their type.
+    #[arg(long, global = true)]
+    show_parse_warnings: bool,
+
+    /// Window searched for chaos events before the first error of a failed run, in
+    /// simulated seconds. They are reported as likely trigger.
+    #[arg(long, value_name = "SECONDS", global = true)]
+    trigger_window: Option<f64>,
+
+    /// Flag coordinator churn above this rate, as `<changes>/<seconds>`: more than
+    /// `changes` coordinator changes within `seconds` of simulated time.
+    #[arg(long, value_name = "CHANGES/SECONDS", global = true)]
+    coordinator_churn: Option<ChurnThreshold>,
+
+    /// Print the summary directly instead of paging it through `$PAGER` when it does
+    /// not fit on the terminal.
+    #[arg(long)]
+    no_pager: bool,
+
+    /// Attach user metadata to the report, e.g. `--tag branch=main --tag commit=abc123`.
+    /// Tags are kept in the serialized report and carried by the exports.
+    #[arg(long, value_name = "KEY=VALUE", global = true, value_parser = parse_tag)]
+    tag: Vec<(String, String)>,
+
+    /// Print a breakdown of the time spent in each parsing phase to stderr.
+    #[arg(long, global = true)]
+    timing: bool,
+}
+
+/// Subcommands for non-interactive workflows.
+#[derive(clap::Subcommand, Debug)]
+enum Command {
+    /// Browse a run in the TUI, or compare two runs side by side.
+    Tui {
+        /// Path to the FDB simulation JSON log file or trace directory, or its s3:// or https:// URL
+        #[arg(
+            value_name = "FILE",
+            required_unless_present = "compare",
+            conflicts_with = "compare"
+        )]
+        log_file: Option<PathBuf>,
+
+        /// Compare two runs, e.g. a failing seed against a passing one of the same test.
+        #[arg(long, num_args = 2, value_names = ["A", "B"])]
+        compare: Vec<PathBuf>,
+
+        /// With `--compare`, align the timelines on absolute time or on each run's
+        /// chaos onset.
+        #[arg(long, value_enum, default_value_t = Alignment::SimTime)]
+        align: Alignment,
+    },
+    /// Export the report in a format consumed by external tools.
+    Export {
+        /// Path to the FDB simulation JSON log file or trace directory, or its s3:// or https:// URL
+        #[arg(value_name = "FILE")]
+        log_file: PathBuf,
+
+        /// The export format.
+        #[arg(long, value_parser = export_formats())]
+        format: String,
+
+        /// Write the export to this file instead of stdout. For `--format csv`, the
+        /// directory to write the CSV files to, the current directory by default.
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+
+        /// Event categories exported by `--format csv`, comma-separated. All of them
+        /// by default.
+        #[arg(long, value_enum, value_delimiter = ',')]
+        events: Vec<EventCategory>,
+    },
+    /// Compare two ensembles of runs and highlight significant regressions.
+    CompareEnsembles {
+        /// Directory of trace files from the baseline ensemble, one file per run
+        #[arg(value_name = "OLD_DIR")]
+        old_dir: PathBuf,
+
+        /// Directory of trace files from the ensemble under test, one file per run
+        #[arg(value_name = "NEW_DIR")]
+        new_dir: PathBuf,
+    },
+    /// Group failing runs of an ensemble by the signature of their first error.
+    ClusterFailures {
+        /// Directory of trace files, one file per run
+        #[arg(value_name = "DIR")]
+        dir: PathBuf,
+
+        /// Width of the time buckets used in failure signatures, in seconds.
+        #[arg(long, default_value_t = 60)]
+        bucket_seconds: u64,
+
+        /// Bucket failure times on absolute time or on time since each run's chaos onset.
+        #[arg(long, value_enum, default_value_t = Alignment::SimTime)]
+        align: Alignment,
+    },
+    /// Report which chaos an ensemble exercised and which it never triggered.
+    Coverage {
+        /// Directory of trace files, one file per run
+        #[arg(value_name = "DIR")]
+        dir: PathBuf,
+    },
+    /// Serve the report and events as JSON over HTTP.
+    Serve {
+        /// Path to the FDB simulation JSON log file
+        #[arg(value_name = "FILE")]
+        log_file: PathBuf,
+
+        /// Address to listen on.
+        #[arg(long, default_value = "127.0.0.1:8080")]
+        addr: String,
+
+        /// Keep reading the log file as the simulation appends to it, streaming new
+        /// events to `/api/stream` clients.
+        #[arg(long)]
+        follow: bool,
+
+        /// Minimum number of seconds between two updates pushed while following.
+        #[arg(
+            long,
+            value_name = "SECONDS",
+            default_value_t = 0.5,
+            requires = "follow"
+        )]
+        refresh: f64,
+    },
+    /// Attach free-text notes to a trace, stored in a sidecar file next to it.
+    Annotate {
+        /// Path to the FDB simulation JSON log file
+        #[arg(value_name = "FILE")]
+        log_file: PathBuf,
+
+        #[command(subcommand)]
+        action: AnnotateAction,
+    },
+    /// Print the cluster state at a simulated time: machines up, coordinators and
+    /// active faults.
+    StateAt {
+        /// Path to the FDB simulation JSON log file or trace directory, or its s3:// or https:// URL
+        #[arg(value_name = "FILE")]
+        log_file: PathBuf,
+
+        /// Simulated time, in seconds
+        #[arg(long, value_name = "SECONDS")]
+        time: f64,
+    },
+    /// Print the unified timeline of a run as aligned text, without the TUI.
+    Timeline {
+        /// Path to the FDB simulation JSON log file or trace directory, or its s3:// or https:// URL
+        #[arg(value_name = "FILE")]
+        log_file: PathBuf,
+
+        /// Only print events at or after this simulated time, in seconds
+        #[arg(long, value_name = "SECONDS")]
+        from: Option<f64>,
+
+        /// Only print events at or before this simulated time, in seconds
+        #[arg(long, value_name = "SECONDS")]
+        to: Option<f64>,
+
+        /// Only print events matching a filter expression, e.g. `type ~ "Clog*"`
+        #[arg(long, value_name = "EXPR")]
+        filter: Option<Filter>,
+    },
+    /// Print the events of a trace matching a filter expression as JSON lines, e.g.
+    /// `type == "KillMachineProcess" && machine ~ "2.0.1.*" && time > 100`.
+    Query {
+        /// Path to the FDB simulation JSON log file, or its s3:// or https:// URL
+        #[arg(value_name = "FILE")]
+        log_file: PathBuf,
+
+        /// Filter expression over the `type`, `machine`, `time` and `details` fields
+        #[arg(value_name = "EXPR")]
+        filter: Filter,
+
+        /// Output format. JSON lines hold the whole event unless `--columns` is given.
+        #[arg(long, value_enum, default_value_t = QueryFormat::Jsonl)]
+        format: QueryFormat,
+
+        /// Fields of the flattened events to print, e.g. `time,type,machine,kill_type`.
+        /// Defaults to `time,type,machine` for the text and CSV formats.
+        #[arg(long, value_name = "FIELDS", value_delimiter = ',')]
+        columns: Vec<String>,
+    },
+    /// Print lines of a trace and the events parsed from them, without parsing the
+    /// whole trace.
+    Show {
+        /// Path to the FDB simulation JSON log file
+        #[arg(value_name = "FILE")]
+        log_file: PathBuf,
+
+        /// Line, or inclusive range of lines, to print, e.g. `120` or `120-130`
+        #[arg(long, value_name = "N[-M]", value_parser = parse_line_range)]
+        line: RangeInclusive<usize>,
+    },
+    /// Split a trace into normalized JSON lines files, e.g. one per machine.
+    Split {
+        /// Path to the FDB simulation JSON log file or trace directory, or its s3:// or https:// URL
+        #[arg(value_name = "FILE")]
+        log_file: PathBuf,
+
+        /// How events are partitioned.
+        #[arg(long, value_enum, default_value_t = SplitBy::Machine)]
+        by: SplitBy,
+
+        /// Directory the files are written to, created if needed.
+        #[arg(short, long, value_name = "DIR")]
+        output: PathBuf,
+    },
+    /// Write the report without launching the TUI, for scripting.
+    Report {
+        /// Path to the FDB simulation JSON log file or trace directory, or its s3:// or https:// URL
+        #[arg(value_name = "FILE")]
+        log_file: PathBuf,
+
+        /// The report format.
+        #[arg(long, value_enum, default_value_t = ReportFormat::Json)]
+        output_format: ReportFormat,
+
+        /// Write the report to this file instead of stdout.
+        #[arg(short, long, value_name = "FILE")]
+        out: Option<PathBuf>,
+    },
+    /// Write everything needed to triage a failed run to one directory: reports,
+    /// analysis findings, timeline SVG, normalized events and the repro command.
+    Triage {
+        /// Path to the FDB simulation JSON log file or trace directory, or its s3:// or https:// URL
+        #[arg(value_name = "FILE")]
+        log_file: PathBuf,
+
+        /// Directory the bundle is written to, created if needed.
+        #[arg(short, long, value_name = "DIR")]
+        output: PathBuf,
+    },
+    /// Check that a trace covers a whole run, exiting with status 1 when it looks
+    /// truncated mid-run.
+    Check {
+        /// Path to the FDB simulation JSON log file
+        #[arg(value_name = "FILE")]
+        log_file: PathBuf,
+    },
+}
+
+/// Actions of the `annotate` subcommand.
+#[derive(clap::Subcommand, Debug)]
+enum AnnotateAction {
+    /// Add a note at a simulated time.
+    Add {
+        /// Simulated time the note refers to, in seconds
+        #[arg(long, value_name = "SECONDS")]
+        at: f64,
+
+        /// Type of the annotated event at that time, e.g. `KillMachineProcess`
+        #[arg(long, value_name = "TYPE")]
+        event: Option<String>,
+
+        /// Author of the note, `$USER` by default
+        #[arg(long)]
+        author: Option<String>,
+
+        /// The note
+        #[arg(value_name = "TEXT")]
+        text: String,
+    },
+    /// List the notes of the trace with their index.
+    List,
+    /// Remove a note by the index shown by `list`.
+    Remove {
+        #[arg(value_name = "INDEX")]
+        index: usize,
+    },
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
     // Parse command line arguments
     let args = Args::parse();
 
-    // Parse the log file and create the report using the parser crate
-    println!("Parsing log file: {}", args.log_file.display());
-    let events = parse_log_file(&args.log_file)?;
-    println!("Parsed {} events.", events.len());
+    if !args.timing {
+        return run(args);
+    }
+    let timing = timing::TimingLayer::default();
+    tracing::subscriber::set_global_default(tracing_subscriber::registry().with(timing.clone()))?;
+    let result = run(args);
+    eprintln!("{}", timing);
+    result
+}
+
+/// Runs the command selected by `args`.
+fn run(args: Args) -> Result<(), Box<dyn Error>> {
+    let time_format = TimeFormat::from(args.time_format);
+    let mut parse_options = ParseOptions {
+        max_memory: args.max_memory,
+        lossy: args.lossy,
+        full_errors: args.full_errors,
+        capture_other: args.capture_other,
+        strict: args.strict,
+        ..ParseOptions::default()
+    };
+    let show_parse_warnings = args.show_parse_warnings;
+    if let Some(window_seconds) = args.trigger_window {
+        parse_options
+            .analyses
+            .register(LikelyTriggerPass { window_seconds });
+    }
+    if let Some(threshold) = args.coordinator_churn {
+        parse_options
+            .analyses
+            .register(CoordinatorChurnPass { threshold });
+    }
+    for spec in &args.sample {
+        for (event_type, rate) in spec.rates() {
+            parse_options.sampling.set_rate(event_type, *rate)?;
+        }
+    }
+    let tags: BTreeMap<String, String> = args.tag.into_iter().collect();
+    let number_format = NumberFormat {
+        group_thousands: args.number_format == NumberDisplay::Grouped,
+        decimals: args.decimals,
+    };
+
+    if let Some(command) = args.command {
+        return match command {
+            Command::Tui {
+                log_file,
+                compare,
+                align,
+            } => match (log_file, compare.as_slice()) {
+                (_, [a, b]) => run_compare_tui(a, b, align.into(), time_format, &parse_options),
+                (Some(log_file), _) => {
+                    eprintln!("Parsing log file: {}", log_file.display());
+                    let mut report =
+                        parse_with_progress(&log_file, &parse_options, show_parse_warnings)?;
+                    report.tags = tags;
+                    let mut app = tui::App::new(report).with_time_format(time_format);
+                    if let Some(store) = open_annotations(&log_file)? {
+                        app = app.with_annotations(store);
+                    }
+                    with_terminal(|terminal| app.run(terminal))
+                }
+                _ => unreachable!("clap requires a log file or --compare"),
+            },
+            Command::Export {
+                log_file,
+                format,
+                output,
+                events,
+            } => run_export(
+                &log_file,
+                &format,
+                output.as_deref(),
+                &ExportOptions {
+                    time_format,
+                    numbers: number_format,
+                    event_tables: if events.is_empty() {
+                        EventTable::ALL.to_vec()
+                    } else {
+                        events.iter().map(|&category| category.into()).collect()
+                    },
+                    trace_hash: None,
+                },
+                &parse_options,
+                tags,
+            ),
+            Command::Serve {
+                log_file,
+                addr,
+                follow,
+                refresh,
+            } => run_serve(log_file, &addr, follow.then_some(refresh)),
+            Command::Annotate { log_file, action } => run_annotate(&log_file, action),
+            Command::StateAt { log_file, time } => {
+                let report = create_simulation_report_from_file(&log_file, &parse_options)?;
+                let state = state::state_at(&report, time);
+                if matches!(args.output_format, OutputFormat::Json) {
+                    println!("{}", serde_json::to_string_pretty(&state)?);
+                } else {
+                    let time = TimeFormatter::new(time_format, &report);
+                    print!("{}", state.display_with(&time));
+                }
+                Ok(())
+            }
+            Command::Timeline {
+                log_file,
+                from,
+                to,
+                filter,
+            } => {
+                let mut report = create_simulation_report_from_file(&log_file, &parse_options)?;
+                report.annotations = load_annotations(&log_file)?;
+                let timeline = timeline::timeline(&report);
+                let mut events = timeline::between(&timeline, from, to).to_vec();
+                if let Some(filter) = filter {
+                    events.retain(|event| filter.matches(event));
+                }
+                let text = timeline::to_text(&events, &TimeFormatter::new(time_format, &report));
+                if args.no_pager {
+                    print!("{}", text);
+                } else {
+                    pager::print_paged(&text)?;
+                }
+                Ok(())
+            }
+            Command::Query {
+                log_file,
+                filter,
+                format,
+                mut columns,
+            } => {
+                let whole_events = format == QueryFormat::Jsonl && columns.is_empty();
+                if columns.is_empty() {
+                    columns = record::DEFAULT_COLUMNS
+                        .iter()
+                        .map(|c| c.to_string())
+                        .collect();
+                }
+                let mut out = std::io::stdout().lock();
+                match format {
+                    QueryFormat::Jsonl => {}
+                    QueryFormat::Text => writeln!(out, "{}", columns.join("\t"))?,
+                    QueryFormat::Csv => writeln!(out, "{}", columns.join(","))?,
+                }
+                let mut written = Ok(());
+                for_each_event(&log_file, &parse_options, |event, _| {
+                    if written.is_err() || !filter.matches(&event) {
+                        return;
+                    }
+                    let line = if whole_events {
+                        serde_json::to_string(&event).map_err(Box::<dyn Error>::from)
+                    } else {
+                        let record = record::to_record(&event);
+                        match format {
+                            QueryFormat::Jsonl => {
+                                let selected: BTreeMap<&str, Option<&String>> = columns
+                                    .iter()
+                                    .map(|column| (column.as_str(), record.get(column)))
+                                    .collect();
+                                serde_json::to_string(&selected).map_err(Box::<dyn Error>::from)
+                            }
+                            QueryFormat::Text => Ok(record::to_text_row(&record, &columns)),
+                            QueryFormat::Csv => Ok(record::to_csv_row(&record, &columns)),
+                        }
+                    };
+                    written = line.and_then(|line| Ok(writeln!(out, "{}", line)?));
+                })?;
+                written
+            }
+            Command::Show { log_file, line } => {
+                let index = LineIndex::open(&log_file)?;
+                for line in index.read_lines(&log_file, line)? {
+                    let event_type = line.event.as_ref().map_or("-", Event::event_type);
+                    println!("{:>8} {:<24} {}", line.number, event_type, line.text);
+                }
+                Ok(())
+            }
+            Command::Split {
+                log_file,
+                by: SplitBy::Machine,
+                output,
+            } => {
+                eprintln!("Parsing log file: {}", log_file.display());
+                let counts = split::split_by_machine(&log_file, &output, &parse_options)?;
+                println!(
+                    "Wrote {} events for {} machines to {}",
+                    counts.values().sum::<usize>(),
+                    counts.len(),
+                    output.display()
+                );
+                Ok(())
+            }
+            Command::Report {
+                log_file,
+                output_format,
+                out,
+            } => {
+                eprintln!("Parsing log file: {}", log_file.display());
+                let mut report = create_simulation_report_from_file(&log_file, &parse_options)?;
+                report.annotations = load_annotations(&log_file)?;
+                report.tags = tags;
+                let content = match output_format {
+                    ReportFormat::Summary => report
+                        .display_with(time_format)
+                        .with_numbers(number_format)
+                        .to_string(),
+                    ReportFormat::Json => serde_json::to_string_pretty(&report)? + "\n",
+                    ReportFormat::Html => report::html::to_html(
+                        &report,
+                        &TimeFormatter::new(time_format, &report),
+                        &number_format,
+                    ),
+                    ReportFormat::Markdown => report::markdown::to_markdown(
+                        &report,
+                        &TimeFormatter::new(time_format, &report),
+                        &number_format,
+                    ),
+                };
+                write_output(out.as_deref(), content.as_bytes(), "Report")
+            }
+            Command::Triage { log_file, output } => {
+                eprintln!("Parsing log file: {}", log_file.display());
+                let mut report = create_simulation_report_from_file(&log_file, &parse_options)?;
+                report.annotations = load_annotations(&log_file)?;
+                report.tags = tags;
+                let written = triage::write_bundle(
+                    &report,
+                    &log_file,
+                    &output,
+                    &parse_options,
+                    time_format,
+                    &number_format,
+                )?;
+                for path in &written {
+                    println!("{}", path.display());
+                }
+                if let Some(command) = triage::repro_command(&report) {
+                    eprintln!("Reproduce with: {}", command);
+                }
+                Ok(())
+            }
+            Command::Check { log_file } => {
+                let report = create_simulation_report_from_file(&log_file, &parse_options)?;
+                let time = TimeFormatter::new(time_format, &report);
+                if let Some(truncated_at) = report.truncated_at {
+                    println!(
+                        "Truncated run: {} stops mid-line at {}",
+                        log_file.display(),
+                        time.timestamp(truncated_at)
+                    );
+                    std::process::exit(1);
+                }
+                println!("{} covers the whole run", log_file.display());
+                Ok(())
+            }
+            Command::CompareEnsembles { old_dir, new_dir } => {
+                run_compare_ensembles(&old_dir, &new_dir)
+            }
+            Command::ClusterFailures {
+                dir,
+                bucket_seconds,
+                align,
+            } => {
+                eprintln!("Scanning traces in: {}", dir.display());
+                let clustering = FailureClustering::from_dir(&dir, bucket_seconds, align.into())?;
+                println!("{}", clustering);
+                Ok(())
+            }
+            Command::Coverage { dir } => {
+                eprintln!("Parsing traces in: {}", dir.display());
+                let coverage = ChaosCoverage::from_dir(&dir)?;
+                println!("{}", coverage);
+                Ok(())
+            }
+        };
+    }
+    let log_file = args
+        .log_file
+        .expect("clap requires --log-file when no subcommand is given");
+
+    // Parse the log file and create the report using the parser crate. Progress goes
+    // to stderr so that JSON output can be piped.
+    eprintln!("Parsing log file: {}", log_file.display());
+    let mut report = parse_with_progress(&log_file, &parse_options, show_parse_warnings)?;
+    let annotations = open_annotations(&log_file)?;
+    report.annotations = annotations
+        .as_ref()
+        .map_or_else(Vec::new, |store| store.annotations().to_vec());
+    report.tags = tags;
+    eprintln!("Report generated.");
 
-    // Create the simulation report
-    println!("Generating simulation report...");
-    let report = create_simulation_report(&events);
-    println!("Report generated.");
+    // Headless rendering replaces the interactive TUI entirely
+    if let Some(path) = &args.render_to {
+        let app = tui::App::new(report).with_time_format(time_format);
+        let mut rendered = app
+            .render_headless(
+                args.render_width,
+                args.render_height,
+                args.render_format,
+                args.render_theme,
+            )
+            .map_err(|e| CliError::TuiError(format!("Failed to render screens: {}", e)))?;
+        if args.render_format == SnapshotFormat::Html && !args.render_fragment {
+            let title = format!("FDB simulation {}", log_file.display());
+            rendered = snapshot::html_page(&title, &rendered, args.render_theme);
+        }
+        fs::write(path, rendered)?;
+        println!("TUI screens written to {}", path.display());
+        return Ok(());
+    }
 
     // Execute based on output format
     match args.output_format {
         OutputFormat::Tui => {
             println!("Launching TUI...");
-            // Setup terminal
-            let mut terminal = tui::setup_terminal()
-                .map_err(|e| CliError::TuiError(format!("Failed to setup terminal: {}", e)))?;
-
-            // Create app and run it
-            let mut app = tui::App::new(report); // Pass the report to the TUI app
-            let run_result = app.run(&mut terminal);
-
-            // Restore terminal even if the app run fails
-            tui::restore_terminal(&mut terminal)
-                .map_err(|e| CliError::TuiError(format!("Failed to restore terminal: {}", e)))?;
-
-            // Handle potential error from app run
-            if let Err(err) = run_result {
-                eprintln!("Error running TUI: {:?}", err);
-                return Err(CliError::TuiError(format!("TUI application error: {}", err)).into());
+            let mut app = tui::App::new(report).with_time_format(time_format); // Pass the report to the TUI app
+            if let Some(store) = annotations {
+                app = app.with_annotations(store);
             }
+            with_terminal(|terminal| app.run(terminal))?;
         }
         OutputFormat::Summary => {
-            println!("\n--- Simulation Report Summary ---\n");
-            println!("{}", report); // Print the Display impl of the report
-            println!("\n--- End Report Summary ---");
+            let summary = format!(
+                "\n--- Simulation Report Summary ---\n\n{}\n\n--- End Report Summary ---\n",
+                report.display_with(time_format).with_numbers(number_format)
+            );
+            if args.no_pager {
+                print!("{}", summary);
+            } else {
+                pager::print_paged(&summary)?;
+            }
         }
         OutputFormat::Json => {
-            println!("{}", report); // Print the Display impl of the report
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        }
+        OutputFormat::Html => {
+            let time = TimeFormatter::new(time_format, &report);
+            print!("{}", report::html::to_html(&report, &time, &number_format));
+        }
+        OutputFormat::Markdown => {
+            let time = TimeFormatter::new(time_format, &report);
+            print!(
+                "{}",
+                report::markdown::to_markdown(&report, &time, &number_format)
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Creates the report of a trace with a progress bar on stderr, then prints what the
+/// parse read and left out, and the events dropped as malformed when `show_warnings`.
+fn parse_with_progress(
+    log_file: &Path,
+    parse_options: &ParseOptions,
+    show_warnings: bool,
+) -> Result<SimulationReport, Box<dyn Error>> {
+    let options = ParseOptions {
+        progress: progress::parse_progress(log_file),
+        ..parse_options.clone()
+    };
+    let (report, stats) = create_simulation_report_with_stats(log_file, &options)?;
+    eprintln!("{}", stats);
+    if show_warnings {
+        for failure in &stats.failures {
+            eprintln!("Warning: {}", failure);
+        }
+        if stats.failed_events > stats.failures.len() {
+            eprintln!(
+                "... and {} more",
+                stats.failed_events - stats.failures.len()
+            );
+        }
+    }
+    Ok(report)
+}
+
+/// Sets up the terminal, runs a TUI application in it and restores the terminal, even
+/// if the application fails.
+fn with_terminal<F>(run_app: F) -> Result<(), Box<dyn Error>>
+where
+    F: FnOnce(&mut tui::TuiTerminal) -> std::io::Result<()>,
+{
+    let mut terminal = tui::setup_terminal()
+        .map_err(|e| CliError::TuiError(format!("Failed to setup terminal: {}", e)))?;
+    let run_result = run_app(&mut terminal);
+    tui::restore_terminal(&mut terminal)
+        .map_err(|e| CliError::TuiError(format!("Failed to restore terminal: {}", e)))?;
+
+    if let Err(err) = run_result {
+        eprintln!("Error running TUI: {:?}", err);
+        return Err(CliError::TuiError(format!("TUI application error: {}", err)).into());
+    }
+    Ok(())
+}
+
+/// Runs `tui --compare`, showing two runs side by side.
+fn run_compare_tui(
+    a: &Path,
+    b: &Path,
+    alignment: TimeAlignment,
+    time_format: TimeFormat,
+    parse_options: &ParseOptions,
+) -> Result<(), Box<dyn Error>> {
+    let label = |path: &Path| {
+        path.file_name().map_or_else(
+            || path.display().to_string(),
+            |name| name.to_string_lossy().into_owned(),
+        )
+    };
+    println!("Parsing log file: {}", a.display());
+    let left = create_simulation_report_from_file(a, parse_options)?;
+    println!("Parsing log file: {}", b.display());
+    let right = create_simulation_report_from_file(b, parse_options)?;
+
+    let mut app = tui::CompareApp::new(left, right, (label(a), label(b)))
+        .with_time_format(time_format)
+        .with_alignment(alignment);
+    with_terminal(|terminal| app.run(terminal))
+}
+
+/// Opens the annotation sidecar of a trace. Remote traces and trace directories have
+/// none, annotating them needs a local copy of a single file.
+fn open_annotations(log_file: &Path) -> Result<Option<AnnotationStore>, Box<dyn Error>> {
+    if remote::remote_location(log_file).is_some() || log_file.is_dir() {
+        return Ok(None);
+    }
+    Ok(Some(AnnotationStore::open(log_file)?))
+}
+
+/// The annotations of a trace, none for remote traces.
+fn load_annotations(log_file: &Path) -> Result<Vec<Annotation>, Box<dyn Error>> {
+    Ok(open_annotations(log_file)?.map_or_else(Vec::new, |store| store.annotations().to_vec()))
+}
+
+/// Runs the `annotate` subcommand.
+fn run_annotate(log_file: &Path, action: AnnotateAction) -> Result<(), Box<dyn Error>> {
+    let mut store = open_annotations(log_file)?.ok_or("Only local trace files can be annotated")?;
+    match action {
+        AnnotateAction::Add {
+            at,
+            event,
+            author,
+            text,
+        } => {
+            store.add(Annotation {
+                timestamp: at,
+                event_type: event,
+                text,
+                author: author.or_else(|| std::env::var("USER").ok()),
+            });
+            store.save()?;
+            println!("Annotation saved to {}", store.path().display());
+        }
+        AnnotateAction::List => {
+            for (index, annotation) in store.annotations().iter().enumerate() {
+                println!(
+                    "{:>3}  {:.6}  {}{}{}",
+                    index,
+                    annotation.timestamp,
+                    annotation
+                        .event_type
+                        .as_deref()
+                        .map(|event_type| format!("[{}] ", event_type))
+                        .unwrap_or_default(),
+                    annotation.text,
+                    annotation
+                        .author
+                        .as_deref()
+                        .map(|author| format!(" ({})", author))
+                        .unwrap_or_default()
+                );
+            }
+        }
+        AnnotateAction::Remove { index } => {
+            let removed = store
+                .remove(index)
+                .ok_or_else(|| format!("No annotation at index {}", index))?;
+            store.save()?;
+            println!("Removed annotation: {}", removed.text);
+        }
+    }
+    Ok(())
+}
+
+/// Runs the `compare-ensembles` subcommand.
+fn run_compare_ensembles(old_dir: &Path, new_dir: &Path) -> Result<(), Box<dyn Error>> {
+    eprintln!("Parsing old ensemble: {}", old_dir.display());
+    let old = EnsembleReport::from_dir(old_dir)?;
+    eprintln!("Parsing new ensemble: {}", new_dir.display());
+    let new = EnsembleReport::from_dir(new_dir)?;
+
+    let comparison = EnsembleComparison::new(&old, &new);
+    println!("{}", comparison);
+    Ok(())
+}
+
+/// Runs the `serve` subcommand. When a refresh interval in seconds is given, the log
+/// file is followed as it grows.
+fn run_serve(log_file: PathBuf, addr: &str, follow: Option<f64>) -> Result<(), Box<dyn Error>> {
+    eprintln!("Parsing log file: {}", log_file.display());
+    let mut watcher = TraceWatcher::new(&log_file)?;
+    let events: Vec<_> = watcher.poll()?.into_iter().map(|e| e.event).collect();
+    eprintln!("Parsed {} events.", events.len());
+
+    let state = Arc::new(serve::ServerState::new(events));
+    if let Some(refresh) = follow {
+        let refresh = Duration::try_from_secs_f64(refresh)
+            .map_err(|_| format!("Invalid refresh interval: {}", refresh))?;
+        let state = Arc::clone(&state);
+        thread::spawn(move || serve::follow(watcher, state, refresh));
+    }
+    serve::serve(addr, state).map_err(|e| e as Box<dyn Error>)
+}
+
+/// Runs the `export` subcommand. Progress goes to stderr so stdout only carries the export.
+fn run_export(
+    log_file: &Path,
+    format: &str,
+    output: Option<&Path>,
+    options: &ExportOptions,
+    parse_options: &ParseOptions,
+    tags: BTreeMap<String, String>,
+) -> Result<(), Box<dyn Error>> {
+    let registry = ExporterRegistry::default();
+    let exporter = registry
+        .get(format)
+        .ok_or_else(|| format!("Unknown export format '{}'", format))?;
+
+    eprintln!("Parsing log file: {}", log_file.display());
+    let mut report = create_simulation_report_from_file(log_file, parse_options)?;
+    report.annotations = load_annotations(log_file)?;
+    report.tags = tags;
+    let options = &ExportOptions {
+        trace_hash: trace_hash(log_file).ok(),
+        ..options.clone()
+    };
+    // The trace is only kept in memory for the formats reading it
+    let events = if exporter.needs_events() {
+        Events::new(if log_file.is_dir() {
+            parse_log_directory_with_options(log_file, parse_options)?
+        } else {
+            parse_log_file_with_options(log_file, parse_options)?
+        })
+    } else {
+        Events::default()
+    };
+
+    // Formats made of several files are written to a directory
+    if let Some(files) = exporter.files(options) {
+        let dir = output.unwrap_or(Path::new("."));
+        fs::create_dir_all(dir)?;
+        for (file_name, options) in files {
+            let mut content = Vec::new();
+            exporter.export(&report, &events, &options, &mut content)?;
+            write_output(Some(&dir.join(file_name)), &content, "Export")?;
         }
+        return Ok(());
     }
+    let mut content = Vec::new();
+    exporter.export(&report, &events, options, &mut content)?;
+    write_output(output, &content, "Export")
+}
 
+/// Writes `content` to a file, or to stdout when no path is given. `what` names the
+/// content in the confirmation printed to stderr.
+fn write_output(path: Option<&Path>, content: &[u8], what: &str) -> Result<(), Box<dyn Error>> {
+    match path {
+        Some(path) => {
+            fs::write(path, content)?;
+            eprintln!("{} written to {}", what, path.display());
+        }
+        None => std::io::stdout().write_all(content)?,
+    }
     Ok(())
 }
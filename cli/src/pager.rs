@@ -0,0 +1,57 @@
+//! Paging of long text output through `$PAGER`.
+
+use std::env;
+use std::io::{self, IsTerminal, Write};
+use std::process::{Command, Stdio};
+
+/// Pager used when `$PAGER` is not set.
+const DEFAULT_PAGER: &str = "less";
+
+/// Prints `output` to stdout, through the user's pager when stdout is a terminal and
+/// the output does not fit on one screen. Falls back to printing directly when the
+/// pager cannot be started.
+pub fn print_paged(output: &str) -> io::Result<()> {
+    let stdout = io::stdout();
+    let fits = match crossterm::terminal::size() {
+        Ok((_, rows)) => output.lines().count() < rows as usize,
+        Err(_) => true,
+    };
+    if !stdout.is_terminal() || fits {
+        return stdout.lock().write_all(output.as_bytes());
+    }
+
+    match spawn_pager(output) {
+        Ok(()) => Ok(()),
+        Err(e) => {
+            eprintln!("Could not start pager: {}", e);
+            stdout.lock().write_all(output.as_bytes())
+        }
+    }
+}
+
+/// Runs the pager with `output` on its stdin and waits for the user to quit it.
+fn spawn_pager(output: &str) -> io::Result<()> {
+    let pager = env::var("PAGER")
+        .ok()
+        .filter(|pager| !pager.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_PAGER.to_string());
+    let mut words = pager.split_whitespace();
+    let program = words.next().unwrap_or(DEFAULT_PAGER);
+
+    let mut command = Command::new(program);
+    command.args(words).stdin(Stdio::piped());
+    if env::var_os("LESS").is_none() {
+        // Keep ANSI colors (R) and quit right away if the output fits after all (F)
+        command.env("LESS", "FRX");
+    }
+    let mut child = command.spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // The user may quit before reading everything, which closes the pipe
+        match stdin.write_all(output.as_bytes()) {
+            Err(e) if e.kind() != io::ErrorKind::BrokenPipe => return Err(e),
+            _ => {}
+        }
+    }
+    child.wait()?;
+    Ok(())
+}
@@ -0,0 +1,45 @@
+//! Progress bar of trace parsing, drawn on stderr.
+
+use parser::parser::{trace_size, ProgressHook};
+use std::io::{self, IsTerminal};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// Width of the bar, in characters.
+const BAR_WIDTH: u64 = 40;
+
+/// Bytes in a mebibyte, the unit of the sizes shown next to the bar.
+const MIB: f64 = 1024.0 * 1024.0;
+
+/// Returns a hook drawing the progress of parsing the trace at `path` on stderr.
+/// `None` when stderr is not a terminal or the size of the trace is unknown, e.g.
+/// for remote traces.
+pub fn parse_progress(path: &Path) -> Option<ProgressHook> {
+    if !io::stderr().is_terminal() {
+        return None;
+    }
+    let total = trace_size(path).filter(|total| *total > 0)?;
+    let read = Arc::new(AtomicU64::new(0));
+    // Percentage last drawn, so that the bar is redrawn at most 100 times
+    let drawn = Arc::new(AtomicU64::new(u64::MAX));
+    Some(ProgressHook::new(move |bytes| {
+        let read = (read.fetch_add(bytes, Ordering::Relaxed) + bytes).min(total);
+        let percent = read * 100 / total;
+        if drawn.swap(percent, Ordering::Relaxed) == percent {
+            return;
+        }
+        let filled = (read * BAR_WIDTH / total) as usize;
+        eprint!(
+            "\r[{}{}] {:>3}% {:.1}/{:.1} MiB",
+            "#".repeat(filled),
+            " ".repeat(BAR_WIDTH as usize - filled),
+            percent,
+            read as f64 / MIB,
+            total as f64 / MIB
+        );
+        if read == total {
+            eprintln!();
+        }
+    }))
+}
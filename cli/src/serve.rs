@@ -0,0 +1,382 @@
+//! HTTP server exposing the parsed simulation as JSON endpoints.
+//!
+//! Routes:
+//! - `GET /api/report`: the full `SimulationReport`
+//! - `GET /api/events?type=&machine=&from=&to=&filter=`: events in time order,
+//!   filtered by variant name, machine IP, time range and filter expression, see
+//!   `parser::filter`
+//! - `GET /api/machines/:id`: details, chaos summary and clogs of a single machine,
+//!   given by machine ID, zone ID, IP or address
+//! - `GET /api/stream`: WebSocket pushing newly parsed events and summary snapshots
+//!   while a live run is followed
+
+use parser::cluster::ClusterIndex;
+use parser::events::Events;
+use parser::filter::Filter;
+use parser::parser::Event;
+use parser::report::{ReportBuilder, SimulationReport};
+use parser::watch::TraceWatcher;
+use serde_json::{json, Value};
+use std::error::Error;
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread;
+use std::time::{Duration, Instant};
+use tiny_http::{Header, Method, Request, Response, Server};
+use tungstenite::handshake::derive_accept_key;
+use tungstenite::protocol::Role;
+use tungstenite::{Message, WebSocket};
+
+/// Shared state of the server. New events are folded into the report builder as they
+/// arrive; the full report is only snapshotted from it when `/api/report` or a machine
+/// is requested after a change, stream summaries read the builder's counts instead.
+pub struct ServerState {
+    events: RwLock<Events>,
+    builder: Mutex<ReportBuilder>,
+    report: Mutex<Option<Arc<SimulationReport>>>,
+    subscribers: Mutex<Vec<Sender<String>>>,
+}
+
+impl ServerState {
+    pub fn new(events: Vec<Event>) -> Self {
+        let mut builder = ReportBuilder::default();
+        for event in &events {
+            builder.add(event);
+        }
+        Self {
+            events: RwLock::new(Events::new(events)),
+            builder: Mutex::new(builder),
+            report: Mutex::new(None),
+            subscribers: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn report(&self) -> Arc<SimulationReport> {
+        let mut report = self.report.lock().unwrap();
+        report
+            .get_or_insert_with(|| Arc::new(self.builder.lock().unwrap().snapshot()))
+            .clone()
+    }
+
+    /// Appends newly parsed events and pushes them, followed by a fresh summary
+    /// snapshot, to every connected WebSocket client.
+    pub fn push_events(&self, new_events: Vec<Event>) {
+        if new_events.is_empty() {
+            return;
+        }
+        let message = json!({ "type": "events", "events": new_events }).to_string();
+        {
+            let mut builder = self.builder.lock().unwrap();
+            for event in &new_events {
+                builder.add(event);
+            }
+        }
+        self.events.write().unwrap().extend(new_events);
+        *self.report.lock().unwrap() = None;
+
+        let summary = self.summary_snapshot().to_string();
+        // Clients that went away have dropped their receiver, so sending fails
+        self.subscribers.lock().unwrap().retain(|subscriber| {
+            subscriber.send(message.clone()).is_ok() && subscriber.send(summary.clone()).is_ok()
+        });
+    }
+
+    /// Registers a new stream client, which immediately receives the current summary.
+    fn subscribe(&self) -> mpsc::Receiver<String> {
+        let (sender, receiver) = mpsc::channel();
+        let _ = sender.send(self.summary_snapshot().to_string());
+        self.subscribers.lock().unwrap().push(sender);
+        receiver
+    }
+
+    /// Compact overview of the run so far, read from the builder's running counts so
+    /// it stays cheap enough to push after every update.
+    fn summary_snapshot(&self) -> Value {
+        let events = self.events.read().unwrap().len();
+        let builder = self.builder.lock().unwrap();
+        json!({
+            "type": "summary",
+            "events": events,
+            "sim_time": builder.last_event_time(),
+            "clogging_pairs": builder.clogging_pair_count(),
+            "clog_interfaces": builder.clog_interface_count(),
+            "kills": builder.kill_count(),
+            "disk_failures": builder.disk_failure_count(),
+            "corrupted_blocks": builder.corrupted_block_count(),
+            "coordinator_changes": builder.coordinators_change_count(),
+            "recruitment_failures": builder.recruitment_failure_count(),
+        })
+    }
+}
+
+/// Feeds the events of followed trace files into the server state forever, pushing
+/// updates to stream clients at most once per `refresh` interval.
+pub fn follow(mut watcher: TraceWatcher, state: Arc<ServerState>, refresh: Duration) {
+    loop {
+        let started = Instant::now();
+        match watcher.next_events(refresh) {
+            Ok(events) => state.push_events(events.into_iter().map(|e| e.event).collect()),
+            Err(e) => eprintln!("Failed to follow trace: {}", e),
+        }
+        // Events written in the meantime are batched into the next update
+        thread::sleep(refresh.saturating_sub(started.elapsed()));
+    }
+}
+
+/// Serves the API on `addr` until the process is interrupted.
+pub fn serve(addr: &str, state: Arc<ServerState>) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let server = Server::http(addr)?;
+    eprintln!("Listening on http://{}", server.server_addr());
+
+    let content_type =
+        Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..]).expect("valid header");
+    for request in server.incoming_requests() {
+        if request.url() == "/api/stream" {
+            open_stream(request, &state);
+            continue;
+        }
+        let (status, body) = handle(&state, request.method(), request.url());
+        let response = Response::from_string(body.to_string())
+            .with_status_code(status)
+            .with_header(content_type.clone());
+        if let Err(e) = request.respond(response) {
+            eprintln!("Failed to send response: {}", e);
+        }
+    }
+    Ok(())
+}
+
+/// Upgrades a `/api/stream` request to a WebSocket and forwards updates to it from a
+/// dedicated thread.
+fn open_stream(request: Request, state: &ServerState) {
+    let key = request
+        .headers()
+        .iter()
+        .find(|header| header.field.equiv("Sec-WebSocket-Key"))
+        .map(|header| derive_accept_key(header.value.as_bytes()));
+    let Some(accept) = key else {
+        let body = error(400, "expected a WebSocket upgrade request").1;
+        let _ = request.respond(Response::from_string(body.to_string()).with_status_code(400));
+        return;
+    };
+
+    let accept_header =
+        Header::from_bytes(&b"Sec-WebSocket-Accept"[..], accept.as_bytes()).expect("valid header");
+    let stream = request.upgrade("websocket", Response::empty(101).with_header(accept_header));
+    let updates = state.subscribe();
+    thread::spawn(move || {
+        let mut socket = WebSocket::from_raw_socket(stream, Role::Server, None);
+        for update in updates {
+            if socket.send(Message::Text(update)).is_err() {
+                break;
+            }
+        }
+    });
+}
+
+/// Routes a request to its handler, returning the HTTP status and JSON body.
+fn handle(state: &ServerState, method: &Method, url: &str) -> (u16, Value) {
+    if *method != Method::Get {
+        return error(405, "only GET is supported");
+    }
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+
+    match path.trim_end_matches('/') {
+        "/api/report" => match serde_json::to_value(&*state.report()) {
+            Ok(report) => (200, report),
+            Err(e) => error(500, &format!("failed to serialize report: {}", e)),
+        },
+        "/api/events" => events(state, query),
+        other => match other.strip_prefix("/api/machines/") {
+            Some(id) if !id.is_empty() && !id.contains('/') => machine(state, id),
+            _ => error(404, "not found"),
+        },
+    }
+}
+
+/// Handles `/api/events`, filtering on the `type`, `machine`, `from`, `to` and
+/// `filter` query parameters.
+fn events(state: &ServerState, query: &str) -> (u16, Value) {
+    let mut event_type = None;
+    let mut machine = None;
+    let mut filter = None;
+    let mut from = f64::NEG_INFINITY;
+    let mut to = f64::INFINITY;
+    for (key, value) in form_urlencoded::parse(query.as_bytes()) {
+        match key.as_ref() {
+            "type" => event_type = Some(value.into_owned()),
+            "machine" => machine = Some(value.into_owned()),
+            "filter" => match value.parse::<Filter>() {
+                Ok(parsed) => filter = Some(parsed),
+                Err(e) => return error(400, &e.to_string()),
+            },
+            "from" | "to" => {
+                let Ok(seconds) = value.parse::<f64>() else {
+                    return error(400, &format!("invalid `{}` value: {}", key, value));
+                };
+                if key == "from" {
+                    from = seconds;
+                } else {
+                    to = seconds;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let events = state.events.read().unwrap();
+    let in_range: Box<dyn Iterator<Item = &Event>> = match machine.as_deref() {
+        Some(ip) => Box::new(events.for_machine_between(ip, from, to)),
+        None => Box::new(events.between(from, to)),
+    };
+    let matching: Vec<&Event> = in_range
+        .filter(|event| {
+            event_type
+                .as_deref()
+                .is_none_or(|t| event.event_type() == t)
+        })
+        .filter(|event| filter.as_ref().is_none_or(|filter| filter.matches(*event)))
+        .collect();
+    (200, json!(matching))
+}
+
+/// Handles `/api/machines/:id`, looking the machine up by its machine ID.
+fn machine(state: &ServerState, id: &str) -> (u16, Value) {
+    let report = state.report();
+    let Some(info) = ClusterIndex::new(&report).resolve(id).first().copied() else {
+        return error(404, &format!("unknown machine: {}", id));
+    };
+    let chaos = info
+        .ip_address
+        .as_deref()
+        .and_then(|ip| report.machine_chaos.get(ip));
+    let clogs = report.clogs_for_machine(id);
+    (
+        200,
+        json!({ "machine": info, "chaos": chaos, "clogs": clogs }),
+    )
+}
+
+fn error(status: u16, message: &str) -> (u16, Value) {
+    (status, json!({ "error": message }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parser::parser::{CloggingPairData, KillMachineProcessData, SimulatedMachineStartData};
+
+    fn state() -> ServerState {
+        ServerState::new(vec![
+            Event::SimulatedMachineStart(SimulatedMachineStartData {
+                timestamp: "0.0".to_string(),
+                process_class: "storage".to_string(),
+                locality: "machineid=m1".to_string(),
+                machine_ips: Some("2.0.1.0".to_string()),
+                zone_id: None,
+                process_id: None,
+                machine_id: Some("m1".to_string()),
+                dc_id: None,
+                data_hall: None,
+            }),
+            Event::CloggingPair(CloggingPairData {
+                timestamp: "1.5".to_string(),
+                from_id: "2.0.1.0".to_string(),
+                to_id: "2.0.1.1".to_string(),
+                seconds: "0.25".to_string(),
+            }),
+            Event::KillMachineProcess(KillMachineProcessData {
+                timestamp: "4".to_string(),
+                raw_kill_type: "6".to_string(),
+                process: "name: Server address: 2.0.1.0:1 zone: z".to_string(),
+                starting_class: "storage".to_string(),
+                failed: "0".to_string(),
+                excluded: "0".to_string(),
+                cleared: "0".to_string(),
+                rebooting: "0".to_string(),
+            }),
+        ])
+    }
+
+    #[test]
+    fn test_events_filters() {
+        let state = state();
+
+        let (status, body) = handle(&state, &Method::Get, "/api/events?type=CloggingPair");
+        assert_eq!(status, 200);
+        assert_eq!(body.as_array().unwrap().len(), 1);
+
+        let (_, body) = handle(&state, &Method::Get, "/api/events?from=1&to=5");
+        assert_eq!(body.as_array().unwrap().len(), 2);
+
+        let (_, body) = handle(&state, &Method::Get, "/api/events?machine=2.0.1.0&from=1");
+        assert_eq!(body.as_array().unwrap().len(), 2);
+        let (_, body) = handle(&state, &Method::Get, "/api/events?machine=2.0.1.1");
+        assert_eq!(body[0]["CloggingPair"]["Time"], "1.5");
+
+        let (status, _) = handle(&state, &Method::Get, "/api/events?from=abc");
+        assert_eq!(status, 400);
+
+        let (_, body) = handle(
+            &state,
+            &Method::Get,
+            "/api/events?filter=machine%20~%202.0.1.*%20%26%26%20time%20%3E%203",
+        );
+        assert_eq!(body[0]["KillMachineProcess"]["Time"], "4");
+        assert_eq!(body.as_array().unwrap().len(), 1);
+        let (status, _) = handle(&state, &Method::Get, "/api/events?filter=time%20%3E");
+        assert_eq!(status, 400);
+    }
+
+    #[test]
+    fn test_report_and_machines() {
+        let state = state();
+
+        let (status, body) = handle(&state, &Method::Get, "/api/report");
+        assert_eq!(status, 200);
+        assert_eq!(body["clogging_pairs"].as_array().unwrap().len(), 1);
+
+        let (status, body) = handle(&state, &Method::Get, "/api/machines/m1");
+        assert_eq!(status, 200);
+        assert_eq!(
+            handle(&state, &Method::Get, "/api/machines/2.0.1.0:1"),
+            (status, body.clone())
+        );
+        assert_eq!(body["machine"]["ip_address"], "2.0.1.0");
+        assert_eq!(body["chaos"]["clogging_pairs"], 1);
+        assert_eq!(body["clogs"][0]["type"], "Pair");
+        assert_eq!(body["clogs"][0]["data"]["To"], "2.0.1.1");
+
+        let (status, _) = handle(&state, &Method::Get, "/api/machines/unknown");
+        assert_eq!(status, 404);
+        let (status, _) = handle(&state, &Method::Post, "/api/report");
+        assert_eq!(status, 405);
+    }
+
+    #[test]
+    fn test_push_events_updates_report_and_subscribers() {
+        let state = state();
+        assert_eq!(state.report().clogging_pairs.len(), 1);
+        let updates = state.subscribe();
+
+        state.push_events(vec![Event::CloggingPair(CloggingPairData {
+            timestamp: "7".to_string(),
+            from_id: "2.0.1.1".to_string(),
+            to_id: "2.0.1.0".to_string(),
+            seconds: "1".to_string(),
+        })]);
+
+        // Summaries come from the builder, the report is only rebuilt on request
+        assert!(state.report.lock().unwrap().is_none());
+        assert_eq!(state.report().clogging_pairs.len(), 2);
+        let messages: Vec<Value> = updates
+            .try_iter()
+            .map(|m| serde_json::from_str(&m).unwrap())
+            .collect();
+        assert_eq!(messages.len(), 3);
+        assert_eq!(messages[0]["clogging_pairs"], 1);
+        assert_eq!(messages[1]["events"].as_array().unwrap().len(), 1);
+        assert_eq!(messages[2]["clogging_pairs"], 2);
+        assert_eq!(messages[2]["sim_time"], 7.0);
+    }
+}
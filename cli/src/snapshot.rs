@@ -0,0 +1,326 @@
+//! Conversion of rendered TUI buffers into shareable text snapshots.
+
+use crossterm::style::{
+    Attribute, Color as CColor, SetAttribute, SetBackgroundColor, SetForegroundColor,
+};
+use crossterm::Command;
+use parser::report::html::escape_html;
+use ratatui::buffer::Buffer;
+use ratatui::style::{Color, Modifier};
+
+/// Output format of a rendered TUI snapshot.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, clap::ValueEnum, Debug)]
+pub enum SnapshotFormat {
+    /// Plain text, colors stripped.
+    Text,
+    /// Text with ANSI escape sequences preserving colors and modifiers.
+    Ansi,
+    /// An HTML `<pre>` fragment with inline styles, wrapped into a standalone page by
+    /// `--render-to` unless `--render-fragment` is set.
+    Html,
+}
+
+/// Color scheme of HTML snapshots.
+#[derive(Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, clap::ValueEnum, Debug)]
+pub enum HtmlTheme {
+    /// Light text on a dark background, like most terminals.
+    #[default]
+    Dark,
+    /// Dark text on a light background. Black and white are swapped, and gray
+    /// darkened, so that cells drawn in them stay readable.
+    Light,
+}
+
+impl HtmlTheme {
+    /// Default text and background colors, for cells without colors of their own.
+    fn colors(self) -> (&'static str, &'static str) {
+        match self {
+            HtmlTheme::Dark => ("#e5e5e5", "#1e1e1e"),
+            HtmlTheme::Light => ("#1e1e1e", "#ffffff"),
+        }
+    }
+
+    /// Maps an index of the xterm palette onto the one shown in this theme.
+    fn palette_index(self, index: u8) -> u8 {
+        match (self, index) {
+            (HtmlTheme::Light, 0) => 15,
+            (HtmlTheme::Light, 7) => 8,
+            (HtmlTheme::Light, 15) => 0,
+            _ => index,
+        }
+    }
+}
+
+impl SnapshotFormat {
+    /// File extension conventionally used for this format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            SnapshotFormat::Text => "txt",
+            SnapshotFormat::Ansi => "ansi",
+            SnapshotFormat::Html => "html",
+        }
+    }
+}
+
+/// Serializes a buffer using the requested format, HTML in the given theme.
+pub fn buffer_to_string(buffer: &Buffer, format: SnapshotFormat, theme: HtmlTheme) -> String {
+    match format {
+        SnapshotFormat::Text => buffer_to_text(buffer),
+        SnapshotFormat::Ansi => buffer_to_ansi(buffer),
+        SnapshotFormat::Html => buffer_to_html(buffer, theme),
+    }
+}
+
+/// Returns the buffer content as plain text, one line per buffer row.
+pub fn buffer_to_text(buffer: &Buffer) -> String {
+    let mut out = String::with_capacity(buffer.content.len() + buffer.area.height as usize);
+    for row in buffer.content.chunks(buffer.area.width as usize) {
+        let line: String = row
+            .iter()
+            .filter(|cell| !cell.skip)
+            .map(|cell| cell.symbol())
+            .collect();
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+/// Returns the buffer content with ANSI escape sequences for colors and modifiers.
+///
+/// Escape sequences are only emitted when the style changes between cells, and every
+/// line is terminated with a reset so the snapshot can be pasted line by line.
+pub fn buffer_to_ansi(buffer: &Buffer) -> String {
+    let mut out = String::with_capacity(buffer.content.len() * 2);
+    for row in buffer.content.chunks(buffer.area.width as usize) {
+        let mut current: Option<(Color, Color, Modifier)> = None;
+        for cell in row.iter().filter(|cell| !cell.skip) {
+            let style = (cell.fg, cell.bg, cell.modifier);
+            if current != Some(style) {
+                write_ansi(&mut out, SetAttribute(Attribute::Reset));
+                write_ansi(&mut out, SetForegroundColor(CColor::from(cell.fg)));
+                write_ansi(&mut out, SetBackgroundColor(CColor::from(cell.bg)));
+                for (modifier, attribute) in MODIFIER_ATTRIBUTES {
+                    if cell.modifier.contains(modifier) {
+                        write_ansi(&mut out, SetAttribute(attribute));
+                    }
+                }
+                current = Some(style);
+            }
+            out.push_str(cell.symbol());
+        }
+        write_ansi(&mut out, SetAttribute(Attribute::Reset));
+        out.push('\n');
+    }
+    out
+}
+
+/// Returns the buffer content as an HTML `<pre>` fragment, using inline styles so the
+/// snapshot can be pasted anywhere, e.g. into an existing dashboard, without an
+/// accompanying stylesheet. See `html_page` for a standalone page.
+pub fn buffer_to_html(buffer: &Buffer, theme: HtmlTheme) -> String {
+    let (foreground, background) = theme.colors();
+    let mut out = format!(
+        "<pre class=\"fdb-sim-snapshot\" style=\"color:{};background-color:{}\">\n",
+        foreground, background
+    );
+    for row in buffer.content.chunks(buffer.area.width as usize) {
+        let mut current: Option<(Color, Color, Modifier)> = None;
+        let mut span_open = false;
+        for cell in row.iter().filter(|cell| !cell.skip) {
+            let style = (cell.fg, cell.bg, cell.modifier);
+            if current != Some(style) {
+                if span_open {
+                    out.push_str("</span>");
+                }
+                let css = css_style(cell.fg, cell.bg, cell.modifier, theme);
+                span_open = !css.is_empty();
+                if span_open {
+                    out.push_str(&format!("<span style=\"{}\">", css));
+                }
+                current = Some(style);
+            }
+            for c in cell.symbol().chars() {
+                match c {
+                    '<' => out.push_str("&lt;"),
+                    '>' => out.push_str("&gt;"),
+                    '&' => out.push_str("&amp;"),
+                    _ => out.push(c),
+                }
+            }
+        }
+        if span_open {
+            out.push_str("</span>");
+        }
+        out.push('\n');
+    }
+    out.push_str("</pre>\n");
+    out
+}
+
+/// Wraps HTML fragments into a standalone page in the given theme.
+pub fn html_page(title: &str, body: &str, theme: HtmlTheme) -> String {
+    let (foreground, background) = theme.colors();
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n</head>\n<body style=\"color:{};background-color:{}\">\n{}</body>\n</html>\n",
+        escape_html(title),
+        foreground,
+        background,
+        body
+    )
+}
+
+/// Builds the inline CSS declarations for a cell style.
+fn css_style(fg: Color, bg: Color, modifier: Modifier, theme: HtmlTheme) -> String {
+    let (fg, bg) = if modifier.contains(Modifier::REVERSED) {
+        (bg, fg)
+    } else {
+        (fg, bg)
+    };
+    let mut declarations = Vec::new();
+    if let Some(color) = css_color(fg, theme) {
+        declarations.push(format!("color:{}", color));
+    }
+    if let Some(color) = css_color(bg, theme) {
+        declarations.push(format!("background-color:{}", color));
+    }
+    if modifier.contains(Modifier::BOLD) {
+        declarations.push("font-weight:bold".to_string());
+    }
+    if modifier.contains(Modifier::DIM) {
+        declarations.push("opacity:0.7".to_string());
+    }
+    if modifier.contains(Modifier::ITALIC) {
+        declarations.push("font-style:italic".to_string());
+    }
+    if modifier.contains(Modifier::UNDERLINED) {
+        declarations.push("text-decoration:underline".to_string());
+    } else if modifier.contains(Modifier::CROSSED_OUT) {
+        declarations.push("text-decoration:line-through".to_string());
+    }
+    declarations.join(";")
+}
+
+/// Maps a terminal color onto a CSS hex color, using the xterm default palette as
+/// shown in the theme.
+fn css_color(color: Color, theme: HtmlTheme) -> Option<String> {
+    let index = match color {
+        Color::Reset => return None,
+        Color::Rgb(r, g, b) => return Some(format!("#{:02x}{:02x}{:02x}", r, g, b)),
+        Color::Indexed(index) => index,
+        Color::Black => 0,
+        Color::Red => 1,
+        Color::Green => 2,
+        Color::Yellow => 3,
+        Color::Blue => 4,
+        Color::Magenta => 5,
+        Color::Cyan => 6,
+        Color::Gray => 7,
+        Color::DarkGray => 8,
+        Color::LightRed => 9,
+        Color::LightGreen => 10,
+        Color::LightYellow => 11,
+        Color::LightBlue => 12,
+        Color::LightMagenta => 13,
+        Color::LightCyan => 14,
+        Color::White => 15,
+    };
+    let index = theme.palette_index(index);
+    let (r, g, b) = match index {
+        0..=15 => XTERM_BASE_COLORS[index as usize],
+        16..=231 => {
+            let level = |v: u8| if v == 0 { 0 } else { 55 + v * 40 };
+            let i = index - 16;
+            (level(i / 36), level((i / 6) % 6), level(i % 6))
+        }
+        _ => {
+            let gray = 8 + (index - 232) * 10;
+            (gray, gray, gray)
+        }
+    };
+    Some(format!("#{:02x}{:02x}{:02x}", r, g, b))
+}
+
+/// The 16 base colors of the xterm palette.
+const XTERM_BASE_COLORS: [(u8, u8, u8); 16] = [
+    (0x00, 0x00, 0x00),
+    (0xcd, 0x00, 0x00),
+    (0x00, 0xcd, 0x00),
+    (0xcd, 0xcd, 0x00),
+    (0x00, 0x00, 0xee),
+    (0xcd, 0x00, 0xcd),
+    (0x00, 0xcd, 0xcd),
+    (0xe5, 0xe5, 0xe5),
+    (0x7f, 0x7f, 0x7f),
+    (0xff, 0x00, 0x00),
+    (0x00, 0xff, 0x00),
+    (0xff, 0xff, 0x00),
+    (0x5c, 0x5c, 0xff),
+    (0xff, 0x00, 0xff),
+    (0x00, 0xff, 0xff),
+    (0xff, 0xff, 0xff),
+];
+
+/// Ratatui modifiers and the ANSI attributes they map to.
+const MODIFIER_ATTRIBUTES: [(Modifier, Attribute); 6] = [
+    (Modifier::BOLD, Attribute::Bold),
+    (Modifier::DIM, Attribute::Dim),
+    (Modifier::ITALIC, Attribute::Italic),
+    (Modifier::UNDERLINED, Attribute::Underlined),
+    (Modifier::REVERSED, Attribute::Reverse),
+    (Modifier::CROSSED_OUT, Attribute::CrossedOut),
+];
+
+fn write_ansi(out: &mut String, command: impl Command) {
+    // Writing into a String cannot fail.
+    let _ = command.write_ansi(out);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::layout::Rect;
+    use ratatui::style::Style;
+
+    #[test]
+    fn test_buffer_to_text_and_ansi() {
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 6, 2));
+        buffer.set_string(0, 0, "Seed", Style::default().fg(Color::Yellow));
+        buffer.set_string(0, 1, "42", Style::default());
+
+        assert_eq!(buffer_to_text(&buffer), "Seed\n42\n");
+
+        let ansi = buffer_to_ansi(&buffer);
+        assert!(
+            ansi.contains("\u{1b}[38;5;3m\u{1b}[49mSeed"),
+            "ANSI output: {:?}",
+            ansi
+        );
+        assert_eq!(ansi.lines().count(), 2);
+    }
+
+    #[test]
+    fn test_buffer_to_html() {
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 8, 1));
+        buffer.set_string(0, 0, "<Kill>", Style::default().fg(Color::Red));
+
+        assert_eq!(
+            buffer_to_html(&buffer, HtmlTheme::Dark),
+            "<pre class=\"fdb-sim-snapshot\" style=\"color:#e5e5e5;background-color:#1e1e1e\">\n<span style=\"color:#cd0000\">&lt;Kill&gt;</span>  \n</pre>\n"
+        );
+
+        // White text stays readable on the light background
+        buffer.set_string(0, 0, "Seed", Style::default().fg(Color::White));
+        let light = buffer_to_html(&buffer, HtmlTheme::Light);
+        assert!(
+            light.contains("<span style=\"color:#000000\">Seed"),
+            "{}",
+            light
+        );
+
+        let page = html_page("a <b>", &light, HtmlTheme::Light);
+        assert!(page.starts_with("<!DOCTYPE html>"));
+        assert!(page.contains("<title>a &lt;b&gt;</title>"));
+        assert!(page.contains(&light));
+    }
+}
@@ -0,0 +1,134 @@
+//! Phase breakdown printed by `--timing`, collected from the parser's tracing spans.
+
+use std::fmt::{self, Write};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+/// Timing and counters of all spans sharing a name.
+#[derive(Debug, Default)]
+struct Phase {
+    calls: usize,
+    total: Duration,
+    /// Fields of the events emitted inside the phase, e.g. "lines=1200 events=80".
+    counters: Vec<String>,
+}
+
+/// Tracing layer accumulating the wall time spent in each span, by span name.
+#[derive(Debug, Clone, Default)]
+pub struct TimingLayer {
+    /// Phases in the order they were first closed or emitted events.
+    phases: Arc<Mutex<Vec<(&'static str, Phase)>>>,
+}
+
+impl TimingLayer {
+    fn with_phase(&self, name: &'static str, update: impl FnOnce(&mut Phase)) {
+        let mut phases = self.phases.lock().expect("timing lock poisoned");
+        match phases
+            .iter_mut()
+            .find(|(phase_name, _)| *phase_name == name)
+        {
+            Some((_, phase)) => update(phase),
+            None => {
+                let mut phase = Phase::default();
+                update(&mut phase);
+                phases.push((name, phase));
+            }
+        }
+    }
+}
+
+impl fmt::Display for TimingLayer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let phases = self.phases.lock().expect("timing lock poisoned");
+        writeln!(f, "Phase breakdown:")?;
+        for (name, phase) in phases.iter() {
+            writeln!(
+                f,
+                "  {:<16} {:>4} call(s) {:>12.3}ms",
+                name,
+                phase.calls,
+                phase.total.as_secs_f64() * 1000.0
+            )?;
+            for counters in &phase.counters {
+                writeln!(f, "      {}", counters)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<S> Layer<S> for TimingLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, _attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(Instant::now());
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(&id) {
+            let Some(elapsed) = span.extensions().get::<Instant>().map(Instant::elapsed) else {
+                return;
+            };
+            self.with_phase(span.name(), |phase| {
+                phase.calls += 1;
+                phase.total += elapsed;
+            });
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.event_span(event) {
+            let mut visitor = CounterVisitor::default();
+            event.record(&mut visitor);
+            self.with_phase(span.name(), |phase| phase.counters.push(visitor.0));
+        }
+    }
+}
+
+/// Renders the fields of an event as `name=value` pairs, skipping the message.
+#[derive(Default)]
+struct CounterVisitor(String);
+
+impl Visit for CounterVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            return;
+        }
+        if !self.0.is_empty() {
+            self.0.push(' ');
+        }
+        let _ = write!(self.0, "{}={:?}", field.name(), value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn test_timing_layer_collects_phases() {
+        let layer = TimingLayer::default();
+        let subscriber = tracing_subscriber::registry().with(layer.clone());
+        tracing::subscriber::with_default(subscriber, || {
+            for _ in 0..2 {
+                let _span = tracing::info_span!("parse_file").entered();
+                tracing::info!(lines = 10, "parsed trace file");
+            }
+        });
+
+        let rendered = layer.to_string();
+        assert!(rendered.contains("parse_file"), "{}", rendered);
+        assert!(rendered.contains("2 call(s)"), "{}", rendered);
+        assert!(rendered.contains("lines=10"), "{}", rendered);
+    }
+}
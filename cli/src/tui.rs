@@ -1,21 +1,445 @@
+mod compare;
+
+pub use compare::CompareApp;
+
+use crate::snapshot::{self, HtmlTheme, SnapshotFormat};
 use crossterm::event::{self, Event as CrosstermEvent, KeyCode, KeyEvent, KeyEventKind};
-use humantime::format_duration;
-use parser::parser::KillType;
+use parser::analysis::FindingLevel;
+use parser::annotations::{Annotation, AnnotationStore};
+use parser::cluster::ClusterIndex;
+use parser::events::Events;
+use parser::filter::{Field, Filter, FilterCriteria, Filterable};
+use parser::parser::{ip_from_address, KillType, SEVERITY_ERROR};
 use parser::report::SimulationReport;
-use ratatui::{prelude::*, widgets::*};
+use parser::state;
+use parser::time_format::{TimeFormat, TimeFormatter};
+use parser::timeline::{self, MarkerKind, TimelineCategory, TimelineEvent};
+use ratatui::{backend::TestBackend, prelude::*, widgets::*};
 use std::{
+    cell::{OnceCell, RefCell},
     collections::HashMap,
+    fs,
     io::{self, Write},
-    str::FromStr,
-    time::{Duration, Instant},
+    path::PathBuf,
+    rc::Rc,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
+/// Screens that can be rendered on their own, e.g. for headless snapshots.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Screen {
+    /// Overview, config summary and chaos summary.
+    Overview,
+    /// The chronological event timeline.
+    Timeline,
+    /// Machine and process distribution.
+    Machines,
+    /// Severity 30+ events grouped by type.
+    Errors,
+    /// One lane of chaos markers per machine.
+    Swimlanes,
+    /// Clog seconds between machine pairs.
+    Heatmap,
+}
+
+impl Screen {
+    /// All screens, in display order.
+    pub const ALL: [Screen; 6] = [
+        Screen::Overview,
+        Screen::Timeline,
+        Screen::Machines,
+        Screen::Errors,
+        Screen::Swimlanes,
+        Screen::Heatmap,
+    ];
+
+    /// Human-readable name of the screen.
+    pub fn title(&self) -> &'static str {
+        match self {
+            Screen::Overview => "Overview",
+            Screen::Timeline => "Timeline",
+            Screen::Machines => "Machines",
+            Screen::Errors => "Errors & Warnings",
+            Screen::Swimlanes => "Swimlanes",
+            Screen::Heatmap => "Clog Heatmap",
+        }
+    }
+}
+
+/// Panes of the dashboard that can hold the keyboard focus.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Pane {
+    Overview,
+    Config,
+    Chaos,
+    Machines,
+    Timeline,
+    State,
+    Errors,
+}
+
+impl Pane {
+    /// All panes, in the order Tab moves the focus through them: the left column of
+    /// the dashboard from top to bottom, then the right one.
+    pub const ALL: [Pane; 7] = [
+        Pane::Overview,
+        Pane::Config,
+        Pane::Chaos,
+        Pane::Errors,
+        Pane::Machines,
+        Pane::Timeline,
+        Pane::State,
+    ];
+
+    /// Title of the pane in the help overlay.
+    fn title(&self) -> &'static str {
+        match self {
+            Pane::Overview => "Overview",
+            Pane::Config => "Config Summary",
+            Pane::Chaos => "Chaos Summary",
+            Pane::Machines => "Machines",
+            Pane::Timeline => "Timeline",
+            Pane::State => "Cluster State",
+            Pane::Errors => "Errors & Warnings",
+        }
+    }
+
+    /// What the pane shows, for the help overlay.
+    fn description(&self) -> &'static str {
+        match self {
+            Pane::Overview => {
+                "Outcome, seed and timing of the run, with badges of the major event categories"
+            }
+            Pane::Config => "Key settings of the simulated cluster; c opens the full config",
+            Pane::Chaos => {
+                "Network splits, disk failures, corrupted blocks and clogged interfaces, with their durations"
+            }
+            Pane::Machines => {
+                "Machines per DC and the process table; ↑ ↓ select a machine, Enter opens it"
+            }
+            Pane::Timeline => {
+                "Every notable event in simulated time order; Enter shows all of its fields"
+            }
+            Pane::State => "Machines up, coordinators and active faults at the playback cursor",
+            Pane::Errors => {
+                "Severity 30+ events grouped by type, errors in red and warnings in yellow"
+            }
+        }
+    }
+
+    /// Short identifier used in exported file names.
+    fn slug(&self) -> &'static str {
+        match self {
+            Pane::Overview => "overview",
+            Pane::Config => "config",
+            Pane::Chaos => "chaos",
+            Pane::Machines => "machines",
+            Pane::Timeline => "timeline",
+            Pane::State => "state",
+            Pane::Errors => "errors",
+        }
+    }
+}
+
+/// What the main area of the TUI shows.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum View {
+    /// The dashboard panes.
+    Dashboard,
+    /// One lane of chaos markers per machine.
+    Swimlanes,
+    /// Clog seconds between machine pairs.
+    Heatmap,
+    /// Locality and chaos history of the selected machine.
+    MachineDetail,
+}
+
+/// Keybindings listed in the help overlay, by section.
+const KEY_BINDINGS: [(&str, &[(&str, &str)]); 6] = [
+    (
+        "Navigation",
+        &[
+            ("Tab l j", "Focus the next pane"),
+            ("S-Tab h k", "Focus the previous pane"),
+            (
+                "↑ ↓ PgUp PgDn Home End",
+                "Select a Timeline event or a machine",
+            ),
+            ("s", "Toggle the per-machine swimlanes"),
+            ("m", "Toggle the clog heatmap"),
+            ("Enter", "Open the selected machine from Machines"),
+            ("Esc", "Back to the dashboard, or quit from it"),
+            ("q", "Quit"),
+        ],
+    ),
+    (
+        "Time",
+        &[
+            ("[ ]", "Move the playback cursor by 1s"),
+            ("{ }", "Move the playback cursor by 10s"),
+            ("g", "Move the playback cursor to the selected event"),
+            ("z", "Cycle the Timeline zoom around the cursor"),
+            ("w", "Restrict every pane to a time window"),
+            ("W", "Reset the time window"),
+            ("- +", "Narrow or expand the time window"),
+            ("t", "Cycle the time format"),
+        ],
+    ),
+    (
+        "Timeline",
+        &[
+            ("Enter", "Show every field of the selected event"),
+            ("|", "Filter events by text or expression"),
+            (
+                "1-5",
+                "Toggle kills, clogs, coordinators, disk and other events",
+            ),
+        ],
+    ),
+    (
+        "Filter and search",
+        &[
+            ("f", "Open the filter builder"),
+            ("F", "Reset the filters and the hidden categories"),
+            ("/", "Search timeline details, machine IDs and IPs"),
+            ("n N", "Jump to the next or previous match"),
+        ],
+    ),
+    (
+        "Export and notes",
+        &[
+            ("e E", "Export the focused pane as ANSI or HTML"),
+            ("x X", "Export the visible Timeline as CSV or JSON lines"),
+            ("a", "Annotate a simulated time"),
+        ],
+    ),
+    (
+        "Popups",
+        &[
+            ("c", "Full config, / searching it"),
+            ("K", "Kill types and their counts"),
+            ("?", "This help"),
+        ],
+    ),
+];
+
+/// Half-widths, in seconds, of the Timeline zoom windows around the cursor, cycled
+/// through with `z` after the whole run.
+const TIMELINE_ZOOM_SECONDS: [f64; 3] = [300.0, 60.0, 10.0];
+
+/// Formats the visible Timeline events can be exported to.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum TimelineExport {
+    Csv,
+    Jsonl,
+}
+
+impl TimelineExport {
+    fn extension(&self) -> &'static str {
+        match self {
+            TimelineExport::Csv => "csv",
+            TimelineExport::Jsonl => "jsonl",
+        }
+    }
+}
+
+/// State of the popup listing the full simulator config and knobs.
+#[derive(Debug, Default)]
+struct ConfigPopup {
+    /// Index of the first visible row.
+    scroll: usize,
+    /// Case-insensitive filter applied to keys and values.
+    query: String,
+    /// Whether keystrokes are typed into `query`.
+    searching: bool,
+}
+
+/// Labels of the inputs of the filter builder, in display order.
+const FILTER_FORM_LABELS: [&str; 5] = [
+    "Event types",
+    "Machine",
+    "From (s)",
+    "To (s)",
+    "Min severity",
+];
+
+/// Background of the Timeline rows involving the selected machine.
+const SELECTED_MACHINE_STYLE: Style = Style::new().bg(Color::DarkGray);
+
+/// Text matching the search in the Timeline and Process Distribution tables.
+const SEARCH_MATCH_STYLE: Style = Style::new().fg(Color::Black).bg(Color::Yellow);
+
+/// Fields the filter builder criteria are applied to in the Timeline pane.
+const TIMELINE_FILTER_FIELDS: [Field; 4] =
+    [Field::Type, Field::Machine, Field::Time, Field::Category];
+
+/// Fields the filter builder criteria are applied to in the Errors pane.
+const ERRORS_FILTER_FIELDS: [Field; 4] =
+    [Field::Type, Field::Machine, Field::Time, Field::Severity];
+
+/// State of the filter builder panel, one text input per `FILTER_FORM_LABELS` entry.
+#[derive(Debug, Default)]
+struct FilterForm {
+    inputs: [String; 5],
+    /// Index of the input keystrokes are typed into.
+    focused: usize,
+    /// Categories hidden with the number keys, kept as they are by the form.
+    hidden_categories: Vec<TimelineCategory>,
+}
+
+impl FilterForm {
+    /// A form showing the given criteria.
+    fn new(criteria: &FilterCriteria) -> Self {
+        let number = |value: Option<f64>| value.map_or_else(String::new, |v| v.to_string());
+        Self {
+            inputs: [
+                criteria.event_types.join(","),
+                criteria.machine.clone().unwrap_or_default(),
+                number(criteria.from),
+                number(criteria.to),
+                number(criteria.min_severity.map(f64::from)),
+            ],
+            focused: 0,
+            hidden_categories: criteria.hidden_categories.clone(),
+        }
+    }
+
+    /// Parses the inputs: a comma-separated list of event type patterns, a machine
+    /// pattern, a time range in seconds and a minimum severity, each optional.
+    fn criteria(&self) -> Result<FilterCriteria, String> {
+        let [types, machine, from, to, severity] = &self.inputs;
+        let text = |input: &str| Some(input.trim().to_string()).filter(|s| !s.is_empty());
+        let seconds = |label: &str, input: &str| -> Result<Option<f64>, String> {
+            text(input)
+                .map(|value| {
+                    value
+                        .parse()
+                        .map_err(|_| format!("{}: not a number", label))
+                })
+                .transpose()
+        };
+        let criteria = FilterCriteria {
+            event_types: types.split(',').filter_map(text).collect(),
+            machine: text(machine),
+            from: seconds(FILTER_FORM_LABELS[2], from)?,
+            to: seconds(FILTER_FORM_LABELS[3], to)?,
+            min_severity: text(severity)
+                .map(|value| {
+                    value
+                        .parse()
+                        .map_err(|_| format!("{}: not a severity", FILTER_FORM_LABELS[4]))
+                })
+                .transpose()?,
+            hidden_categories: self.hidden_categories.clone(),
+        };
+        if let (Some(from), Some(to)) = (criteria.from, criteria.to) {
+            if from > to {
+                return Err("From is after To".to_string());
+            }
+        }
+        Ok(criteria)
+    }
+}
+
+/// State of the popup showing every field of a Timeline event.
+#[derive(Debug)]
+struct EventPopup {
+    event: TimelineEvent,
+    /// Index of the first visible line.
+    scroll: usize,
+}
+
+/// A match of the search, stepped through with `n` and `N`.
+#[derive(Debug, Clone, PartialEq)]
+enum SearchMatch {
+    /// Row of a visible Timeline event whose details match.
+    Timeline(usize),
+    /// IP of a machine whose ID or address matches.
+    Machine(String),
+}
+
 /// Represents the main application state.
 pub struct App {
     /// The simulation report data.
     report: SimulationReport,
     /// Flag to control application exit.
     should_quit: bool,
+    /// The pane currently holding the keyboard focus.
+    focused_pane: Pane,
+    /// Feedback message shown in the status bar (e.g. after an export).
+    status_message: Option<String>,
+    /// How simulated times are displayed.
+    time_format: TimeFormat,
+    /// The full config popup, when open.
+    config_popup: Option<ConfigPopup>,
+    /// Whether the kill type legend popup is open.
+    kill_legend_open: bool,
+    /// Scroll offset of the help overlay, when open.
+    help_scroll: Option<usize>,
+    /// Sidecar file new annotations are saved to, when the trace is on disk.
+    annotations: Option<AnnotationStore>,
+    /// Input of the annotation prompt, when open.
+    note_prompt: Option<String>,
+    /// Input of the Timeline filter prompt, when open.
+    filter_prompt: Option<String>,
+    /// Case-insensitive filter on the type and details of Timeline events.
+    timeline_filter: String,
+    /// Input of the search prompt, when open.
+    search_prompt: Option<String>,
+    /// Case-insensitive search through the Timeline details and the machine IDs and
+    /// IPs, its matches highlighted.
+    search: String,
+    /// Index of the current match in `search_matches`.
+    search_match: usize,
+    /// The filter builder panel, when open.
+    filter_form: Option<FilterForm>,
+    /// Criteria of the filter builder and the Timeline categories hidden with the
+    /// number keys, applied to the Timeline, Machines and Errors panes on top of the
+    /// Timeline filter.
+    filter: FilterCriteria,
+    /// Simulated time window, in seconds, the Timeline and the statistics of every
+    /// pane but Cluster State are restricted to, `None` for the whole run.
+    time_window: Option<(f64, f64)>,
+    /// The report restricted to `time_window`, see `SimulationReport::window`.
+    window_report: Option<SimulationReport>,
+    /// Input of the time window prompt, when open.
+    window_prompt: Option<String>,
+    /// What the main area shows: the dashboard panes or a full-screen view.
+    view: View,
+    /// Half-width in seconds of the window around the cursor the Timeline is zoomed
+    /// to, `None` for the whole run.
+    timeline_zoom: Option<f64>,
+    /// Simulated time of the playback cursor, shown in the Cluster State pane and
+    /// highlighted in the Timeline.
+    cursor: f64,
+    /// Selected row and scroll offset of the Timeline table, the selection following
+    /// the playback cursor until rows are selected with the arrow keys.
+    timeline_state: RefCell<TableState>,
+    /// Rows of the Timeline table visible at the last render, for paging.
+    timeline_page: std::cell::Cell<usize>,
+    /// The detail popup of the selected Timeline event, when open.
+    event_popup: Option<EventPopup>,
+    /// Machine selected in the Process Distribution table or through the event
+    /// selected in the Timeline, by IP, highlighted in both.
+    selected_machine: Option<String>,
+    /// Scroll offset of the Process Distribution table, its selection being the row
+    /// of `selected_machine`.
+    machine_state: RefCell<TableState>,
+    /// Rows of the Process Distribution table visible at the last render, for paging.
+    machine_page: std::cell::Cell<usize>,
+    /// First row shown in the event table of the machine detail view.
+    machine_detail_scroll: usize,
+    /// Rows of the machine detail event table visible at the last render, for paging.
+    machine_detail_page: std::cell::Cell<usize>,
+    /// Timeline of the whole run, built once and sliced to the time window and zoom,
+    /// see `timeline::between`.
+    run_timeline: Vec<TimelineEvent>,
+    /// Chaos events of the run indexed by machine, built when a machine detail view
+    /// is first opened.
+    machine_index: OnceCell<Events>,
+    /// Timeline of the machine shown in the detail view, by IP, computed once per
+    /// machine and time window rather than on every render and keypress.
+    machine_events: RefCell<Option<(String, Rc<Vec<TimelineEvent>>)>>,
     // Add state for scrolling within panes later if needed
     // e.g., overview_scroll: u16, topology_scroll: u16, etc.
 }
@@ -24,12 +448,139 @@ impl App {
     /// Creates a new application instance.
     pub fn new(report: SimulationReport) -> Self {
         Self {
+            run_timeline: timeline::timeline(&report),
+            machine_index: OnceCell::new(),
             report,
             should_quit: false,
+            focused_pane: Pane::Timeline,
+            status_message: None,
+            time_format: TimeFormat::default(),
+            config_popup: None,
+            kill_legend_open: false,
+            help_scroll: None,
+            annotations: None,
+            note_prompt: None,
+            filter_prompt: None,
+            timeline_filter: String::new(),
+            search_prompt: None,
+            search: String::new(),
+            search_match: 0,
+            filter_form: None,
+            filter: FilterCriteria::default(),
+            time_window: None,
+            window_report: None,
+            window_prompt: None,
+            view: View::Dashboard,
+            timeline_zoom: None,
+            cursor: 0.0,
+            timeline_state: RefCell::new(TableState::default()),
+            timeline_page: std::cell::Cell::new(1),
+            event_popup: None,
+            selected_machine: None,
+            machine_state: RefCell::new(TableState::default()),
+            machine_page: std::cell::Cell::new(1),
+            machine_detail_scroll: 0,
+            machine_detail_page: std::cell::Cell::new(1),
+            machine_events: RefCell::new(None),
             // Initialize scroll states here if added
         }
     }
 
+    /// Sets the initial time display format.
+    pub fn with_time_format(mut self, time_format: TimeFormat) -> Self {
+        self.time_format = time_format;
+        self
+    }
+
+    /// Shows the annotations of the trace and saves new ones to its sidecar file.
+    pub fn with_annotations(mut self, annotations: AnnotationStore) -> Self {
+        self.report.annotations = annotations.annotations().to_vec();
+        self.run_timeline = timeline::timeline(&self.report);
+        self.annotations = Some(annotations);
+        self
+    }
+
+    /// Formatter for the current time display format.
+    fn time(&self) -> TimeFormatter {
+        TimeFormatter::new(self.time_format, &self.report)
+    }
+
+    /// The report the panes compute their statistics from: restricted to the time
+    /// window when one is set, the whole run otherwise.
+    fn stats(&self) -> &SimulationReport {
+        self.window_report.as_ref().unwrap_or(&self.report)
+    }
+
+    /// Timeline of the machine at `ip` in the time window, see
+    /// `timeline::machine_timeline`.
+    fn machine_events(&self, ip: &str) -> Rc<Vec<TimelineEvent>> {
+        let mut cached = self.machine_events.borrow_mut();
+        if let Some((cached_ip, events)) = cached.as_ref() {
+            if cached_ip == ip {
+                return Rc::clone(events);
+            }
+        }
+        let (from, to) = self
+            .time_window
+            .unwrap_or((f64::NEG_INFINITY, f64::INFINITY));
+        let index = self
+            .machine_index
+            .get_or_init(|| Events::new(self.report.chaos_events()));
+        let events = Rc::new(timeline::machine_timeline(
+            &self.report,
+            index,
+            ip,
+            from,
+            to,
+        ));
+        *cached = Some((ip.to_string(), Rc::clone(&events)));
+        events
+    }
+
+    /// End of the run in simulated seconds: its elapsed time, or the time of its last
+    /// Timeline event.
+    fn run_end(&self) -> f64 {
+        self.report
+            .elapsed_time
+            .as_deref()
+            .and_then(|t| t.parse().ok())
+            .or_else(|| self.run_timeline.last().map(|e| e.timestamp))
+            .unwrap_or(0.0)
+    }
+
+    /// Restricts the panes to a simulated time window, or shows the whole run again.
+    fn set_time_window(&mut self, window: Option<(f64, f64)>) {
+        self.time_window = window;
+        self.window_report = window.map(|(from, to)| self.report.window(from, to));
+        self.machine_events.take();
+        self.timeline_state.get_mut().select(None);
+        let time = self.time();
+        self.status_message = Some(match window {
+            Some((from, to)) => {
+                format!("Showing {} to {}", time.timestamp(from), time.timestamp(to))
+            }
+            None => "Showing the whole run".to_string(),
+        });
+    }
+
+    /// Narrows (`factor` < 1) or expands the time window around its center, starting
+    /// from the whole run. A window covering the whole run is dropped.
+    fn scale_time_window(&mut self, factor: f64) {
+        let end = self.run_end();
+        let (from, to) = self.time_window.unwrap_or((0.0, end));
+        let center = (from + to) / 2.0;
+        let half_width = (to - from) / 2.0 * factor;
+        let (from, to) = (
+            (center - half_width).max(0.0),
+            (center + half_width).min(end),
+        );
+        if from <= 0.0 && to >= end {
+            self.set_time_window(None);
+        } else {
+            self.set_time_window(Some((from, to)));
+        }
+    }
+
     /// Runs the main application loop.
     pub fn run(&mut self, terminal: &mut Terminal<impl Backend + Write>) -> io::Result<()> {
         // (Main loop remains the same)
@@ -61,19 +612,917 @@ impl App {
 
     /// Handles key press events (only exit for now).
     fn handle_key_event(&mut self, key_event: KeyEvent) -> io::Result<()> {
-        if key_event.kind == KeyEventKind::Press {
+        if key_event.kind == KeyEventKind::Press && self.note_prompt.is_some() {
+            self.handle_note_prompt_key(key_event.code);
+        } else if key_event.kind == KeyEventKind::Press && self.filter_prompt.is_some() {
+            self.handle_filter_prompt_key(key_event.code);
+        } else if key_event.kind == KeyEventKind::Press && self.search_prompt.is_some() {
+            self.handle_search_prompt_key(key_event.code);
+        } else if key_event.kind == KeyEventKind::Press && self.window_prompt.is_some() {
+            self.handle_window_prompt_key(key_event.code);
+        } else if key_event.kind == KeyEventKind::Press && self.filter_form.is_some() {
+            self.handle_filter_form_key(key_event.code);
+        } else if key_event.kind == KeyEventKind::Press && self.event_popup.is_some() {
+            self.handle_event_popup_key(key_event.code);
+        } else if key_event.kind == KeyEventKind::Press && self.config_popup.is_some() {
+            self.handle_config_popup_key(key_event.code);
+        } else if key_event.kind == KeyEventKind::Press && self.help_scroll.is_some() {
+            self.handle_help_key(key_event.code);
+        } else if key_event.kind == KeyEventKind::Press && self.kill_legend_open {
+            if matches!(
+                key_event.code,
+                KeyCode::Esc | KeyCode::Char('K') | KeyCode::Char('q')
+            ) {
+                self.kill_legend_open = false;
+            }
+        } else if key_event.kind == KeyEventKind::Press {
             match key_event.code {
+                KeyCode::Esc if self.view != View::Dashboard => self.view = View::Dashboard,
                 KeyCode::Char('q') | KeyCode::Esc => {
                     self.should_quit = true;
                 }
-                // TODO: Add keys for scrolling within focused panes (e.g., Up/Down/PgUp/PgDown)
-                // TODO: Add keys for switching focus between panes (e.g., Arrow keys, Tab)
+                KeyCode::Char('s') => self.toggle_view(View::Swimlanes),
+                KeyCode::Char('m') => self.toggle_view(View::Heatmap),
+                KeyCode::Char('e') => self.export_focused_pane(SnapshotFormat::Ansi),
+                KeyCode::Char('E') => self.export_focused_pane(SnapshotFormat::Html),
+                KeyCode::Char('x') => self.export_timeline(TimelineExport::Csv),
+                KeyCode::Char('X') => self.export_timeline(TimelineExport::Jsonl),
+                KeyCode::Char('|') => self.filter_prompt = Some(self.timeline_filter.clone()),
+                KeyCode::Char('/') => self.search_prompt = Some(self.search.clone()),
+                KeyCode::Char('n') => self.step_search(1),
+                KeyCode::Char('N') => self.step_search(-1),
+                KeyCode::Char('f') => self.filter_form = Some(FilterForm::new(&self.filter)),
+                KeyCode::Char(c @ '1'..='5') => {
+                    let category = TimelineCategory::ALL[c as usize - '1' as usize];
+                    self.filter.toggle_category(category);
+                    self.timeline_state.get_mut().select(None);
+                }
+                KeyCode::Char('w') => {
+                    self.window_prompt = Some(
+                        self.time_window
+                            .map(|(from, to)| format!("{}-{}", from, to))
+                            .unwrap_or_default(),
+                    );
+                }
+                KeyCode::Char('W') => self.set_time_window(None),
+                KeyCode::Char('-') => self.scale_time_window(0.5),
+                KeyCode::Char('+') | KeyCode::Char('=') => self.scale_time_window(2.0),
+                KeyCode::Char('F') => {
+                    self.filter = FilterCriteria::default();
+                    self.timeline_filter.clear();
+                    self.timeline_state.get_mut().select(None);
+                    self.status_message = Some("Filter reset".to_string());
+                }
+                KeyCode::Char('z') => {
+                    let next = match self.timeline_zoom {
+                        None => Some(0),
+                        Some(zoom) => TIMELINE_ZOOM_SECONDS
+                            .iter()
+                            .position(|level| *level == zoom)
+                            .map(|i| i + 1)
+                            .filter(|i| *i < TIMELINE_ZOOM_SECONDS.len()),
+                    };
+                    self.timeline_zoom = next.map(|i| TIMELINE_ZOOM_SECONDS[i]);
+                    self.timeline_state.get_mut().select(None);
+                    self.status_message = Some(match self.timeline_zoom {
+                        Some(seconds) => format!("Timeline zoomed to cursor ± {}s", seconds),
+                        None => "Timeline shows the whole run".to_string(),
+                    });
+                }
+                KeyCode::Char('t') => {
+                    self.time_format = self.time_format.next();
+                    self.status_message =
+                        Some(format!("Times shown as {}", self.time_format.label()));
+                }
+                KeyCode::Char('c') => self.config_popup = Some(ConfigPopup::default()),
+                KeyCode::Char('K') => self.kill_legend_open = true,
+                KeyCode::Char('?') => self.help_scroll = Some(0),
+                KeyCode::Char('[') => self.move_cursor(-1.0),
+                KeyCode::Char(']') => self.move_cursor(1.0),
+                KeyCode::Char('{') => self.move_cursor(-10.0),
+                KeyCode::Char('}') => self.move_cursor(10.0),
+                KeyCode::Char('a') => {
+                    if self.annotations.is_some() {
+                        self.note_prompt = Some(String::new());
+                    } else {
+                        self.status_message = Some("Annotations need a trace file".to_string());
+                    }
+                }
+                KeyCode::Tab | KeyCode::Char('l') | KeyCode::Char('j') => self.cycle_focus(1),
+                KeyCode::BackTab | KeyCode::Char('h') | KeyCode::Char('k') => self.cycle_focus(-1),
+                KeyCode::Up
+                | KeyCode::Down
+                | KeyCode::PageUp
+                | KeyCode::PageDown
+                | KeyCode::Home
+                | KeyCode::End => match self.focused_pane {
+                    _ if self.view == View::MachineDetail => {
+                        self.scroll_machine_detail(key_event.code)
+                    }
+                    _ if self.view != View::Dashboard => self.select_machine_row(key_event.code),
+                    Pane::Machines => self.select_machine_row(key_event.code),
+                    Pane::Timeline => self.select_timeline_row(key_event.code),
+                    _ => {}
+                },
+                KeyCode::Enter
+                    if self.focused_pane == Pane::Machines && self.selected_machine.is_some() =>
+                {
+                    self.machine_detail_scroll = 0;
+                    self.view = View::MachineDetail;
+                }
+                KeyCode::Enter if self.focused_pane == Pane::Timeline => {
+                    self.event_popup = self
+                        .selected_timeline_event()
+                        .map(|event| EventPopup { event, scroll: 0 });
+                }
+                KeyCode::Char('g') => {
+                    if let Some(event) = self.selected_timeline_event() {
+                        self.cursor = event.timestamp;
+                        self.timeline_state.get_mut().select(None);
+                    }
+                }
                 _ => {}
             }
         }
         Ok(())
     }
 
+    /// Shows a full-screen view, or the dashboard again when it is already shown.
+    fn toggle_view(&mut self, view: View) {
+        self.view = if self.view == view {
+            View::Dashboard
+        } else {
+            view
+        };
+    }
+
+    /// Moves the focus by `step` panes in the order of `Pane::ALL`, wrapping around.
+    fn cycle_focus(&mut self, step: isize) {
+        let count = Pane::ALL.len() as isize;
+        let current = Pane::ALL
+            .iter()
+            .position(|pane| *pane == self.focused_pane)
+            .unwrap_or(0) as isize;
+        self.focused_pane = Pane::ALL[(current + step).rem_euclid(count) as usize];
+    }
+
+    /// Border of a pane, highlighted when it holds the focus.
+    fn border_style(&self, pane: Pane) -> Style {
+        if pane == self.focused_pane {
+            Style::default().fg(Color::Yellow)
+        } else {
+            Style::default()
+        }
+    }
+
+    /// Moves the playback cursor by `seconds`, within the simulated time of the run.
+    fn move_cursor(&mut self, seconds: f64) {
+        let run_end: f64 = self
+            .report
+            .elapsed_time
+            .as_deref()
+            .and_then(|t| t.parse().ok())
+            .unwrap_or(f64::MAX);
+        self.cursor = (self.cursor + seconds).clamp(0.0, run_end);
+        self.timeline_state.get_mut().select(None);
+    }
+
+    /// Moves the Timeline selection for a navigation key, starting from the row of the
+    /// playback cursor when no row is selected yet.
+    fn select_timeline_row(&mut self, code: KeyCode) {
+        let events = self.visible_timeline_events();
+        let Some(last) = events.len().checked_sub(1) else {
+            return;
+        };
+        let current = self
+            .timeline_state
+            .borrow()
+            .selected()
+            .or_else(|| cursor_row(&events, self.cursor));
+        let row = step_row(code, current, last, self.timeline_page.get());
+        let event = events[row].clone();
+        self.timeline_state.get_mut().select(Some(row));
+        self.select_machine_of(&event);
+    }
+
+    /// Selects the first machine of a Timeline event that is part of the cluster.
+    fn select_machine_of(&mut self, event: &TimelineEvent) {
+        let machines: Vec<String> = self
+            .machine_rows()
+            .into_iter()
+            .map(|(_, _, ip, ..)| ip_from_address(&ip).to_string())
+            .collect();
+        self.selected_machine = event
+            .field_values(Field::Machine)
+            .iter()
+            .map(|word| ip_from_address(word))
+            .find(|ip| machines.iter().any(|machine| machine == ip))
+            .map(str::to_string);
+    }
+
+    /// The Timeline event of the selected row, or of the playback cursor when no row
+    /// is selected.
+    fn selected_timeline_event(&mut self) -> Option<TimelineEvent> {
+        let events = self.visible_timeline_events();
+        let row = self
+            .timeline_state
+            .borrow()
+            .selected()
+            .or_else(|| cursor_row(&events, self.cursor))?;
+        events.get(row).map(|event| (*event).clone())
+    }
+
+    /// Handles keys while the event popup is open: scrolling, and Esc, Enter or `q`
+    /// to close it.
+    fn handle_event_popup_key(&mut self, code: KeyCode) {
+        let Some(popup) = &mut self.event_popup else {
+            return;
+        };
+        match code {
+            KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => self.event_popup = None,
+            KeyCode::Up | KeyCode::Char('k') => popup.scroll = popup.scroll.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => popup.scroll += 1,
+            KeyCode::PageUp => popup.scroll = popup.scroll.saturating_sub(10),
+            KeyCode::PageDown => popup.scroll += 10,
+            KeyCode::Home => popup.scroll = 0,
+            _ => {}
+        }
+    }
+
+    /// Renders every parsed field of a Timeline event, as pretty-printed JSON under
+    /// its row.
+    fn render_event_popup(&self, frame: &mut Frame, popup: &EventPopup) {
+        let area = centered_rect(70, 60, frame.size());
+        frame.render_widget(Clear, area);
+        let event = &popup.event;
+        let time = self.time();
+        let block = Block::default()
+            .title(Span::styled(
+                format!(
+                    " {} at {} | ↑ ↓: scroll | Esc: close ",
+                    event.event_type,
+                    time.timestamp(event.timestamp)
+                ),
+                Style::default().fg(Color::Green),
+            ))
+            .borders(Borders::ALL);
+
+        let label = |text: &str| Span::styled(text.to_string(), Style::default().fg(Color::Cyan));
+        let mut lines = vec![
+            Line::from(vec![label("Details: "), Span::raw(event.details.clone())]),
+            Line::from(""),
+        ];
+        let source = serde_json::to_string_pretty(&event.source).unwrap_or_default();
+        lines.extend(source.lines().map(|line| Line::from(line.to_string())));
+
+        // Keep the last page full
+        let height = usize::from(area.height.saturating_sub(2));
+        let scroll = popup.scroll.min(lines.len().saturating_sub(height));
+        frame.render_widget(
+            Paragraph::new(lines)
+                .block(block)
+                .style(Style::default().fg(Color::White))
+                .scroll((scroll as u16, 0)),
+            area,
+        );
+    }
+
+    /// Moves the Process Distribution selection for a navigation key, selecting the
+    /// machine highlighted in the other panes.
+    fn select_machine_row(&mut self, code: KeyCode) {
+        let rows = self.machine_rows();
+        let Some(last) = rows.len().checked_sub(1) else {
+            return;
+        };
+        let current = self.selected_machine_row(&rows);
+        let row = step_row(code, current, last, self.machine_page.get());
+        self.selected_machine = Some(ip_from_address(&rows[row].2).to_string());
+    }
+
+    /// Scrolls the event table of the machine detail view for a navigation key.
+    fn scroll_machine_detail(&mut self, code: KeyCode) {
+        let Some(ip) = self.selected_machine.as_deref() else {
+            return;
+        };
+        let last = self
+            .machine_events(ip)
+            .len()
+            .saturating_sub(self.machine_detail_page.get());
+        self.machine_detail_scroll = step_row(
+            code,
+            Some(self.machine_detail_scroll),
+            last,
+            self.machine_detail_page.get(),
+        );
+    }
+
+    /// Row of the selected machine in the Process Distribution table.
+    fn selected_machine_row(
+        &self,
+        rows: &[(String, String, String, String, String)],
+    ) -> Option<usize> {
+        let selected = self.selected_machine.as_deref()?;
+        rows.iter()
+            .position(|(_, _, ip, ..)| ip_from_address(ip) == selected)
+    }
+
+    /// Whether a Timeline event involves the selected machine.
+    fn involves_selected_machine(&self, event: &TimelineEvent) -> bool {
+        self.selected_machine.as_deref().is_some_and(|selected| {
+            event
+                .field_values(Field::Machine)
+                .iter()
+                .any(|word| ip_from_address(word) == selected)
+        })
+    }
+
+    /// Handles keys while the annotation prompt is open. Enter saves the input, typed
+    /// as "<seconds> <note>", to the sidecar file.
+    fn handle_note_prompt_key(&mut self, code: KeyCode) {
+        let Some(input) = &mut self.note_prompt else {
+            return;
+        };
+        match code {
+            KeyCode::Esc => self.note_prompt = None,
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Char(c) => input.push(c),
+            KeyCode::Enter => {
+                let input = self.note_prompt.take().unwrap_or_default();
+                self.status_message = Some(match self.add_annotation(&input) {
+                    Ok(message) | Err(message) => message,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    /// Handles keys while the Timeline filter prompt is open. Enter applies the input,
+    /// an empty input clearing the filter.
+    fn handle_filter_prompt_key(&mut self, code: KeyCode) {
+        let Some(input) = &mut self.filter_prompt else {
+            return;
+        };
+        match code {
+            KeyCode::Esc => self.filter_prompt = None,
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Char(c) => input.push(c),
+            KeyCode::Enter => {
+                self.timeline_filter = self
+                    .filter_prompt
+                    .take()
+                    .unwrap_or_default()
+                    .trim()
+                    .to_string();
+                self.timeline_state.get_mut().select(None);
+            }
+            _ => {}
+        }
+    }
+
+    /// Handles keys while the search prompt is open. Enter searches for the input and
+    /// jumps to its first match, an empty input clearing the search.
+    fn handle_search_prompt_key(&mut self, code: KeyCode) {
+        let Some(input) = &mut self.search_prompt else {
+            return;
+        };
+        match code {
+            KeyCode::Esc => self.search_prompt = None,
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Char(c) => input.push(c),
+            KeyCode::Enter => {
+                self.search = self
+                    .search_prompt
+                    .take()
+                    .unwrap_or_default()
+                    .trim()
+                    .to_string();
+                self.search_match = 0;
+                self.step_search(0);
+            }
+            _ => {}
+        }
+    }
+
+    /// Matches of the search: the visible Timeline events whose details contain it,
+    /// in the order of the Timeline, then the machines whose ID or IP contains it, in
+    /// the order of the Process Distribution table.
+    fn search_matches(&self) -> Vec<SearchMatch> {
+        if self.search.is_empty() {
+            return Vec::new();
+        }
+        let query = self.search.to_ascii_lowercase();
+        let events = self.visible_timeline_events();
+        let timeline_matches = events
+            .iter()
+            .enumerate()
+            .filter(|(_, event)| event.details.to_ascii_lowercase().contains(&query))
+            .map(|(row, _)| SearchMatch::Timeline(row));
+        let machine_matches = self
+            .machine_rows()
+            .into_iter()
+            .filter(|(_, machine_id, ip, ..)| {
+                machine_id.to_ascii_lowercase().contains(&query)
+                    || ip.to_ascii_lowercase().contains(&query)
+            })
+            .map(|(_, _, ip, ..)| SearchMatch::Machine(ip_from_address(&ip).to_string()));
+        timeline_matches.chain(machine_matches).collect()
+    }
+
+    /// Moves `step` matches away from the current search match, wrapping around, and
+    /// jumps to it: a Timeline match is selected in the dashboard Timeline, a machine
+    /// match in the Process Distribution table and the full-screen views.
+    fn step_search(&mut self, step: isize) {
+        if self.search.is_empty() {
+            self.status_message = Some("Nothing searched yet, search with /".to_string());
+            return;
+        }
+        let matches = self.search_matches();
+        if matches.is_empty() {
+            self.status_message = Some(format!("No match for '{}'", self.search));
+            return;
+        }
+        let index = (self.search_match as isize + step).rem_euclid(matches.len() as isize);
+        self.search_match = index as usize;
+        match &matches[self.search_match] {
+            SearchMatch::Timeline(row) => {
+                self.view = View::Dashboard;
+                self.focused_pane = Pane::Timeline;
+                self.timeline_state.get_mut().select(Some(*row));
+                if let Some(event) = self.visible_timeline_events().get(*row).copied().cloned() {
+                    self.select_machine_of(&event);
+                }
+            }
+            SearchMatch::Machine(ip) => {
+                if self.view == View::Dashboard {
+                    self.focused_pane = Pane::Machines;
+                }
+                self.selected_machine = Some(ip.clone());
+            }
+        }
+        self.status_message = Some(format!(
+            "Match {}/{} for '{}'",
+            self.search_match + 1,
+            matches.len(),
+            self.search
+        ));
+    }
+
+    /// Handles keys while the time window prompt is open. Enter applies a window typed
+    /// as `<from>-<to>` in seconds, or clears it when empty.
+    fn handle_window_prompt_key(&mut self, code: KeyCode) {
+        let Some(input) = &mut self.window_prompt else {
+            return;
+        };
+        match code {
+            KeyCode::Esc => self.window_prompt = None,
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Char(c) => input.push(c),
+            KeyCode::Enter => {
+                let input = self.window_prompt.take().unwrap_or_default();
+                if input.trim().is_empty() {
+                    self.set_time_window(None);
+                    return;
+                }
+                match parse_time_window(&input) {
+                    Some(window) => self.set_time_window(Some(window)),
+                    None => {
+                        self.status_message =
+                            Some(format!("Invalid time window '{}'", input.trim()));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Handles keys while the filter builder is open: Tab and the arrows move between
+    /// inputs, Enter applies them and Esc closes the panel without applying them.
+    fn handle_filter_form_key(&mut self, code: KeyCode) {
+        let Some(form) = &mut self.filter_form else {
+            return;
+        };
+        let count = FILTER_FORM_LABELS.len();
+        match code {
+            KeyCode::Esc => self.filter_form = None,
+            KeyCode::Tab | KeyCode::Down => form.focused = (form.focused + 1) % count,
+            KeyCode::BackTab | KeyCode::Up => form.focused = (form.focused + count - 1) % count,
+            KeyCode::Backspace => {
+                form.inputs[form.focused].pop();
+            }
+            KeyCode::Char(c) => form.inputs[form.focused].push(c),
+            // An invalid input leaves the panel open, showing why
+            KeyCode::Enter => {
+                if let Ok(criteria) = form.criteria() {
+                    self.status_message = Some(if criteria.is_empty() {
+                        "Filter reset".to_string()
+                    } else {
+                        "Filter applied".to_string()
+                    });
+                    self.filter = criteria;
+                    self.timeline_state.get_mut().select(None);
+                    self.filter_form = None;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Renders the filter builder panel, with the expression the inputs compose or why
+    /// they are invalid.
+    fn render_filter_form(&self, frame: &mut Frame, form: &FilterForm) {
+        let area = centered_rect(60, 40, frame.size());
+        frame.render_widget(Clear, area);
+        let block = Block::default()
+            .title(Span::styled(
+                " Filter | Tab: next field | Enter: apply | Esc: cancel ",
+                Style::default().fg(Color::Green),
+            ))
+            .borders(Borders::ALL);
+
+        let mut lines: Vec<Line> = FILTER_FORM_LABELS
+            .iter()
+            .zip(&form.inputs)
+            .enumerate()
+            .map(|(i, (label, input))| {
+                let focused = i == form.focused;
+                let input_style = if focused {
+                    Style::default()
+                        .fg(Color::White)
+                        .add_modifier(Modifier::REVERSED)
+                } else {
+                    Style::default().fg(Color::White)
+                };
+                Line::from(vec![
+                    Span::styled(format!("{:>14}: ", label), Style::default().fg(Color::Cyan)),
+                    Span::styled(
+                        format!("{}{}", input, if focused { "_" } else { "" }),
+                        input_style,
+                    ),
+                ])
+            })
+            .collect();
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            "Event types and machine are globs, e.g. Kill*,Clog* or 2.0.1.*",
+            Style::default().fg(Color::DarkGray),
+        )));
+        let (preview, color) = match form.criteria() {
+            Ok(criteria) => (all_fields_expression(&criteria), Color::Yellow),
+            Err(error) => (error, Color::Red),
+        };
+        lines.push(Line::from(Span::styled(
+            preview,
+            Style::default().fg(color),
+        )));
+        frame.render_widget(
+            Paragraph::new(lines)
+                .wrap(Wrap { trim: false })
+                .block(block),
+            area,
+        );
+    }
+
+    /// Parses a "<seconds> <note>" prompt input and saves it as an annotation,
+    /// returning the status message to show.
+    fn add_annotation(&mut self, input: &str) -> Result<String, String> {
+        let (seconds, text) = input
+            .trim()
+            .split_once(' ')
+            .ok_or_else(|| "Expected <seconds> <note>".to_string())?;
+        let timestamp: f64 = seconds
+            .parse()
+            .map_err(|_| format!("Invalid time: {}", seconds))?;
+        let Some(store) = &mut self.annotations else {
+            return Err("Annotations need a trace file".to_string());
+        };
+        store.add(Annotation {
+            timestamp,
+            event_type: None,
+            text: text.trim().to_string(),
+            author: std::env::var("USER").ok(),
+        });
+        store
+            .save()
+            .map_err(|e| format!("Failed to save annotation: {}", e))?;
+        self.report.annotations = store.annotations().to_vec();
+        self.run_timeline = timeline::timeline(&self.report);
+        if let Some((from, to)) = self.time_window {
+            self.window_report = Some(self.report.window(from, to));
+        }
+        Ok(format!("Annotation saved to {}", store.path().display()))
+    }
+
+    /// Renders a single-line input prompt at the bottom of the screen.
+    fn render_prompt(&self, frame: &mut Frame, title: &str, input: &str) {
+        let screen = frame.size();
+        let area = Rect {
+            x: screen.x + screen.width / 10,
+            y: screen.height.saturating_sub(5),
+            width: screen.width - screen.width / 5,
+            height: 3.min(screen.height),
+        };
+        frame.render_widget(Clear, area);
+        let block = Block::default()
+            .title(Span::styled(title, Style::default().fg(Color::Green)))
+            .borders(Borders::ALL);
+        frame.render_widget(
+            Paragraph::new(format!("{}_", input))
+                .style(Style::default().fg(Color::White))
+                .block(block),
+            area,
+        );
+    }
+
+    /// Handles keys while the config popup is open: scrolling, `/` to search and
+    /// Esc to leave the search or close the popup.
+    fn handle_config_popup_key(&mut self, code: KeyCode) {
+        let row_count = self.config_rows("").len();
+        let Some(popup) = &mut self.config_popup else {
+            return;
+        };
+        if popup.searching {
+            match code {
+                KeyCode::Enter | KeyCode::Esc => popup.searching = false,
+                KeyCode::Backspace => {
+                    popup.query.pop();
+                    popup.scroll = 0;
+                }
+                KeyCode::Char(c) => {
+                    popup.query.push(c);
+                    popup.scroll = 0;
+                }
+                _ => {}
+            }
+            return;
+        }
+        match code {
+            KeyCode::Esc | KeyCode::Char('c') | KeyCode::Char('q') => {
+                self.config_popup = None;
+                return;
+            }
+            KeyCode::Char('/') => popup.searching = true,
+            KeyCode::Up | KeyCode::Char('k') => popup.scroll = popup.scroll.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => popup.scroll += 1,
+            KeyCode::PageUp => popup.scroll = popup.scroll.saturating_sub(10),
+            KeyCode::PageDown => popup.scroll += 10,
+            KeyCode::Home => popup.scroll = 0,
+            KeyCode::End => popup.scroll = row_count,
+            _ => {}
+        }
+        // Rendering clamps further to keep the last page full
+        popup.scroll = popup.scroll.min(row_count.saturating_sub(1));
+    }
+
+    /// Simulator config entries followed by knobs, as (source, key, value), keeping
+    /// those whose key or value contains `query`, ignoring case.
+    fn config_rows(&self, query: &str) -> Vec<(&'static str, &str, &str)> {
+        let query = query.to_lowercase();
+        let config = self.report.simulator_config.iter().flatten();
+        config
+            .map(|(key, value)| ("config", key.as_str(), value.as_str()))
+            .chain(
+                self.report
+                    .knobs
+                    .iter()
+                    .map(|(key, value)| ("knob", key.as_str(), value.as_str())),
+            )
+            .filter(|(_, key, value)| {
+                query.is_empty()
+                    || key.to_lowercase().contains(&query)
+                    || value.to_lowercase().contains(&query)
+            })
+            .collect()
+    }
+
+    /// Renders the content for the config popup over the dashboard.
+    fn render_config_popup(&self, frame: &mut Frame, popup: &ConfigPopup) {
+        let area = centered_rect(80, 80, frame.size());
+        frame.render_widget(Clear, area);
+
+        let rows = self.config_rows(&popup.query);
+        let total = self.config_rows("").len();
+        let block = Block::default()
+            .title(Span::styled(
+                format!(" Simulator Config & Knobs ({}/{}) ", rows.len(), total),
+                Style::default().fg(Color::Green),
+            ))
+            .borders(Borders::ALL);
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(1), Constraint::Length(1)])
+            .split(inner);
+
+        // Header takes one row
+        let visible = (layout[0].height as usize).saturating_sub(1);
+        let scroll = popup.scroll.min(rows.len().saturating_sub(visible));
+        let key_width = rows.iter().map(|(_, key, _)| key.len()).max().unwrap_or(0);
+        let table_rows: Vec<Row> = rows
+            .iter()
+            .skip(scroll)
+            .take(visible)
+            .map(|(source, key, value)| {
+                Row::new(vec![
+                    Cell::from(Span::styled(*source, Style::default().fg(Color::Magenta))),
+                    Cell::from(Span::styled(*key, Style::default().fg(Color::Cyan))),
+                    Cell::from(*value),
+                ])
+            })
+            .collect();
+        let header = Row::new(["Source", "Key", "Value"]).style(
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        );
+        let widths = [
+            Constraint::Length(6),
+            Constraint::Length(key_width.clamp(10, 50) as u16),
+            Constraint::Min(10),
+        ];
+        frame.render_widget(
+            Table::new(table_rows, widths)
+                .header(header)
+                .style(Style::default().fg(Color::White)),
+            layout[0],
+        );
+
+        let footer = if popup.searching {
+            format!("Search: {}_ | Enter: done", popup.query)
+        } else if popup.query.is_empty() {
+            "Search: / | Scroll: Up/Down PgUp/PgDn | Close: Esc".to_string()
+        } else {
+            format!(
+                "Filter: {} | Search: / | Scroll: Up/Down PgUp/PgDn | Close: Esc",
+                popup.query
+            )
+        };
+        frame.render_widget(
+            Paragraph::new(footer).style(Style::default().fg(Color::Gray)),
+            layout[1],
+        );
+    }
+
+    /// Renders the legend explaining each kill type, with the number of kills of
+    /// that type in this run.
+    fn render_kill_legend(&self, frame: &mut Frame) {
+        let area = centered_rect(80, 50, frame.size());
+        frame.render_widget(Clear, area);
+
+        let block = Block::default()
+            .title(Span::styled(
+                " Kill Types ",
+                Style::default().fg(Color::Green),
+            ))
+            .borders(Borders::ALL);
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(1), Constraint::Length(1)])
+            .split(inner);
+
+        let rows: Vec<Row> = KillType::INJECTABLE
+            .iter()
+            .map(|kill_type| {
+                let count = self
+                    .report
+                    .kill_machine_process_summary
+                    .get(kill_type)
+                    .copied()
+                    .unwrap_or(0);
+                let count_style = if count > 0 {
+                    Style::default().fg(Color::Red)
+                } else {
+                    Style::default().fg(Color::DarkGray)
+                };
+                Row::new(vec![
+                    Cell::from(Span::styled(
+                        format!("{:?}", kill_type),
+                        Style::default().fg(Color::Cyan),
+                    )),
+                    Cell::from(Span::styled(count.to_string(), count_style)),
+                    Cell::from(kill_type.description()),
+                ])
+            })
+            .collect();
+        let header = Row::new(["Kill Type", "Count", "Meaning"]).style(
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        );
+        let widths = [
+            Constraint::Length(24),
+            Constraint::Length(6),
+            Constraint::Min(20),
+        ];
+        frame.render_widget(
+            Table::new(rows, widths)
+                .header(header)
+                .style(Style::default().fg(Color::White)),
+            layout[0],
+        );
+        frame.render_widget(
+            Paragraph::new("Close: Esc").style(Style::default().fg(Color::Gray)),
+            layout[1],
+        );
+    }
+
+    /// Handles keys while the help overlay is open: scrolling, and Esc, `?` or `q` to
+    /// close it.
+    fn handle_help_key(&mut self, code: KeyCode) {
+        let Some(scroll) = &mut self.help_scroll else {
+            return;
+        };
+        match code {
+            KeyCode::Esc | KeyCode::Char('?') | KeyCode::Char('q') => self.help_scroll = None,
+            KeyCode::Up | KeyCode::Char('k') => *scroll = scroll.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => *scroll += 1,
+            KeyCode::PageUp => *scroll = scroll.saturating_sub(10),
+            KeyCode::PageDown => *scroll += 10,
+            KeyCode::Home => *scroll = 0,
+            _ => {}
+        }
+    }
+
+    /// Renders the help overlay: every keybinding by section, then what each pane of
+    /// the dashboard shows.
+    fn render_help(&self, frame: &mut Frame, scroll: usize) {
+        let area = centered_rect(80, 80, frame.size());
+        frame.render_widget(Clear, area);
+        let block = Block::default()
+            .title(Span::styled(
+                " Help | ↑ ↓: scroll | Esc: close ",
+                Style::default().fg(Color::Green),
+            ))
+            .borders(Borders::ALL);
+
+        let heading = |text: &str| {
+            Line::from(Span::styled(
+                text.to_string(),
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            ))
+        };
+        let key_width = KEY_BINDINGS
+            .iter()
+            .flat_map(|(_, bindings)| bindings.iter())
+            .map(|(keys, _)| keys.chars().count())
+            .max()
+            .unwrap_or(0);
+        let mut lines = Vec::new();
+        for (section, bindings) in KEY_BINDINGS {
+            lines.push(heading(section));
+            for (keys, description) in bindings {
+                lines.push(Line::from(vec![
+                    Span::styled(
+                        format!("  {:<width$}  ", keys, width = key_width),
+                        Style::default().fg(Color::Cyan),
+                    ),
+                    Span::raw(*description),
+                ]));
+            }
+            lines.push(Line::from(""));
+        }
+        lines.push(heading("Panes"));
+        let title_width = Pane::ALL
+            .iter()
+            .map(|pane| pane.title().len())
+            .max()
+            .unwrap_or(0);
+        for pane in Pane::ALL {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("  {:<width$}  ", pane.title(), width = title_width),
+                    Style::default().fg(Color::Cyan),
+                ),
+                Span::raw(pane.description()),
+            ]));
+        }
+
+        // Keep the last page full
+        let height = usize::from(area.height.saturating_sub(2));
+        let scroll = scroll.min(lines.len().saturating_sub(height));
+        frame.render_widget(
+            Paragraph::new(lines)
+                .block(block)
+                .style(Style::default().fg(Color::White))
+                .scroll((scroll as u16, 0)),
+            area,
+        );
+    }
+
     /// Renders the user interface with a split-pane layout.
     fn ui(&self, frame: &mut Frame) {
         // Define outer layout for status bar
@@ -85,18 +1534,76 @@ impl App {
             ])
             .split(frame.size());
 
+        match self.view {
+            View::Dashboard => self.render_dashboard(frame, outer_layout[0]),
+            View::Swimlanes => self.render_swimlanes(frame, outer_layout[0]),
+            View::Heatmap => self.render_heatmap(frame, outer_layout[0]),
+            View::MachineDetail => self.render_machine_detail(frame, outer_layout[0]),
+        }
+
+        // Render Status Bar
+        self.render_status_bar(frame, outer_layout[1]);
+
+        if let Some(popup) = &self.config_popup {
+            self.render_config_popup(frame, popup);
+        }
+        if self.kill_legend_open {
+            self.render_kill_legend(frame);
+        }
+        if let Some(scroll) = self.help_scroll {
+            self.render_help(frame, scroll);
+        }
+        if let Some(input) = &self.note_prompt {
+            self.render_prompt(
+                frame,
+                " Annotate: <seconds> <note> | Enter: save | Esc: cancel ",
+                input,
+            );
+        }
+        if let Some(popup) = &self.event_popup {
+            self.render_event_popup(frame, popup);
+        }
+        if let Some(form) = &self.filter_form {
+            self.render_filter_form(frame, form);
+        }
+        if let Some(input) = &self.filter_prompt {
+            self.render_prompt(
+                frame,
+                " Filter timeline by event or details | Enter: apply | Esc: cancel ",
+                input,
+            );
+        }
+        if let Some(input) = &self.search_prompt {
+            self.render_prompt(
+                frame,
+                " Search timeline details, machine IDs and IPs | Enter: search | Esc: cancel ",
+                input,
+            );
+        }
+        if let Some(input) = &self.window_prompt {
+            self.render_prompt(
+                frame,
+                " Time window: <from>-<to> seconds, empty for the whole run | Enter: apply | Esc: cancel ",
+                input,
+            );
+        }
+    }
+
+    /// Renders the dashboard panes into the given area.
+    fn render_dashboard(&self, frame: &mut Frame, area: Rect) {
         // Define the main horizontal split into two columns
         let main_columns = Layout::default()
             .direction(Direction::Horizontal)
             .constraints([Constraint::Percentage(50), Constraint::Percentage(50)]) // Left | Right
-            .split(outer_layout[0]);
+            .split(area);
 
         // --- Left Column Layout ---
         let left_column_layout = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
                 Constraint::Percentage(35), // Top: Combined Overview & Config
-                Constraint::Min(10),        // Bottom: Chaos Summary
+                Constraint::Min(10),        // Middle: Chaos Summary
+                Constraint::Percentage(25), // Bottom: Errors & Warnings
             ])
             .split(main_columns[0]);
 
@@ -111,6 +1618,7 @@ impl App {
         let overview_area = overview_config_split[0];
         let config_summary_area = overview_config_split[1];
         let chaos_area = left_column_layout[1];
+        let errors_area = left_column_layout[2];
 
         // --- Right Column Layout ---
         let right_column_layout = Layout::default()
@@ -131,20 +1639,168 @@ impl App {
         // Assign final areas
         let machine_summary_area = distribution_split[0];
         let process_detail_area = distribution_split[1];
-        let timeline_area = right_column_layout[1];
+        let timeline_state_split = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(65), Constraint::Percentage(35)])
+            .split(right_column_layout[1]);
+        let timeline_area = timeline_state_split[0];
+        let state_area = timeline_state_split[1];
 
         // --- Render Panes ---
         self.render_overview_pane(frame, overview_area);
         self.render_config_summary_pane(frame, config_summary_area);
         self.render_chaos_summary_pane(frame, chaos_area);
+        self.render_errors_pane(frame, errors_area);
 
         // Render the new distribution panes
         self.render_distribution_panes(frame, machine_summary_area, process_detail_area);
 
         self.render_timeline_pane(frame, timeline_area);
+        self.render_state_pane(frame, state_area);
+    }
 
-        // Render Status Bar
-        self.render_status_bar(frame, outer_layout[1]);
+    /// Renders a single pane into the given area.
+    fn render_pane(&self, frame: &mut Frame, pane: Pane, area: Rect) {
+        match pane {
+            Pane::Overview => self.render_overview_pane(frame, area),
+            Pane::Config => self.render_config_summary_pane(frame, area),
+            Pane::Chaos => self.render_chaos_summary_pane(frame, area),
+            Pane::Machines => {
+                let rows = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([Constraint::Length(5), Constraint::Min(10)])
+                    .split(area);
+                self.render_distribution_panes(frame, rows[0], rows[1]);
+            }
+            Pane::Timeline => self.render_timeline_pane(frame, area),
+            Pane::State => self.render_state_pane(frame, area),
+            Pane::Errors => self.render_errors_pane(frame, area),
+        }
+    }
+
+    /// Exports the focused pane to a file in the current directory and reports the
+    /// outcome in the status bar.
+    fn export_focused_pane(&mut self, format: SnapshotFormat) {
+        self.status_message = Some(match self.export_pane(self.focused_pane, format) {
+            Ok(path) => format!("Exported to {}", path.display()),
+            Err(e) => format!("Export failed: {}", e),
+        });
+    }
+
+    /// Renders a pane off-screen, tall enough to hold all of its rows, and writes it
+    /// to a file using the given format.
+    fn export_pane(&self, pane: Pane, format: SnapshotFormat) -> io::Result<PathBuf> {
+        let (width, height) = crossterm::terminal::size().unwrap_or((160, 50));
+        // Table panes are exported in full rather than cropped to the screen
+        let content_rows = match pane {
+            Pane::Timeline => self.visible_timeline_events().len() + 4,
+            Pane::Machines => self.report.machine_details.len() + 10,
+            Pane::Errors => self.stats().errors_and_warnings.len() + 4,
+            _ => 0,
+        };
+        let height = height.max(content_rows.min(u16::MAX as usize) as u16);
+
+        let mut terminal = Terminal::new(TestBackend::new(width, height))?;
+        terminal.draw(|frame| self.render_pane(frame, pane, frame.size()))?;
+        let content =
+            snapshot::buffer_to_string(terminal.backend().buffer(), format, HtmlTheme::default());
+
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let path = PathBuf::from(format!(
+            "fdb-sim-{}-{}.{}",
+            pane.slug(),
+            secs,
+            format.extension()
+        ));
+        fs::write(&path, content)?;
+        Ok(path)
+    }
+
+    /// Writes the Timeline events passing the current filter and zoom to a file in the
+    /// current directory and reports the outcome in the status bar.
+    fn export_timeline(&mut self, format: TimelineExport) {
+        let events: Vec<TimelineEvent> = self
+            .visible_timeline_events()
+            .into_iter()
+            .cloned()
+            .collect();
+        let content = match format {
+            TimelineExport::Csv => timeline::to_csv(&events),
+            TimelineExport::Jsonl => timeline::to_jsonl(&events),
+        };
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let path = PathBuf::from(format!("fdb-sim-timeline-{}.{}", secs, format.extension()));
+        self.status_message = Some(match fs::write(&path, content) {
+            Ok(()) => format!("Exported {} events to {}", events.len(), path.display()),
+            Err(e) => format!("Export failed: {}", e),
+        });
+    }
+
+    /// Renders a single screen using the whole frame.
+    fn render_screen(&self, frame: &mut Frame, screen: Screen) {
+        let area = frame.size();
+        match screen {
+            Screen::Overview => {
+                let rows = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([Constraint::Percentage(35), Constraint::Min(10)])
+                    .split(area);
+                let top = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+                    .split(rows[0]);
+                self.render_pane(frame, Pane::Overview, top[0]);
+                self.render_pane(frame, Pane::Config, top[1]);
+                self.render_pane(frame, Pane::Chaos, rows[1]);
+            }
+            Screen::Timeline => {
+                let columns = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([Constraint::Percentage(65), Constraint::Percentage(35)])
+                    .split(area);
+                self.render_pane(frame, Pane::Timeline, columns[0]);
+                self.render_pane(frame, Pane::State, columns[1]);
+            }
+            Screen::Machines => self.render_pane(frame, Pane::Machines, area),
+            Screen::Errors => self.render_pane(frame, Pane::Errors, area),
+            Screen::Swimlanes => self.render_swimlanes(frame, area),
+            Screen::Heatmap => self.render_heatmap(frame, area),
+        }
+    }
+
+    /// Draws every screen into an off-screen buffer of the given size and returns
+    /// them concatenated as text, without needing a real terminal. HTML screens are
+    /// fragments in the given theme.
+    pub fn render_headless(
+        &self,
+        width: u16,
+        height: u16,
+        format: SnapshotFormat,
+        theme: HtmlTheme,
+    ) -> io::Result<String> {
+        let mut output = String::new();
+        for screen in Screen::ALL {
+            let mut terminal = Terminal::new(TestBackend::new(width, height))?;
+            terminal.draw(|frame| self.render_screen(frame, screen))?;
+            if format == SnapshotFormat::Html {
+                output.push_str(&format!("<h2>{}</h2>\n", screen.title()));
+            } else {
+                output.push_str(&format!("=== {} ===\n", screen.title()));
+            }
+            output.push_str(&snapshot::buffer_to_string(
+                terminal.backend().buffer(),
+                format,
+                theme,
+            ));
+            output.push('\n');
+        }
+        Ok(output)
     }
 
     /// Renders the content for the "Overview" pane.
@@ -154,11 +1810,53 @@ impl App {
                 " Overview ",
                 Style::default().fg(Color::Green),
             ))
-            .borders(Borders::ALL);
+            .borders(Borders::ALL)
+            .border_style(self.border_style(Pane::Overview));
         let inner_area = overview_block.inner(area);
 
         let mut overview_items: Vec<ListItem> = Vec::new();
 
+        // Test outcome, FAIL covering runs that never logged their results and UNKNOWN
+        // reports saved before outcomes were recorded
+        let (verdict, color) = match self.report.passed {
+            Some(true) => (" PASS ", Color::Green),
+            Some(false) => (" FAIL ", Color::Red),
+            None => (" UNKNOWN ", Color::Yellow),
+        };
+        let mut outcome = vec![
+            Span::styled(
+                verdict,
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(color)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(" "),
+            Span::styled(
+                self.report.test_name.as_deref().unwrap_or("unknown test"),
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+        ];
+        if self.report.error_count > 0 {
+            outcome.push(Span::styled(
+                format!("  {} error(s)", self.report.error_count),
+                Style::default().fg(Color::Red),
+            ));
+        }
+        overview_items.push(ListItem::new(Line::from(outcome)));
+
+        // How violent the run was, at a glance
+        let mut badges = Vec::new();
+        for (label, count, color) in overview_badges(self.stats()) {
+            let color = if count == 0 { Color::DarkGray } else { color };
+            badges.push(Span::styled(
+                format!(" {} {} ", count, label),
+                Style::default().fg(Color::Black).bg(color),
+            ));
+            badges.push(Span::raw(" "));
+        }
+        overview_items.push(ListItem::new(Line::from(badges)));
+
         // Seed
         overview_items.push(ListItem::new(Line::from(vec![
             Span::styled("Seed:             ", Style::default().fg(Color::Yellow)),
@@ -166,12 +1864,12 @@ impl App {
         ])));
 
         // Simulated Time
+        let time = self.time();
         let sim_time_str = self.report.elapsed_time.as_deref().map_or_else(
             || "N/A".to_string(),
             |t| {
-                t.parse::<f64>().map_or(format!("{} (Invalid)", t), |d| {
-                    format_duration(Duration::from_secs_f64(d)).to_string()
-                })
+                t.parse::<f64>()
+                    .map_or(format!("{} (Invalid)", t), |d| time.duration(d))
             },
         );
         overview_items.push(ListItem::new(Line::from(vec![
@@ -183,9 +1881,8 @@ impl App {
         let real_time_str = self.report.real_time.as_deref().map_or_else(
             || "N/A".to_string(),
             |t| {
-                t.parse::<f64>().map_or(format!("{} (Invalid)", t), |d| {
-                    format_duration(Duration::from_secs_f64(d)).to_string()
-                })
+                t.parse::<f64>()
+                    .map_or(format!("{} (Invalid)", t), |d| time.duration(d))
             },
         );
         overview_items.push(ListItem::new(Line::from(vec![
@@ -193,6 +1890,50 @@ impl App {
             Span::styled(real_time_str, Style::default().fg(Color::Cyan)),
         ])));
 
+        // Storage Server Churn
+        let churn_str = self.stats().storage_server_churn.as_ref().map_or_else(
+            || "N/A".to_string(),
+            |churn| format!("+{} / -{}", churn.added, churn.removed),
+        );
+        overview_items.push(ListItem::new(Line::from(vec![
+            Span::styled("SS Churn:         ", Style::default().fg(Color::Yellow)),
+            Span::raw(churn_str),
+        ])));
+
+        if let Some(truncated_at) = self.report.truncated_at {
+            overview_items.push(ListItem::new(Line::from(Span::styled(
+                format!(
+                    "! Truncated run, trace stops at {}",
+                    time.timestamp(truncated_at)
+                ),
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            ))));
+        }
+
+        // Recruitment failures are the usual root cause of timed-out runs
+        if !self.stats().recruitment_failures.is_empty() {
+            overview_items.push(ListItem::new(Line::from(Span::styled(
+                format!(
+                    "! {} recruitment failures",
+                    self.stats().recruitment_failures.len()
+                ),
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            ))));
+        }
+
+        // Warnings of the analysis passes, e.g. the first error of a failed run
+        for finding in self.report.analyses.iter().flat_map(|s| &s.findings) {
+            let color = match finding.level {
+                FindingLevel::Error => Color::Red,
+                FindingLevel::Warning => Color::Yellow,
+                FindingLevel::Info => continue,
+            };
+            overview_items.push(ListItem::new(Line::from(Span::styled(
+                format!("! {}", finding.message),
+                Style::default().fg(color),
+            ))));
+        }
+
         let overview_list = List::new(overview_items)
             .block(overview_block)
             .style(Style::default().fg(Color::White));
@@ -208,7 +1949,8 @@ impl App {
                 " Config Summary ",
                 Style::default().fg(Color::Green),
             ))
-            .borders(Borders::ALL);
+            .borders(Borders::ALL)
+            .border_style(self.border_style(Pane::Config));
 
         let mut config_items: Vec<Line> = Vec::new();
 
@@ -260,27 +2002,23 @@ impl App {
         frame.render_widget(config_paragraph, area);
     }
 
-    /// Renders the Machine Distribution summary and Process Distribution table.
-    fn render_distribution_panes(
-        &self,
-        frame: &mut Frame,
-        summary_area: Rect, // Area for the DC summary
-        table_area: Rect,   // Area for the detailed process table
-    ) {
-        // --- Data Collection and Processing (Same as before) ---
+    /// Rows of the Process Distribution table passing the filter, as (DC, machine ID,
+    /// IP address, process ID, class type), sorted by DC and machine ID.
+    fn machine_rows(&self) -> Vec<(String, String, String, String, String)> {
         let mut machine_list: Vec<(String, String, String, String, String)> = Vec::new();
-        let mut dc_counts: HashMap<String, usize> = HashMap::new();
 
-        for machine in self.report.machine_details.values() {
+        let criteria = self.filter.to_filter(&[Field::Machine]);
+        let machines = self.report.machine_details.values().filter(|machine| {
+            criteria
+                .as_ref()
+                .is_none_or(|filter| filter.matches(*machine))
+        });
+        for machine in machines {
             let class_type = machine
                 .class_type
                 .clone()
                 .unwrap_or_else(|| "unset".to_string());
 
-            if class_type == "sim_http_server" {
-                continue;
-            }
-
             let dc_id = machine.dc_id.clone().unwrap_or_else(|| "N/A".to_string());
             let machine_id = machine
                 .machine_id
@@ -295,11 +2033,10 @@ impl App {
                 .clone()
                 .unwrap_or_else(|| "N/A".to_string())
                 .split(&['-', '.'])
-                .last()
+                .next_back()
                 .unwrap_or("N/A")
                 .to_string();
 
-            *dc_counts.entry(dc_id.clone()).or_insert(0) += 1;
             machine_list.push((dc_id, machine_id, ip_address, process_id, class_type));
         }
 
@@ -311,6 +2048,21 @@ impl App {
                 dc_cmp
             }
         });
+        machine_list
+    }
+
+    /// Renders the Machine Distribution summary and Process Distribution table.
+    fn render_distribution_panes(
+        &self,
+        frame: &mut Frame,
+        summary_area: Rect, // Area for the DC summary
+        table_area: Rect,   // Area for the detailed process table
+    ) {
+        let machine_list = self.machine_rows();
+        let mut dc_counts: HashMap<String, usize> = HashMap::new();
+        for (dc_id, ..) in &machine_list {
+            *dc_counts.entry(dc_id.clone()).or_insert(0) += 1;
+        }
 
         // --- Render Machine Distribution Summary ---
         let summary_block = Block::default()
@@ -318,13 +2070,14 @@ impl App {
                 " Machine Distribution ",
                 Style::default().fg(Color::Green),
             ))
-            .borders(Borders::ALL);
+            .borders(Borders::ALL)
+            .border_style(self.border_style(Pane::Machines));
         let summary_inner_area = summary_block.inner(summary_area);
         frame.render_widget(summary_block, summary_area);
 
         let mut sorted_dcs: Vec<_> = dc_counts.into_iter().collect();
         sorted_dcs.sort_by(|a, b| a.0.cmp(&b.0));
-        let summary_lines: Vec<Line> = sorted_dcs
+        let mut summary_lines: Vec<Line> = sorted_dcs
             .iter()
             .map(|(dc, count)| {
                 Line::from(vec![
@@ -333,6 +2086,15 @@ impl App {
                 ])
             })
             .collect();
+        if !self.report.auxiliary_processes.is_empty() {
+            summary_lines.push(Line::from(vec![
+                Span::styled("Auxiliary: ", Style::default().fg(Color::DarkGray)),
+                Span::raw(format!(
+                    "{} processes",
+                    self.report.auxiliary_processes.len()
+                )),
+            ]));
+        }
         let summary_paragraph = Paragraph::new(summary_lines).wrap(Wrap { trim: true });
         frame.render_widget(summary_paragraph, summary_inner_area);
 
@@ -342,17 +2104,19 @@ impl App {
                 " Process Distribution ",
                 Style::default().fg(Color::Green),
             ))
-            .borders(Borders::ALL);
+            .borders(Borders::ALL)
+            .border_style(self.border_style(Pane::Machines));
         let table_inner_area = table_block.inner(table_area);
         frame.render_widget(table_block, table_area);
 
+        let selected = self.selected_machine_row(&machine_list);
         let rows: Vec<Row> = machine_list
             .into_iter()
             .map(|(dc, machine_id, ip_addr, process_id, class)| {
                 Row::new(vec![
                     Cell::from(dc),
-                    Cell::from(machine_id),
-                    Cell::from(ip_addr),
+                    Cell::from(highlight_search(machine_id, &self.search)),
+                    Cell::from(highlight_search(ip_addr, &self.search)),
                     Cell::from(process_id),
                     Cell::from(class),
                 ])
@@ -380,7 +2144,92 @@ impl App {
         .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
         .highlight_symbol("> ");
 
-        frame.render_widget(table, table_inner_area);
+        // Rendering scrolls the table to keep the selected machine visible
+        let mut table_state = self.machine_state.borrow_mut();
+        table_state.select(selected);
+        // Header and its margin
+        self.machine_page
+            .set(usize::from(table_inner_area.height.saturating_sub(2)));
+        frame.render_stateful_widget(table, table_inner_area, &mut table_state);
+    }
+
+    /// Renders the "Errors & Warnings" pane, the Severity 30+ events grouped by type,
+    /// errors in red and warnings in yellow.
+    fn render_errors_pane(&self, frame: &mut Frame, area: Rect) {
+        let criteria = self.filter.to_filter(&ERRORS_FILTER_FIELDS);
+        let summaries: Vec<_> = self
+            .stats()
+            .errors_and_warnings
+            .iter()
+            .filter(|summary| {
+                criteria
+                    .as_ref()
+                    .is_none_or(|filter| filter.matches(*summary))
+            })
+            .collect();
+        let block = Block::default()
+            .title(Span::styled(
+                format!(" Errors & Warnings ({} errors) ", self.stats().error_count),
+                Style::default().fg(Color::Green),
+            ))
+            .borders(Borders::ALL)
+            .border_style(self.border_style(Pane::Errors));
+        if summaries.is_empty() {
+            frame.render_widget(Paragraph::new("No Severity 30+ events.").block(block), area);
+            return;
+        }
+
+        let time = self.time();
+        let rows: Vec<Row> = summaries
+            .iter()
+            .map(|summary| {
+                let color = if summary.severity >= SEVERITY_ERROR {
+                    Color::Red
+                } else {
+                    Color::Yellow
+                };
+                Row::new(vec![
+                    Cell::from(summary.event_type.clone()),
+                    Cell::from(summary.severity.to_string()),
+                    Cell::from(summary.count.to_string()),
+                    Cell::from(time.timestamp(summary.first_seen)),
+                    Cell::from(time.timestamp(summary.last_seen)),
+                    Cell::from(
+                        summary
+                            .top_machines
+                            .first()
+                            .map_or_else(String::new, |(machine, _)| machine.clone()),
+                    ),
+                ])
+                .style(Style::default().fg(color))
+            })
+            .collect();
+        let headers = [
+            "Event Type".to_string(),
+            "Sev".to_string(),
+            "Count".to_string(),
+            time.header("First Seen"),
+            time.header("Last Seen"),
+            "Top Machine".to_string(),
+        ]
+        .into_iter()
+        .map(|h| Cell::from(h).style(Style::default().fg(Color::Yellow)))
+        .collect::<Row>()
+        .height(1);
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Min(20),
+                Constraint::Length(4),
+                Constraint::Length(7),
+                Constraint::Length(14),
+                Constraint::Length(14),
+                Constraint::Length(16),
+            ],
+        )
+        .header(headers)
+        .block(block);
+        frame.render_widget(table, area);
     }
 
     /// Renders the content for the "Chaos Summary" pane.
@@ -397,21 +2246,27 @@ impl App {
                 "Network splits",
                 Style::default().fg(Color::Green),
             ))
-            .borders(Borders::ALL);
+            .borders(Borders::ALL)
+            .border_style(self.border_style(Pane::Chaos));
+        let time = self.time();
         let mut clogging_pairs_text = Vec::new();
-        if let Some(summary) = &self.report.clogging_pair_summary {
-            clogging_pairs_text.push(Line::from(format!("Count: {}", summary.count)));
+        if let Some(summary) = &self.stats().clogging_pair_summary {
+            clogging_pairs_text.push(Line::from(format!(
+                "Count: {}{}",
+                summary.count,
+                sampled_suffix(self.stats().sampling_note("CloggingPair"))
+            )));
             clogging_pairs_text.push(Line::from(format!(
                 "  Min Duration:   {}",
-                format_duration(Duration::from_secs_f64(summary.min_seconds))
+                time.duration(summary.min_seconds)
             )));
             clogging_pairs_text.push(Line::from(format!(
                 "  Mean Duration:  {}",
-                format_duration(Duration::from_secs_f64(summary.mean_seconds))
+                time.duration(summary.mean_seconds)
             )));
             clogging_pairs_text.push(Line::from(format!(
                 "  Max Duration:   {}",
-                format_duration(Duration::from_secs_f64(summary.max_seconds))
+                time.duration(summary.max_seconds)
             )));
         } else {
             clogging_pairs_text.push(Line::from("No clogging pairs reported."));
@@ -421,7 +2276,86 @@ impl App {
             .block(clogging_pairs_block)
             .style(Style::default().fg(Color::White));
 
-        frame.render_widget(clogging_pairs_paragraph, chunks[0]);
+        let left = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Ratio(1, 3),
+                Constraint::Ratio(1, 3),
+                Constraint::Ratio(1, 3),
+            ])
+            .split(chunks[0]);
+        frame.render_widget(clogging_pairs_paragraph, left[0]);
+
+        // --- Disk Failures ---
+        let disk_failures_block = Block::default()
+            .title(Span::styled(
+                "Disk failures",
+                Style::default().fg(Color::Green),
+            ))
+            .borders(Borders::ALL)
+            .border_style(self.border_style(Pane::Chaos));
+        let mut disk_failures_text = Vec::new();
+        if let Some(summary) = &self.stats().disk_failure_summary {
+            disk_failures_text.push(Line::from(format!("Count: {}", summary.count)));
+            disk_failures_text.push(Line::from(format!(
+                "  Stalled:   {}",
+                time.duration(summary.total_stall_seconds)
+            )));
+            disk_failures_text.push(Line::from(format!(
+                "  Throttled: {}",
+                time.duration(summary.total_throttle_seconds)
+            )));
+            for (ip, machine) in &summary.by_machine {
+                disk_failures_text.push(Line::from(format!(
+                    "  {}: {} (stall {}, throttle {})",
+                    ip,
+                    machine.count,
+                    time.duration(machine.stall_seconds),
+                    time.duration(machine.throttle_seconds)
+                )));
+            }
+        } else {
+            disk_failures_text.push(Line::from("No disk failures reported."));
+        }
+        frame.render_widget(
+            Paragraph::new(disk_failures_text)
+                .block(disk_failures_block)
+                .style(Style::default().fg(Color::White)),
+            left[1],
+        );
+
+        // --- Corrupted Blocks ---
+        let corrupted_blocks_block = Block::default()
+            .title(Span::styled(
+                "Corrupted blocks",
+                Style::default().fg(Color::Green),
+            ))
+            .borders(Borders::ALL)
+            .border_style(self.border_style(Pane::Chaos));
+        let mut corrupted_blocks_text = Vec::new();
+        if let Some(summary) = self.stats().corrupted_block_summary() {
+            corrupted_blocks_text.push(Line::from(format!(
+                "Count: {}",
+                self.stats().corrupted_blocks.len()
+            )));
+            for (name, group) in summary.by_file.iter().chain(&summary.by_machine) {
+                corrupted_blocks_text.push(Line::from(format!(
+                    "  {}: {} ({} - {})",
+                    name,
+                    group.count,
+                    time.timestamp(group.first_seen),
+                    time.timestamp(group.last_seen)
+                )));
+            }
+        } else {
+            corrupted_blocks_text.push(Line::from("No corrupted blocks reported."));
+        }
+        frame.render_widget(
+            Paragraph::new(corrupted_blocks_text)
+                .block(corrupted_blocks_block)
+                .style(Style::default().fg(Color::White)),
+            left[2],
+        );
 
         // --- Clogged Interfaces ---
         let clogged_interfaces_block = Block::default()
@@ -429,11 +2363,13 @@ impl App {
                 "Network latencies",
                 Style::default().fg(Color::Green),
             ))
-            .borders(Borders::ALL);
+            .borders(Borders::ALL)
+            .border_style(self.border_style(Pane::Chaos));
         let mut clogged_interface_items: Vec<ListItem> = Vec::new();
 
-        if !self.report.clog_interface_summary.is_empty() {
-            let mut sorted_interfaces: Vec<_> = self.report.clog_interface_summary.iter().collect();
+        if !self.stats().clog_interface_summary.is_empty() {
+            let mut sorted_interfaces: Vec<_> =
+                self.stats().clog_interface_summary.iter().collect();
             sorted_interfaces.sort_by(|a, b| a.0.cmp(b.0)); // Sort by queue name
 
             for (queue_name, summary) in sorted_interfaces {
@@ -449,20 +2385,43 @@ impl App {
                         .add_modifier(Modifier::BOLD)
                         .fg(Color::Yellow),
                 ))));
-                clogged_interface_items.push(ListItem::new(format!("  Count: {}", summary.count)));
+                clogged_interface_items.push(ListItem::new(format!(
+                    "  Count: {}{}",
+                    summary.count,
+                    sampled_suffix(self.stats().sampling_note("ClogInterface"))
+                )));
                 clogged_interface_items.push(ListItem::new(format!(
                     "    Min Duration:  {}",
-                    format_duration(Duration::from_secs_f64(summary.min_seconds))
+                    time.duration(summary.min_seconds)
                 )));
                 clogged_interface_items.push(ListItem::new(format!(
                     "    Mean Duration: {}",
-                    format_duration(Duration::from_secs_f64(summary.mean_seconds))
+                    time.duration(summary.mean_seconds)
                 )));
                 clogged_interface_items.push(ListItem::new(format!(
                     "    Max Duration:  {}",
-                    format_duration(Duration::from_secs_f64(summary.max_seconds))
+                    time.duration(summary.max_seconds)
                 )));
             }
+
+            // Latency on TLogs hurts commits, on storage servers only reads
+            if !self.stats().clog_interface_roles.is_empty() {
+                clogged_interface_items.push(ListItem::new(""));
+                clogged_interface_items.push(ListItem::new(Line::from(Span::styled(
+                    "By Role",
+                    Style::default()
+                        .add_modifier(Modifier::BOLD)
+                        .fg(Color::Yellow),
+                ))));
+                for (role, summary) in &self.stats().clog_interface_roles {
+                    clogged_interface_items.push(ListItem::new(format!(
+                        "  {}: {} (max {})",
+                        role,
+                        summary.count,
+                        time.duration(summary.max_seconds)
+                    )));
+                }
+            }
         } else {
             clogged_interface_items.push(ListItem::new("No clogged interfaces reported."));
         }
@@ -474,18 +2433,78 @@ impl App {
         frame.render_widget(clogged_interfaces_list, chunks[1]);
     }
 
+    /// The Timeline events of the time window, sorted chronologically.
+    fn timeline_events(&self) -> &[TimelineEvent] {
+        match self.time_window {
+            Some((from, to)) => timeline::between(&self.run_timeline, Some(from), Some(to)),
+            None => &self.run_timeline,
+        }
+    }
+
+    /// The Timeline events passing the current filter and zoom. A filter that parses
+    /// as an expression, e.g. `type ~ clog* && time > 100`, is evaluated as such;
+    /// anything else is searched for in the event types and details.
+    fn visible_timeline_events(&self) -> Vec<&TimelineEvent> {
+        let expression = self.timeline_filter.parse::<Filter>().ok();
+        let criteria = self.filter.to_filter(&TIMELINE_FILTER_FIELDS);
+        let query = self.timeline_filter.to_lowercase();
+        let window = self
+            .timeline_zoom
+            .map(|seconds| (self.cursor - seconds, self.cursor + seconds));
+        let events = timeline::between(
+            self.timeline_events(),
+            window.map(|(from, _)| from),
+            window.map(|(_, to)| to),
+        );
+        events
+            .iter()
+            .filter(|event| {
+                criteria
+                    .as_ref()
+                    .is_none_or(|filter| filter.matches(*event))
+            })
+            .filter(|event| match &expression {
+                Some(filter) => filter.matches(*event),
+                None => {
+                    query.is_empty()
+                        || event.event_type.to_lowercase().contains(&query)
+                        || event.details.to_lowercase().contains(&query)
+                }
+            })
+            .collect()
+    }
+
     /// Renders the content for the "Timeline" pane. (Placeholder)
     fn render_timeline_pane(&self, frame: &mut Frame, area: Rect) {
+        let mut title = " Timeline ".to_string();
+        if let Some(seconds) = self.timeline_zoom {
+            title.push_str(&format!("(cursor ± {}s) ", seconds));
+        }
+        if !self.timeline_filter.is_empty() {
+            title.push_str(&format!("[|{}] ", self.timeline_filter));
+        }
+        let timeline_events = self.visible_timeline_events();
+        if let Some(row) = self.timeline_state.borrow().selected() {
+            if row < timeline_events.len() {
+                title.push_str(&format!("{}/{} ", row + 1, timeline_events.len()));
+            }
+        }
         let timeline_block = Block::default()
-            .title(Span::styled(
-                " Timeline ",
-                Style::default().fg(Color::Green),
-            ))
-            .borders(Borders::ALL);
+            .title(Span::styled(title, Style::default().fg(Color::Green)))
+            .borders(Borders::ALL)
+            .border_style(self.border_style(Pane::Timeline));
 
         // Use a Table widget for better alignment
-        let header_cells = ["Time (s)", "Event", "Details"].iter().map(|h| {
-            Cell::from(*h).style(
+        let time = self.time();
+        let header_cells = [
+            time.header("Time"),
+            " ".to_string(),
+            "Event".to_string(),
+            "Details".to_string(),
+        ]
+        .into_iter()
+        .map(|h| {
+            Cell::from(h).style(
                 Style::default()
                     .fg(Color::Yellow)
                     .add_modifier(Modifier::BOLD),
@@ -496,100 +2515,370 @@ impl App {
             .height(1)
             .bottom_margin(1);
 
+        let time_strs: Vec<String> = timeline_events
+            .iter()
+            .map(|event| time.timestamp(event.timestamp))
+            .collect();
+
         // Define column widths
+        let time_width = time_strs.iter().map(String::len).max().unwrap_or(0).max(10);
         let widths = [
-            Constraint::Length(10), // Fixed width for time
-            Constraint::Length(15), // Fixed width for event type
-            Constraint::Min(30),    // Minimum width for details, expands
+            Constraint::Length(time_width as u16), // Wide enough for the time format
+            Constraint::Length(1),                 // Event glyph
+            Constraint::Length(15),                // Fixed width for event type
+            Constraint::Min(30),                   // Minimum width for details, expands
         ];
 
-        #[derive(Debug)]
-        struct TimelineEvent {
-            timestamp: f64,
-            event_type: String,
-            details: String,
-        }
+        // Create table rows from events
+        let rows: Vec<Row> = timeline_events
+            .iter()
+            .zip(time_strs)
+            .map(|(event, time_str)| {
+                let row_style = if self.involves_selected_machine(event) {
+                    SELECTED_MACHINE_STYLE
+                } else {
+                    Style::default()
+                };
+                Row::new(vec![
+                    Cell::from(Span::styled(time_str, Style::default().fg(Color::Cyan))),
+                    Cell::from(Span::styled(event.glyph, Style::default().fg(Color::Red))),
+                    Cell::from(Span::styled(
+                        event.event_type.clone(),
+                        Style::default().fg(Color::Magenta),
+                    )),
+                    Cell::from(highlight_search(event.details.clone(), &self.search)),
+                ])
+                .style(row_style)
+            })
+            .collect();
 
-        let mut timeline_events: Vec<TimelineEvent> = Vec::new();
+        // Create the table
+        let timeline_table = Table::new(rows, widths)
+            .block(timeline_block)
+            .header(header)
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED)) // Optional: for selection
+            .highlight_symbol(">> ") // Optional: for selection
+            .style(Style::default().fg(Color::White));
 
-        // Helper to parse timestamp and add event
-        let mut add_event = |timestamp_str: &str, event_type: String, details: String| {
-            if let Ok(ts) = f64::from_str(timestamp_str) {
-                timeline_events.push(TimelineEvent {
-                    timestamp: ts,
-                    event_type,
-                    details,
-                });
-            } else {
-                // Log or handle parse error if needed
-                eprintln!(
-                    "Warning: Could not parse timestamp '{}' for timeline",
-                    timestamp_str
-                );
-            }
+        // Rendering scrolls the table to keep the selected row visible
+        let mut table_state = self.timeline_state.borrow_mut();
+        let selected = table_state
+            .selected()
+            .filter(|row| *row < timeline_events.len())
+            .or_else(|| cursor_row(&timeline_events, self.cursor));
+        table_state.select(selected);
+        // Borders, header and its margin
+        self.timeline_page
+            .set(usize::from(area.height.saturating_sub(4)));
+        frame.render_stateful_widget(timeline_table, area, &mut table_state);
+    }
+
+    /// Renders one lane per machine of the Process Distribution table, in its order,
+    /// with markers for the chaos hitting it along simulated time: the whole run, or
+    /// the time window when one is set. Lanes scroll to keep the selected machine,
+    /// whose label is highlighted, visible.
+    fn render_swimlanes(&self, frame: &mut Frame, area: Rect) {
+        let mut title = vec![Span::styled(
+            " Swimlanes ",
+            Style::default().fg(Color::Green),
+        )];
+        for kind in MarkerKind::ALL {
+            title.push(Span::styled(
+                format!("{} {} ", kind.glyph(), kind.name()),
+                Style::default().fg(marker_color(kind)),
+            ));
+        }
+        let block = Block::default()
+            .title(Line::from(title))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Yellow));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let rows = self.machine_rows();
+        if rows.is_empty() {
+            frame.render_widget(Paragraph::new("No machines."), inner);
+            return;
+        }
+        let rows_and_axis = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(1), Constraint::Length(1)])
+            .split(inner);
+        let lane_count = usize::from(rows_and_axis[0].height);
+        self.machine_page.set(lane_count);
+        let selected = self.selected_machine_row(&rows);
+        let offset = selected.map_or(0, |row| (row + 1).saturating_sub(lane_count));
+        let lanes: Vec<&str> = rows
+            .iter()
+            .skip(offset)
+            .take(lane_count)
+            .map(|(_, _, ip, ..)| ip_from_address(ip))
+            .collect();
+        let label_width = lanes.iter().map(|ip| ip.len()).max().unwrap_or(0) as u16 + 1;
+        let columns = |area: Rect| {
+            Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Length(label_width), Constraint::Min(1)])
+                .split(area)
         };
+        let lane_columns = columns(rows_and_axis[0]);
+        let axis_columns = columns(rows_and_axis[1]);
 
-        // 1. Coordinator Changes
-        for event in &self.report.coordinators_changes {
-            let details = "Triggering leader election".to_string();
-            add_event(&event.timestamp, "Coord Change".to_string(), details);
-        }
-
-        // 2. Killed Processes
-        for event in &self.report.kill_machine_processes {
-            // Parse KillType
-            let kill_type = KillType::from_str(&event.raw_kill_type).unwrap_or(KillType::Unknown);
-
-            // Extract IP address, handling both IPv4 and IPv6 ([...]:port) and extra info
-            let ip_addr = event
-                .process // e.g., "... address: 2.1.1.2:1 zone: id ..." or "... address: [::1]:80 zone: id ..."
-                .split("address: ")
-                .nth(1)
-                .map(|addr_part| {
-                    // Isolate the ip:port part before the first space (if any)
-                    let ip_with_port = addr_part.split(' ').next().unwrap_or(addr_part);
-
-                    if ip_with_port.starts_with('[') && ip_with_port.contains(']') {
-                        // Likely IPv6: Extract content within brackets
-                        ip_with_port
-                            .split('[')
-                            .nth(1)
-                            .and_then(|s| s.split(']').next())
-                            .unwrap_or(ip_with_port) // Fallback
-                    } else {
-                        // Likely IPv4: Split by last colon to remove port
-                        ip_with_port
-                            .rsplit_once(':')
-                            .map_or(ip_with_port, |(ip, _port)| ip)
+        let labels: Vec<Line> = lanes
+            .iter()
+            .map(|ip| {
+                let style = if self.selected_machine.as_deref() == Some(*ip) {
+                    SELECTED_MACHINE_STYLE
+                } else {
+                    Style::default().fg(Color::Cyan)
+                };
+                Line::styled(ip.to_string(), style)
+            })
+            .collect();
+        frame.render_widget(Paragraph::new(labels), lane_columns[0]);
+
+        let (from, to) = self.time_window.unwrap_or((0.0, self.run_end()));
+        let to = to.max(from + f64::EPSILON);
+        let markers = timeline::machine_markers(self.stats());
+        let height = lane_count as f64;
+        let cursor = self.cursor;
+        let canvas = canvas::Canvas::default()
+            .x_bounds([from, to])
+            .y_bounds([0.0, height])
+            .paint(|ctx| {
+                if (from..=to).contains(&cursor) {
+                    ctx.draw(&canvas::Line {
+                        x1: cursor,
+                        y1: 0.0,
+                        x2: cursor,
+                        y2: height,
+                        color: Color::DarkGray,
+                    });
+                }
+                for (lane, ip) in lanes.iter().enumerate() {
+                    // Lanes go top down, one terminal row each
+                    let y = height - lane as f64 - 0.5;
+                    // Kills come last, drawn over the other markers
+                    for kind in MarkerKind::ALL {
+                        let lane_markers = markers.get(*ip).into_iter().flatten();
+                        for marker in lane_markers.filter(|m| m.kind == kind) {
+                            if (from..=to).contains(&marker.timestamp) {
+                                ctx.print(
+                                    marker.timestamp,
+                                    y,
+                                    Span::styled(kind.glyph(), marker_color(kind)),
+                                );
+                            }
+                        }
                     }
-                })
-                .unwrap_or("?.?.?.?"); // Default if parsing fails
+                }
+            });
+        frame.render_widget(canvas, lane_columns[1]);
+
+        let time = self.time();
+        let axis_style = Style::default().fg(Color::Gray);
+        frame.render_widget(
+            Paragraph::new(time.timestamp(from)).style(axis_style),
+            axis_columns[1],
+        );
+        frame.render_widget(
+            Paragraph::new(time.timestamp(to))
+                .style(axis_style)
+                .alignment(Alignment::Right),
+            axis_columns[1],
+        );
+    }
 
-            // Simplify details format
-            let details = format!("{:?} {}", kill_type, ip_addr);
-            add_event(&event.timestamp, "Reboot".to_string(), details);
+    /// Renders the seconds of clogging between machine pairs as a table, rows being
+    /// sources and columns destinations, shaded by their share of the largest value.
+    /// Columns are numbered like the rows to fit many machines. Rows scroll to keep
+    /// the selected machine, whose label is highlighted, visible.
+    fn render_heatmap(&self, frame: &mut Frame, area: Rect) {
+        let report = self.stats();
+        let matrix = report.machine_clog_seconds();
+        let max = matrix
+            .values()
+            .flat_map(|to| to.values())
+            .fold(0.0_f64, |max, seconds| max.max(*seconds));
+        let mut title = vec![Span::styled(
+            " Clog Heatmap: seconds clogged from row to column ",
+            Style::default().fg(Color::Green),
+        )];
+        for (level, color) in HEATMAP_COLORS.iter().enumerate() {
+            let ceiling = max * (level + 1) as f64 / HEATMAP_COLORS.len() as f64;
+            title.push(Span::styled(
+                format!(" ≤{:.1} ", ceiling),
+                Style::default().fg(Color::Black).bg(*color),
+            ));
+        }
+        let block = Block::default()
+            .title(Line::from(title))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Yellow));
+        if matrix.is_empty() {
+            let message = if report.aggregate_only {
+                "No clogging pairs kept: raw clog events were dropped to stay within the memory budget."
+            } else {
+                "No clogging pairs reported."
+            };
+            frame.render_widget(Paragraph::new(message).block(block), area);
+            return;
         }
 
-        // 3. Disk Swaps
-        for event in &self.report.disk_swaps {
-            let details = format!("IPs: {}", event.machine_ips);
-            add_event(&event.timestamp, "Disk Swap".to_string(), details);
+        // Machines in Process Distribution order, then clogged IPs of unknown machines
+        let mut ips: Vec<&str> = Vec::new();
+        let machine_rows = self.machine_rows();
+        let known = machine_rows
+            .iter()
+            .map(|(_, _, ip, ..)| ip_from_address(ip));
+        let clogged = matrix
+            .iter()
+            .flat_map(|(from, to)| std::iter::once(from).chain(to.keys()))
+            .map(String::as_str);
+        for ip in known.chain(clogged) {
+            if !ips.contains(&ip) {
+                ips.push(ip);
+            }
         }
 
-        // Sort events chronologically
-        timeline_events.sort_by(|a, b| {
-            a.timestamp
-                .partial_cmp(&b.timestamp)
-                .unwrap_or(std::cmp::Ordering::Equal)
-        });
+        let inner_height = usize::from(block.inner(area).height.saturating_sub(1));
+        self.machine_page.set(inner_height);
+        let selected = self
+            .selected_machine
+            .as_deref()
+            .and_then(|selected| ips.iter().position(|ip| *ip == selected));
+        let offset = selected.map_or(0, |row| (row + 1).saturating_sub(inner_height));
+        let number_width = ips.len().to_string().len();
+        let label_width = ips.iter().map(|ip| ip.len()).max().unwrap_or(0) + number_width + 1;
+        let cell_width = number_width.max(4) as u16;
+
+        let header = std::iter::once(Cell::from(""))
+            .chain((1..=ips.len()).map(|number| Cell::from(number.to_string())))
+            .collect::<Row>()
+            .style(Style::default().fg(Color::Yellow));
+        let rows: Vec<Row> = ips
+            .iter()
+            .enumerate()
+            .skip(offset)
+            .map(|(row, from)| {
+                let label_style = if self.selected_machine.as_deref() == Some(*from) {
+                    SELECTED_MACHINE_STYLE
+                } else {
+                    Style::default().fg(Color::Cyan)
+                };
+                let cells =
+                    ips.iter()
+                        .map(|to| match matrix.get(*from).and_then(|row| row.get(*to)) {
+                            Some(seconds) => Cell::from(format!("{:.1}", seconds)).style(
+                                Style::default()
+                                    .fg(Color::Black)
+                                    .bg(heatmap_color(*seconds, max)),
+                            ),
+                            None => Cell::from("·").style(Style::default().fg(Color::DarkGray)),
+                        });
+                let label = format!("{:>width$} {}", row + 1, from, width = number_width);
+                std::iter::once(Cell::from(label).style(label_style))
+                    .chain(cells)
+                    .collect::<Row>()
+            })
+            .collect();
+        let widths = std::iter::once(Constraint::Length(label_width as u16))
+            .chain(ips.iter().map(|_| Constraint::Length(cell_width)));
+        let table = Table::new(rows, widths).header(header).block(block);
+        frame.render_widget(table, area);
+    }
 
-        // Create table rows from events
-        let rows: Vec<Row> = timeline_events
+    /// Renders the selected machine: its locality and process class, then the chaos
+    /// that affected it in chronological order, over the whole run or the time window.
+    fn render_machine_detail(&self, frame: &mut Frame, area: Rect) {
+        let Some(ip) = self.selected_machine.as_deref() else {
+            frame.render_widget(
+                Paragraph::new("No machine selected.")
+                    .block(Block::default().borders(Borders::ALL)),
+                area,
+            );
+            return;
+        };
+        let block = Block::default()
+            .title(Span::styled(
+                format!(" Machine {} | ↑ ↓: scroll | Esc: back ", ip),
+                Style::default().fg(Color::Green),
+            ))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Yellow));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(4), Constraint::Min(1)])
+            .split(inner);
+
+        let cluster = ClusterIndex::new(&self.report);
+        let machine = cluster.by_address(ip);
+        let value = |field: fn(&parser::report::MachineInfo) -> &Option<String>| {
+            machine
+                .and_then(|machine| field(machine).clone())
+                .unwrap_or_else(|| "N/A".to_string())
+        };
+        let label = |text: &str| Span::styled(text.to_string(), Style::default().fg(Color::Cyan));
+        let events = self.machine_events(ip);
+        let count = |category: TimelineCategory| {
+            events
+                .iter()
+                .filter(|event| event.category() == category && event.event_type != "Clog End")
+                .count()
+        };
+        let lines = vec![
+            Line::from(vec![
+                label("Machine ID: "),
+                Span::raw(format!("{}  ", value(|m| &m.machine_id))),
+                label("Process class: "),
+                Span::raw(value(|m| &m.class_type)),
+            ]),
+            Line::from(vec![
+                label("DC: "),
+                Span::raw(format!("{}  ", value(|m| &m.dc_id))),
+                label("Zone: "),
+                Span::raw(format!("{}  ", value(|m| &m.zone_id))),
+                label("Data hall: "),
+                Span::raw(value(|m| &m.data_hall_id)),
+            ]),
+            Line::from(vec![
+                label("Chaos: "),
+                Span::raw(format!(
+                    "{} kills, {} clogs, {} disk faults",
+                    count(TimelineCategory::Kills),
+                    count(TimelineCategory::Clogs),
+                    count(TimelineCategory::Disk)
+                )),
+            ]),
+        ];
+        frame.render_widget(Paragraph::new(lines), layout[0]);
+
+        if events.is_empty() {
+            frame.render_widget(Paragraph::new("No chaos affected this machine."), layout[1]);
+            return;
+        }
+        // Header row
+        let page = usize::from(layout[1].height.saturating_sub(1));
+        self.machine_detail_page.set(page);
+        let scroll = self
+            .machine_detail_scroll
+            .min(events.len().saturating_sub(page));
+        let time = self.time();
+        let time_strs: Vec<String> = events
+            .iter()
+            .map(|event| time.timestamp(event.timestamp))
+            .collect();
+        let time_width = time_strs.iter().map(String::len).max().unwrap_or(0).max(10);
+        let rows: Vec<Row> = events
             .iter()
-            .map(|event| {
-                let time_str = format!("{:.3}", event.timestamp);
+            .zip(time_strs)
+            .skip(scroll)
+            .map(|(event, time_str)| {
                 Row::new(vec![
                     Cell::from(Span::styled(time_str, Style::default().fg(Color::Cyan))),
+                    Cell::from(Span::styled(event.glyph, Style::default().fg(Color::Red))),
                     Cell::from(Span::styled(
                         event.event_type.clone(),
                         Style::default().fg(Color::Magenta),
@@ -598,30 +2887,315 @@ impl App {
                 ])
             })
             .collect();
+        let header = Row::new([
+            time.header("Time"),
+            " ".to_string(),
+            "Event".to_string(),
+            "Details".to_string(),
+        ])
+        .style(
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        );
+        let widths = [
+            Constraint::Length(time_width as u16),
+            Constraint::Length(1),
+            Constraint::Length(15),
+            Constraint::Min(30),
+        ];
+        frame.render_widget(
+            Table::new(rows, widths)
+                .header(header)
+                .style(Style::default().fg(Color::White)),
+            layout[1],
+        );
+    }
 
-        // Create the table
-        let timeline_table = Table::new(rows, widths)
-            .block(timeline_block)
-            .header(header)
-            .highlight_style(Style::new().add_modifier(Modifier::REVERSED)) // Optional: for selection
-            .highlight_symbol(">> ") // Optional: for selection
-            .style(Style::default().fg(Color::White));
+    /// Renders the cluster state at the playback cursor.
+    fn render_state_pane(&self, frame: &mut Frame, area: Rect) {
+        let time = self.time();
+        let state_block = Block::default()
+            .title(Span::styled(
+                format!(" Cluster State at {} ", time.timestamp(self.cursor)),
+                Style::default().fg(Color::Green),
+            ))
+            .borders(Borders::ALL)
+            .border_style(self.border_style(Pane::State));
+        let state = state::state_at(&self.report, self.cursor);
+        let label = |text: &str| Span::styled(text.to_string(), Style::default().fg(Color::Yellow));
+        let fault = Style::default().fg(Color::Red);
+
+        let mut items: Vec<ListItem> = vec![
+            ListItem::new(Line::from(vec![
+                label("Machines up:  "),
+                Span::raw(state.machines_up.len().to_string()),
+            ])),
+            ListItem::new(Line::from(vec![
+                label("Coordinators: "),
+                Span::raw(
+                    match (&state.coordinators, state.last_coordinators_change) {
+                        (Some(key), _) => key.clone(),
+                        (None, Some(changed)) => format!("changed at {}", time.timestamp(changed)),
+                        (None, None) => "unchanged".to_string(),
+                    },
+                ),
+            ])),
+        ];
+        if !state.is_degraded() {
+            items.push(ListItem::new(Span::styled(
+                "No active faults",
+                Style::default().fg(Color::Green),
+            )));
+        }
+        for interval in &state.processes_down {
+            items.push(ListItem::new(Span::styled(
+                format!("✖ {} down ({:?})", interval.address, interval.cause),
+                fault,
+            )));
+        }
+        for pair in &state.clogged_pairs {
+            items.push(ListItem::new(Span::styled(
+                format!("▶ {} -> {} clogged", pair.from_id, pair.to_id),
+                fault,
+            )));
+        }
+        for interface in &state.clogged_interfaces {
+            items.push(ListItem::new(Span::styled(
+                format!("▶ {} ({}) delayed", interface.ip, interface.queue),
+                fault,
+            )));
+        }
+        for failure in &state.disk_faults {
+            items.push(ListItem::new(Span::styled(
+                format!("▼ {} disk degraded", ip_from_address(&failure.machine)),
+                fault,
+            )));
+        }
 
-        frame.render_widget(timeline_table, area);
+        let state_list = List::new(items)
+            .block(state_block)
+            .style(Style::default().fg(Color::White));
+        frame.render_widget(state_list, area);
     }
 
     /// Renders a simple status bar at the bottom.
     fn render_status_bar(&self, frame: &mut Frame, area: Rect) {
-        let status_text = "Quit: q";
+        let status_text = "Help: ? | Quit: q";
         let status_paragraph = Paragraph::new(status_text)
             .style(Style::default().fg(Color::Gray))
             .alignment(Alignment::Right);
         frame.render_widget(status_paragraph, area);
+
+        let mut spans = Vec::new();
+        if !self.filter.is_empty() {
+            spans.push(Span::styled(
+                format!("[Filter: {}] ", all_fields_expression(&self.filter)),
+                Style::default().fg(Color::Cyan),
+            ));
+        }
+        if !self.timeline_filter.is_empty() {
+            spans.push(Span::styled(
+                format!("[|{}] ", self.timeline_filter),
+                Style::default().fg(Color::Cyan),
+            ));
+        }
+        if let Some((from, to)) = self.time_window {
+            let time = self.time();
+            spans.push(Span::styled(
+                format!(
+                    "[Window: {} to {}] ",
+                    time.timestamp(from),
+                    time.timestamp(to)
+                ),
+                Style::default().fg(Color::Cyan),
+            ));
+        }
+        if !self.search.is_empty() {
+            spans.push(Span::styled(
+                format!("[Search: {}] ", self.search),
+                Style::default().fg(Color::Cyan),
+            ));
+        }
+        if let Some(message) = &self.status_message {
+            spans.push(Span::styled(
+                message.as_str(),
+                Style::default().fg(Color::Yellow),
+            ));
+        }
+        if !spans.is_empty() {
+            frame.render_widget(Paragraph::new(Line::from(spans)), area);
+        }
     }
 } // End of impl App
 
+/// Row selected by a navigation key from the `current` one, if any, in a table whose
+/// last row is `last` and showing `page` rows at once.
+fn step_row(code: KeyCode, current: Option<usize>, last: usize, page: usize) -> usize {
+    let page = page.max(1);
+    let current = current.unwrap_or(0);
+    let row = match code {
+        KeyCode::Up => current.saturating_sub(1),
+        KeyCode::Down => current + 1,
+        KeyCode::PageUp => current.saturating_sub(page),
+        KeyCode::PageDown => current + page,
+        KeyCode::Home => 0,
+        KeyCode::End => last,
+        _ => current,
+    };
+    row.min(last)
+}
+
+/// A table cell's text with the occurrences of the search, case-insensitive,
+/// highlighted.
+fn highlight_search(text: String, search: &str) -> Line<'static> {
+    if search.is_empty() {
+        return Line::from(text);
+    }
+    // ASCII lowercasing keeps byte offsets valid in the original text
+    let lowercase = text.to_ascii_lowercase();
+    let query = search.to_ascii_lowercase();
+    let mut spans = Vec::new();
+    let mut start = 0;
+    for (offset, _) in lowercase.match_indices(&query) {
+        if offset > start {
+            spans.push(Span::raw(text[start..offset].to_string()));
+        }
+        let end = offset + query.len();
+        spans.push(Span::styled(
+            text[offset..end].to_string(),
+            SEARCH_MATCH_STYLE,
+        ));
+        start = end;
+    }
+    if start < text.len() {
+        spans.push(Span::raw(text[start..].to_string()));
+    }
+    Line::from(spans)
+}
+
+/// Background of heatmap cells, from the smallest to the largest values.
+const HEATMAP_COLORS: [Color; 5] = [
+    Color::Blue,
+    Color::Cyan,
+    Color::Yellow,
+    Color::LightRed,
+    Color::Red,
+];
+
+/// Background of a heatmap cell holding `seconds`, graded by its share of `max`.
+fn heatmap_color(seconds: f64, max: f64) -> Color {
+    let levels = HEATMAP_COLORS.len();
+    let level = if max > 0.0 {
+        ((seconds / max * levels as f64).ceil() as usize).clamp(1, levels) - 1
+    } else {
+        0
+    };
+    HEATMAP_COLORS[level]
+}
+
+/// Color of a swimlane marker.
+fn marker_color(kind: MarkerKind) -> Color {
+    match kind {
+        MarkerKind::Clog => Color::Yellow,
+        MarkerKind::Disk => Color::Magenta,
+        MarkerKind::Restart => Color::Green,
+        MarkerKind::Kill => Color::Red,
+    }
+}
+
+/// Parses a time window typed as `<from>-<to>` in seconds.
+fn parse_time_window(input: &str) -> Option<(f64, f64)> {
+    let (from, to) = input.split_once('-')?;
+    let (from, to): (f64, f64) = (from.trim().parse().ok()?, to.trim().parse().ok()?);
+    (from <= to).then_some((from, to))
+}
+
+/// Row of the last event at or before the playback cursor.
+fn cursor_row(events: &[&TimelineEvent], cursor: f64) -> Option<usize> {
+    events
+        .partition_point(|event| event.timestamp <= cursor)
+        .checked_sub(1)
+}
+
+/// The expression composed from every criterion of the filter builder and the hidden
+/// Timeline categories.
+fn all_fields_expression(criteria: &FilterCriteria) -> String {
+    criteria.expression(&[
+        Field::Type,
+        Field::Machine,
+        Field::Time,
+        Field::Severity,
+        Field::Category,
+    ])
+}
+
+/// Returns a rectangle of the given percentage size centered in `area`.
+fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
+    let vertical = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage((100 - percent_y) / 2),
+            Constraint::Percentage(percent_y),
+            Constraint::Percentage((100 - percent_y) / 2),
+        ])
+        .split(area);
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage((100 - percent_x) / 2),
+            Constraint::Percentage(percent_x),
+            Constraint::Percentage((100 - percent_x) / 2),
+        ])
+        .split(vertical[1])[1]
+}
+
+/// Counts of the major event categories shown as badges in the Overview pane, with
+/// the color of the badge when the count is not zero. Clog counts come from the
+/// summaries, so they cover sampled and dropped events too.
+fn overview_badges(report: &SimulationReport) -> [(&'static str, usize, Color); 5] {
+    let clogs = report
+        .clogging_pair_summary
+        .as_ref()
+        .map_or(0, |summary| summary.count)
+        + report
+            .clog_interface_summary
+            .values()
+            .map(|summary| summary.count)
+            .sum::<usize>();
+    [
+        (
+            "kills",
+            report.kill_machine_processes.len() + report.assassinations.len(),
+            Color::Red,
+        ),
+        ("clogs", clogs, Color::Yellow),
+        (
+            "disk",
+            report.set_disk_failures.len()
+                + report.corrupted_blocks.len()
+                + report.disk_swaps.len(),
+            Color::Magenta,
+        ),
+        ("errors", report.error_count, Color::LightRed),
+        (
+            "coord changes",
+            report.coordinators_change_count,
+            Color::Cyan,
+        ),
+    ]
+}
+
+/// Formats an optional sampling note as a suffix for a count.
+fn sampled_suffix(note: Option<String>) -> String {
+    note.map_or_else(String::new, |note| format!(" ({})", note))
+}
+
+/// Terminal the interactive TUI runs in.
+pub type TuiTerminal = Terminal<CrosstermBackend<io::Stdout>>;
+
 /// Sets up the terminal for TUI interaction.
-pub fn setup_terminal() -> io::Result<Terminal<impl Backend + Write>> {
+pub fn setup_terminal() -> io::Result<TuiTerminal> {
     crossterm::terminal::enable_raw_mode()?;
     let mut stdout = io::stdout();
     crossterm::execute!(stdout, crossterm::terminal::EnterAlternateScreen)?;
@@ -638,3 +3212,230 @@ pub fn restore_terminal(terminal: &mut Terminal<impl Backend + Write>) -> io::Re
     )?;
     terminal.show_cursor()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+    use parser::parser::{CloggingPairData, CoordinatorsChangeData, Event, KillMachineProcessData};
+    use parser::report::create_simulation_report;
+
+    /// An app over a run with a clog at 10s, a coordinator change at 20s and kills at
+    /// 30s and 40s.
+    fn app() -> App {
+        let kill = |timestamp: &str, ip: &str| {
+            Event::KillMachineProcess(KillMachineProcessData {
+                timestamp: timestamp.to_string(),
+                raw_kill_type: "6".to_string(),
+                process: format!("name: Server address: {}:1 zone: z", ip),
+                starting_class: "storage".to_string(),
+                failed: "0".to_string(),
+                excluded: "0".to_string(),
+                cleared: "0".to_string(),
+                rebooting: "0".to_string(),
+            })
+        };
+        App::new(create_simulation_report(&[
+            Event::CloggingPair(CloggingPairData {
+                timestamp: "10.0".to_string(),
+                from_id: "2.0.1.0:1".to_string(),
+                to_id: "2.0.1.1:1".to_string(),
+                seconds: "1.0".to_string(),
+            }),
+            Event::CoordinatorsChange(CoordinatorsChangeData {
+                timestamp: "20.0".to_string(),
+                new_coordinators_key: "key".to_string(),
+            }),
+            kill("30.0", "2.0.1.0"),
+            kill("40.0", "2.0.1.2"),
+        ]))
+    }
+
+    fn press(app: &mut App, code: KeyCode) {
+        app.handle_key_event(KeyEvent::new(code, KeyModifiers::NONE))
+            .unwrap();
+    }
+
+    /// Types `text` into the open prompt or input, then presses Enter.
+    fn enter(app: &mut App, text: &str) {
+        for c in text.chars() {
+            press(app, KeyCode::Char(c));
+        }
+        press(app, KeyCode::Enter);
+    }
+
+    fn visible_types(app: &App) -> Vec<&str> {
+        app.visible_timeline_events()
+            .iter()
+            .map(|event| event.event_type.as_str())
+            .collect()
+    }
+
+    fn status_bar(app: &App) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(120, 1)).unwrap();
+        terminal
+            .draw(|frame| app.render_status_bar(frame, frame.size()))
+            .unwrap();
+        snapshot::buffer_to_text(terminal.backend().buffer())
+    }
+
+    #[test]
+    fn test_category_toggles() {
+        let mut app = app();
+        press(&mut app, KeyCode::Char('1'));
+        press(&mut app, KeyCode::Char('3'));
+        assert_eq!(visible_types(&app), ["Clog Start", "Clog End"]);
+        assert!(status_bar(&app)
+            .contains(r#"[Filter: category != "kills" && category != "coordinators"]"#));
+
+        // The filter builder keeps the hidden categories
+        press(&mut app, KeyCode::Char('f'));
+        enter(&mut app, "Clog*,R*");
+        assert_eq!(visible_types(&app), ["Clog Start", "Clog End"]);
+        press(&mut app, KeyCode::Char('1'));
+        assert_eq!(
+            visible_types(&app),
+            ["Clog Start", "Clog End", "Reboot", "Reboot"]
+        );
+
+        press(&mut app, KeyCode::Char('F'));
+        assert!(app.filter.is_empty());
+        assert_eq!(visible_types(&app).len(), 5);
+    }
+
+    #[test]
+    fn test_search_stepping() {
+        let mut app = app();
+        press(&mut app, KeyCode::Char('n'));
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("Nothing searched yet, search with /")
+        );
+
+        // The clog and the first kill involve 2.0.1.0
+        press(&mut app, KeyCode::Char('/'));
+        enter(&mut app, "2.0.1.0");
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("Match 1/3 for '2.0.1.0'")
+        );
+        assert_eq!(app.timeline_state.get_mut().selected(), Some(0));
+        press(&mut app, KeyCode::Char('n'));
+        press(&mut app, KeyCode::Char('n'));
+        assert_eq!(app.timeline_state.get_mut().selected(), Some(3));
+        press(&mut app, KeyCode::Char('n'));
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("Match 1/3 for '2.0.1.0'")
+        );
+        press(&mut app, KeyCode::Char('N'));
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("Match 3/3 for '2.0.1.0'")
+        );
+        assert_eq!(app.timeline_state.get_mut().selected(), Some(3));
+
+        // Matches are those of the rows left visible by the filter
+        press(&mut app, KeyCode::Char('|'));
+        enter(&mut app, "type == Reboot");
+        press(&mut app, KeyCode::Char('n'));
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("Match 1/1 for '2.0.1.0'")
+        );
+        assert_eq!(app.timeline_state.get_mut().selected(), Some(0));
+
+        press(&mut app, KeyCode::Char('/'));
+        for _ in 0.."2.0.1.0".len() {
+            press(&mut app, KeyCode::Backspace);
+        }
+        enter(&mut app, "nowhere");
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("No match for 'nowhere'")
+        );
+    }
+
+    #[test]
+    fn test_filter_composition() {
+        let mut app = app();
+        // A Timeline filter that is not an expression searches types and details
+        press(&mut app, KeyCode::Char('|'));
+        enter(&mut app, "coord");
+        assert_eq!(visible_types(&app), ["Coord Change"]);
+
+        // The Timeline filter and the filter builder criteria must both hold
+        press(&mut app, KeyCode::Char('|'));
+        for _ in 0.."coord".len() {
+            press(&mut app, KeyCode::Backspace);
+        }
+        enter(&mut app, "time > 15");
+        assert_eq!(visible_types(&app), ["Coord Change", "Reboot", "Reboot"]);
+        press(&mut app, KeyCode::Char('f'));
+        enter(&mut app, "reboot");
+        assert_eq!(visible_types(&app), ["Reboot", "Reboot"]);
+        press(&mut app, KeyCode::Char('f'));
+        press(&mut app, KeyCode::Tab);
+        enter(&mut app, "*.2");
+        assert_eq!(visible_types(&app), ["Reboot"]);
+        assert_eq!(app.visible_timeline_events()[0].timestamp, 40.0);
+        let status = status_bar(&app);
+        assert!(status.contains(r#"[Filter: type ~ "reboot" && machine ~ "*.2"]"#));
+        assert!(status.contains("[|time > 15]"));
+
+        // An invalid input leaves the filter builder open
+        press(&mut app, KeyCode::Char('f'));
+        press(&mut app, KeyCode::Tab);
+        press(&mut app, KeyCode::Tab);
+        enter(&mut app, "soon");
+        assert!(app.filter_form.is_some());
+        press(&mut app, KeyCode::Esc);
+        assert_eq!(app.filter.machine.as_deref(), Some("*.2"));
+
+        press(&mut app, KeyCode::Char('F'));
+        assert!(app.timeline_filter.is_empty());
+        assert_eq!(visible_types(&app).len(), 5);
+    }
+
+    #[test]
+    fn test_time_window() {
+        let mut app = app();
+        // Narrowing halves the window around its center, the run ending at 40s
+        press(&mut app, KeyCode::Char('-'));
+        assert_eq!(app.time_window, Some((10.0, 30.0)));
+        assert_eq!(
+            visible_types(&app),
+            ["Clog Start", "Clog End", "Coord Change", "Reboot"]
+        );
+        assert_eq!(app.stats().kill_machine_processes.len(), 1);
+        press(&mut app, KeyCode::Char('-'));
+        assert_eq!(app.time_window, Some((15.0, 25.0)));
+        assert_eq!(visible_types(&app), ["Coord Change"]);
+
+        // Expanding back to the whole run drops the window
+        press(&mut app, KeyCode::Char('+'));
+        assert_eq!(app.time_window, Some((10.0, 30.0)));
+        press(&mut app, KeyCode::Char('+'));
+        assert_eq!(app.time_window, None);
+        assert_eq!(app.stats().kill_machine_processes.len(), 2);
+        assert_eq!(visible_types(&app).len(), 5);
+
+        press(&mut app, KeyCode::Char('w'));
+        enter(&mut app, "35-45");
+        assert_eq!(app.time_window, Some((35.0, 45.0)));
+        assert_eq!(visible_types(&app), ["Reboot"]);
+        assert!(status_bar(&app).contains("[Window: "));
+        press(&mut app, KeyCode::Char('w'));
+        for _ in 0.."35-45".len() {
+            press(&mut app, KeyCode::Backspace);
+        }
+        enter(&mut app, "later");
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("Invalid time window 'later'")
+        );
+        assert_eq!(app.time_window, Some((35.0, 45.0)));
+        press(&mut app, KeyCode::Char('W'));
+        assert_eq!(app.time_window, None);
+    }
+}
@@ -0,0 +1,337 @@
+//! Side-by-side comparison of two runs, e.g. a failing seed against a passing one of
+//! the same test.
+//!
+//! The overview and chaos summaries of both runs are shown in split columns, above a
+//! unified timeline in the style of a diff: events found in both runs at the same
+//! simulated time are listed once, the others are marked `-` (first run only) or `+`
+//! (second run only). Runs can be aligned by chaos onset so that runs with warm-up
+//! phases of different lengths line up.
+
+use super::{App, TimelineEvent};
+use crossterm::event::{self, Event as CrosstermEvent, KeyCode, KeyEventKind};
+use parser::report::SimulationReport;
+use parser::time_format::{TimeAlignment, TimeFormat};
+use ratatui::{prelude::*, widgets::*};
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::time::Duration;
+
+/// Which of the compared runs a timeline row comes from.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Side {
+    Both,
+    Left,
+    Right,
+}
+
+impl Side {
+    /// Diff-style marker and color of the side.
+    fn marker(&self) -> (&'static str, Color) {
+        match self {
+            Side::Both => (" ", Color::Gray),
+            Side::Left => ("-", Color::Red),
+            Side::Right => ("+", Color::Green),
+        }
+    }
+}
+
+/// A row of the unified timeline.
+struct DiffRow {
+    side: Side,
+    event: TimelineEvent,
+}
+
+/// Application state of the comparison mode.
+pub struct CompareApp {
+    /// The first run, shown on the left.
+    left: App,
+    /// The second run, shown on the right.
+    right: App,
+    /// Names of the compared runs, e.g. their file names.
+    labels: (String, String),
+    /// Origin of the timeline's time axis.
+    alignment: TimeAlignment,
+    /// Unified timeline of both runs, in time order.
+    rows: Vec<DiffRow>,
+    /// Index of the first visible timeline row.
+    scroll: usize,
+    should_quit: bool,
+}
+
+impl CompareApp {
+    /// Creates a comparison of two runs, labelled e.g. with their file names.
+    pub fn new(left: SimulationReport, right: SimulationReport, labels: (String, String)) -> Self {
+        let left = App::new(left);
+        let right = App::new(right);
+        let rows = diff_timelines(
+            left.timeline_events().to_vec(),
+            right.timeline_events().to_vec(),
+        );
+        Self {
+            left,
+            right,
+            labels,
+            alignment: TimeAlignment::SimTime,
+            rows,
+            scroll: 0,
+            should_quit: false,
+        }
+    }
+
+    /// Sets the initial time display format of both runs.
+    pub fn with_time_format(mut self, time_format: TimeFormat) -> Self {
+        self.left.time_format = time_format;
+        self.right.time_format = time_format;
+        self
+    }
+
+    /// Aligns the timelines of both runs, e.g. by chaos onset.
+    pub fn with_alignment(mut self, alignment: TimeAlignment) -> Self {
+        let aligned = |app: &App| {
+            let origin = alignment.origin(&app.report);
+            let mut events = app.timeline_events().to_vec();
+            for event in &mut events {
+                event.timestamp -= origin;
+            }
+            events
+        };
+        self.rows = diff_timelines(aligned(&self.left), aligned(&self.right));
+        self.alignment = alignment;
+        self
+    }
+
+    /// Formats a time of the unified timeline. Aligned times are signed offsets, as
+    /// events of the warm-up phase come before the origin.
+    fn timestamp(&self, seconds: f64) -> String {
+        match self.alignment {
+            TimeAlignment::SimTime => self.left.time().timestamp(seconds),
+            TimeAlignment::ChaosOnset => format!("{:+.6}", seconds),
+        }
+    }
+
+    /// Runs the main application loop.
+    pub fn run(&mut self, terminal: &mut Terminal<impl Backend + Write>) -> io::Result<()> {
+        while !self.should_quit {
+            terminal.draw(|frame| self.ui(frame))?;
+            if event::poll(Duration::from_millis(250))? {
+                if let CrosstermEvent::Key(key) = event::read()? {
+                    if key.kind == KeyEventKind::Press {
+                        self.handle_key(key.code);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_key(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => self.should_quit = true,
+            KeyCode::Char('t') => {
+                let time_format = self.left.time_format.next();
+                self.left.time_format = time_format;
+                self.right.time_format = time_format;
+            }
+            KeyCode::Up | KeyCode::Char('k') => self.scroll = self.scroll.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => self.scroll += 1,
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(10),
+            KeyCode::PageDown => self.scroll += 10,
+            KeyCode::Home => self.scroll = 0,
+            KeyCode::End => self.scroll = self.rows.len(),
+            _ => {}
+        }
+        self.scroll = self.scroll.min(self.rows.len().saturating_sub(1));
+    }
+
+    fn ui(&self, frame: &mut Frame) {
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Percentage(50), // Both runs' summaries
+                Constraint::Min(5),         // Unified timeline
+                Constraint::Length(1),      // Status bar
+            ])
+            .split(frame.size());
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(layout[0]);
+
+        render_run(
+            frame,
+            &self.left,
+            &format!(" - {} ", self.labels.0),
+            Color::Red,
+            columns[0],
+        );
+        render_run(
+            frame,
+            &self.right,
+            &format!(" + {} ", self.labels.1),
+            Color::Green,
+            columns[1],
+        );
+        self.render_timeline(frame, layout[1]);
+        self.render_status_bar(frame, layout[2]);
+    }
+
+    /// Renders the unified timeline of both runs.
+    fn render_timeline(&self, frame: &mut Frame, area: Rect) {
+        let count = |side: Side| self.rows.iter().filter(|row| row.side == side).count();
+        let block = Block::default()
+            .title(Span::styled(
+                format!(
+                    " Timeline ({} common, {} only in -, {} only in +) ",
+                    count(Side::Both),
+                    count(Side::Left),
+                    count(Side::Right)
+                ),
+                Style::default().fg(Color::Green),
+            ))
+            .borders(Borders::ALL);
+
+        // Absolute times are shown relative to the first run's start
+        let time_header: String = match self.alignment {
+            TimeAlignment::SimTime => self.left.time().header("Time"),
+            TimeAlignment::ChaosOnset => format!("Time (s {})", self.alignment.label()),
+        };
+        let time_header_len = time_header.len();
+        let header = Row::new(vec![
+            String::new(),
+            time_header,
+            " ".to_string(),
+            "Event".to_string(),
+            "Details".to_string(),
+        ])
+        .style(
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        );
+
+        // Borders and header take three rows
+        let visible = (area.height as usize).saturating_sub(3);
+        let scroll = self.scroll.min(self.rows.len().saturating_sub(visible));
+        let visible_rows: Vec<(&DiffRow, String)> = self
+            .rows
+            .iter()
+            .skip(scroll)
+            .take(visible)
+            .map(|row| (row, self.timestamp(row.event.timestamp)))
+            .collect();
+        let time_width = visible_rows
+            .iter()
+            .map(|(_, time_str)| time_str.len())
+            .max()
+            .unwrap_or(0)
+            .max(10)
+            .max(time_header_len);
+        let rows: Vec<Row> = visible_rows
+            .into_iter()
+            .map(|(row, time_str)| {
+                let (marker, color) = row.side.marker();
+                let text_style = if row.side == Side::Both {
+                    Style::default().fg(Color::White)
+                } else {
+                    Style::default().fg(color)
+                };
+                Row::new(vec![
+                    Cell::from(Span::styled(marker, Style::default().fg(color))),
+                    Cell::from(Span::styled(time_str, Style::default().fg(Color::Cyan))),
+                    Cell::from(Span::styled(row.event.glyph, Style::default().fg(color))),
+                    Cell::from(Span::styled(row.event.event_type.clone(), text_style)),
+                    Cell::from(Span::styled(row.event.details.clone(), text_style)),
+                ])
+            })
+            .collect();
+        let widths = [
+            Constraint::Length(1),
+            Constraint::Length(time_width as u16),
+            Constraint::Length(1),
+            Constraint::Length(15),
+            Constraint::Min(30),
+        ];
+        frame.render_widget(Table::new(rows, widths).header(header).block(block), area);
+    }
+
+    fn render_status_bar(&self, frame: &mut Frame, area: Rect) {
+        let status_text = "Scroll timeline: Up/Down PgUp/PgDn | Time format: t | Quit: q";
+        frame.render_widget(
+            Paragraph::new(status_text)
+                .style(Style::default().fg(Color::Gray))
+                .alignment(Alignment::Right),
+            area,
+        );
+    }
+}
+
+/// Renders the overview and chaos summary of one run in a column titled `title`.
+fn render_run(frame: &mut Frame, app: &App, title: &str, color: Color, area: Rect) {
+    let block = Block::default()
+        .title(Span::styled(
+            title,
+            Style::default().fg(color).add_modifier(Modifier::BOLD),
+        ))
+        .borders(Borders::ALL);
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(8), Constraint::Min(5)])
+        .split(inner);
+    app.render_overview_pane(frame, rows[0]);
+    app.render_chaos_summary_pane(frame, rows[1]);
+}
+
+/// Merges the timelines of two runs. Events with the same time, type and details in
+/// both runs are paired up; the remaining ones are attributed to their run.
+fn diff_timelines(left: Vec<TimelineEvent>, right: Vec<TimelineEvent>) -> Vec<DiffRow> {
+    // Times are rounded to microseconds, the precision of trace timestamps
+    let key = |event: &TimelineEvent| {
+        (
+            (event.timestamp * 1e6).round() as i64,
+            event.event_type.clone(),
+            event.details.clone(),
+        )
+    };
+    let mut unmatched: HashMap<_, usize> = HashMap::new();
+    for event in &right {
+        *unmatched.entry(key(event)).or_insert(0) += 1;
+    }
+
+    let mut rows: Vec<DiffRow> = Vec::with_capacity(left.len() + right.len());
+    let mut matched: HashMap<_, usize> = HashMap::new();
+    for event in left {
+        let event_key = key(&event);
+        let side = match unmatched.get_mut(&event_key) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                *matched.entry(event_key).or_insert(0) += 1;
+                Side::Both
+            }
+            _ => Side::Left,
+        };
+        rows.push(DiffRow { side, event });
+    }
+    for event in right {
+        // Right events paired with a left one are already listed
+        if let Some(count) = matched.get_mut(&key(&event)).filter(|count| **count > 0) {
+            *count -= 1;
+            continue;
+        }
+        rows.push(DiffRow {
+            side: Side::Right,
+            event,
+        });
+    }
+
+    // Stable sort keeps left rows before right rows at the same time
+    rows.sort_by(|a, b| {
+        a.event
+            .timestamp
+            .partial_cmp(&b.event.timestamp)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    rows
+}
@@ -0,0 +1,204 @@
+//! Analyses of a finished report, run as a list of pluggable passes.
+//!
+//! Each `AnalysisPass` inspects a `SimulationReport` and returns an `AnalysisSection`
+//! of findings and an optional table. Sections are stored in the report, so every
+//! output format renders them, including those of passes defined in other crates and
+//! registered in `ParseOptions::analyses`.
+
+pub mod availability;
+pub mod churn;
+pub mod trigger;
+
+use crate::report::SimulationReport;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::Arc;
+
+/// An analysis run over every report.
+pub trait AnalysisPass: Send + Sync {
+    /// Unique name of the pass, e.g. "likely-trigger".
+    fn name(&self) -> &'static str;
+
+    /// Analyzes the report. Returns `None` when there is nothing to report.
+    fn run(&self, report: &SimulationReport) -> Option<AnalysisSection>;
+}
+
+/// Importance of a finding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum FindingLevel {
+    Info,
+    Warning,
+    Error,
+}
+
+/// A one-line result of an analysis.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Finding {
+    pub level: FindingLevel,
+    /// Simulated time the finding refers to, if any.
+    pub time: Option<f64>,
+    pub message: String,
+}
+
+/// A typed table cell, so that times follow the chosen time format and numbers stay
+/// numbers in spreadsheets.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum AnalysisValue {
+    Text(String),
+    Number(f64),
+    /// Simulated time, in seconds.
+    Time(f64),
+}
+
+/// Tabular details of an analysis.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AnalysisTable {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<AnalysisValue>>,
+}
+
+/// The output of an analysis pass.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnalysisSection {
+    /// Name of the pass that produced the section.
+    pub pass: String,
+    pub title: String,
+    /// Findings, in the order the pass reported them.
+    pub findings: Vec<Finding>,
+    pub table: Option<AnalysisTable>,
+}
+
+impl AnalysisSection {
+    /// Creates an empty section.
+    pub fn new(pass: &str, title: impl Into<String>) -> Self {
+        Self {
+            pass: pass.to_string(),
+            title: title.into(),
+            findings: Vec::new(),
+            table: None,
+        }
+    }
+
+    /// Adds a finding.
+    pub fn push_finding(
+        &mut self,
+        level: FindingLevel,
+        time: Option<f64>,
+        message: impl Into<String>,
+    ) {
+        self.findings.push(Finding {
+            level,
+            time,
+            message: message.into(),
+        });
+    }
+
+    /// Level of the most important finding, `None` for sections without findings.
+    pub fn level(&self) -> Option<FindingLevel> {
+        self.findings.iter().map(|finding| finding.level).max()
+    }
+}
+
+/// The ordered list of passes run over a report.
+#[derive(Clone)]
+pub struct AnalysisRegistry {
+    passes: Vec<Arc<dyn AnalysisPass>>,
+}
+
+impl Default for AnalysisRegistry {
+    /// The built-in passes with their default settings.
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        registry.register(trigger::LikelyTriggerPass::default());
+        registry.register(churn::CoordinatorChurnPass::default());
+        registry.register(availability::AvailabilityPass);
+        registry
+    }
+}
+
+impl fmt::Debug for AnalysisRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.names()).finish()
+    }
+}
+
+impl AnalysisRegistry {
+    /// A registry without any pass.
+    pub fn empty() -> Self {
+        Self { passes: Vec::new() }
+    }
+
+    /// Adds a pass after the registered ones. A pass with the same name is replaced in
+    /// place, e.g. to change the settings of a built-in pass.
+    pub fn register(&mut self, pass: impl AnalysisPass + 'static) {
+        let pass: Arc<dyn AnalysisPass> = Arc::new(pass);
+        match self.passes.iter_mut().find(|p| p.name() == pass.name()) {
+            Some(existing) => *existing = pass,
+            None => self.passes.push(pass),
+        }
+    }
+
+    /// Names of the registered passes, in run order.
+    pub fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.passes.iter().map(|pass| pass.name())
+    }
+
+    /// Runs every pass over the report.
+    pub fn run(&self, report: &SimulationReport) -> Vec<AnalysisSection> {
+        self.passes
+            .iter()
+            .filter_map(|pass| {
+                let _span = tracing::info_span!("analysis", pass = pass.name()).entered();
+                pass.run(report)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::create_simulation_report;
+
+    struct EventCountPass;
+
+    impl AnalysisPass for EventCountPass {
+        fn name(&self) -> &'static str {
+            "event-count"
+        }
+
+        fn run(&self, report: &SimulationReport) -> Option<AnalysisSection> {
+            let mut section = AnalysisSection::new(self.name(), "Event Count");
+            section.push_finding(
+                FindingLevel::Info,
+                None,
+                format!("{} kills", report.kill_machine_processes.len()),
+            );
+            Some(section)
+        }
+    }
+
+    #[test]
+    fn test_registry_runs_custom_passes() {
+        let mut registry = AnalysisRegistry::default();
+        registry.register(EventCountPass);
+        registry.register(trigger::LikelyTriggerPass {
+            window_seconds: 1.0,
+        });
+        assert_eq!(
+            registry.names().collect::<Vec<_>>(),
+            [
+                "likely-trigger",
+                "coordinator-churn",
+                "availability",
+                "event-count"
+            ]
+        );
+
+        let report = create_simulation_report(&[]);
+        let sections = registry.run(&report);
+        assert_eq!(sections.len(), 1);
+        assert_eq!(sections[0].findings[0].message, "0 kills");
+        assert_eq!(sections[0].level(), Some(FindingLevel::Info));
+    }
+}
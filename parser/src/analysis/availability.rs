@@ -0,0 +1,143 @@
+//! Availability of each machine over the run, derived from its downtime intervals.
+
+use super::{AnalysisPass, AnalysisSection, AnalysisTable, AnalysisValue, FindingLevel};
+use crate::lifecycle::{downtime_intervals, DowntimeInterval};
+use crate::report::SimulationReport;
+use std::collections::BTreeMap;
+
+/// Summarizes the downtime of every killed machine, and flags processes that never
+/// came back.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AvailabilityPass;
+
+impl AnalysisPass for AvailabilityPass {
+    fn name(&self) -> &'static str {
+        "availability"
+    }
+
+    fn run(&self, report: &SimulationReport) -> Option<AnalysisSection> {
+        let intervals = downtime_intervals(report);
+        if intervals.is_empty() {
+            return None;
+        }
+        let run_seconds: f64 = report
+            .elapsed_time
+            .as_deref()
+            .and_then(|t| t.parse().ok())
+            .unwrap_or(0.0);
+
+        let mut by_machine: BTreeMap<&str, Vec<&DowntimeInterval>> = BTreeMap::new();
+        for interval in &intervals {
+            by_machine
+                .entry(interval.machine.as_str())
+                .or_default()
+                .push(interval);
+        }
+
+        let mut section = AnalysisSection::new(self.name(), "Availability");
+        let never_restarted: Vec<&str> = intervals
+            .iter()
+            .filter(|interval| !interval.restarted)
+            .map(|interval| interval.address.as_str())
+            .collect();
+        if !never_restarted.is_empty() {
+            section.push_finding(
+                FindingLevel::Warning,
+                None,
+                format!(
+                    "{} killed process(es) never restarted: {}",
+                    never_restarted.len(),
+                    never_restarted.join(", ")
+                ),
+            );
+        }
+
+        let rows = by_machine
+            .into_iter()
+            .map(|(machine, intervals)| {
+                let downtime = union_length(&intervals);
+                let availability = if run_seconds > 0.0 {
+                    100.0 * (1.0 - downtime / run_seconds)
+                } else {
+                    100.0
+                };
+                vec![
+                    AnalysisValue::Text(machine.to_string()),
+                    AnalysisValue::Text(intervals[0].dc_id.clone().unwrap_or_default()),
+                    AnalysisValue::Number(intervals.len() as f64),
+                    AnalysisValue::Number((downtime * 1e6).round() / 1e6),
+                    AnalysisValue::Number((availability * 100.0).round() / 100.0),
+                ]
+            })
+            .collect();
+        section.table = Some(AnalysisTable {
+            headers: [
+                "Machine",
+                "DC ID",
+                "Kills",
+                "Downtime (s)",
+                "Availability (%)",
+            ]
+            .map(String::from)
+            .to_vec(),
+            rows,
+        });
+        Some(section)
+    }
+}
+
+/// Total time covered by intervals sorted by start, counting overlaps once, e.g. when
+/// two processes of a machine are down at the same time.
+fn union_length(intervals: &[&DowntimeInterval]) -> f64 {
+    let mut total = 0.0;
+    let mut covered_until = f64::NEG_INFINITY;
+    for interval in intervals {
+        let start = interval.start.max(covered_until);
+        if interval.end > start {
+            total += interval.end - start;
+            covered_until = interval.end;
+        }
+    }
+    total
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{ElapsedTimeData, Event, KillMachineProcessData};
+    use crate::report::create_simulation_report;
+
+    #[test]
+    fn test_availability() {
+        let kill = |timestamp: &str, address: &str| {
+            Event::KillMachineProcess(KillMachineProcessData {
+                timestamp: timestamp.to_string(),
+                raw_kill_type: "6".to_string(),
+                process: format!("name: Server address: {} zone: z", address),
+                starting_class: "storage".to_string(),
+                failed: "0".to_string(),
+                excluded: "0".to_string(),
+                cleared: "0".to_string(),
+                rebooting: "0".to_string(),
+            })
+        };
+        let events = vec![
+            kill("50", "2.0.1.0:1"),
+            kill("75", "2.0.1.0:2"),
+            Event::ElapsedTime(ElapsedTimeData {
+                timestamp: "100".to_string(),
+                sim_time: "100".to_string(),
+                real_time: "1".to_string(),
+            }),
+        ];
+        let report = create_simulation_report(&events);
+
+        let section = AvailabilityPass.run(&report).unwrap();
+        assert_eq!(section.level(), Some(FindingLevel::Warning));
+        let rows = &section.table.unwrap().rows;
+        assert_eq!(rows.len(), 1);
+        // Both processes stay down until the end, overlapping from 75 on
+        assert_eq!(rows[0][3], AnalysisValue::Number(50.0));
+        assert_eq!(rows[0][4], AnalysisValue::Number(50.0));
+    }
+}
@@ -0,0 +1,167 @@
+//! Detection of abnormally frequent coordinator changes.
+//!
+//! Repeated coordinator elections usually mean the chaos scheduler found a quorum
+//! weakness, so bursts of changes are flagged even in passing runs.
+
+use super::{AnalysisPass, AnalysisSection, FindingLevel};
+use crate::report::SimulationReport;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ChurnThresholdError {
+    #[error("Invalid coordinator churn threshold '{0}', expected <changes>/<seconds>")]
+    InvalidSpec(String),
+}
+
+/// Coordinator churn is flagged when more than `max_changes` coordinator changes
+/// happen within `window_seconds`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChurnThreshold {
+    pub max_changes: usize,
+    pub window_seconds: f64,
+}
+
+impl Default for ChurnThreshold {
+    fn default() -> Self {
+        Self {
+            max_changes: 3,
+            window_seconds: 30.0,
+        }
+    }
+}
+
+impl FromStr for ChurnThreshold {
+    type Err = ChurnThresholdError;
+
+    /// Parses `<changes>/<seconds>`, e.g. "3/30".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ChurnThresholdError::InvalidSpec(s.to_string());
+        let (changes, seconds) = s.split_once('/').ok_or_else(invalid)?;
+        let max_changes = changes.trim().parse().map_err(|_| invalid())?;
+        let window_seconds: f64 = seconds.trim().parse().map_err(|_| invalid())?;
+        if window_seconds <= 0.0 {
+            return Err(invalid());
+        }
+        Ok(Self {
+            max_changes,
+            window_seconds,
+        })
+    }
+}
+
+/// A burst of coordinator changes exceeding the `ChurnThreshold`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CoordinatorChurn {
+    /// Time of the first change of the burst.
+    pub start: f64,
+    /// Time of the last change of the burst.
+    pub end: f64,
+    /// Number of coordinator changes within the burst.
+    pub changes: usize,
+}
+
+/// Finds bursts of coordinator changes exceeding `threshold`. Overlapping windows
+/// are merged, so a long stretch of churn is reported once.
+pub fn coordinator_churn(
+    report: &SimulationReport,
+    threshold: ChurnThreshold,
+) -> Vec<CoordinatorChurn> {
+    // coordinators_changes is sorted by timestamp
+    let times: Vec<f64> = report
+        .coordinators_changes
+        .iter()
+        .filter_map(|change| change.timestamp.parse().ok())
+        .collect();
+
+    let mut bursts: Vec<(usize, usize)> = Vec::new();
+    let mut end = 0;
+    for start in 0..times.len() {
+        end = end.max(start);
+        while end + 1 < times.len() && times[end + 1] - times[start] <= threshold.window_seconds {
+            end += 1;
+        }
+        if end - start < threshold.max_changes {
+            continue;
+        }
+        match bursts.last_mut() {
+            Some((_, last_end)) if start <= *last_end => *last_end = end,
+            _ => bursts.push((start, end)),
+        }
+    }
+
+    bursts
+        .into_iter()
+        .map(|(start, end)| CoordinatorChurn {
+            start: times[start],
+            end: times[end],
+            changes: end - start + 1,
+        })
+        .collect()
+}
+
+/// Flags bursts of coordinator changes.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CoordinatorChurnPass {
+    pub threshold: ChurnThreshold,
+}
+
+impl AnalysisPass for CoordinatorChurnPass {
+    fn name(&self) -> &'static str {
+        "coordinator-churn"
+    }
+
+    fn run(&self, report: &SimulationReport) -> Option<AnalysisSection> {
+        let bursts = coordinator_churn(report, self.threshold);
+        if bursts.is_empty() {
+            return None;
+        }
+        let mut section = AnalysisSection::new(self.name(), "Coordinator Churn");
+        for burst in bursts {
+            section.push_finding(
+                FindingLevel::Warning,
+                Some(burst.start),
+                format!(
+                    "ANOMALY: {} coordinator changes within {:.3}s, repeated elections suggest a quorum weakness",
+                    burst.changes,
+                    burst.end - burst.start
+                ),
+            );
+        }
+        Some(section)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{CoordinatorsChangeData, Event};
+    use crate::report::create_simulation_report;
+
+    #[test]
+    fn test_coordinator_churn() {
+        let events: Vec<Event> = [10, 12, 15, 18, 20, 100, 150, 151]
+            .iter()
+            .map(|t| {
+                Event::CoordinatorsChange(CoordinatorsChangeData {
+                    timestamp: t.to_string(),
+                    new_coordinators_key: "key".to_string(),
+                })
+            })
+            .collect();
+        let report = create_simulation_report(&events);
+
+        let threshold: ChurnThreshold = "3/10".parse().unwrap();
+        assert_eq!(
+            coordinator_churn(&report, threshold),
+            [CoordinatorChurn {
+                start: 10.0,
+                end: 20.0,
+                changes: 5,
+            }]
+        );
+        assert!(coordinator_churn(&report, "5/10".parse().unwrap()).is_empty());
+        assert!("3".parse::<ChurnThreshold>().is_err());
+    }
+}
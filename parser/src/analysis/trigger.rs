@@ -0,0 +1,277 @@
+//! Root-cause hint for failed runs: the chaos events shortly preceding the first error.
+
+use super::{AnalysisPass, AnalysisSection, AnalysisTable, AnalysisValue, FindingLevel};
+use crate::parser::{ip_from_address, SevereEventData, SEVERITY_ERROR};
+use crate::report::SimulationReport;
+use serde::{Deserialize, Serialize};
+
+/// Default length of the window searched for chaos before the first error, in
+/// simulated seconds.
+pub const DEFAULT_TRIGGER_WINDOW_SECONDS: f64 = 10.0;
+
+/// Maximum number of chaos events reported as likely trigger.
+pub const MAX_TRIGGER_CANDIDATES: usize = 5;
+
+/// Chaos events shortly preceding the first error of a failed run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LikelyTrigger {
+    /// The first Severity 40+ event of the run.
+    pub error: SevereEventData,
+    /// Length of the window searched for chaos before the error, in seconds.
+    pub window_seconds: f64,
+    /// Chaos events within the window, the closest to the error first. Empty when the
+    /// error was not preceded by any chaos.
+    pub candidates: Vec<TriggerCandidate>,
+}
+
+/// A chaos event that may have triggered an error.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TriggerCandidate {
+    pub timestamp: f64,
+    /// Time between the chaos event and the error, in seconds.
+    pub seconds_before: f64,
+    /// Event variant name, see `Event::event_type`.
+    pub event_type: String,
+    /// One-line description, e.g. "Reboot 2.0.1.0" for a kill.
+    pub details: String,
+}
+
+/// Finds the chaos events within `window_seconds` before the first Severity 40+
+/// event. Returns `None` for runs without errors.
+pub fn likely_trigger(report: &SimulationReport, window_seconds: f64) -> Option<LikelyTrigger> {
+    // severe_events is sorted and always keeps the first event of each type
+    let error = report
+        .severe_events
+        .iter()
+        .find(|event| event.severity() >= SEVERITY_ERROR)?;
+    let error_time: f64 = error.timestamp.parse().unwrap_or(0.0);
+
+    let mut candidates: Vec<TriggerCandidate> = chaos_events(report)
+        .into_iter()
+        .filter_map(|(timestamp, event_type, details)| {
+            let timestamp: f64 = timestamp.parse().ok()?;
+            let seconds_before = error_time - timestamp;
+            (0.0..=window_seconds)
+                .contains(&seconds_before)
+                .then(|| TriggerCandidate {
+                    timestamp,
+                    seconds_before,
+                    event_type: event_type.to_string(),
+                    details,
+                })
+        })
+        .collect();
+    candidates.sort_by(|a, b| {
+        a.seconds_before
+            .partial_cmp(&b.seconds_before)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    candidates.truncate(MAX_TRIGGER_CANDIDATES);
+
+    Some(LikelyTrigger {
+        error: error.clone(),
+        window_seconds,
+        candidates,
+    })
+}
+
+/// Reports the first error of failed runs and its likely trigger.
+#[derive(Debug, Clone, Copy)]
+pub struct LikelyTriggerPass {
+    /// Window searched for chaos before the first error, in simulated seconds.
+    pub window_seconds: f64,
+}
+
+impl Default for LikelyTriggerPass {
+    fn default() -> Self {
+        Self {
+            window_seconds: DEFAULT_TRIGGER_WINDOW_SECONDS,
+        }
+    }
+}
+
+impl AnalysisPass for LikelyTriggerPass {
+    fn name(&self) -> &'static str {
+        "likely-trigger"
+    }
+
+    fn run(&self, report: &SimulationReport) -> Option<AnalysisSection> {
+        let trigger = likely_trigger(report, self.window_seconds)?;
+        let error = &trigger.error;
+        let mut section = AnalysisSection::new(self.name(), "Likely Trigger");
+        section.push_finding(
+            FindingLevel::Error,
+            error.timestamp.parse().ok(),
+            format!(
+                "FAILED: first error {} (Severity {}){}{}",
+                error.event_type,
+                error.severity,
+                error
+                    .machine
+                    .as_deref()
+                    .map(|machine| format!(" on {}", machine))
+                    .unwrap_or_default(),
+                error
+                    .error
+                    .as_deref()
+                    .map(|e| format!(": {}", e))
+                    .unwrap_or_default()
+            ),
+        );
+        if trigger.candidates.is_empty() {
+            section.push_finding(
+                FindingLevel::Warning,
+                None,
+                format!(
+                    "No chaos event in the {}s before the error, the failure is likely not chaos-induced",
+                    trigger.window_seconds
+                ),
+            );
+            return Some(section);
+        }
+
+        section.push_finding(
+            FindingLevel::Info,
+            None,
+            format!(
+                "Likely trigger: chaos within {}s before the error, closest first",
+                trigger.window_seconds
+            ),
+        );
+        section.table = Some(AnalysisTable {
+            headers: ["Time", "Before Error (s)", "Event Type", "Details"]
+                .map(String::from)
+                .to_vec(),
+            rows: trigger
+                .candidates
+                .into_iter()
+                .map(|candidate| {
+                    vec![
+                        AnalysisValue::Time(candidate.timestamp),
+                        AnalysisValue::Number((candidate.seconds_before * 1e6).round() / 1e6),
+                        AnalysisValue::Text(candidate.event_type),
+                        AnalysisValue::Text(candidate.details),
+                    ]
+                })
+                .collect(),
+        });
+        Some(section)
+    }
+}
+
+/// Lists the chaos events of a report (see `Event::is_chaos`) as (timestamp, event
+/// type, details).
+fn chaos_events(report: &SimulationReport) -> Vec<(&str, &'static str, String)> {
+    let mut events = Vec::new();
+    for pair in &report.clogging_pairs {
+        let details = format!("{} -> {} for {}s", pair.from_id, pair.to_id, pair.seconds);
+        events.push((pair.timestamp.as_str(), "CloggingPair", details));
+    }
+    for interface in &report.clog_interfaces {
+        let details = format!(
+            "{} ({}) for {}s",
+            interface.ip, interface.queue, interface.delay
+        );
+        events.push((interface.timestamp.as_str(), "ClogInterface", details));
+    }
+    for kill in &report.kill_machine_processes {
+        let kill_type = kill.kill_type();
+        let details = format!("{:?} {}", kill_type, kill.ip_address().unwrap_or("?"));
+        events.push((kill.timestamp.as_str(), "KillMachineProcess", details));
+    }
+    for failure in &report.set_disk_failures {
+        let details = format!(
+            "{} stall {}s throttle {}s",
+            ip_from_address(&failure.machine),
+            failure.stall_period,
+            failure.throttle_period
+        );
+        events.push((failure.timestamp.as_str(), "SetDiskFailure", details));
+    }
+    for block in &report.corrupted_blocks {
+        let details = format!("{} {}", ip_from_address(&block.machine), block.filename);
+        events.push((block.time.as_str(), "CorruptedBlock", details));
+    }
+    for swap in &report.disk_swaps {
+        let details = format!("IPs: {}", swap.machine_ips);
+        events.push((swap.timestamp.as_str(), "DiskSwap", details));
+    }
+    for assassination in &report.assassinations {
+        let action = if assassination.is_reboot() {
+            "Reboot"
+        } else {
+            "Kill"
+        };
+        let details = format!("{} zone {}", action, assassination.zone_id);
+        events.push((assassination.timestamp.as_str(), "Assassination", details));
+    }
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{CloggingPairData, Event, KillMachineProcessData};
+    use crate::report::create_simulation_report;
+
+    fn severe(timestamp: &str, severity: &str) -> Event {
+        Event::SevereEvent(SevereEventData {
+            timestamp: timestamp.to_string(),
+            severity: severity.to_string(),
+            event_type: "StorageServerFailed".to_string(),
+            machine: Some("2.0.1.0:1".to_string()),
+            error: Some("io_error".to_string()),
+        })
+    }
+
+    #[test]
+    fn test_likely_trigger() {
+        let events = vec![
+            Event::CloggingPair(CloggingPairData {
+                timestamp: "5".to_string(),
+                from_id: "2.0.1.0".to_string(),
+                to_id: "2.0.1.1".to_string(),
+                seconds: "1".to_string(),
+            }),
+            Event::CloggingPair(CloggingPairData {
+                timestamp: "18".to_string(),
+                from_id: "2.0.1.1".to_string(),
+                to_id: "2.0.1.2".to_string(),
+                seconds: "1".to_string(),
+            }),
+            Event::KillMachineProcess(KillMachineProcessData {
+                timestamp: "19".to_string(),
+                raw_kill_type: "6".to_string(),
+                process: "name: Server address: 2.0.1.0:1 zone: z".to_string(),
+                starting_class: "storage".to_string(),
+                failed: "0".to_string(),
+                excluded: "0".to_string(),
+                cleared: "0".to_string(),
+                rebooting: "0".to_string(),
+            }),
+            severe("15", "30"),
+            severe("20", "40"),
+            severe("25", "40"),
+        ];
+        let report = create_simulation_report(&events);
+
+        let trigger = likely_trigger(&report, 10.0).unwrap();
+        assert_eq!(trigger.error.timestamp, "20");
+        let details: Vec<&str> = trigger
+            .candidates
+            .iter()
+            .map(|c| c.details.as_str())
+            .collect();
+        assert_eq!(details, ["Reboot 2.0.1.0", "2.0.1.1 -> 2.0.1.2 for 1s"]);
+        assert_eq!(trigger.candidates[0].seconds_before, 1.0);
+
+        let section = &report.analyses[0];
+        assert_eq!(section.pass, "likely-trigger");
+        assert_eq!(section.level(), Some(FindingLevel::Error));
+        assert_eq!(section.table.as_ref().unwrap().rows.len(), 2);
+
+        assert!(likely_trigger(&report, 0.5).unwrap().candidates.is_empty());
+        let passed = create_simulation_report(&[severe("15", "30")]);
+        assert_eq!(likely_trigger(&passed, 10.0), None);
+    }
+}
@@ -0,0 +1,182 @@
+//! Free-text annotations of a trace, kept in a sidecar file for collaborative triage.
+//!
+//! Annotations of `trace.json` are stored in `trace.json.annotations.json`, keyed by
+//! a hash of the trace content. A trace regenerated at the same path therefore starts
+//! without annotations, while the notes on the previous version are kept.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Errors that can occur when loading or saving annotations.
+#[derive(Error, Debug)]
+pub enum AnnotationError {
+    #[error("I/O error on annotations: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid annotations file: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// A note attached to a point of a trace.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Annotation {
+    /// Simulated time the note refers to, in seconds.
+    pub timestamp: f64,
+    /// Type of the annotated event at that time (see `Event::event_type`), if the note
+    /// is about a specific event.
+    #[serde(default)]
+    pub event_type: Option<String>,
+    pub text: String,
+    #[serde(default)]
+    pub author: Option<String>,
+}
+
+/// On-disk format of the sidecar file.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SidecarFile {
+    /// Annotations by trace content hash, sorted by timestamp.
+    traces: BTreeMap<String, Vec<Annotation>>,
+}
+
+/// The annotations of a trace, loaded from and saved to its sidecar file.
+#[derive(Debug)]
+pub struct AnnotationStore {
+    path: PathBuf,
+    trace_hash: String,
+    sidecar: SidecarFile,
+}
+
+impl AnnotationStore {
+    /// Loads the annotations of a trace. A missing sidecar file means no annotations.
+    pub fn open<P: AsRef<Path>>(trace_path: P) -> Result<Self, AnnotationError> {
+        let trace_path = trace_path.as_ref();
+        let path = sidecar_path(trace_path);
+        let sidecar = if path.exists() {
+            serde_json::from_reader(BufReader::new(File::open(&path)?))?
+        } else {
+            SidecarFile::default()
+        };
+        Ok(Self {
+            path,
+            trace_hash: trace_hash(trace_path)?,
+            sidecar,
+        })
+    }
+
+    /// Path of the sidecar file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The annotations of the trace, sorted by timestamp.
+    pub fn annotations(&self) -> &[Annotation] {
+        self.sidecar
+            .traces
+            .get(&self.trace_hash)
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Adds an annotation, after those with the same timestamp.
+    pub fn add(&mut self, annotation: Annotation) {
+        let annotations = self
+            .sidecar
+            .traces
+            .entry(self.trace_hash.clone())
+            .or_default();
+        let index = annotations.partition_point(|a| a.timestamp <= annotation.timestamp);
+        annotations.insert(index, annotation);
+    }
+
+    /// Removes the annotation at `index` of `annotations()`.
+    pub fn remove(&mut self, index: usize) -> Option<Annotation> {
+        let annotations = self.sidecar.traces.get_mut(&self.trace_hash)?;
+        let removed = (index < annotations.len()).then(|| annotations.remove(index));
+        if annotations.is_empty() {
+            self.sidecar.traces.remove(&self.trace_hash);
+        }
+        removed
+    }
+
+    /// Writes the sidecar file as pretty-printed JSON.
+    pub fn save(&self) -> Result<(), AnnotationError> {
+        let mut writer = BufWriter::new(File::create(&self.path)?);
+        serde_json::to_writer_pretty(&mut writer, &self.sidecar)?;
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Returns the sidecar file of a trace, e.g. "trace.json.annotations.json".
+pub fn sidecar_path(trace_path: &Path) -> PathBuf {
+    let mut path = OsString::from(trace_path.as_os_str());
+    path.push(".annotations.json");
+    PathBuf::from(path)
+}
+
+/// Hashes the content of a trace with 64-bit FNV-1a, which is stable across Rust
+/// versions unlike `DefaultHasher`.
+pub fn trace_hash(trace_path: &Path) -> std::io::Result<String> {
+    const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+    let mut reader = BufReader::new(File::open(trace_path)?);
+    let mut buffer = [0u8; 64 * 1024];
+    let mut hash = FNV_OFFSET_BASIS;
+    loop {
+        let read = reader.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        for byte in &buffer[..read] {
+            hash ^= u64::from(*byte);
+            hash = hash.wrapping_mul(FNV_PRIME);
+        }
+    }
+    Ok(format!("{:016x}", hash))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn note(timestamp: f64, text: &str) -> Annotation {
+        Annotation {
+            timestamp,
+            event_type: None,
+            text: text.to_string(),
+            author: None,
+        }
+    }
+
+    #[test]
+    fn test_annotations_are_keyed_by_trace_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let trace = dir.path().join("trace.json");
+        std::fs::write(&trace, "{\"Type\": \"ProgramStart\"}\n").unwrap();
+
+        let mut store = AnnotationStore::open(&trace).unwrap();
+        assert!(store.annotations().is_empty());
+        store.add(note(12.0, "second"));
+        store.add(note(3.5, "first"));
+        store.save().unwrap();
+        assert_eq!(store.path(), dir.path().join("trace.json.annotations.json"));
+
+        let store = AnnotationStore::open(&trace).unwrap();
+        let texts: Vec<&str> = store
+            .annotations()
+            .iter()
+            .map(|a| a.text.as_str())
+            .collect();
+        assert_eq!(texts, ["first", "second"]);
+
+        // A regenerated trace at the same path does not inherit the notes
+        std::fs::write(&trace, "{\"Type\": \"ProgramStart\", \"Time\": \"0\"}\n").unwrap();
+        let mut store = AnnotationStore::open(&trace).unwrap();
+        assert!(store.annotations().is_empty());
+        assert_eq!(store.remove(0), None);
+    }
+}
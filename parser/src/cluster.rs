@@ -0,0 +1,115 @@
+//! Resolution between the different ways traces identify a machine.
+//!
+//! Trace lines refer to machines by machine ID, zone ID, IP or full address
+//! ("2.0.1.0:1:tls"). `ClusterIndex` maps all of them to the machine's details so
+//! filters can accept whichever form a user copied.
+
+use crate::parser::ip_from_address;
+use crate::report::{MachineInfo, SimulationReport};
+use std::collections::HashMap;
+
+/// Constant-time lookups of machines by any identifier.
+#[derive(Debug, Clone, Default)]
+pub struct ClusterIndex<'a> {
+    by_machine_id: HashMap<&'a str, &'a MachineInfo>,
+    by_ip: HashMap<&'a str, &'a MachineInfo>,
+    by_zone_id: HashMap<&'a str, Vec<&'a MachineInfo>>,
+}
+
+impl<'a> ClusterIndex<'a> {
+    /// Indexes the machines of a report, auxiliary processes included.
+    pub fn new(report: &'a SimulationReport) -> Self {
+        let mut index = ClusterIndex::default();
+        let machines = report
+            .machine_details
+            .values()
+            .chain(&report.auxiliary_processes);
+        for machine in machines {
+            if let Some(machine_id) = &machine.machine_id {
+                index.by_machine_id.insert(machine_id, machine);
+            }
+            for ip in machine.ips() {
+                index.by_ip.insert(ip, machine);
+            }
+            if let Some(zone_id) = &machine.zone_id {
+                index.by_zone_id.entry(zone_id).or_default().push(machine);
+            }
+        }
+        index
+    }
+
+    /// Looks a machine up by machine ID.
+    pub fn by_machine_id(&self, machine_id: &str) -> Option<&'a MachineInfo> {
+        self.by_machine_id.get(machine_id).copied()
+    }
+
+    /// Looks a machine up by IP, or by address such as "2.0.1.0:1:tls".
+    pub fn by_address(&self, address: &str) -> Option<&'a MachineInfo> {
+        self.by_ip.get(ip_from_address(address)).copied()
+    }
+
+    /// Returns the DC of the machine at an IP or address.
+    pub fn dc_id(&self, address: &str) -> Option<&'a str> {
+        self.by_address(address)?.dc_id.as_deref()
+    }
+
+    /// Returns the machines of a zone.
+    pub fn by_zone_id(&self, zone_id: &str) -> &[&'a MachineInfo] {
+        self.by_zone_id.get(zone_id).map_or(&[], Vec::as_slice)
+    }
+
+    /// Resolves any identifier form to the machines it designates: a single machine
+    /// for a machine ID, IP or address, and every machine of the zone for a zone ID.
+    pub fn resolve(&self, identifier: &str) -> Vec<&'a MachineInfo> {
+        if let Some(machine) = self
+            .by_machine_id(identifier)
+            .or_else(|| self.by_address(identifier))
+        {
+            return vec![machine];
+        }
+        self.by_zone_id(identifier).to_vec()
+    }
+}
+
+impl MachineInfo {
+    /// The machine's IP addresses, `MachineIPs` being a space or comma separated list.
+    pub fn ips(&self) -> impl Iterator<Item = &str> {
+        self.ip_address
+            .as_deref()
+            .unwrap_or("")
+            .split([' ', ','])
+            .filter(|ip| !ip.is_empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_log_file;
+    use crate::report::create_simulation_report;
+
+    #[test]
+    fn test_resolve_any_identifier() {
+        let events = parse_log_file("logs/combined_trace.0.0.0.0.24.1745498878.p7Loj0.json")
+            .expect("Failed to parse log file");
+        let report = create_simulation_report(&events);
+        let index = ClusterIndex::new(&report);
+
+        let (machine_id, machine) = report.machine_details.iter().next().unwrap();
+        let ip = machine.ip_address.as_deref().unwrap();
+        let zone_id = machine.zone_id.as_deref().unwrap();
+
+        assert_eq!(index.resolve(machine_id), vec![machine]);
+        assert_eq!(index.resolve(ip), vec![machine]);
+        assert_eq!(index.resolve(&format!("{}:1:tls", ip)), vec![machine]);
+        assert!(index.resolve(zone_id).contains(&machine));
+        assert!(index.resolve("unknown").is_empty());
+
+        let http_server = &report.auxiliary_processes[0];
+        assert!(http_server.is_auxiliary());
+        assert_eq!(
+            index.by_address(http_server.ip_address.as_deref().unwrap()),
+            Some(http_server)
+        );
+    }
+}
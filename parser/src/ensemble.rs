@@ -0,0 +1,978 @@
+//! Ensemble-level analysis: per-run metrics for a directory of simulation traces,
+//! statistical comparison between two ensembles and failure-signature clustering.
+
+use crate::parser::{parse_event_from_node, KillType, ParsingError};
+use crate::report::SimulationReport;
+use crate::report_cache::load_or_create_report;
+use crate::time_format::TimeAlignment;
+use colored::Colorize;
+use comfy_table::{presets::UTF8_FULL, Cell, Color, ContentArrangement, Table};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonNode;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+/// |t| above which a difference between two ensembles is flagged as significant.
+/// Roughly a 95% two-sided confidence level for ensembles of more than a handful of runs.
+pub const SIGNIFICANCE_THRESHOLD: f64 = 2.0;
+
+/// Headline metrics of a single simulation run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunMetrics {
+    /// Trace file the run was parsed from.
+    pub path: PathBuf,
+    /// The random seed of the run, if reported.
+    pub seed: Option<String>,
+    /// Simulated time reached by the run, in seconds.
+    pub sim_time: f64,
+    /// Number of KillMachineProcess events.
+    pub kills: usize,
+    /// Number of CloggingPair events.
+    pub clogging_pairs: usize,
+    /// Number of ClogInterface events.
+    pub clog_interfaces: usize,
+    /// Number of SetDiskFailure and CorruptedBlock events.
+    pub disk_faults: usize,
+    /// Number of coordinator changes.
+    pub coordinator_changes: usize,
+    /// Number of worker recruitment failures.
+    pub recruitment_failures: usize,
+    /// Storage servers added to or removed from the running set.
+    pub storage_server_churn: usize,
+    /// Mean duration in seconds of the recoveries that completed, `None` when none did.
+    pub mean_recovery_duration: Option<f64>,
+    /// Number of Severity 40+ events.
+    pub errors: usize,
+}
+
+impl RunMetrics {
+    /// Extracts the headline metrics from a run's report.
+    pub fn from_report(path: impl Into<PathBuf>, report: &SimulationReport) -> Self {
+        RunMetrics {
+            path: path.into(),
+            seed: report.seed.clone(),
+            sim_time: report
+                .elapsed_time
+                .as_deref()
+                .and_then(|t| t.parse().ok())
+                .unwrap_or(0.0),
+            kills: report.kill_machine_processes.len(),
+            clogging_pairs: report.clogging_pairs.len(),
+            clog_interfaces: report.clog_interfaces.len(),
+            disk_faults: report.set_disk_failures.len() + report.corrupted_blocks.len(),
+            coordinator_changes: report.coordinators_change_count,
+            recruitment_failures: report.recruitment_failures.len(),
+            storage_server_churn: report.storage_servers_added.len()
+                + report.storage_servers_removed.len(),
+            mean_recovery_duration: mean_recovery_duration(report),
+            errors: report.error_count,
+        }
+    }
+
+    /// Severity 40+ events per simulated second, 0.0 for a run without simulated time.
+    pub fn error_rate(&self) -> f64 {
+        if self.sim_time > 0.0 {
+            self.errors as f64 / self.sim_time
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Mean duration in seconds of the recoveries of a run that completed, `None` when no
+/// recovery completed rather than a misleading 0.
+fn mean_recovery_duration(report: &SimulationReport) -> Option<f64> {
+    let durations: Vec<f64> = report
+        .recoveries
+        .iter()
+        .filter_map(|recovery| recovery.duration)
+        .collect();
+    (!durations.is_empty()).then(|| mean_and_variance(&durations).0)
+}
+
+/// A metric compared across ensembles.
+struct Metric {
+    name: &'static str,
+    /// Whether an increase indicates a regression rather than merely different chaos.
+    higher_is_worse: bool,
+    /// Value of the metric for a run, `None` for runs it is undefined for, which are
+    /// left out of the sample.
+    value: fn(&RunMetrics) -> Option<f64>,
+}
+
+const METRICS: [Metric; 10] = [
+    Metric {
+        name: "Simulated Time (s)",
+        higher_is_worse: false,
+        value: |run| Some(run.sim_time),
+    },
+    Metric {
+        name: "Kills",
+        higher_is_worse: false,
+        value: |run| Some(run.kills as f64),
+    },
+    Metric {
+        name: "Clogging Pairs",
+        higher_is_worse: false,
+        value: |run| Some(run.clogging_pairs as f64),
+    },
+    Metric {
+        name: "Clogged Interfaces",
+        higher_is_worse: false,
+        value: |run| Some(run.clog_interfaces as f64),
+    },
+    Metric {
+        name: "Disk Faults",
+        higher_is_worse: false,
+        value: |run| Some(run.disk_faults as f64),
+    },
+    Metric {
+        name: "Coordinator Changes",
+        higher_is_worse: false,
+        value: |run| Some(run.coordinator_changes as f64),
+    },
+    Metric {
+        name: "Recruitment Failures",
+        higher_is_worse: true,
+        value: |run| Some(run.recruitment_failures as f64),
+    },
+    Metric {
+        name: "Storage Server Churn",
+        higher_is_worse: true,
+        value: |run| Some(run.storage_server_churn as f64),
+    },
+    Metric {
+        name: "Recovery Time (s)",
+        higher_is_worse: true,
+        value: |run| run.mean_recovery_duration,
+    },
+    Metric {
+        name: "Error Rate (/s)",
+        higher_is_worse: true,
+        value: |run| Some(run.error_rate()),
+    },
+];
+
+/// Per-run metrics for every trace of an ensemble.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EnsembleReport {
+    /// Runs sorted by trace path.
+    pub runs: Vec<RunMetrics>,
+}
+
+impl EnsembleReport {
+    /// Parses every `.json` trace directly inside `dir`, treating each file as one run.
+    /// Reports are cached next to the traces, see `report_cache`.
+    pub fn from_dir<P: AsRef<Path>>(dir: P) -> Result<Self, ParsingError> {
+        let runs = trace_files(dir)?
+            .into_iter()
+            .map(|path| {
+                let report = load_or_create_report(&path)?;
+                Ok(RunMetrics::from_report(path, &report))
+            })
+            .collect::<Result<_, ParsingError>>()?;
+        Ok(EnsembleReport { runs })
+    }
+}
+
+/// Lists the `.json` and `.xml` trace files directly inside `dir`, sorted by path.
+pub(crate) fn trace_files<P: AsRef<Path>>(dir: P) -> Result<Vec<PathBuf>, ParsingError> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<Result<_, _>>()?;
+    paths.retain(|path| is_trace_file(path));
+    paths.sort();
+    Ok(paths)
+}
+
+/// Returns whether `path` is a JSON or XML trace file.
+pub(crate) fn is_trace_file(path: &Path) -> bool {
+    path.is_file()
+        && path
+            .extension()
+            .is_some_and(|ext| ext == "json" || ext == "xml")
+}
+
+/// Comparison of a single metric between two ensembles.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricComparison {
+    /// Name of the metric.
+    pub metric: String,
+    /// Mean over the old ensemble.
+    pub old_mean: f64,
+    /// Sample standard deviation over the old ensemble.
+    pub old_std_dev: f64,
+    /// Mean over the new ensemble.
+    pub new_mean: f64,
+    /// Sample standard deviation over the new ensemble.
+    pub new_std_dev: f64,
+    /// Welch's t statistic of the difference (new - old), if computable.
+    pub t_statistic: Option<f64>,
+    /// Whether |t| exceeds `SIGNIFICANCE_THRESHOLD`.
+    pub significant: bool,
+    /// Whether the change is significant and in the direction indicating a regression.
+    pub regression: bool,
+}
+
+/// Statistical comparison between an old and a new ensemble.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EnsembleComparison {
+    /// Number of runs in the old ensemble.
+    pub old_runs: usize,
+    /// Number of runs in the new ensemble.
+    pub new_runs: usize,
+    /// One entry per compared metric.
+    pub metrics: Vec<MetricComparison>,
+}
+
+impl EnsembleComparison {
+    /// Compares every metric of `new` against `old` using Welch's t-test.
+    pub fn new(old: &EnsembleReport, new: &EnsembleReport) -> Self {
+        let metrics = METRICS
+            .iter()
+            .map(|metric| {
+                let old_values: Vec<f64> = old.runs.iter().filter_map(metric.value).collect();
+                let new_values: Vec<f64> = new.runs.iter().filter_map(metric.value).collect();
+                let (old_mean, old_var) = mean_and_variance(&old_values);
+                let (new_mean, new_var) = mean_and_variance(&new_values);
+
+                let t_statistic = welch_t(
+                    (new_mean, new_var, new_values.len()),
+                    (old_mean, old_var, old_values.len()),
+                );
+                let significant = t_statistic.is_some_and(|t| t.abs() >= SIGNIFICANCE_THRESHOLD);
+                MetricComparison {
+                    metric: metric.name.to_string(),
+                    old_mean,
+                    old_std_dev: old_var.sqrt(),
+                    new_mean,
+                    new_std_dev: new_var.sqrt(),
+                    t_statistic,
+                    significant,
+                    regression: significant && metric.higher_is_worse && new_mean > old_mean,
+                }
+            })
+            .collect();
+
+        EnsembleComparison {
+            old_runs: old.runs.len(),
+            new_runs: new.runs.len(),
+            metrics,
+        }
+    }
+
+    /// Metrics whose change is a significant regression.
+    pub fn regressions(&self) -> impl Iterator<Item = &MetricComparison> {
+        self.metrics.iter().filter(|m| m.regression)
+    }
+}
+
+/// Returns the mean and sample variance, both 0.0 when undefined.
+fn mean_and_variance(values: &[f64]) -> (f64, f64) {
+    if values.is_empty() {
+        return (0.0, 0.0);
+    }
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    if values.len() < 2 {
+        return (mean, 0.0);
+    }
+    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    (mean, variance)
+}
+
+/// Welch's t statistic for samples given as (mean, variance, size).
+///
+/// Returns `None` with fewer than two runs on either side. When both samples have no
+/// variance, any difference in means is treated as infinitely significant.
+fn welch_t(a: (f64, f64, usize), b: (f64, f64, usize)) -> Option<f64> {
+    let ((mean_a, var_a, n_a), (mean_b, var_b, n_b)) = (a, b);
+    if n_a < 2 || n_b < 2 {
+        return None;
+    }
+    let standard_error = (var_a / n_a as f64 + var_b / n_b as f64).sqrt();
+    let diff = mean_a - mean_b;
+    if standard_error == 0.0 {
+        return Some(if diff == 0.0 {
+            0.0
+        } else {
+            f64::INFINITY.copysign(diff)
+        });
+    }
+    Some(diff / standard_error)
+}
+
+impl fmt::Display for EnsembleComparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", "Ensemble Comparison".bold().underline())?;
+        writeln!(f)?;
+        writeln!(f, "Old ensemble: {} run(s)", self.old_runs)?;
+        writeln!(f, "New ensemble: {} run(s)", self.new_runs)?;
+        writeln!(f)?;
+
+        let regressions = self.regressions().count();
+        if regressions > 0 {
+            writeln!(
+                f,
+                "{}",
+                format!(
+                    "WARNING: {} significant regression(s) detected",
+                    regressions
+                )
+                .red()
+                .bold()
+            )?;
+            writeln!(f)?;
+        }
+
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_header(vec![
+                "Metric",
+                "Old Mean",
+                "Old Std Dev",
+                "New Mean",
+                "New Std Dev",
+                "Change",
+                "t",
+                "Verdict",
+            ]);
+        for metric in &self.metrics {
+            let change = if metric.old_mean == 0.0 {
+                "N/A".to_string()
+            } else {
+                format!(
+                    "{:+.1}%",
+                    (metric.new_mean - metric.old_mean) / metric.old_mean * 100.0
+                )
+            };
+            let verdict = if metric.regression {
+                Cell::new("REGRESSION").fg(Color::Red)
+            } else if metric.significant {
+                Cell::new("changed").fg(Color::Yellow)
+            } else {
+                Cell::new("-")
+            };
+            table.add_row(vec![
+                Cell::new(&metric.metric),
+                Cell::new(format!("{:.2}", metric.old_mean)),
+                Cell::new(format!("{:.2}", metric.old_std_dev)),
+                Cell::new(format!("{:.2}", metric.new_mean)),
+                Cell::new(format!("{:.2}", metric.new_std_dev)),
+                Cell::new(change),
+                Cell::new(
+                    metric
+                        .t_statistic
+                        .map_or("N/A".to_string(), |t| format!("{:.2}", t)),
+                ),
+                verdict,
+            ]);
+        }
+        writeln!(f, "{}", table)?;
+        if self.old_runs < 2 || self.new_runs < 2 {
+            writeln!(
+                f,
+                "{}",
+                "Note: at least two runs per ensemble are needed for significance testing."
+                    .yellow()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Severity of trace events reporting a failure of the run.
+const SEVERITY_ERROR: &str = "40";
+
+/// What a run's first error looked like, coarse enough for identical failures to match.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct FailureSignature {
+    /// Type of the first Severity 40 event.
+    pub event_type: String,
+    /// Roles of the process that logged it (e.g. "SS,TL"), or "unknown".
+    pub roles: String,
+    /// Start of the time bucket the event fell into, in seconds on the time axis of
+    /// the clustering's `TimeAlignment`. Runs failing before their first chaos event
+    /// fall into the first bucket when aligned by chaos onset.
+    pub time_bucket: u64,
+}
+
+/// Extracts the failure signature of a trace, or `None` if it has no Severity 40 event.
+///
+/// Severity 40 events can have any Type, so this scans raw JSON lines rather than
+/// parsed `Event`s.
+pub fn failure_signature<P: AsRef<Path>>(
+    path: P,
+    bucket_seconds: u64,
+    alignment: TimeAlignment,
+) -> Result<Option<FailureSignature>, ParsingError> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut chaos_onset: Option<f64> = None;
+    for line in reader.lines() {
+        let line = line?;
+        let Ok(node) = serde_json::from_str::<JsonNode>(&line) else {
+            continue;
+        };
+        if node.get("Severity").and_then(|v| v.as_str()) != Some(SEVERITY_ERROR) {
+            if alignment == TimeAlignment::ChaosOnset && chaos_onset.is_none() {
+                chaos_onset = parse_event_from_node(&node)
+                    .filter(|event| event.is_chaos())
+                    .map(|event| event.timestamp());
+            }
+            continue;
+        }
+        let field = |name: &str| node.get(name).and_then(|v| v.as_str());
+        let time: f64 = field("Time").and_then(|t| t.parse().ok()).unwrap_or(0.0);
+        let time = (time - chaos_onset.unwrap_or(0.0)).max(0.0);
+        let bucket_seconds = bucket_seconds.max(1);
+        return Ok(Some(FailureSignature {
+            event_type: field("Type").unwrap_or("Unknown").to_string(),
+            roles: field("Roles").unwrap_or("unknown").to_string(),
+            time_bucket: (time as u64 / bucket_seconds) * bucket_seconds,
+        }));
+    }
+    Ok(None)
+}
+
+/// Runs sharing the same failure signature.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FailureCluster {
+    /// The shared signature.
+    pub signature: FailureSignature,
+    /// Trace files of the runs in this cluster, sorted by path.
+    pub runs: Vec<PathBuf>,
+}
+
+/// Failing runs of an ensemble grouped by failure signature.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FailureClustering {
+    /// Width of the time buckets used in signatures, in seconds.
+    pub bucket_seconds: u64,
+    /// Origin of the time buckets.
+    pub alignment: TimeAlignment,
+    /// Clusters sorted by decreasing size.
+    pub clusters: Vec<FailureCluster>,
+    /// Runs in which no Severity 40 event was found.
+    pub runs_without_failure: Vec<PathBuf>,
+}
+
+impl FailureClustering {
+    /// Extracts and clusters the failure signature of every `.json` trace in `dir`.
+    pub fn from_dir<P: AsRef<Path>>(
+        dir: P,
+        bucket_seconds: u64,
+        alignment: TimeAlignment,
+    ) -> Result<Self, ParsingError> {
+        let signatures = trace_files(dir)?
+            .into_iter()
+            .map(|path| {
+                let signature = failure_signature(&path, bucket_seconds, alignment)?;
+                Ok((path, signature))
+            })
+            .collect::<Result<Vec<_>, ParsingError>>()?;
+        Ok(Self::from_signatures(signatures, bucket_seconds, alignment))
+    }
+
+    /// Groups already extracted signatures.
+    pub fn from_signatures(
+        signatures: impl IntoIterator<Item = (PathBuf, Option<FailureSignature>)>,
+        bucket_seconds: u64,
+        alignment: TimeAlignment,
+    ) -> Self {
+        let mut by_signature: HashMap<FailureSignature, Vec<PathBuf>> = HashMap::new();
+        let mut runs_without_failure = Vec::new();
+        for (path, signature) in signatures {
+            match signature {
+                Some(signature) => by_signature.entry(signature).or_default().push(path),
+                None => runs_without_failure.push(path),
+            }
+        }
+
+        let mut clusters: Vec<FailureCluster> = by_signature
+            .into_iter()
+            .map(|(signature, mut runs)| {
+                runs.sort();
+                FailureCluster { signature, runs }
+            })
+            .collect();
+        clusters.sort_by(|a, b| {
+            b.runs
+                .len()
+                .cmp(&a.runs.len())
+                .then_with(|| a.signature.cmp(&b.signature))
+        });
+        runs_without_failure.sort();
+
+        FailureClustering {
+            bucket_seconds,
+            alignment,
+            clusters,
+            runs_without_failure,
+        }
+    }
+}
+
+impl fmt::Display for FailureClustering {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", "Failure Signatures".bold().underline())?;
+        writeln!(f)?;
+        let failing: usize = self.clusters.iter().map(|c| c.runs.len()).sum();
+        writeln!(
+            f,
+            "{} failing run(s) in {} cluster(s), {} run(s) without a Severity 40 event",
+            failing,
+            self.clusters.len(),
+            self.runs_without_failure.len()
+        )?;
+        if self.clusters.is_empty() {
+            return Ok(());
+        }
+        writeln!(f)?;
+
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_header(vec![
+                "Runs",
+                "Event Type",
+                "Roles",
+                match self.alignment {
+                    TimeAlignment::SimTime => "Time Bucket (s)",
+                    TimeAlignment::ChaosOnset => "Time Bucket (s after chaos onset)",
+                },
+                "Example Runs",
+            ]);
+        for cluster in &self.clusters {
+            let examples: Vec<String> = cluster
+                .runs
+                .iter()
+                .take(3)
+                .map(|path| {
+                    path.file_name().map_or_else(
+                        || path.display().to_string(),
+                        |n| n.to_string_lossy().into_owned(),
+                    )
+                })
+                .collect();
+            let bucket = cluster.signature.time_bucket;
+            table.add_row(vec![
+                Cell::new(cluster.runs.len()),
+                Cell::new(&cluster.signature.event_type),
+                Cell::new(&cluster.signature.roles),
+                Cell::new(format!("{}-{}", bucket, bucket + self.bucket_seconds)),
+                Cell::new(examples.join("\n")),
+            ]);
+        }
+        writeln!(f, "{}", table)
+    }
+}
+
+/// Queues the simulator can clog with ClogInterface.
+const CLOG_QUEUES: [&str; 3] = ["All", "Send", "Receive"];
+
+/// Disk fault events the simulator can inject.
+const DISK_FAULT_KINDS: [&str; 3] = ["SetDiskFailure", "CorruptedBlock", "DiskSwap"];
+
+/// Maximum number of never-activated buggify points listed by the Display impl.
+const MAX_LISTED_BUGGIFY_GAPS: usize = 20;
+
+/// Which chaos an ensemble exercised. Every count is a number of runs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChaosCoverage {
+    /// Number of runs aggregated.
+    pub runs: usize,
+    /// Runs with at least one kill of each type, including never-seen injectable types.
+    pub kill_types: BTreeMap<KillType, usize>,
+    /// Runs clogging each interface queue.
+    pub clog_queues: BTreeMap<String, usize>,
+    /// Runs with at least one disk fault of each kind.
+    pub disk_faults: BTreeMap<String, usize>,
+    /// Runs activating each buggify point ("file:line") that was evaluated at least once.
+    pub buggify_points: BTreeMap<String, usize>,
+}
+
+impl Default for ChaosCoverage {
+    fn default() -> Self {
+        ChaosCoverage {
+            runs: 0,
+            kill_types: KillType::INJECTABLE.into_iter().map(|k| (k, 0)).collect(),
+            clog_queues: CLOG_QUEUES.iter().map(|q| (q.to_string(), 0)).collect(),
+            disk_faults: DISK_FAULT_KINDS
+                .iter()
+                .map(|d| (d.to_string(), 0))
+                .collect(),
+            buggify_points: BTreeMap::new(),
+        }
+    }
+}
+
+impl ChaosCoverage {
+    /// Aggregates the coverage of every `.json` trace in `dir`.
+    pub fn from_dir<P: AsRef<Path>>(dir: P) -> Result<Self, ParsingError> {
+        let mut coverage = ChaosCoverage::default();
+        for path in trace_files(dir)? {
+            coverage.add_run(&load_or_create_report(&path)?);
+        }
+        Ok(coverage)
+    }
+
+    /// Folds one run into the coverage.
+    pub fn add_run(&mut self, report: &SimulationReport) {
+        self.runs += 1;
+
+        let kill_types: BTreeSet<KillType> = report
+            .kill_machine_processes
+            .iter()
+            .map(|kill| kill.kill_type())
+            .collect();
+        for kill_type in kill_types {
+            *self.kill_types.entry(kill_type).or_insert(0) += 1;
+        }
+
+        let queues: BTreeSet<&str> = report
+            .clog_interfaces
+            .iter()
+            .map(|interface| interface.queue.as_str())
+            .collect();
+        for queue in queues {
+            *self.clog_queues.entry(queue.to_string()).or_insert(0) += 1;
+        }
+
+        let disk_faults = [
+            ("SetDiskFailure", report.set_disk_failures.len()),
+            ("CorruptedBlock", report.corrupted_blocks.len()),
+            ("DiskSwap", report.disk_swaps.len()),
+        ];
+        for (kind, count) in disk_faults {
+            if count > 0 {
+                *self.disk_faults.entry(kind.to_string()).or_insert(0) += 1;
+            }
+        }
+
+        let mut buggify_points: BTreeMap<String, bool> = BTreeMap::new();
+        for section in &report.buggify_sections {
+            *buggify_points.entry(section.location()).or_insert(false) |= section.is_activated();
+        }
+        for (location, activated) in buggify_points {
+            *self.buggify_points.entry(location).or_insert(0) += usize::from(activated);
+        }
+    }
+
+    /// Describes every kind of chaos that was never exercised across the ensemble.
+    pub fn gaps(&self) -> Vec<String> {
+        let never =
+            |kind: &dyn fmt::Display| format!("{} never triggered in {} run(s)", kind, self.runs);
+        let mut gaps: Vec<String> = Vec::new();
+        gaps.extend(
+            self.kill_types
+                .iter()
+                .filter(|(_, runs)| **runs == 0)
+                .map(|(kill_type, _)| never(&format!("{:?}", kill_type))),
+        );
+        gaps.extend(
+            self.clog_queues
+                .iter()
+                .filter(|(_, runs)| **runs == 0)
+                .map(|(queue, _)| never(&format!("ClogInterface on queue {}", queue))),
+        );
+        gaps.extend(
+            self.disk_faults
+                .iter()
+                .filter(|(_, runs)| **runs == 0)
+                .map(|(kind, _)| never(kind)),
+        );
+        gaps.extend(
+            self.buggify_points
+                .iter()
+                .filter(|(_, runs)| **runs == 0)
+                .map(|(location, _)| never(&format!("Buggify point {}", location))),
+        );
+        gaps
+    }
+}
+
+impl fmt::Display for ChaosCoverage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", "Chaos Coverage".bold().underline())?;
+        writeln!(f)?;
+        writeln!(f, "Runs analyzed: {}", self.runs)?;
+        writeln!(f)?;
+
+        let percent = |runs: usize| {
+            if self.runs == 0 {
+                "N/A".to_string()
+            } else {
+                format!("{:.1}%", runs as f64 / self.runs as f64 * 100.0)
+            }
+        };
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_header(vec!["Category", "Kind", "Runs", "Coverage"]);
+        let rows = self
+            .kill_types
+            .iter()
+            .map(|(kill_type, runs)| ("Kill", format!("{:?}", kill_type), *runs))
+            .chain(
+                self.clog_queues
+                    .iter()
+                    .map(|(queue, runs)| ("Clog Queue", queue.clone(), *runs)),
+            )
+            .chain(
+                self.disk_faults
+                    .iter()
+                    .map(|(kind, runs)| ("Disk Fault", kind.clone(), *runs)),
+            );
+        for (category, kind, runs) in rows {
+            let runs_cell = if runs == 0 {
+                Cell::new(runs).fg(Color::Red)
+            } else {
+                Cell::new(runs)
+            };
+            table.add_row(vec![
+                Cell::new(category),
+                Cell::new(kind),
+                runs_cell,
+                Cell::new(percent(runs)),
+            ]);
+        }
+        writeln!(f, "{}", table)?;
+
+        let activated = self
+            .buggify_points
+            .values()
+            .filter(|runs| **runs > 0)
+            .count();
+        writeln!(
+            f,
+            "Buggify points activated in at least one run: {} / {}",
+            activated,
+            self.buggify_points.len()
+        )?;
+        writeln!(f)?;
+
+        let (buggify_gaps, chaos_gaps): (Vec<String>, Vec<String>) = self
+            .gaps()
+            .into_iter()
+            .partition(|gap| gap.starts_with("Buggify point"));
+        if chaos_gaps.is_empty() && buggify_gaps.is_empty() {
+            return writeln!(f, "{}", "No coverage gaps found.".green());
+        }
+        writeln!(f, "{}", "Coverage Gaps".red().bold())?;
+        for gap in &chaos_gaps {
+            writeln!(f, "  - {}", gap)?;
+        }
+        for gap in buggify_gaps.iter().take(MAX_LISTED_BUGGIFY_GAPS) {
+            writeln!(f, "  - {}", gap)?;
+        }
+        if buggify_gaps.len() > MAX_LISTED_BUGGIFY_GAPS {
+            writeln!(
+                f,
+                "  ... and {} more never-activated buggify points",
+                buggify_gaps.len() - MAX_LISTED_BUGGIFY_GAPS
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_log_file;
+    use crate::report::create_simulation_report;
+
+    fn run(kills: usize, recruitment_failures: usize) -> RunMetrics {
+        RunMetrics {
+            path: PathBuf::from("trace.json"),
+            seed: None,
+            sim_time: 100.0,
+            kills,
+            clogging_pairs: 10,
+            clog_interfaces: 10,
+            disk_faults: 0,
+            coordinator_changes: 1,
+            recruitment_failures,
+            storage_server_churn: 0,
+            mean_recovery_duration: Some(2.0),
+            errors: 0,
+        }
+    }
+
+    #[test]
+    fn test_comparison_flags_regressions() {
+        let old = EnsembleReport {
+            runs: vec![run(5, 0), run(6, 1), run(7, 0), run(6, 0)],
+        };
+        let new = EnsembleReport {
+            runs: vec![run(6, 8), run(5, 9), run(7, 10), run(6, 9)],
+        };
+
+        let comparison = EnsembleComparison::new(&old, &new);
+
+        let by_name = |name: &str| {
+            comparison
+                .metrics
+                .iter()
+                .find(|m| m.metric == name)
+                .unwrap()
+        };
+        assert!(!by_name("Kills").significant);
+        assert!(by_name("Recruitment Failures").regression);
+        assert_eq!(by_name("Simulated Time (s)").t_statistic, Some(0.0));
+        assert_eq!(comparison.regressions().count(), 1);
+    }
+
+    #[test]
+    fn test_comparison_flags_recovery_and_error_regressions() {
+        let with = |mean_recovery_duration: f64, errors: usize| RunMetrics {
+            mean_recovery_duration: Some(mean_recovery_duration),
+            errors,
+            ..run(6, 0)
+        };
+        let old = EnsembleReport {
+            runs: vec![with(2.0, 0), with(2.5, 1), with(1.5, 0), with(2.0, 0)],
+        };
+        let new = EnsembleReport {
+            runs: vec![with(6.0, 0), with(7.0, 1), with(6.5, 0), with(5.5, 0)],
+        };
+        let comparison = EnsembleComparison::new(&old, &new);
+        let regressions: Vec<&str> = comparison
+            .regressions()
+            .map(|m| m.metric.as_str())
+            .collect();
+        assert_eq!(regressions, ["Recovery Time (s)"]);
+
+        let new = EnsembleReport {
+            runs: vec![with(2.0, 5), with(2.5, 6), with(1.5, 4), with(2.0, 5)],
+        };
+        let comparison = EnsembleComparison::new(&old, &new);
+        let error_rate = comparison
+            .metrics
+            .iter()
+            .find(|m| m.metric == "Error Rate (/s)")
+            .unwrap();
+        assert!((error_rate.new_mean - 0.05).abs() < 1e-9);
+        let regressions: Vec<&str> = comparison
+            .regressions()
+            .map(|m| m.metric.as_str())
+            .collect();
+        assert_eq!(regressions, ["Error Rate (/s)"]);
+
+        // Runs whose recoveries never completed are left out instead of counting as 0 s
+        let stuck = RunMetrics {
+            mean_recovery_duration: None,
+            ..run(6, 0)
+        };
+        let new = EnsembleReport {
+            runs: vec![with(6.0, 0), with(7.0, 0), stuck.clone(), stuck],
+        };
+        let comparison = EnsembleComparison::new(&old, &new);
+        let recovery = comparison
+            .metrics
+            .iter()
+            .find(|m| m.metric == "Recovery Time (s)")
+            .unwrap();
+        assert_eq!(recovery.new_mean, 6.5);
+        assert!(recovery.regression);
+    }
+
+    #[test]
+    fn test_welch_t() {
+        assert_eq!(welch_t((1.0, 0.0, 1), (0.0, 0.0, 5)), None);
+        let t = welch_t((3.0, 1.0, 4), (2.0, 1.0, 4)).unwrap();
+        assert!((t - 1.0 / 0.5f64.sqrt()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_failure_signature_and_clustering() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("failure.json");
+        fs::write(
+            &path,
+            concat!(
+                r#"{"Severity": "30", "Time": "10.0", "Type": "SlowTask"}"#,
+                "\n",
+                r#"{"Severity": "10", "Time": "70.0", "Type": "CloggingPair", "From": "2.0.1.0", "To": "2.0.1.1", "Seconds": "1.0"}"#,
+                "\n",
+                r#"{"Severity": "40", "Time": "125.5", "Type": "StorageServerFailed", "Roles": "SS,TL"}"#,
+                "\n",
+                r#"{"Severity": "40", "Time": "130.0", "Type": "Other"}"#,
+                "\n",
+            ),
+        )
+        .unwrap();
+        let signature = failure_signature(&path, 60, TimeAlignment::SimTime)
+            .unwrap()
+            .unwrap();
+        let aligned = failure_signature(&path, 60, TimeAlignment::ChaosOnset)
+            .unwrap()
+            .unwrap();
+        assert_eq!(aligned.time_bucket, 0);
+
+        assert_eq!(signature.event_type, "StorageServerFailed");
+        assert_eq!(signature.roles, "SS,TL");
+        assert_eq!(signature.time_bucket, 120);
+
+        let other = FailureSignature {
+            event_type: "Other".to_string(),
+            roles: "unknown".to_string(),
+            time_bucket: 0,
+        };
+        let clustering = FailureClustering::from_signatures(
+            vec![
+                (PathBuf::from("c.json"), Some(signature.clone())),
+                (PathBuf::from("b.json"), Some(other)),
+                (PathBuf::from("a.json"), Some(signature.clone())),
+                (PathBuf::from("d.json"), None),
+            ],
+            60,
+            TimeAlignment::SimTime,
+        );
+        assert_eq!(clustering.clusters.len(), 2);
+        assert_eq!(clustering.clusters[0].signature, signature);
+        assert_eq!(
+            clustering.clusters[0].runs,
+            vec![PathBuf::from("a.json"), PathBuf::from("c.json")]
+        );
+        assert_eq!(
+            clustering.runs_without_failure,
+            vec![PathBuf::from("d.json")]
+        );
+    }
+
+    #[test]
+    fn test_chaos_coverage_gaps() {
+        let events = parse_log_file("logs/combined_trace.0.0.0.0.24.1745498878.p7Loj0.json")
+            .expect("Failed to parse log file");
+        let report = create_simulation_report(&events);
+        let mut coverage = ChaosCoverage::default();
+        coverage.add_run(&report);
+        coverage.add_run(&report);
+
+        assert_eq!(coverage.runs, 2);
+        assert_eq!(coverage.clog_queues["Send"], 2);
+        assert_eq!(coverage.disk_faults["DiskSwap"], 0);
+        assert!(!coverage.buggify_points.is_empty());
+
+        let gaps = coverage.gaps();
+        assert!(gaps.contains(&"DiskSwap never triggered in 2 run(s)".to_string()));
+        assert!(!gaps.iter().any(|gap| gap.contains("ClogInterface")));
+    }
+
+    #[test]
+    fn test_ensemble_from_dir() {
+        let ensemble = EnsembleReport::from_dir("logs").expect("Failed to parse ensemble");
+
+        assert_eq!(ensemble.runs.len(), 1);
+        assert_eq!(ensemble.runs[0].seed.as_deref(), Some("292006968"));
+        assert_eq!(ensemble.runs[0].kills, 7);
+    }
+}
@@ -0,0 +1,226 @@
+//! Time-indexed collection of parsed events.
+//!
+//! Interactive features look up what happened around a point in simulated time, or
+//! to a single machine, on every keypress, so `Events` keeps indexes sorted by
+//! timestamp and answers those lookups with binary searches instead of scanning the
+//! whole trace.
+
+use crate::parser::{ip_from_address, Event};
+use std::collections::HashMap;
+use std::ops::Index;
+
+/// Parsed events in trace order, with by-timestamp and by-machine indexes.
+#[derive(Debug, Clone, Default)]
+pub struct Events {
+    events: Vec<Event>,
+    /// (timestamp, position in `events`), sorted by timestamp then position.
+    by_time: Vec<(f64, usize)>,
+    /// The same entries for the events of each machine IP, see `Event::machine_ips`.
+    by_machine: HashMap<String, Vec<(f64, usize)>>,
+}
+
+impl Events {
+    pub fn new(events: Vec<Event>) -> Self {
+        let mut by_time: Vec<(f64, usize)> = events
+            .iter()
+            .enumerate()
+            .map(|(position, event)| (event.timestamp(), position))
+            .collect();
+        // The sort is stable, so events sharing a timestamp keep their trace order
+        by_time.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let mut by_machine: HashMap<String, Vec<(f64, usize)>> = HashMap::new();
+        for (timestamp, position) in &by_time {
+            for ip in events[*position].machine_ips() {
+                by_machine
+                    .entry(ip.to_string())
+                    .or_default()
+                    .push((*timestamp, *position));
+            }
+        }
+        Events {
+            events,
+            by_time,
+            by_machine,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Iterates over the events in trace order.
+    pub fn iter(&self) -> std::slice::Iter<'_, Event> {
+        self.events.iter()
+    }
+
+    /// The events in trace order.
+    pub fn as_slice(&self) -> &[Event] {
+        &self.events
+    }
+
+    /// Appends an event, e.g. one read from a trace that is still being written.
+    pub fn push(&mut self, event: Event) {
+        let timestamp = event.timestamp();
+        let entry = (timestamp, self.events.len());
+        let at = self.by_time.partition_point(|(t, _)| *t <= timestamp);
+        self.by_time.insert(at, entry);
+        for ip in event.machine_ips() {
+            let entries = self.by_machine.entry(ip.to_string()).or_default();
+            let at = entries.partition_point(|(t, _)| *t <= timestamp);
+            entries.insert(at, entry);
+        }
+        self.events.push(event);
+    }
+
+    /// Returns the event closest in time to `t`, the earliest one on ties.
+    pub fn nearest(&self, t: f64) -> Option<&Event> {
+        let after = self
+            .by_time
+            .partition_point(|(timestamp, _)| *timestamp < t);
+        // The closest timestamp is either the last one before `t` or the first one after
+        let before = after.checked_sub(1).map(|i| self.by_time[i].0);
+        let closest = match (before, self.by_time.get(after).map(|(t, _)| *t)) {
+            (Some(before), Some(after)) if after - t < t - before => after,
+            (Some(before), _) => before,
+            (None, Some(after)) => after,
+            (None, None) => return None,
+        };
+        let first = self
+            .by_time
+            .partition_point(|(timestamp, _)| *timestamp < closest);
+        Some(&self.events[self.by_time[first].1])
+    }
+
+    /// Iterates, in time order, over the events with a timestamp in `[from, to]`.
+    pub fn between(&self, from: f64, to: f64) -> impl Iterator<Item = &Event> + '_ {
+        let start = self.by_time.partition_point(|(t, _)| *t < from);
+        let end = self.by_time.partition_point(|(t, _)| *t <= to).max(start);
+        self.by_time[start..end]
+            .iter()
+            .map(|(_, position)| &self.events[*position])
+    }
+
+    /// Iterates, in time order, over the events of the machine at `ip`, an IP or an
+    /// address, with a timestamp in `[from, to]`.
+    pub fn for_machine_between(
+        &self,
+        ip: &str,
+        from: f64,
+        to: f64,
+    ) -> impl Iterator<Item = &Event> + '_ {
+        let entries = self
+            .by_machine
+            .get(ip_from_address(ip))
+            .map_or(&[][..], Vec::as_slice);
+        let start = entries.partition_point(|(t, _)| *t < from);
+        let end = entries.partition_point(|(t, _)| *t <= to).max(start);
+        entries[start..end]
+            .iter()
+            .map(|(_, position)| &self.events[*position])
+    }
+
+    /// Iterates, in time order, over the events of the machine at `ip`.
+    pub fn for_machine(&self, ip: &str) -> impl Iterator<Item = &Event> + '_ {
+        self.for_machine_between(ip, f64::NEG_INFINITY, f64::INFINITY)
+    }
+
+    /// Iterates, in time order, over the events within `width` seconds of `t`.
+    pub fn window(&self, t: f64, width: f64) -> impl Iterator<Item = &Event> + '_ {
+        self.between(t - width, t + width)
+    }
+}
+
+impl From<Vec<Event>> for Events {
+    fn from(events: Vec<Event>) -> Self {
+        Events::new(events)
+    }
+}
+
+impl Extend<Event> for Events {
+    fn extend<I: IntoIterator<Item = Event>>(&mut self, events: I) {
+        for event in events {
+            self.push(event);
+        }
+    }
+}
+
+impl Index<usize> for Events {
+    type Output = Event;
+
+    fn index(&self, position: usize) -> &Event {
+        &self.events[position]
+    }
+}
+
+impl<'a> IntoIterator for &'a Events {
+    type Item = &'a Event;
+    type IntoIter = std::slice::Iter<'a, Event>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.events.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{ElapsedTimeData, RebooterStartData};
+
+    fn event(timestamp: &str) -> Event {
+        Event::ElapsedTime(ElapsedTimeData {
+            timestamp: timestamp.to_string(),
+            sim_time: timestamp.to_string(),
+            real_time: "0".to_string(),
+        })
+    }
+
+    fn times<'a>(events: impl Iterator<Item = &'a Event>) -> Vec<f64> {
+        events.map(Event::timestamp).collect()
+    }
+
+    #[test]
+    fn test_nearest_and_window() {
+        let mut events = Events::new(vec![event("5"), event("1"), event("3"), event("3")]);
+        events.push(event("2"));
+
+        assert_eq!(events.nearest(0.0).map(Event::timestamp), Some(1.0));
+        assert_eq!(events.nearest(2.4).map(Event::timestamp), Some(2.0));
+        assert_eq!(events.nearest(100.0).map(Event::timestamp), Some(5.0));
+        assert!(Events::default().nearest(1.0).is_none());
+
+        assert_eq!(times(events.window(3.0, 1.0)), vec![2.0, 3.0, 3.0]);
+        assert_eq!(times(events.between(3.5, 4.5)), Vec::<f64>::new());
+        assert_eq!(times(events.iter()), vec![5.0, 1.0, 3.0, 3.0, 2.0]);
+    }
+
+    #[test]
+    fn test_for_machine() {
+        let start = |timestamp: &str, address: &str| {
+            Event::RebooterStart(RebooterStartData {
+                timestamp: timestamp.to_string(),
+                address: address.to_string(),
+                zone_id: None,
+                cycles: "1".to_string(),
+            })
+        };
+        let mut events = Events::new(vec![
+            start("4", "2.0.1.0:1"),
+            event("1"),
+            start("2", "2.0.1.1:1"),
+            start("1", "2.0.1.0:2:tls"),
+        ]);
+        events.push(start("3", "2.0.1.0:1"));
+
+        assert_eq!(times(events.for_machine("2.0.1.0")), vec![1.0, 3.0, 4.0]);
+        assert_eq!(
+            times(events.for_machine_between("2.0.1.0:1", 2.0, 3.5)),
+            vec![3.0]
+        );
+        assert_eq!(times(events.for_machine("2.0.1.1")), vec![2.0]);
+        assert_eq!(events.for_machine("10.0.0.1").count(), 0);
+    }
+}
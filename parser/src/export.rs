@@ -0,0 +1,691 @@
+//! Exporters rendering a `SimulationReport` into formats consumed by external tools.
+//!
+//! Every format is an `Exporter` registered by name in an `ExporterRegistry`, so that
+//! formats defined in other crates plug into `export --format` like the built-in ones.
+//! Exporters render the report, and can ask for the parsed events of the trace too.
+//! The built-in formats embed an `ExportMetadata` identifying the run, so exported
+//! files stay traceable to their trace.
+
+pub mod downtime;
+pub mod events;
+pub mod influx;
+pub mod vega;
+pub mod xlsx;
+
+use crate::ensemble::trace_files;
+use crate::events::Events;
+use crate::number_format::NumberFormat;
+use crate::report::{html, markdown, SimulationReport};
+use crate::time_format::{TimeFormat, TimeFormatter};
+use events::EventTable;
+use serde::Serialize;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::path::Path;
+use std::sync::Arc;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ExportError {
+    #[error("I/O error writing export: {0}")]
+    Io(#[from] io::Error),
+    #[error("Failed to serialize export: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Failed to build workbook: {0}")]
+    Xlsx(#[from] rust_xlsxwriter::XlsxError),
+    #[error("{0}")]
+    Invalid(String),
+}
+
+/// Settings shared by all exporters; each uses the ones relevant to its format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportOptions {
+    pub time_format: TimeFormat,
+    pub numbers: NumberFormat,
+    /// Event categories exported by `csv`, all of them by default.
+    pub event_tables: Vec<EventTable>,
+    /// Hash of the trace the report was built from, see `trace_hash`.
+    pub trace_hash: Option<String>,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            time_format: TimeFormat::default(),
+            numbers: NumberFormat::default(),
+            event_tables: EventTable::ALL.to_vec(),
+            trace_hash: None,
+        }
+    }
+}
+
+impl ExportOptions {
+    /// Metadata of an export of the report with these options.
+    pub fn metadata(&self, report: &SimulationReport) -> ExportMetadata {
+        ExportMetadata {
+            seed: report.seed.clone(),
+            fdb_version: report.fdb_version.clone(),
+            test_name: report.test_name.clone(),
+            trace_hash: self.trace_hash.clone(),
+            tool_version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+}
+
+/// Identifies the run an export comes from: a `meta` object in JSON, `#` comment lines
+/// in CSV and line protocol, `usermeta` in Vega-Lite, custom document properties in
+/// XLSX, and `<meta>` tags or an HTML comment in HTML and Markdown.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ExportMetadata {
+    pub seed: Option<String>,
+    /// fdbserver version of the run.
+    pub fdb_version: Option<String>,
+    pub test_name: Option<String>,
+    /// Hash of the trace, see `trace_hash`.
+    pub trace_hash: Option<String>,
+    /// Version of this tool.
+    pub tool_version: String,
+}
+
+impl ExportMetadata {
+    /// The known fields, as (key, value).
+    pub fn entries(&self) -> Vec<(&'static str, &str)> {
+        [
+            ("seed", self.seed.as_deref()),
+            ("fdb_version", self.fdb_version.as_deref()),
+            ("test_name", self.test_name.as_deref()),
+            ("trace_hash", self.trace_hash.as_deref()),
+            ("tool_version", Some(self.tool_version.as_str())),
+        ]
+        .into_iter()
+        .filter_map(|(key, value)| Some((key, value?)))
+        .collect()
+    }
+
+    /// The known fields as space-separated `key=value` pairs.
+    pub fn to_line(&self) -> String {
+        let pairs: Vec<String> = self
+            .entries()
+            .into_iter()
+            .map(|(key, value)| format!("{}={}", key, value.replace(char::is_whitespace, "_")))
+            .collect();
+        pairs.join(" ")
+    }
+}
+
+/// Hash of the bytes of a trace file, or of the trace files of a directory in name
+/// order, as 16 hex digits of 64-bit FNV-1a. It tells traces apart, it does not
+/// detect tampering.
+pub fn trace_hash(path: &Path) -> io::Result<String> {
+    let paths = if path.is_dir() {
+        trace_files(path).map_err(|e| io::Error::other(e.to_string()))?
+    } else {
+        vec![path.to_path_buf()]
+    };
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    let mut buffer = vec![0; 64 * 1024];
+    for path in paths {
+        let mut file = File::open(path)?;
+        loop {
+            let read = file.read(&mut buffer)?;
+            if read == 0 {
+                break;
+            }
+            for byte in &buffer[..read] {
+                hash ^= u64::from(*byte);
+                hash = hash.wrapping_mul(0x0100_0000_01b3);
+            }
+        }
+    }
+    Ok(format!("{:016x}", hash))
+}
+
+/// An output format of the `export` subcommand.
+pub trait Exporter: Send + Sync {
+    /// Unique name of the format, e.g. "influx".
+    fn name(&self) -> &'static str;
+
+    /// One-line description, for the list of formats in `--help`.
+    fn description(&self) -> &'static str;
+
+    /// Writes the report in this format. `events` holds the parsed trace when
+    /// `needs_events` is true, and is empty otherwise.
+    fn export(
+        &self,
+        report: &SimulationReport,
+        events: &Events,
+        options: &ExportOptions,
+        out: &mut dyn Write,
+    ) -> Result<(), ExportError>;
+
+    /// Whether `export` reads the parsed events. False, the default, for formats built
+    /// from the report alone, which spares keeping the whole trace in memory.
+    fn needs_events(&self) -> bool {
+        false
+    }
+
+    /// For formats made of several files, the file names along with the options
+    /// exporting each of them, written to a directory. `None`, the default, for
+    /// formats written as a single stream.
+    fn files(&self, _options: &ExportOptions) -> Option<Vec<(&'static str, ExportOptions)>> {
+        None
+    }
+}
+
+/// The export formats available by name.
+#[derive(Clone)]
+pub struct ExporterRegistry {
+    exporters: Vec<Arc<dyn Exporter>>,
+}
+
+impl Default for ExporterRegistry {
+    /// The built-in formats.
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        registry.register(InfluxExporter);
+        registry.register(VegaExporter);
+        registry.register(XlsxExporter);
+        registry.register(DowntimeJsonExporter);
+        registry.register(DowntimeCsvExporter);
+        registry.register(CsvExporter);
+        registry.register(JsonExporter);
+        registry.register(MarkdownExporter);
+        registry.register(HtmlExporter);
+        registry
+    }
+}
+
+impl fmt::Debug for ExporterRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.names()).finish()
+    }
+}
+
+impl ExporterRegistry {
+    /// A registry without any format.
+    pub fn empty() -> Self {
+        Self {
+            exporters: Vec::new(),
+        }
+    }
+
+    /// Adds a format after the registered ones. A format with the same name is
+    /// replaced in place.
+    pub fn register(&mut self, exporter: impl Exporter + 'static) {
+        let exporter: Arc<dyn Exporter> = Arc::new(exporter);
+        match self
+            .exporters
+            .iter_mut()
+            .find(|e| e.name() == exporter.name())
+        {
+            Some(existing) => *existing = exporter,
+            None => self.exporters.push(exporter),
+        }
+    }
+
+    /// Names of the registered formats, in registration order.
+    pub fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.exporters.iter().map(|exporter| exporter.name())
+    }
+
+    /// The registered formats, in registration order.
+    pub fn exporters(&self) -> impl Iterator<Item = &dyn Exporter> + '_ {
+        self.exporters.iter().map(|exporter| exporter.as_ref())
+    }
+
+    /// Looks a format up by name.
+    pub fn get(&self, name: &str) -> Option<&dyn Exporter> {
+        self.exporters().find(|exporter| exporter.name() == name)
+    }
+}
+
+/// InfluxDB line protocol, see `influx`.
+pub struct InfluxExporter;
+
+impl Exporter for InfluxExporter {
+    fn name(&self) -> &'static str {
+        "influx"
+    }
+
+    fn description(&self) -> &'static str {
+        "InfluxDB line protocol (chaos metrics and per-event points)"
+    }
+
+    fn export(
+        &self,
+        report: &SimulationReport,
+        _events: &Events,
+        options: &ExportOptions,
+        out: &mut dyn Write,
+    ) -> Result<(), ExportError> {
+        write_comment(&options.metadata(report), out)?;
+        Ok(out.write_all(influx::to_line_protocol(report).as_bytes())?)
+    }
+}
+
+/// Vega-Lite chart specification, see `vega`.
+pub struct VegaExporter;
+
+impl Exporter for VegaExporter {
+    fn name(&self) -> &'static str {
+        "vega"
+    }
+
+    fn description(&self) -> &'static str {
+        "Vega-Lite chart specification with inlined data"
+    }
+
+    fn export(
+        &self,
+        report: &SimulationReport,
+        _events: &Events,
+        options: &ExportOptions,
+        out: &mut dyn Write,
+    ) -> Result<(), ExportError> {
+        let mut spec = vega::to_vega_lite(report);
+        spec["usermeta"] = serde_json::to_value(options.metadata(report))?;
+        write_json(&spec, out)
+    }
+}
+
+/// Excel workbook, see `xlsx`.
+pub struct XlsxExporter;
+
+impl Exporter for XlsxExporter {
+    fn name(&self) -> &'static str {
+        "xlsx"
+    }
+
+    fn description(&self) -> &'static str {
+        "Excel workbook with one sheet per report section"
+    }
+
+    fn export(
+        &self,
+        report: &SimulationReport,
+        _events: &Events,
+        options: &ExportOptions,
+        out: &mut dyn Write,
+    ) -> Result<(), ExportError> {
+        let metadata = options.metadata(report);
+        Ok(out.write_all(&xlsx::to_xlsx(report, options.time_format, &metadata)?)?)
+    }
+}
+
+/// Downtime intervals as JSON, see `downtime`.
+pub struct DowntimeJsonExporter;
+
+impl Exporter for DowntimeJsonExporter {
+    fn name(&self) -> &'static str {
+        "downtime-json"
+    }
+
+    fn description(&self) -> &'static str {
+        "Per-machine downtime intervals (kill to restart) as JSON, under `intervals`"
+    }
+
+    fn export(
+        &self,
+        report: &SimulationReport,
+        _events: &Events,
+        options: &ExportOptions,
+        out: &mut dyn Write,
+    ) -> Result<(), ExportError> {
+        let export = serde_json::json!({
+            "meta": options.metadata(report),
+            "intervals": downtime::to_json(report),
+        });
+        write_json(&export, out)
+    }
+}
+
+/// Downtime intervals as CSV, see `downtime`.
+pub struct DowntimeCsvExporter;
+
+impl Exporter for DowntimeCsvExporter {
+    fn name(&self) -> &'static str {
+        "downtime-csv"
+    }
+
+    fn description(&self) -> &'static str {
+        "Per-machine downtime intervals (kill to restart) as CSV"
+    }
+
+    fn export(
+        &self,
+        report: &SimulationReport,
+        _events: &Events,
+        options: &ExportOptions,
+        out: &mut dyn Write,
+    ) -> Result<(), ExportError> {
+        write_comment(&options.metadata(report), out)?;
+        Ok(out.write_all(downtime::to_csv(report).as_bytes())?)
+    }
+}
+
+/// One CSV file per event category, see `events`.
+pub struct CsvExporter;
+
+impl Exporter for CsvExporter {
+    fn name(&self) -> &'static str {
+        "csv"
+    }
+
+    fn description(&self) -> &'static str {
+        "One CSV file per event category of --events, written to the --output directory"
+    }
+
+    /// Writes the single selected table; several tables are written as `files`.
+    fn export(
+        &self,
+        report: &SimulationReport,
+        _events: &Events,
+        options: &ExportOptions,
+        out: &mut dyn Write,
+    ) -> Result<(), ExportError> {
+        let [table] = options.event_tables.as_slice() else {
+            return Err(ExportError::Invalid(
+                "CSV exports a single event category per file".to_string(),
+            ));
+        };
+        write_comment(&options.metadata(report), out)?;
+        Ok(out.write_all(events::to_csv(report, *table).as_bytes())?)
+    }
+
+    fn files(&self, options: &ExportOptions) -> Option<Vec<(&'static str, ExportOptions)>> {
+        let mut tables = options.event_tables.clone();
+        tables.sort();
+        tables.dedup();
+        Some(
+            tables
+                .into_iter()
+                .map(|table| {
+                    let options = ExportOptions {
+                        event_tables: vec![table],
+                        ..options.clone()
+                    };
+                    (table.file_name(), options)
+                })
+                .collect(),
+        )
+    }
+}
+
+/// The serialized report, with its `schema_version` and a `meta` object.
+pub struct JsonExporter;
+
+impl Exporter for JsonExporter {
+    fn name(&self) -> &'static str {
+        "json"
+    }
+
+    fn description(&self) -> &'static str {
+        "The full report as JSON"
+    }
+
+    fn export(
+        &self,
+        report: &SimulationReport,
+        _events: &Events,
+        options: &ExportOptions,
+        out: &mut dyn Write,
+    ) -> Result<(), ExportError> {
+        let mut value = serde_json::to_value(report)?;
+        value["meta"] = serde_json::to_value(options.metadata(report))?;
+        write_json(&value, out)
+    }
+}
+
+/// GitHub-flavored Markdown summary, see `report::markdown`.
+pub struct MarkdownExporter;
+
+impl Exporter for MarkdownExporter {
+    fn name(&self) -> &'static str {
+        "markdown"
+    }
+
+    fn description(&self) -> &'static str {
+        "GitHub-flavored Markdown, e.g. for pull request comments"
+    }
+
+    fn export(
+        &self,
+        report: &SimulationReport,
+        _events: &Events,
+        options: &ExportOptions,
+        out: &mut dyn Write,
+    ) -> Result<(), ExportError> {
+        let time = TimeFormatter::new(options.time_format, report);
+        let markdown = markdown::to_markdown(report, &time, &options.numbers);
+        // Hidden when the Markdown is rendered
+        let line = options.metadata(report).to_line().replace("--", "-");
+        writeln!(out, "<!-- {} -->", line)?;
+        Ok(out.write_all(markdown.as_bytes())?)
+    }
+}
+
+/// Standalone HTML page, see `report::html`.
+pub struct HtmlExporter;
+
+impl Exporter for HtmlExporter {
+    fn name(&self) -> &'static str {
+        "html"
+    }
+
+    fn description(&self) -> &'static str {
+        "Standalone HTML page with tables and an SVG timeline"
+    }
+
+    fn export(
+        &self,
+        report: &SimulationReport,
+        _events: &Events,
+        options: &ExportOptions,
+        out: &mut dyn Write,
+    ) -> Result<(), ExportError> {
+        let time = TimeFormatter::new(options.time_format, report);
+        let tags: String = options
+            .metadata(report)
+            .entries()
+            .into_iter()
+            .map(|(key, value)| {
+                format!(
+                    "<meta name=\"fdb-sim:{}\" content=\"{}\">\n",
+                    key,
+                    html::escape_html(value)
+                )
+            })
+            .collect();
+        let page = html::to_html(report, &time, &options.numbers);
+        Ok(out.write_all(
+            page.replacen("<head>\n", &format!("<head>\n{}", tags), 1)
+                .as_bytes(),
+        )?)
+    }
+}
+
+/// Writes the metadata as a `#` comment line.
+fn write_comment(metadata: &ExportMetadata, out: &mut dyn Write) -> Result<(), ExportError> {
+    Ok(writeln!(out, "# {}", metadata.to_line())?)
+}
+
+/// Writes pretty-printed JSON followed by a line break.
+fn write_json<T: serde::Serialize + ?Sized>(
+    value: &T,
+    out: &mut dyn Write,
+) -> Result<(), ExportError> {
+    serde_json::to_writer_pretty(&mut *out, value)?;
+    Ok(out.write_all(b"\n")?)
+}
+
+/// Quotes a CSV value when it contains a separator, quote or line break.
+pub(crate) fn escape_csv(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_log_file;
+    use crate::report::create_simulation_report;
+
+    struct KillCountExporter;
+
+    impl Exporter for KillCountExporter {
+        fn name(&self) -> &'static str {
+            "kill-count"
+        }
+
+        fn description(&self) -> &'static str {
+            "Number of kills"
+        }
+
+        fn export(
+            &self,
+            report: &SimulationReport,
+            _events: &Events,
+            _options: &ExportOptions,
+            out: &mut dyn Write,
+        ) -> Result<(), ExportError> {
+            Ok(writeln!(out, "{}", report.kill_machine_processes.len())?)
+        }
+    }
+
+    struct EventTypesExporter;
+
+    impl Exporter for EventTypesExporter {
+        fn name(&self) -> &'static str {
+            "event-types"
+        }
+
+        fn description(&self) -> &'static str {
+            "Type of every event, in time order"
+        }
+
+        fn needs_events(&self) -> bool {
+            true
+        }
+
+        fn export(
+            &self,
+            _report: &SimulationReport,
+            events: &Events,
+            _options: &ExportOptions,
+            out: &mut dyn Write,
+        ) -> Result<(), ExportError> {
+            for event in events.between(f64::NEG_INFINITY, f64::INFINITY) {
+                writeln!(out, "{}", event.event_type())?;
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_registry_exports_custom_formats() {
+        let mut registry = ExporterRegistry::default();
+        registry.register(KillCountExporter);
+        assert_eq!(registry.names().last(), Some("kill-count"));
+        assert!(registry.get("parquet").is_none());
+
+        let report = create_simulation_report(&[]);
+        let options = ExportOptions::default();
+        let mut out = Vec::new();
+        let exporter = registry.get("kill-count").unwrap();
+        exporter
+            .export(&report, &Events::default(), &options, &mut out)
+            .unwrap();
+        assert_eq!(out, b"0\n");
+        assert!(exporter.files(&options).is_none());
+        assert!(!exporter.needs_events());
+
+        // Formats asking for the events get the parsed trace
+        registry.register(EventTypesExporter);
+        let exporter = registry.get("event-types").unwrap();
+        assert!(exporter.needs_events());
+        let events = parse_log_file("logs/combined_trace.0.0.0.0.24.1745498878.p7Loj0.json")
+            .expect("Failed to parse log file");
+        let first_type = events
+            .iter()
+            .min_by(|a, b| a.timestamp().total_cmp(&b.timestamp()))
+            .map(|event| event.event_type())
+            .unwrap();
+        let events = Events::new(events);
+        let mut out = Vec::new();
+        exporter
+            .export(&report, &events, &options, &mut out)
+            .unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert_eq!(out.lines().count(), events.len());
+        assert_eq!(out.lines().next(), Some(first_type));
+
+        // CSV is split into one file per category
+        let csv = registry.get("csv").unwrap();
+        let files = csv.files(&options).unwrap();
+        assert_eq!(files.len(), 3);
+        let mut out = Vec::new();
+        csv.export(&report, &Events::default(), &files[1].1, &mut out)
+            .unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let mut lines = out.lines();
+        assert_eq!(
+            lines.next(),
+            Some(concat!("# tool_version=", env!("CARGO_PKG_VERSION")))
+        );
+        assert!(lines
+            .next()
+            .unwrap()
+            .starts_with("timestamp,machine,address"));
+        assert!(csv
+            .export(&report, &Events::default(), &options, &mut Vec::new())
+            .is_err());
+    }
+
+    #[test]
+    fn test_exports_embed_metadata() {
+        let mut report = create_simulation_report(&[]);
+        report.seed = Some("42".to_string());
+        report.fdb_version = Some("7.3.43".to_string());
+        let options = ExportOptions {
+            trace_hash: trace_hash(Path::new(
+                "logs/combined_trace.0.0.0.0.24.1745498878.p7Loj0.json",
+            ))
+            .ok(),
+            ..ExportOptions::default()
+        };
+        let hash = options.trace_hash.clone().unwrap();
+        assert_eq!(hash.len(), 16);
+        assert_eq!(
+            options.metadata(&report).to_line(),
+            format!(
+                "seed=42 fdb_version=7.3.43 trace_hash={} tool_version={}",
+                hash,
+                env!("CARGO_PKG_VERSION")
+            )
+        );
+
+        let export = |format: &str| {
+            let mut out = Vec::new();
+            let registry = ExporterRegistry::default();
+            let exporter = registry.get(format).unwrap();
+            exporter
+                .export(&report, &Events::default(), &options, &mut out)
+                .unwrap();
+            String::from_utf8(out).unwrap()
+        };
+        let json: serde_json::Value = serde_json::from_str(&export("json")).unwrap();
+        assert_eq!(json["meta"]["trace_hash"], hash.as_str());
+        let vega: serde_json::Value = serde_json::from_str(&export("vega")).unwrap();
+        assert_eq!(vega["usermeta"]["seed"], "42");
+        let downtime: serde_json::Value = serde_json::from_str(&export("downtime-json")).unwrap();
+        assert_eq!(downtime["meta"]["fdb_version"], "7.3.43");
+        assert!(downtime["intervals"].is_array());
+        assert!(export("influx").starts_with("# seed=42 "));
+        assert!(export("markdown").starts_with("<!-- seed=42 "));
+        assert!(export("html").contains("<meta name=\"fdb-sim:seed\" content=\"42\">"));
+    }
+}
@@ -0,0 +1,147 @@
+//! Per-machine downtime intervals (see `lifecycle::downtime_intervals`), for
+//! availability modeling tools.
+
+use super::escape_csv;
+use crate::lifecycle::downtime_intervals;
+use crate::report::SimulationReport;
+use std::fmt::Write;
+
+/// Renders the downtime intervals as a JSON array. Intervals carry the run's tags,
+/// if any, so that the intervals of several runs can be concatenated.
+pub fn to_json(report: &SimulationReport) -> serde_json::Value {
+    serde_json::Value::Array(
+        downtime_intervals(report)
+            .into_iter()
+            .map(|interval| {
+                let duration = interval.duration();
+                let mut value = serde_json::to_value(interval).expect("serializable interval");
+                value["duration"] = serde_json::Value::from(duration);
+                if !report.tags.is_empty() {
+                    value["tags"] = serde_json::json!(report.tags);
+                }
+                value
+            })
+            .collect(),
+    )
+}
+
+/// Renders the downtime intervals as CSV, with a header row. The run's tags are
+/// appended as one column each.
+pub fn to_csv(report: &SimulationReport) -> String {
+    let mut out = String::from("machine,address,dc_id,zone_id,start,end,duration,cause,restarted");
+    let mut tag_values = String::new();
+    for (key, value) in &report.tags {
+        let _ = write!(out, ",{}", escape_csv(key));
+        let _ = write!(tag_values, ",{}", escape_csv(value));
+    }
+    out.push('\n');
+    for interval in downtime_intervals(report) {
+        let _ = writeln!(
+            out,
+            "{},{},{},{},{},{},{},{:?},{}{}",
+            escape_csv(&interval.machine),
+            escape_csv(&interval.address),
+            escape_csv(interval.dc_id.as_deref().unwrap_or("")),
+            escape_csv(interval.zone_id.as_deref().unwrap_or("")),
+            interval.start,
+            interval.end,
+            interval.duration(),
+            interval.cause,
+            interval.restarted,
+            tag_values
+        );
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{
+        ElapsedTimeData, Event, KillMachineProcessData, KillType, RebooterStartData,
+        SimulatedMachineStartData,
+    };
+    use crate::report::create_simulation_report;
+
+    fn kill(timestamp: &str, address: &str) -> Event {
+        Event::KillMachineProcess(KillMachineProcessData {
+            timestamp: timestamp.to_string(),
+            raw_kill_type: "6".to_string(),
+            process: format!("name: Server address: {} zone: z", address),
+            starting_class: "storage".to_string(),
+            failed: "0".to_string(),
+            excluded: "0".to_string(),
+            cleared: "0".to_string(),
+            rebooting: "0".to_string(),
+        })
+    }
+
+    fn start(timestamp: &str, address: &str, cycles: &str) -> Event {
+        Event::RebooterStart(RebooterStartData {
+            timestamp: timestamp.to_string(),
+            address: format!("{}:tls", address),
+            zone_id: None,
+            cycles: cycles.to_string(),
+        })
+    }
+
+    #[test]
+    fn test_downtime_intervals() {
+        let events = vec![
+            Event::SimulatedMachineStart(SimulatedMachineStartData {
+                timestamp: "0.0".to_string(),
+                process_class: "storage".to_string(),
+                locality: "machineid=m1".to_string(),
+                machine_ips: Some("2.0.1.0".to_string()),
+                zone_id: Some("z1".to_string()),
+                process_id: None,
+                machine_id: Some("m1".to_string()),
+                dc_id: Some("dc0".to_string()),
+                data_hall: None,
+            }),
+            start("0.5", "2.0.1.0:1", "1"),
+            kill("10", "2.0.1.0:1"),
+            // Killed again while down: part of the same interval
+            kill("11", "2.0.1.0:1"),
+            start("14", "2.0.1.0:1", "2"),
+            kill("20", "2.0.1.1:1"),
+            Event::ElapsedTime(ElapsedTimeData {
+                timestamp: "30".to_string(),
+                sim_time: "30".to_string(),
+                real_time: "1".to_string(),
+            }),
+        ];
+        let report = create_simulation_report(&events);
+
+        let intervals = downtime_intervals(&report);
+        assert_eq!(intervals.len(), 2);
+        assert_eq!(intervals[0].dc_id.as_deref(), Some("dc0"));
+        assert_eq!(intervals[0].zone_id.as_deref(), Some("z1"));
+        assert_eq!((intervals[0].start, intervals[0].end), (10.0, 14.0));
+        assert!(intervals[0].restarted);
+        assert_eq!(intervals[0].cause, KillType::Reboot);
+        assert_eq!((intervals[1].start, intervals[1].end), (20.0, 30.0));
+        assert!(!intervals[1].restarted);
+
+        let csv = to_csv(&report);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[1], "2.0.1.0,2.0.1.0:1,dc0,z1,10,14,4,Reboot,true");
+        assert_eq!(to_json(&report)[1]["duration"], 10.0);
+
+        let mut report = report;
+        report.tags.insert("branch".to_string(), "main".to_string());
+        let csv = to_csv(&report);
+        assert!(csv.starts_with(
+            "machine,address,dc_id,zone_id,start,end,duration,cause,restarted,branch\n"
+        ));
+        assert!(csv.lines().nth(1).unwrap().ends_with(",true,main"));
+        assert_eq!(to_json(&report)[0]["tags"]["branch"], "main");
+    }
+
+    #[test]
+    fn test_escape_csv() {
+        assert_eq!(escape_csv("a,\"b\""), "\"a,\"\"b\"\"\"");
+        assert_eq!(escape_csv("dc0"), "dc0");
+    }
+}
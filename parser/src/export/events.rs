@@ -0,0 +1,212 @@
+//! One CSV table per chaos event category, one row per event, for pandas or
+//! spreadsheets.
+//!
+//! Unlike the text outputs, columns are typed: times and durations are plain seconds,
+//! flags are `true` or `false`, and values that do not parse are left empty rather
+//! than passed through. Machines are resolved to their DC through the report's
+//! topology. Only the events kept by the report are exported, so tables of sampled or
+//! aggregate-only reports are partial. The `csv` exporter starts each file with a
+//! `#` metadata line, skipped by pandas with `read_csv(..., comment="#")`.
+
+use super::escape_csv;
+use crate::cluster::ClusterIndex;
+use crate::parser::ip_from_address;
+use crate::report::SimulationReport;
+use std::fmt::Write;
+
+/// Category of events exported as a CSV table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum EventTable {
+    /// CloggingPair events.
+    CloggingPairs,
+    /// KillMachineProcess events.
+    Kills,
+    /// SetDiskFailure events.
+    DiskFailures,
+}
+
+impl EventTable {
+    pub const ALL: [EventTable; 3] = [
+        EventTable::CloggingPairs,
+        EventTable::Kills,
+        EventTable::DiskFailures,
+    ];
+
+    /// Name of the file holding the table, e.g. "kills.csv".
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            EventTable::CloggingPairs => "clogging_pairs.csv",
+            EventTable::Kills => "kills.csv",
+            EventTable::DiskFailures => "disk_failures.csv",
+        }
+    }
+}
+
+/// Renders the events of a category as CSV, with a header row. The run's tags are
+/// appended as one column each, like in the downtime CSV.
+pub fn to_csv(report: &SimulationReport, table: EventTable) -> String {
+    let cluster = ClusterIndex::new(report);
+    let dc = |address: &str| cluster.dc_id(address).unwrap_or("").to_string();
+    let (header, rows): (&str, Vec<Vec<String>>) = match table {
+        EventTable::CloggingPairs => (
+            "timestamp,from_ip,to_ip,from_dc_id,to_dc_id,seconds",
+            report
+                .clogging_pairs
+                .iter()
+                .map(|pair| {
+                    vec![
+                        number(&pair.timestamp),
+                        escape_csv(ip_from_address(&pair.from_id)),
+                        escape_csv(ip_from_address(&pair.to_id)),
+                        escape_csv(&dc(&pair.from_id)),
+                        escape_csv(&dc(&pair.to_id)),
+                        number(&pair.seconds),
+                    ]
+                })
+                .collect(),
+        ),
+        EventTable::Kills => (
+            "timestamp,machine,address,dc_id,kill_type,kill_type_code,starting_class,failed,excluded,cleared,rebooting",
+            report
+                .kill_machine_processes
+                .iter()
+                .map(|kill| {
+                    let address = kill.address().unwrap_or("");
+                    let kill_type = kill.kill_type();
+                    vec![
+                        number(&kill.timestamp),
+                        escape_csv(kill.ip_address().unwrap_or("")),
+                        escape_csv(address),
+                        escape_csv(&dc(address)),
+                        format!("{:?}", kill_type),
+                        kill.raw_kill_type
+                            .trim()
+                            .parse::<u32>()
+                            .map_or_else(|_| String::new(), |code| code.to_string()),
+                        escape_csv(&kill.starting_class),
+                        flag(&kill.failed),
+                        flag(&kill.excluded),
+                        flag(&kill.cleared),
+                        flag(&kill.rebooting),
+                    ]
+                })
+                .collect(),
+        ),
+        EventTable::DiskFailures => (
+            "timestamp,machine,dc_id,stall_interval,stall_period,stall_until,throttle_period,throttle_until",
+            report
+                .set_disk_failures
+                .iter()
+                .map(|failure| {
+                    vec![
+                        number(&failure.timestamp),
+                        escape_csv(ip_from_address(&failure.machine)),
+                        escape_csv(&dc(&failure.machine)),
+                        number(&failure.stall_interval),
+                        number(&failure.stall_period),
+                        number(&failure.stall_until),
+                        number(&failure.throttle_period),
+                        number(&failure.throttle_until),
+                    ]
+                })
+                .collect(),
+        ),
+    };
+
+    let mut out = header.to_string();
+    let mut tag_values = String::new();
+    for (key, value) in &report.tags {
+        let _ = write!(out, ",{}", escape_csv(key));
+        let _ = write!(tag_values, ",{}", escape_csv(value));
+    }
+    out.push('\n');
+    for row in rows {
+        let _ = writeln!(out, "{}{}", row.join(","), tag_values);
+    }
+    out
+}
+
+/// A numeric trace field as a CSV number, empty when it does not parse.
+fn number(value: &str) -> String {
+    value
+        .trim()
+        .parse::<f64>()
+        .map_or_else(|_| String::new(), |number| number.to_string())
+}
+
+/// A "0"/"1" trace flag as a CSV boolean, empty when it is neither.
+fn flag(value: &str) -> String {
+    match value.trim() {
+        "0" => "false".to_string(),
+        "1" => "true".to_string(),
+        _ => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{
+        CloggingPairData, Event, KillMachineProcessData, SetDiskFailureData,
+        SimulatedMachineStartData,
+    };
+    use crate::report::create_simulation_report;
+
+    #[test]
+    fn test_event_tables() {
+        let events = vec![
+            Event::SimulatedMachineStart(SimulatedMachineStartData {
+                timestamp: "0.0".to_string(),
+                process_class: "storage".to_string(),
+                locality: "machineid=m1".to_string(),
+                machine_ips: Some("2.0.1.0".to_string()),
+                zone_id: Some("z1".to_string()),
+                process_id: None,
+                machine_id: Some("m1".to_string()),
+                dc_id: Some("dc0".to_string()),
+                data_hall: None,
+            }),
+            Event::CloggingPair(CloggingPairData {
+                timestamp: "10.5".to_string(),
+                from_id: "2.0.1.0:1".to_string(),
+                to_id: "2.0.1.1:1".to_string(),
+                seconds: "0.25".to_string(),
+            }),
+            Event::KillMachineProcess(KillMachineProcessData {
+                timestamp: "20".to_string(),
+                raw_kill_type: "6".to_string(),
+                process: "name: Server address: 2.0.1.0:1 zone: z1".to_string(),
+                starting_class: "storage".to_string(),
+                failed: "0".to_string(),
+                excluded: "1".to_string(),
+                cleared: "0".to_string(),
+                rebooting: "?".to_string(),
+            }),
+            Event::SetDiskFailure(SetDiskFailureData {
+                timestamp: "30".to_string(),
+                machine: "2.0.1.0:1".to_string(),
+                stall_interval: "5".to_string(),
+                stall_period: "5".to_string(),
+                stall_until: "35".to_string(),
+                throttle_period: "0".to_string(),
+                throttle_until: "n/a".to_string(),
+            }),
+        ];
+        let mut report = create_simulation_report(&events);
+        report.tags.insert("branch".to_string(), "main".to_string());
+
+        assert_eq!(
+            to_csv(&report, EventTable::CloggingPairs),
+            "timestamp,from_ip,to_ip,from_dc_id,to_dc_id,seconds,branch\n\
+             10.5,2.0.1.0,2.0.1.1,dc0,,0.25,main\n"
+        );
+        assert_eq!(
+            to_csv(&report, EventTable::Kills).lines().nth(1),
+            Some("20,2.0.1.0,2.0.1.0:1,dc0,Reboot,6,storage,false,true,false,,main")
+        );
+        assert_eq!(
+            to_csv(&report, EventTable::DiskFailures).lines().nth(1),
+            Some("30,2.0.1.0,dc0,5,5,35,0,,main")
+        );
+    }
+}
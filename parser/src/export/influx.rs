@@ -0,0 +1,256 @@
+//! InfluxDB line protocol export.
+//!
+//! Produces one `fdb_sim_chaos` point summarizing the run plus one `fdb_sim_event`
+//! point per chaos event. Every point is tagged with the run's seed and user tags so
+//! the output of a whole ensemble can be written into the same bucket. Simulated time has no
+//! wall-clock anchor, so point timestamps are the simulated seconds expressed as
+//! nanoseconds since the Unix epoch.
+
+use crate::cluster::ClusterIndex;
+use crate::parser::ip_from_address;
+use crate::report::SimulationReport;
+use std::fmt::Write;
+
+/// Renders the report as InfluxDB line protocol, one point per line.
+pub fn to_line_protocol(report: &SimulationReport) -> String {
+    let seed = report.seed.as_deref().unwrap_or("unknown");
+    let cluster = ClusterIndex::new(report);
+    let mut out = String::new();
+
+    // Run-level summary point, stamped at the end of the simulation
+    let kills: usize = report.kill_machine_process_summary.values().sum();
+    let mut summary = Point::new(
+        "fdb_sim_chaos",
+        parse_seconds(report.elapsed_time.as_deref()),
+    );
+    summary
+        .tag("seed", seed)
+        .run_tags(report)
+        .int_field("clogging_pairs", report.clogging_pairs.len())
+        .int_field("clog_interfaces", report.clog_interfaces.len())
+        .int_field("kills", kills)
+        .int_field("coordinator_changes", report.coordinators_change_count)
+        .int_field("disk_swaps", report.disk_swaps.len())
+        .int_field("disk_failures", report.set_disk_failures.len())
+        .int_field("corrupted_blocks", report.corrupted_blocks.len())
+        .int_field("recruitment_failures", report.recruitment_failures.len());
+    if let Some(summary_stats) = &report.clogging_pair_summary {
+        summary.float_field("clog_mean_seconds", summary_stats.mean_seconds);
+    }
+    for (kill_type, count) in &report.kill_machine_process_summary {
+        summary.int_field(&format!("kills_{:?}", kill_type), *count);
+    }
+    summary.write_to(&mut out);
+
+    // Per-event points
+    let event_point = |event_type: &str, timestamp: &str, ip: Option<&str>| {
+        let mut point = Point::new("fdb_sim_event", parse_seconds(Some(timestamp)));
+        point
+            .tag("seed", seed)
+            .tag("type", event_type)
+            .run_tags(report);
+        if let Some(ip) = ip {
+            point.tag("machine", ip);
+            if let Some(dc) = cluster.dc_id(ip) {
+                point.tag("dc", dc);
+            }
+        }
+        point
+    };
+
+    for pair in &report.clogging_pairs {
+        let mut point = event_point("CloggingPair", &pair.timestamp, Some(&pair.from_id));
+        point
+            .str_field("to", &pair.to_id)
+            .float_field("seconds", parse_seconds(Some(&pair.seconds)));
+        point.write_to(&mut out);
+    }
+    for interface in &report.clog_interfaces {
+        let mut point = event_point("ClogInterface", &interface.timestamp, Some(&interface.ip));
+        point
+            .str_field("queue", &interface.queue)
+            .float_field("delay", parse_seconds(Some(&interface.delay)));
+        point.write_to(&mut out);
+    }
+    for kill in &report.kill_machine_processes {
+        let kill_type = kill.kill_type();
+        let mut point = event_point("KillMachineProcess", &kill.timestamp, kill.ip_address());
+        point.str_field("kill_type", &format!("{:?}", kill_type));
+        point.write_to(&mut out);
+    }
+    for failure in &report.set_disk_failures {
+        let ip = ip_from_address(&failure.machine);
+        let mut point = event_point("SetDiskFailure", &failure.timestamp, Some(ip));
+        point
+            .float_field("stall_period", parse_seconds(Some(&failure.stall_period)))
+            .float_field(
+                "throttle_period",
+                parse_seconds(Some(&failure.throttle_period)),
+            );
+        point.write_to(&mut out);
+    }
+    for block in &report.corrupted_blocks {
+        let ip = ip_from_address(&block.machine);
+        let mut point = event_point("CorruptedBlock", &block.time, Some(ip));
+        point.str_field("filename", &block.filename);
+        point.write_to(&mut out);
+    }
+    for swap in &report.disk_swaps {
+        let mut point = event_point("DiskSwap", &swap.timestamp, None);
+        point.str_field("machine_ips", &swap.machine_ips);
+        point.write_to(&mut out);
+    }
+    for change in &report.coordinators_changes {
+        let mut point = event_point("CoordinatorsChange", &change.timestamp, None);
+        point.str_field("new_coordinators", &change.new_coordinators_key);
+        point.write_to(&mut out);
+    }
+
+    out
+}
+
+/// Parses a seconds string, falling back to 0.0 like `Event::timestamp`.
+fn parse_seconds(value: Option<&str>) -> f64 {
+    value.and_then(|v| v.parse().ok()).unwrap_or(0.0)
+}
+
+/// A single line protocol point under construction.
+struct Point {
+    measurement: &'static str,
+    tags: Vec<(String, String)>,
+    fields: Vec<(String, String)>,
+    timestamp_ns: i64,
+}
+
+impl Point {
+    fn new(measurement: &'static str, seconds: f64) -> Self {
+        Self {
+            measurement,
+            tags: Vec::new(),
+            fields: Vec::new(),
+            timestamp_ns: (seconds * 1e9) as i64,
+        }
+    }
+
+    fn tag(&mut self, key: &str, value: &str) -> &mut Self {
+        // Empty tag values are invalid in line protocol
+        if !value.is_empty() {
+            self.tags.push((escape_key(key), escape_key(value)));
+        }
+        self
+    }
+
+    /// Tags the point with the user tags of the run, except those clashing with the
+    /// tags set by the exporter.
+    fn run_tags(&mut self, report: &SimulationReport) -> &mut Self {
+        for (key, value) in &report.tags {
+            if !matches!(key.as_str(), "seed" | "type" | "machine" | "dc") {
+                self.tag(key, value);
+            }
+        }
+        self
+    }
+
+    fn int_field(&mut self, key: &str, value: usize) -> &mut Self {
+        self.fields.push((escape_key(key), format!("{}i", value)));
+        self
+    }
+
+    fn float_field(&mut self, key: &str, value: f64) -> &mut Self {
+        self.fields.push((escape_key(key), format!("{}", value)));
+        self
+    }
+
+    fn str_field(&mut self, key: &str, value: &str) -> &mut Self {
+        let escaped = value.replace('\\', "\\\\").replace('"', "\\\"");
+        self.fields
+            .push((escape_key(key), format!("\"{}\"", escaped)));
+        self
+    }
+
+    fn write_to(&self, out: &mut String) {
+        out.push_str(self.measurement);
+        for (key, value) in &self.tags {
+            let _ = write!(out, ",{}={}", key, value);
+        }
+        let fields: Vec<String> = self
+            .fields
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect();
+        let _ = writeln!(out, " {} {}", fields.join(","), self.timestamp_ns);
+    }
+}
+
+/// Escapes commas, spaces and equal signs in tag keys, tag values and field keys.
+fn escape_key(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, ',' | ' ' | '=' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{CloggingPairData, Event, KillMachineProcessData, ProgramStartData};
+    use crate::report::create_simulation_report;
+
+    #[test]
+    fn test_to_line_protocol() {
+        let events = vec![
+            Event::ProgramStart(ProgramStartData {
+                timestamp: "0.0".to_string(),
+                machine: "0.0.0.0:0".to_string(),
+                random_seed: Some("42".to_string()),
+                date_time: None,
+                version: None,
+                command_line: None,
+                connection_string: None,
+            }),
+            Event::CloggingPair(CloggingPairData {
+                timestamp: "1.5".to_string(),
+                from_id: "2.0.1.0".to_string(),
+                to_id: "2.0.1.1".to_string(),
+                seconds: "0.25".to_string(),
+            }),
+            Event::KillMachineProcess(KillMachineProcessData {
+                timestamp: "2".to_string(),
+                raw_kill_type: "6".to_string(),
+                process: "name: Server address: 2.2.1.2:1 zone: z".to_string(),
+                starting_class: "storage".to_string(),
+                failed: "0".to_string(),
+                excluded: "0".to_string(),
+                cleared: "0".to_string(),
+                rebooting: "0".to_string(),
+            }),
+        ];
+        let report = create_simulation_report(&events);
+
+        let lines: Vec<String> = to_line_protocol(&report)
+            .lines()
+            .map(str::to_string)
+            .collect();
+
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("fdb_sim_chaos,seed=42 clogging_pairs=1i,"));
+        assert!(lines[0].contains("kills_Reboot=1i"));
+        assert_eq!(
+            lines[1],
+            "fdb_sim_event,seed=42,type=CloggingPair,machine=2.0.1.0 to=\"2.0.1.1\",seconds=0.25 1500000000"
+        );
+        assert_eq!(
+            lines[2],
+            "fdb_sim_event,seed=42,type=KillMachineProcess,machine=2.2.1.2 kill_type=\"Reboot\" 2000000000"
+        );
+    }
+
+    #[test]
+    fn test_escape_key() {
+        assert_eq!(escape_key("a b,c=d"), "a\\ b\\,c\\=d");
+    }
+}
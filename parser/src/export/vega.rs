@@ -0,0 +1,173 @@
+//! Vega-Lite chart export.
+//!
+//! Emits a single Vega-Lite specification vertically concatenating the charts, with
+//! the data inlined so the file can be opened in any Vega viewer or notebook as is.
+
+use crate::parser::KillType;
+use crate::report::SimulationReport;
+use serde_json::{json, Value};
+
+const VEGA_LITE_SCHEMA: &str = "https://vega.github.io/schema/vega-lite/v5.json";
+
+/// Builds the Vega-Lite specification for the report.
+pub fn to_vega_lite(report: &SimulationReport) -> Value {
+    let title = match &report.seed {
+        Some(seed) => format!("Simulation seed {}", seed),
+        None => "Simulation".to_string(),
+    };
+    let mut spec = json!({
+        "$schema": VEGA_LITE_SCHEMA,
+        "title": title,
+        "vconcat": [
+            event_density_chart(report),
+            clog_duration_histogram(report),
+            kill_type_breakdown(report),
+        ],
+    });
+    if !report.tags.is_empty() {
+        spec["usermeta"] = json!({ "tags": report.tags });
+    }
+    spec
+}
+
+/// Stacked histogram of chaos events over simulated time, colored by category.
+fn event_density_chart(report: &SimulationReport) -> Value {
+    let mut values = Vec::new();
+    let mut push = |timestamp: &str, category: &str| {
+        if let Ok(time) = timestamp.parse::<f64>() {
+            values.push(json!({ "time": time, "category": category }));
+        }
+    };
+    for event in &report.clogging_pairs {
+        push(&event.timestamp, "Clogging Pair");
+    }
+    for event in &report.clog_interfaces {
+        push(&event.timestamp, "Clog Interface");
+    }
+    for event in &report.kill_machine_processes {
+        push(&event.timestamp, "Kill");
+    }
+    for event in &report.coordinators_changes {
+        push(&event.timestamp, "Coordinator Change");
+    }
+    for event in &report.disk_swaps {
+        push(&event.timestamp, "Disk Swap");
+    }
+    for event in &report.set_disk_failures {
+        push(&event.timestamp, "Disk Failure");
+    }
+    for event in &report.corrupted_blocks {
+        push(&event.time, "Corrupted Block");
+    }
+
+    json!({
+        "title": "Event density over time",
+        "width": 600,
+        "data": { "values": values },
+        "mark": "bar",
+        "encoding": {
+            "x": { "field": "time", "bin": { "maxbins": 60 }, "title": "Simulated time (s)" },
+            "y": { "aggregate": "count", "title": "Events" },
+            "color": { "field": "category", "type": "nominal", "title": "Category" },
+        },
+    })
+}
+
+/// Histogram of CloggingPair durations.
+fn clog_duration_histogram(report: &SimulationReport) -> Value {
+    let values: Vec<Value> = report
+        .clogging_pairs
+        .iter()
+        .filter_map(|pair| pair.seconds.parse::<f64>().ok())
+        .map(|seconds| json!({ "seconds": seconds }))
+        .collect();
+
+    json!({
+        "title": "Clog duration histogram",
+        "width": 600,
+        "data": { "values": values },
+        "mark": "bar",
+        "encoding": {
+            "x": { "field": "seconds", "bin": { "maxbins": 40 }, "title": "Clog duration (s)" },
+            "y": { "aggregate": "count", "title": "Clogging pairs" },
+        },
+    })
+}
+
+/// Bar chart of process kills per KillType.
+fn kill_type_breakdown(report: &SimulationReport) -> Value {
+    let mut counts: Vec<(&KillType, &usize)> = report.kill_machine_process_summary.iter().collect();
+    counts.sort();
+    let values: Vec<Value> = counts
+        .into_iter()
+        .map(|(kill_type, count)| {
+            json!({
+                "kill_type": format!("{:?}", kill_type),
+                "count": count,
+                "description": kill_type.description(),
+            })
+        })
+        .collect();
+
+    json!({
+        "title": "Kill type breakdown",
+        "width": 600,
+        "data": { "values": values },
+        "mark": "bar",
+        "encoding": {
+            "x": { "field": "kill_type", "type": "nominal", "title": "Kill type", "sort": "-y" },
+            "y": { "field": "count", "type": "quantitative", "title": "Kills" },
+            "tooltip": [
+                { "field": "kill_type", "title": "Kill type" },
+                { "field": "count", "title": "Kills" },
+                { "field": "description", "title": "Meaning" },
+            ],
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{CloggingPairData, Event, KillMachineProcessData};
+    use crate::report::create_simulation_report;
+
+    #[test]
+    fn test_to_vega_lite() {
+        let events = vec![
+            Event::CloggingPair(CloggingPairData {
+                timestamp: "1.5".to_string(),
+                from_id: "2.0.1.0".to_string(),
+                to_id: "2.0.1.1".to_string(),
+                seconds: "0.25".to_string(),
+            }),
+            Event::KillMachineProcess(KillMachineProcessData {
+                timestamp: "2".to_string(),
+                raw_kill_type: "2".to_string(),
+                process: "name: Server address: 2.2.1.2:1 zone: z".to_string(),
+                starting_class: "storage".to_string(),
+                failed: "0".to_string(),
+                excluded: "0".to_string(),
+                cleared: "0".to_string(),
+                rebooting: "0".to_string(),
+            }),
+        ];
+        let report = create_simulation_report(&events);
+
+        let spec = to_vega_lite(&report);
+
+        assert_eq!(spec["$schema"], VEGA_LITE_SCHEMA);
+        let charts = spec["vconcat"]
+            .as_array()
+            .expect("vconcat should be an array");
+        assert_eq!(charts.len(), 3);
+        assert_eq!(charts[0]["data"]["values"].as_array().unwrap().len(), 2);
+        assert_eq!(charts[1]["data"]["values"][0]["seconds"], 0.25);
+        assert_eq!(charts[2]["data"]["values"][0]["kill_type"], "FailDisk");
+        assert_eq!(
+            charts[2]["data"]["values"][0]["description"],
+            KillType::FailDisk.description()
+        );
+        assert_eq!(charts[2]["data"]["values"][0]["count"], 1);
+    }
+}
@@ -0,0 +1,416 @@
+//! Excel workbook export.
+//!
+//! Writes one worksheet per report section. Numeric values (timestamps, durations,
+//! counts) are stored as numbers rather than text so they can be sorted and charted
+//! directly in the spreadsheet.
+
+use super::ExportMetadata;
+use crate::analysis::AnalysisValue;
+use crate::cluster::ClusterIndex;
+use crate::parser::ip_from_address;
+use crate::report::SimulationReport;
+use crate::time_format::{TimeFormat, TimeFormatter};
+use rust_xlsxwriter::{DocProperties, Format, Workbook, XlsxError};
+use std::collections::HashSet;
+
+/// A typed worksheet cell.
+enum CellValue {
+    Text(String),
+    Number(f64),
+    Empty,
+}
+
+impl CellValue {
+    fn text(value: impl Into<String>) -> Self {
+        CellValue::Text(value.into())
+    }
+
+    /// Stores numeric strings as numbers, falling back to text for anything else.
+    fn parsed(value: &str) -> Self {
+        value
+            .parse::<f64>()
+            .map(CellValue::Number)
+            .unwrap_or_else(|_| CellValue::text(value))
+    }
+
+    fn optional(value: Option<&str>) -> Self {
+        value.map_or(CellValue::Empty, CellValue::text)
+    }
+
+    /// Stores a timestamp as a number of seconds, or as formatted text for the other
+    /// time formats.
+    fn time(time: &TimeFormatter, seconds: &str) -> Self {
+        match time.format() {
+            TimeFormat::Seconds => CellValue::parsed(seconds),
+            _ => CellValue::text(time.timestamp_str(seconds)),
+        }
+    }
+}
+
+/// Renders the report into an in-memory `.xlsx` file, with event times displayed in
+/// `time_format`.
+pub fn to_xlsx(
+    report: &SimulationReport,
+    time_format: TimeFormat,
+    metadata: &ExportMetadata,
+) -> Result<Vec<u8>, XlsxError> {
+    let time = TimeFormatter::new(time_format, report);
+    let time_header = time.header("Time");
+    let mut workbook = Workbook::new();
+    // The metadata is kept as custom document properties, see File > Properties
+    let mut properties = DocProperties::new();
+    for (key, value) in metadata.entries() {
+        properties = properties.set_custom_property(key, value);
+    }
+    workbook.set_properties(&properties);
+    let header_format = Format::new().set_bold();
+
+    // --- Overview ---
+    let mut overview = vec![
+        vec![
+            CellValue::text("Seed"),
+            CellValue::optional(report.seed.as_deref()),
+        ],
+        vec![
+            CellValue::text("Simulated Time (s)"),
+            report
+                .elapsed_time
+                .as_deref()
+                .map_or(CellValue::Empty, CellValue::parsed),
+        ],
+        vec![
+            CellValue::text("Real Time (s)"),
+            report
+                .real_time
+                .as_deref()
+                .map_or(CellValue::Empty, CellValue::parsed),
+        ],
+    ];
+    for (key, value) in &report.tags {
+        overview.push(vec![
+            CellValue::text(format!("Tag {}", key)),
+            CellValue::text(value),
+        ]);
+    }
+    if let Some(config) = &report.simulator_config {
+        let mut sorted_config: Vec<_> = config.iter().collect();
+        sorted_config.sort();
+        for (key, value) in sorted_config {
+            overview.push(vec![CellValue::text(key), CellValue::parsed(value)]);
+        }
+    }
+    write_sheet(
+        &mut workbook,
+        "Overview",
+        &["Key", "Value"],
+        overview,
+        &header_format,
+    )?;
+
+    // --- Machines ---
+    let mut machines: Vec<_> = report
+        .machine_details
+        .values()
+        .chain(&report.auxiliary_processes)
+        .collect();
+    machines.sort_by(|a, b| a.machine_id.cmp(&b.machine_id));
+    let machine_rows = machines
+        .into_iter()
+        .map(|machine| {
+            let chaos = machine
+                .ip_address
+                .as_deref()
+                .and_then(|ip| report.machine_chaos.get(ip));
+            let kills: usize = chaos.map_or(0, |c| c.kills_by_type.values().sum());
+            vec![
+                CellValue::optional(machine.machine_id.as_deref()),
+                CellValue::optional(machine.ip_address.as_deref()),
+                CellValue::optional(machine.dc_id.as_deref()),
+                CellValue::optional(machine.data_hall_id.as_deref()),
+                CellValue::optional(machine.zone_id.as_deref()),
+                CellValue::optional(machine.class_type.as_deref()),
+                CellValue::Number(kills as f64),
+                CellValue::Number(chaos.map_or(0, |c| c.disk_failures) as f64),
+                CellValue::Number(chaos.map_or(0, |c| c.clogging_pairs) as f64),
+            ]
+        })
+        .collect();
+    write_sheet(
+        &mut workbook,
+        "Machines",
+        &[
+            "Machine ID",
+            "IP Address",
+            "DC ID",
+            "Data Hall",
+            "Zone ID",
+            "Class Type",
+            "Kills",
+            "Disk Failures",
+            "Clogging Pairs",
+        ],
+        machine_rows,
+        &header_format,
+    )?;
+
+    let cluster = ClusterIndex::new(report);
+
+    // --- Kills ---
+    let kill_rows = report
+        .kill_machine_processes
+        .iter()
+        .map(|kill| {
+            let kill_type = kill.kill_type();
+            let ip = kill.ip_address();
+            vec![
+                CellValue::time(&time, &kill.timestamp),
+                CellValue::text(format!("{:?}", kill_type)),
+                CellValue::optional(ip),
+                CellValue::optional(ip.and_then(|ip| cluster.dc_id(ip))),
+                CellValue::text(&kill.starting_class),
+                CellValue::text(&kill.process),
+            ]
+        })
+        .collect();
+    write_sheet(
+        &mut workbook,
+        "Kills",
+        &[
+            &time_header,
+            "Kill Type",
+            "IP Address",
+            "DC ID",
+            "Starting Class",
+            "Process",
+        ],
+        kill_rows,
+        &header_format,
+    )?;
+
+    // --- Clogs ---
+    let mut clog_rows: Vec<Vec<CellValue>> = report
+        .clogging_pairs
+        .iter()
+        .map(|pair| {
+            vec![
+                CellValue::time(&time, &pair.timestamp),
+                CellValue::text("CloggingPair"),
+                CellValue::text(&pair.from_id),
+                CellValue::text(&pair.to_id),
+                CellValue::Empty,
+                CellValue::parsed(&pair.seconds),
+            ]
+        })
+        .collect();
+    clog_rows.extend(report.clog_interfaces.iter().map(|interface| {
+        vec![
+            CellValue::time(&time, &interface.timestamp),
+            CellValue::text("ClogInterface"),
+            CellValue::text(&interface.ip),
+            CellValue::Empty,
+            CellValue::text(&interface.queue),
+            CellValue::parsed(&interface.delay),
+        ]
+    }));
+    write_sheet(
+        &mut workbook,
+        "Clogs",
+        &[&time_header, "Kind", "From / IP", "To", "Queue", "Seconds"],
+        clog_rows,
+        &header_format,
+    )?;
+
+    // --- Disk Faults ---
+    let mut disk_rows: Vec<Vec<CellValue>> = report
+        .set_disk_failures
+        .iter()
+        .map(|failure| {
+            vec![
+                CellValue::time(&time, &failure.timestamp),
+                CellValue::text("SetDiskFailure"),
+                CellValue::text(ip_from_address(&failure.machine)),
+                CellValue::parsed(&failure.stall_period),
+                CellValue::parsed(&failure.throttle_period),
+                CellValue::Empty,
+            ]
+        })
+        .collect();
+    disk_rows.extend(report.corrupted_blocks.iter().map(|block| {
+        vec![
+            CellValue::time(&time, &block.time),
+            CellValue::text("CorruptedBlock"),
+            CellValue::text(ip_from_address(&block.machine)),
+            CellValue::Empty,
+            CellValue::Empty,
+            CellValue::text(&block.filename),
+        ]
+    }));
+    disk_rows.extend(report.disk_swaps.iter().map(|swap| {
+        vec![
+            CellValue::time(&time, &swap.timestamp),
+            CellValue::text("DiskSwap"),
+            CellValue::text(&swap.machine_ips),
+            CellValue::Empty,
+            CellValue::Empty,
+            CellValue::Empty,
+        ]
+    }));
+    write_sheet(
+        &mut workbook,
+        "Disk Faults",
+        &[
+            &time_header,
+            "Kind",
+            "Machine",
+            "Stall Period (s)",
+            "Throttle Period (s)",
+            "Filename",
+        ],
+        disk_rows,
+        &header_format,
+    )?;
+
+    // --- Annotations ---
+    let annotation_rows: Vec<Vec<CellValue>> = report
+        .annotations
+        .iter()
+        .map(|annotation| {
+            vec![
+                CellValue::time(&time, &annotation.timestamp.to_string()),
+                CellValue::optional(annotation.event_type.as_deref()),
+                CellValue::text(&annotation.text),
+                CellValue::optional(annotation.author.as_deref()),
+            ]
+        })
+        .collect();
+    if !annotation_rows.is_empty() {
+        write_sheet(
+            &mut workbook,
+            "Annotations",
+            &[&time_header, "Event Type", "Note", "Author"],
+            annotation_rows,
+            &header_format,
+        )?;
+    }
+
+    // --- Analyses ---
+    let finding_rows: Vec<Vec<CellValue>> = report
+        .analyses
+        .iter()
+        .flat_map(|section| {
+            section.findings.iter().map(|finding| {
+                vec![
+                    CellValue::text(&section.pass),
+                    CellValue::text(format!("{:?}", finding.level)),
+                    finding.time.map_or(CellValue::Empty, |seconds| {
+                        CellValue::time(&time, &seconds.to_string())
+                    }),
+                    CellValue::text(&finding.message),
+                ]
+            })
+        })
+        .collect();
+    if !finding_rows.is_empty() {
+        write_sheet(
+            &mut workbook,
+            "Analyses",
+            &["Pass", "Level", &time_header, "Message"],
+            finding_rows,
+            &header_format,
+        )?;
+    }
+    let mut sheet_names: HashSet<String> = HashSet::new();
+    for section in &report.analyses {
+        let Some(table) = &section.table else {
+            continue;
+        };
+        let headers: Vec<&str> = table.headers.iter().map(String::as_str).collect();
+        let rows = table
+            .rows
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|value| match value {
+                        AnalysisValue::Text(text) => CellValue::text(text),
+                        AnalysisValue::Number(number) => CellValue::Number(*number),
+                        AnalysisValue::Time(seconds) => {
+                            CellValue::time(&time, &seconds.to_string())
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        let name = sheet_name(&section.title, &mut sheet_names);
+        write_sheet(&mut workbook, &name, &headers, rows, &header_format)?;
+    }
+
+    workbook.save_to_buffer()
+}
+
+/// Returns a worksheet name for `title` within Excel's 31 character limit, numbered if
+/// a sheet already has that name.
+fn sheet_name(title: &str, taken: &mut HashSet<String>) -> String {
+    let base: String = title.chars().take(31).collect();
+    let mut name = base.clone();
+    let mut suffix = 2;
+    while !taken.insert(name.to_lowercase()) {
+        let number = format!(" {}", suffix);
+        name = base.chars().take(31 - number.len()).collect::<String>() + &number;
+        suffix += 1;
+    }
+    name
+}
+
+/// Adds a worksheet with a bold, frozen header row followed by the given rows.
+fn write_sheet(
+    workbook: &mut Workbook,
+    name: &str,
+    headers: &[&str],
+    rows: Vec<Vec<CellValue>>,
+    header_format: &Format,
+) -> Result<(), XlsxError> {
+    let sheet = workbook.add_worksheet();
+    sheet.set_name(name)?;
+    sheet.write_row_with_format(0, 0, headers.iter().copied(), header_format)?;
+    sheet.set_freeze_panes(1, 0)?;
+
+    for (row_index, row) in rows.into_iter().enumerate() {
+        let row_num = row_index as u32 + 1;
+        for (col_index, value) in row.into_iter().enumerate() {
+            let col_num = col_index as u16;
+            match value {
+                CellValue::Text(text) => {
+                    sheet.write_string(row_num, col_num, text)?;
+                }
+                CellValue::Number(number) => {
+                    sheet.write_number(row_num, col_num, number)?;
+                }
+                CellValue::Empty => {}
+            }
+        }
+    }
+    sheet.autofit();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::ExportOptions;
+    use crate::report::create_simulation_report;
+
+    #[test]
+    fn test_to_xlsx_from_log() {
+        let events =
+            crate::parser::parse_log_file("logs/combined_trace.0.0.0.0.24.1745498878.p7Loj0.json")
+                .expect("Failed to parse log file");
+        let report = create_simulation_report(&events);
+
+        let metadata = ExportOptions::default().metadata(&report);
+        let bytes = to_xlsx(&report, TimeFormat::DateTime, &metadata)
+            .expect("Workbook should be generated");
+
+        // xlsx files are zip archives
+        assert!(bytes.starts_with(b"PK"));
+    }
+}
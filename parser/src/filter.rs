@@ -0,0 +1,871 @@
+//! Filter expressions shared by the `query` and `timeline` subcommands, the TUI search
+//! bar and the serve API.
+//!
+//! An expression compares fields of an event with values, e.g.
+//! `type == "KillMachineProcess" && machine ~ "2.0.1.*" && time > 100`:
+//!
+//! - fields: `type`, `machine`, `time`, `details`, `severity`, only set on
+//!   Severity 30+ events, and `category`, only set on Timeline rows, e.g. "kills"
+//! - comparisons: `==`, `!=`, `<`, `<=`, `>`, `>=`, and `~` / `!~` matching a glob
+//!   pattern where `*` stands for any characters and `?` for one, ignoring case
+//! - values: double-quoted strings, or bare words and numbers
+//! - combinators: `&&`, `||`, `!` and parentheses, `&&` binding tighter than `||`
+//!
+//! A field may have several values, e.g. the two ends of a clog for `machine`: a
+//! comparison holds when any of them matches, and `!=` / `!~` when none does. Values
+//! that both parse as numbers are compared as numbers, so `time == 100` matches an
+//! event at `100.0`.
+
+use crate::parser::{ip_from_address, Event};
+use crate::report::{MachineInfo, SevereEventSummary};
+use crate::timeline::{TimelineCategory, TimelineEvent};
+use std::borrow::Cow;
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// A field of an event that an expression compares.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    /// Event type, e.g. "KillMachineProcess" or "Clog Start" in the timeline.
+    Type,
+    /// IPs of the machines involved.
+    Machine,
+    /// Simulated time, in seconds.
+    Time,
+    /// Every field of the event, as text.
+    Details,
+    /// Logged severity, for Severity 30+ events only.
+    Severity,
+    /// Category of a Timeline row, see `TimelineCategory::name`.
+    Category,
+}
+
+impl FromStr for Field {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "type" => Ok(Field::Type),
+            "machine" => Ok(Field::Machine),
+            "time" => Ok(Field::Time),
+            "details" => Ok(Field::Details),
+            "severity" => Ok(Field::Severity),
+            "category" => Ok(Field::Category),
+            _ => Err(format!(
+                "unknown field '{}', expected type, machine, time, details, severity or category",
+                s
+            )),
+        }
+    }
+}
+
+/// Comparison between a field and a value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    Matches,
+    NotMatches,
+}
+
+/// A parsed filter expression.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Filter {
+    Compare {
+        field: Field,
+        comparison: Comparison,
+        value: String,
+    },
+    Not(Box<Filter>),
+    And(Box<Filter>, Box<Filter>),
+    Or(Box<Filter>, Box<Filter>),
+}
+
+/// An invalid filter expression.
+#[derive(Error, Debug, Clone, PartialEq)]
+#[error("Invalid filter at column {column}: {message}")]
+pub struct FilterError {
+    /// Column of the offending token, starting at 1.
+    pub column: usize,
+    pub message: String,
+}
+
+/// Something a filter can be evaluated against.
+pub trait Filterable {
+    /// Values of a field, empty when the record has none.
+    fn field_values(&self, field: Field) -> Vec<Cow<'_, str>>;
+}
+
+impl Filterable for Event {
+    fn field_values(&self, field: Field) -> Vec<Cow<'_, str>> {
+        match field {
+            Field::Type => vec![Cow::Borrowed(self.event_type())],
+            Field::Machine => self.machine_ips().into_iter().map(Cow::Borrowed).collect(),
+            Field::Time => vec![Cow::Owned(self.timestamp().to_string())],
+            Field::Details => serde_json::to_string(self)
+                .map(Cow::Owned)
+                .into_iter()
+                .collect(),
+            Field::Severity => self
+                .severe_event()
+                .map(|severe| Cow::Owned(severe.severity))
+                .into_iter()
+                .collect(),
+            Field::Category => Vec::new(),
+        }
+    }
+}
+
+/// Timeline rows have no machine field of their own: `machine` matches the words of
+/// their details, which name the machines involved.
+impl Filterable for TimelineEvent {
+    fn field_values(&self, field: Field) -> Vec<Cow<'_, str>> {
+        match field {
+            Field::Type => vec![Cow::Borrowed(self.event_type.as_str())],
+            Field::Machine => self
+                .details
+                .split(|c: char| c.is_whitespace() || matches!(c, '(' | ')' | ','))
+                .filter(|word| !word.is_empty())
+                .map(Cow::Borrowed)
+                .collect(),
+            Field::Time => vec![Cow::Owned(self.timestamp.to_string())],
+            Field::Details => vec![Cow::Borrowed(self.details.as_str())],
+            Field::Severity => Vec::new(),
+            Field::Category => vec![Cow::Borrowed(self.category().name())],
+        }
+    }
+}
+
+/// Machines only have a `machine` field, their IP, and `details`, their locality.
+impl Filterable for MachineInfo {
+    fn field_values(&self, field: Field) -> Vec<Cow<'_, str>> {
+        match field {
+            Field::Machine => self
+                .ip_address
+                .as_deref()
+                .map(|ip| Cow::Borrowed(ip_from_address(ip)))
+                .into_iter()
+                .collect(),
+            Field::Details => [
+                &self.dc_id,
+                &self.data_hall_id,
+                &self.zone_id,
+                &self.machine_id,
+                &self.ip_address,
+                &self.class_type,
+            ]
+            .into_iter()
+            .flatten()
+            .map(|value| Cow::Borrowed(value.as_str()))
+            .collect(),
+            Field::Type | Field::Time | Field::Severity | Field::Category => Vec::new(),
+        }
+    }
+}
+
+/// A group of Severity 30+ events has the times it was first and last seen, and its
+/// top machines.
+impl Filterable for SevereEventSummary {
+    fn field_values(&self, field: Field) -> Vec<Cow<'_, str>> {
+        match field {
+            Field::Type | Field::Details => vec![Cow::Borrowed(self.event_type.as_str())],
+            Field::Machine => self
+                .top_machines
+                .iter()
+                .map(|(machine, _)| Cow::Borrowed(ip_from_address(machine)))
+                .collect(),
+            Field::Time => vec![
+                Cow::Owned(self.first_seen.to_string()),
+                Cow::Owned(self.last_seen.to_string()),
+            ],
+            Field::Severity => vec![Cow::Owned(self.severity.to_string())],
+            Field::Category => Vec::new(),
+        }
+    }
+}
+
+impl Filter {
+    /// Returns whether a record satisfies the expression.
+    pub fn matches<T: Filterable + ?Sized>(&self, record: &T) -> bool {
+        match self {
+            Filter::Compare {
+                field,
+                comparison,
+                value,
+            } => {
+                let values = record.field_values(*field);
+                match comparison {
+                    Comparison::NotEqual => {
+                        !values.iter().any(|v| compare(v, Comparison::Equal, value))
+                    }
+                    Comparison::NotMatches => !values
+                        .iter()
+                        .any(|v| compare(v, Comparison::Matches, value)),
+                    _ => values.iter().any(|v| compare(v, *comparison, value)),
+                }
+            }
+            Filter::Not(inner) => !inner.matches(record),
+            Filter::And(left, right) => left.matches(record) && right.matches(record),
+            Filter::Or(left, right) => left.matches(record) || right.matches(record),
+        }
+    }
+}
+
+/// Compares a field value with the value of an expression, `NotEqual` and
+/// `NotMatches` being handled by the caller.
+fn compare(actual: &str, comparison: Comparison, expected: &str) -> bool {
+    let numbers = actual.parse::<f64>().ok().zip(expected.parse::<f64>().ok());
+    match comparison {
+        Comparison::Equal | Comparison::NotEqual => match numbers {
+            Some((actual, expected)) => actual == expected,
+            None => actual == expected,
+        },
+        Comparison::Matches | Comparison::NotMatches => glob_match(
+            &expected.to_lowercase().chars().collect::<Vec<_>>(),
+            &actual.to_lowercase().chars().collect::<Vec<_>>(),
+        ),
+        Comparison::Less => numbers.is_some_and(|(a, e)| a < e),
+        Comparison::LessOrEqual => numbers.is_some_and(|(a, e)| a <= e),
+        Comparison::Greater => numbers.is_some_and(|(a, e)| a > e),
+        Comparison::GreaterOrEqual => numbers.is_some_and(|(a, e)| a >= e),
+    }
+}
+
+/// Matches text against a pattern where `*` stands for any characters and `?` for
+/// exactly one.
+fn glob_match(pattern: &[char], text: &[char]) -> bool {
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` and of the text it was matched against
+    let mut backtrack = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(c) if *c == '?' || *c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                // Let the last `*` absorb one more character
+                Some((star, matched)) => {
+                    p = star + 1;
+                    t = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// Criteria of the TUI filter builder, composed into an expression. Empty criteria
+/// match everything.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FilterCriteria {
+    /// Glob patterns of event types, any of which may match.
+    pub event_types: Vec<String>,
+    /// Glob pattern of the machine IPs.
+    pub machine: Option<String>,
+    /// Earliest simulated time, in seconds.
+    pub from: Option<f64>,
+    /// Latest simulated time, in seconds.
+    pub to: Option<f64>,
+    /// Lowest severity.
+    pub min_severity: Option<u32>,
+    /// Timeline categories left out, toggled in the TUI with the number keys.
+    pub hidden_categories: Vec<TimelineCategory>,
+}
+
+impl FilterCriteria {
+    /// Whether no criterion is set.
+    pub fn is_empty(&self) -> bool {
+        self.event_types.is_empty()
+            && self.machine.is_none()
+            && self.from.is_none()
+            && self.to.is_none()
+            && self.min_severity.is_none()
+            && self.hidden_categories.is_empty()
+    }
+
+    /// Hides the Timeline events of a category, or shows them again if hidden.
+    pub fn toggle_category(&mut self, category: TimelineCategory) {
+        match self.hidden_categories.iter().position(|c| *c == category) {
+            Some(i) => {
+                self.hidden_categories.remove(i);
+            }
+            None => self.hidden_categories.push(category),
+        }
+    }
+
+    /// Composes the criteria on the given fields into an expression, leaving out
+    /// those on fields a view does not have, e.g. `time` for a list of machines. The
+    /// expression is empty when no criterion applies.
+    pub fn expression(&self, fields: &[Field]) -> String {
+        let mut terms = Vec::new();
+        if fields.contains(&Field::Type) && !self.event_types.is_empty() {
+            let types: Vec<String> = self
+                .event_types
+                .iter()
+                .map(|pattern| format!("type ~ {}", quote(pattern)))
+                .collect();
+            terms.push(match types.len() {
+                1 => types.join(""),
+                _ => format!("({})", types.join(" || ")),
+            });
+        }
+        if let Some(machine) = self
+            .machine
+            .as_ref()
+            .filter(|_| fields.contains(&Field::Machine))
+        {
+            terms.push(format!("machine ~ {}", quote(machine)));
+        }
+        if fields.contains(&Field::Time) {
+            if let Some(from) = self.from {
+                terms.push(format!("time >= {}", from));
+            }
+            if let Some(to) = self.to {
+                terms.push(format!("time <= {}", to));
+            }
+        }
+        if let Some(severity) = self
+            .min_severity
+            .filter(|_| fields.contains(&Field::Severity))
+        {
+            terms.push(format!("severity >= {}", severity));
+        }
+        if fields.contains(&Field::Category) {
+            for category in &self.hidden_categories {
+                terms.push(format!("category != {}", quote(category.name())));
+            }
+        }
+        terms.join(" && ")
+    }
+
+    /// The criteria on the given fields as a filter, `None` when none applies.
+    pub fn to_filter(&self, fields: &[Field]) -> Option<Filter> {
+        let expression = self.expression(fields);
+        // Composed from validated criteria, so it always parses
+        (!expression.is_empty())
+            .then(|| expression.parse().ok())
+            .flatten()
+    }
+}
+
+/// Quotes a value of an expression.
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    LeftParen,
+    RightParen,
+    And,
+    Or,
+    Not,
+    Comparison(Comparison),
+    /// A quoted string or a bare word.
+    Value(String),
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::LeftParen => write!(f, "'('"),
+            Token::RightParen => write!(f, "')'"),
+            Token::And => write!(f, "'&&'"),
+            Token::Or => write!(f, "'||'"),
+            Token::Not => write!(f, "'!'"),
+            Token::Comparison(_) => write!(f, "comparison"),
+            Token::Value(value) => write!(f, "'{}'", value),
+        }
+    }
+}
+
+/// Splits an expression into tokens, each with its column.
+fn tokenize(input: &str) -> Result<Vec<(usize, Token)>, FilterError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let column = i + 1;
+        let next = chars.get(i + 1).copied();
+        let (token, len) = match (chars[i], next) {
+            (c, _) if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            ('(', _) => (Token::LeftParen, 1),
+            (')', _) => (Token::RightParen, 1),
+            ('&', Some('&')) => (Token::And, 2),
+            ('|', Some('|')) => (Token::Or, 2),
+            ('=', Some('=')) => (Token::Comparison(Comparison::Equal), 2),
+            ('!', Some('=')) => (Token::Comparison(Comparison::NotEqual), 2),
+            ('!', Some('~')) => (Token::Comparison(Comparison::NotMatches), 2),
+            ('!', _) => (Token::Not, 1),
+            ('~', _) => (Token::Comparison(Comparison::Matches), 1),
+            ('<', Some('=')) => (Token::Comparison(Comparison::LessOrEqual), 2),
+            ('<', _) => (Token::Comparison(Comparison::Less), 1),
+            ('>', Some('=')) => (Token::Comparison(Comparison::GreaterOrEqual), 2),
+            ('>', _) => (Token::Comparison(Comparison::Greater), 1),
+            ('"', _) => {
+                let mut value = String::new();
+                let mut end = i + 1;
+                loop {
+                    match chars.get(end) {
+                        Some('"') => break,
+                        Some('\\') if chars.get(end + 1).is_some() => {
+                            value.push(chars[end + 1]);
+                            end += 2;
+                        }
+                        Some(c) => {
+                            value.push(*c);
+                            end += 1;
+                        }
+                        None => {
+                            return Err(FilterError {
+                                column,
+                                message: "unterminated string".to_string(),
+                            })
+                        }
+                    }
+                }
+                (Token::Value(value), end + 1 - i)
+            }
+            (c, _) if is_word_char(c) => {
+                let len = chars[i..].iter().take_while(|c| is_word_char(**c)).count();
+                (Token::Value(chars[i..i + len].iter().collect()), len)
+            }
+            (c, _) => {
+                return Err(FilterError {
+                    column,
+                    message: format!("unexpected character '{}'", c),
+                })
+            }
+        };
+        tokens.push((column, token));
+        i += len;
+    }
+    Ok(tokens)
+}
+
+/// Characters of bare words, enough for field names, numbers, IPs and globs.
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '.' | '_' | '-' | ':' | '*' | '?' | '/')
+}
+
+/// Recursive descent parser over the tokens of an expression.
+struct ExpressionParser {
+    tokens: Vec<(usize, Token)>,
+    position: usize,
+    /// Column just past the end of the expression, for errors at its end.
+    end_column: usize,
+}
+
+impl ExpressionParser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(_, token)| token)
+    }
+
+    fn next(&mut self) -> Option<(usize, Token)> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn error(&self, message: String) -> FilterError {
+        let column = self
+            .tokens
+            .get(self.position)
+            .map_or(self.end_column, |(column, _)| *column);
+        FilterError { column, message }
+    }
+
+    fn or(&mut self) -> Result<Filter, FilterError> {
+        let mut filter = self.and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.position += 1;
+            filter = Filter::Or(Box::new(filter), Box::new(self.and()?));
+        }
+        Ok(filter)
+    }
+
+    fn and(&mut self) -> Result<Filter, FilterError> {
+        let mut filter = self.unary()?;
+        while self.peek() == Some(&Token::And) {
+            self.position += 1;
+            filter = Filter::And(Box::new(filter), Box::new(self.unary()?));
+        }
+        Ok(filter)
+    }
+
+    fn unary(&mut self) -> Result<Filter, FilterError> {
+        match self.peek() {
+            Some(Token::Not) => {
+                self.position += 1;
+                Ok(Filter::Not(Box::new(self.unary()?)))
+            }
+            Some(Token::LeftParen) => {
+                self.position += 1;
+                let filter = self.or()?;
+                match self.next() {
+                    Some((_, Token::RightParen)) => Ok(filter),
+                    _ => {
+                        self.position -= 1;
+                        Err(self.error("expected ')'".to_string()))
+                    }
+                }
+            }
+            _ => self.comparison(),
+        }
+    }
+
+    fn comparison(&mut self) -> Result<Filter, FilterError> {
+        let field = match self.next() {
+            Some((column, Token::Value(name))) => name
+                .parse::<Field>()
+                .map_err(|message| FilterError { column, message })?,
+            _ => {
+                self.position -= 1;
+                return Err(self.error("expected a field".to_string()));
+            }
+        };
+        let comparison = match self.next() {
+            Some((_, Token::Comparison(comparison))) => comparison,
+            _ => {
+                self.position -= 1;
+                return Err(self.error("expected a comparison".to_string()));
+            }
+        };
+        let (column, value) = match self.next() {
+            Some((column, Token::Value(value))) => (column, value),
+            _ => {
+                self.position -= 1;
+                return Err(self.error("expected a value".to_string()));
+            }
+        };
+        let ordering = matches!(
+            comparison,
+            Comparison::Less
+                | Comparison::LessOrEqual
+                | Comparison::Greater
+                | Comparison::GreaterOrEqual
+        );
+        if ordering && value.parse::<f64>().is_err() {
+            return Err(FilterError {
+                column,
+                message: format!("'{}' is not a number", value),
+            });
+        }
+        Ok(Filter::Compare {
+            field,
+            comparison,
+            value,
+        })
+    }
+}
+
+impl FromStr for Filter {
+    type Err = FilterError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = ExpressionParser {
+            tokens: tokenize(s)?,
+            position: 0,
+            end_column: s.chars().count() + 1,
+        };
+        let filter = parser.or()?;
+        match parser.peek() {
+            None => Ok(filter),
+            Some(token) => Err(parser.error(format!("unexpected {}", token))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{CloggingPairData, KillMachineProcessData, SevereEventData};
+
+    #[test]
+    fn test_filter_expressions() {
+        let kill = Event::KillMachineProcess(KillMachineProcessData {
+            timestamp: "150".to_string(),
+            raw_kill_type: "6".to_string(),
+            process: "name: Server address: 2.0.1.3:1 zone: z".to_string(),
+            starting_class: "storage".to_string(),
+            failed: "0".to_string(),
+            excluded: "0".to_string(),
+            cleared: "0".to_string(),
+            rebooting: "0".to_string(),
+        });
+        let clog = Event::CloggingPair(CloggingPairData {
+            timestamp: "100.0".to_string(),
+            from_id: "2.0.1.0".to_string(),
+            to_id: "3.4.3.1".to_string(),
+            seconds: "1".to_string(),
+        });
+        let matching = |expression: &str| -> Vec<&str> {
+            let filter: Filter = expression.parse().unwrap();
+            [&kill, &clog]
+                .into_iter()
+                .filter(|event| filter.matches(*event))
+                .map(Event::event_type)
+                .collect()
+        };
+
+        assert_eq!(
+            matching(r#"type == "KillMachineProcess" && machine ~ "2.0.1.*" && time > 100"#),
+            ["KillMachineProcess"]
+        );
+        assert_eq!(
+            matching("machine ~ 2.0.1.*"),
+            ["KillMachineProcess", "CloggingPair"]
+        );
+        assert_eq!(matching("machine !~ 3.4.*"), ["KillMachineProcess"]);
+        assert_eq!(matching("time == 100"), ["CloggingPair"]);
+        assert_eq!(
+            matching("!(type == CloggingPair) || time <= 100"),
+            ["KillMachineProcess", "CloggingPair"]
+        );
+        assert_eq!(matching("details ~ *KillType*"), ["KillMachineProcess"]);
+        assert_eq!(matching("type ~ clog*"), ["CloggingPair"]);
+
+        let error = |expression: &str| expression.parse::<Filter>().unwrap_err();
+        assert_eq!(error("kind == x").column, 1);
+        assert_eq!(error("type == ").column, 9);
+        assert_eq!(error("time > soon").message, "'soon' is not a number");
+        assert_eq!(error("(type == x").message, "expected ')'");
+        assert_eq!(error("type == \"x").message, "unterminated string");
+        assert_eq!(error("type == x y").message, "unexpected 'y'");
+    }
+
+    #[test]
+    fn test_filter_precedence() {
+        let compare = |value: &str| Filter::Compare {
+            field: Field::Type,
+            comparison: Comparison::Equal,
+            value: value.to_string(),
+        };
+        let (a, b, c) = (compare("a"), compare("b"), compare("c"));
+        let boxed = |filter: &Filter| Box::new(filter.clone());
+        let parse = |expression: &str| expression.parse::<Filter>().unwrap();
+
+        // `&&` binds tighter than `||`, and `!` than both
+        assert_eq!(
+            parse("type == a || type == b && type == c"),
+            Filter::Or(boxed(&a), Box::new(Filter::And(boxed(&b), boxed(&c))))
+        );
+        assert_eq!(
+            parse("type == a && type == b || type == c"),
+            Filter::Or(Box::new(Filter::And(boxed(&a), boxed(&b))), boxed(&c))
+        );
+        assert_eq!(
+            parse("!type == a && type == b"),
+            Filter::And(Box::new(Filter::Not(boxed(&a))), boxed(&b))
+        );
+        assert_eq!(
+            parse("!(type == a || type == b)"),
+            Filter::Not(Box::new(Filter::Or(boxed(&a), boxed(&b))))
+        );
+        assert_eq!(
+            parse("!!type == a"),
+            Filter::Not(Box::new(Filter::Not(boxed(&a))))
+        );
+        // Operators of the same precedence group to the left
+        assert_eq!(
+            parse("type == a || type == b || type == c"),
+            Filter::Or(Box::new(Filter::Or(boxed(&a), boxed(&b))), boxed(&c))
+        );
+    }
+
+    #[test]
+    fn test_filter_multi_valued_fields() {
+        let clog = Event::CloggingPair(CloggingPairData {
+            timestamp: "100.0".to_string(),
+            from_id: "2.0.1.0".to_string(),
+            to_id: "3.4.3.1".to_string(),
+            seconds: "1".to_string(),
+        });
+        let matches = |expression: &str| expression.parse::<Filter>().unwrap().matches(&clog);
+
+        // Positive comparisons hold when any value matches, negated ones when none does
+        assert!(matches("machine == 2.0.1.0"));
+        assert!(matches("machine == 3.4.3.1"));
+        assert!(!matches("machine != 2.0.1.0"));
+        assert!(matches("machine != 9.9.9.9"));
+        assert!(!matches("machine !~ 3.4.*"));
+        assert!(matches("machine !~ 9.*"));
+        assert!(!matches("!(machine == 2.0.1.0)"));
+        assert!(!matches("time != 100"));
+
+        // A field without values matches no comparison, so every negated one holds
+        assert!(!matches("severity == 40"));
+        assert!(!matches("severity ~ *"));
+        assert!(!matches("severity >= 0"));
+        assert!(matches("severity != 40"));
+        assert!(matches("severity !~ *"));
+    }
+
+    #[test]
+    fn test_filter_quoting() {
+        let value = |expression: &str| match expression.parse::<Filter>().unwrap() {
+            Filter::Compare { value, .. } => value,
+            filter => panic!("not a comparison: {:?}", filter),
+        };
+        assert_eq!(value(r#"type == "Clog Start""#), "Clog Start");
+        assert_eq!(value(r#"type == "Clog \"Start\"""#), r#"Clog "Start""#);
+        assert_eq!(value(r#"details == "a\\b""#), r"a\b");
+        assert_eq!(value(r#"details == "\x""#), "x");
+        assert_eq!(value(r#"details == "&& || ( ) !""#), "&& || ( ) !");
+        assert_eq!(value("machine ~ 2.0.1.*:1"), "2.0.1.*:1");
+        assert_eq!(value("details ~ /var/*"), "/var/*");
+        for raw in [r#"a"b"#, r"a\b", r#"\""#, ""] {
+            assert_eq!(value(&format!("details == {}", quote(raw))), raw);
+        }
+
+        let error = |expression: &str| expression.parse::<Filter>().unwrap_err();
+        assert_eq!(
+            error(r#"type == "abc\"#),
+            FilterError {
+                column: 9,
+                message: "unterminated string".to_string()
+            }
+        );
+        assert_eq!(error(r#"type == "x" || "y"#).column, 16);
+    }
+
+    #[test]
+    fn test_filter_error_columns() {
+        let error = |expression: &str| {
+            let error = expression.parse::<Filter>().unwrap_err();
+            (error.column, error.message)
+        };
+        let at = |column: usize, message: &str| (column, message.to_string());
+
+        assert_eq!(error("type == x &&"), at(13, "expected a field"));
+        assert_eq!(error("(type == x || )"), at(15, "expected a field"));
+        assert_eq!(error("type x"), at(6, "expected a comparison"));
+        assert_eq!(error("type # x"), at(6, "unexpected character '#'"));
+        assert_eq!(error(r#"time >= "1x""#), at(9, "'1x' is not a number"));
+        assert_eq!(error("type == x)"), at(10, "unexpected ')'"));
+        assert_eq!(error(""), at(1, "expected a field"));
+        // Columns count characters rather than bytes
+        assert_eq!(error("type == é &"), at(11, "unexpected character '&'"));
+        assert_eq!(
+            error("machin == x").1,
+            "unknown field 'machin', expected type, machine, time, details, severity or category"
+        );
+        assert_eq!(
+            "time > soon".parse::<Filter>().unwrap_err().to_string(),
+            "Invalid filter at column 8: 'soon' is not a number"
+        );
+    }
+
+    #[test]
+    fn test_filter_timeline_events_and_machines() {
+        let row = TimelineEvent {
+            timestamp: 100.5,
+            glyph: "",
+            event_type: "Clog Start".to_string(),
+            details: "2.0.1.0 -> 3.4.3.1 (1s)".to_string(),
+            source: serde_json::Value::Null,
+        };
+        let row_matches = |expression: &str| expression.parse::<Filter>().unwrap().matches(&row);
+        assert!(row_matches(r#"type == "Clog Start""#));
+        assert!(row_matches("type ~ clog*"));
+        assert!(row_matches("machine == 3.4.3.1"));
+        assert!(row_matches("machine != 2.0.1.9"));
+        assert!(row_matches("time > 100 && time < 101"));
+        assert!(row_matches(r#"details ~ "*-> 3.4*""#));
+        assert!(row_matches("category == clogs"));
+        assert!(!row_matches("severity >= 0"));
+
+        let machine = MachineInfo {
+            dc_id: Some("dc1".to_string()),
+            data_hall_id: None,
+            zone_id: Some("z1".to_string()),
+            machine_id: Some("m1".to_string()),
+            ip_address: Some("2.0.1.0:1".to_string()),
+            class_type: Some("storage".to_string()),
+        };
+        let machine_matches =
+            |expression: &str| expression.parse::<Filter>().unwrap().matches(&machine);
+        // The port is left out of the machine IP
+        assert!(machine_matches("machine == 2.0.1.0"));
+        assert!(!machine_matches(r#"machine == "2.0.1.0:1""#));
+        assert!(machine_matches(r#"details == "2.0.1.0:1""#));
+        assert!(machine_matches("details == storage && details ~ z*"));
+        assert!(!machine_matches("type ~ *"));
+        assert!(!machine_matches("category ~ *"));
+        assert!(machine_matches("time != 1"));
+    }
+
+    #[test]
+    fn test_filter_criteria() {
+        let severe = Event::SevereEvent(SevereEventData {
+            timestamp: "120".to_string(),
+            severity: "40".to_string(),
+            event_type: "StorageServerFailed".to_string(),
+            machine: Some("2.0.1.3:1".to_string()),
+            error: None,
+        });
+        let criteria = FilterCriteria {
+            event_types: vec!["severe*".to_string(), "Kill\"x".to_string()],
+            machine: Some("2.0.1.*".to_string()),
+            from: Some(100.0),
+            to: None,
+            min_severity: Some(30),
+            hidden_categories: Vec::new(),
+        };
+        let all = [
+            Field::Type,
+            Field::Machine,
+            Field::Time,
+            Field::Details,
+            Field::Severity,
+        ];
+        assert_eq!(
+            criteria.expression(&all),
+            r#"(type ~ "severe*" || type ~ "Kill\"x") && machine ~ "2.0.1.*" && time >= 100 && severity >= 30"#
+        );
+        assert!(criteria.to_filter(&all).unwrap().matches(&severe));
+        assert_eq!(
+            criteria.expression(&[Field::Machine]),
+            r#"machine ~ "2.0.1.*""#
+        );
+        assert!(criteria.to_filter(&[Field::Details]).is_none());
+        assert!(FilterCriteria::default().is_empty());
+        assert!(!"severity >= 50".parse::<Filter>().unwrap().matches(&severe));
+        let mut hidden = FilterCriteria::default();
+        hidden.toggle_category(TimelineCategory::Kills);
+        hidden.toggle_category(TimelineCategory::Disk);
+        hidden.toggle_category(TimelineCategory::Kills);
+        assert_eq!(hidden.expression(&all), "");
+        assert_eq!(
+            hidden.expression(&[Field::Category]),
+            r#"category != "disk""#
+        );
+        let row = |event_type: &str| TimelineEvent {
+            timestamp: 1.0,
+            glyph: "",
+            event_type: event_type.to_string(),
+            details: String::new(),
+            source: serde_json::Value::Null,
+        };
+        let filter = hidden.to_filter(&[Field::Category]).unwrap();
+        assert!(filter.matches(&row("Reboot")));
+        assert!(!filter.matches(&row("Disk Swap")));
+        // Events other than Timeline rows have no category
+        assert!(filter.matches(&severe));
+    }
+}
@@ -1,2 +1,25 @@
+pub mod analysis;
+pub mod annotations;
+pub mod cluster;
+pub mod ensemble;
+pub mod events;
+pub mod export;
+pub mod filter;
+pub mod lifecycle;
+pub mod line_index;
+pub mod number_format;
 pub mod parser;
+pub mod plugin;
+pub mod record;
+pub mod remote;
 pub mod report;
+pub mod report_cache;
+pub mod sampling;
+pub mod sketch;
+pub mod split;
+pub mod state;
+pub mod time_format;
+pub mod timeline;
+pub mod triage;
+pub mod watch;
+pub mod xml;
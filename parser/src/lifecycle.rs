@@ -0,0 +1,359 @@
+//! Availability of every process over simulated time.
+//!
+//! Each process address goes through a small state machine: `Up` once started,
+//! `Rebooting` after a kill that reboots it, `Down` after a kill that does not, and
+//! `Up` again when it restarts. Kills of a process that is not up do not change its
+//! state. In simulation every ProgramStart is logged by "0.0.0.0:0", so process
+//! starts are taken from the SimulatedRebooterStarting event logged right after.
+//!
+//! Downtime intervals are derived from the kills and restarts of the report: every
+//! process kill opens an interval on the killed machine, closed by the next restart
+//! of a process at the same address (a SimulatedRebooterStarting event with more than
+//! one cycle). Processes that never come back stay down until the end of the run.
+//! Kills of a process that is already down are part of the open interval.
+
+use crate::cluster::ClusterIndex;
+use crate::parser::{Event, KillType};
+use crate::report::SimulationReport;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// State of a process, see the module documentation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ProcessState {
+    Up,
+    Rebooting,
+    Down,
+}
+
+/// Consecutive `(start, end, state)` intervals of a process, in simulated seconds,
+/// from its first start to the end of the run.
+pub type Availability = Vec<(f64, f64, ProcessState)>;
+
+/// Collects the state transitions of every process as events are added.
+#[derive(Debug, Clone, Default)]
+pub struct LifecycleTracker {
+    /// Transitions by process address, e.g. "2.0.1.0:1", in the order added.
+    transitions: HashMap<String, Vec<(f64, ProcessState)>>,
+}
+
+impl LifecycleTracker {
+    /// Records the transition caused by an event, if any.
+    pub fn add(&mut self, event: &Event) {
+        let (address, state) = match event {
+            Event::RebooterStart(data) => (data.process_address(), ProcessState::Up),
+            Event::KillMachineProcess(data) => {
+                let Some(address) = data.address() else {
+                    return;
+                };
+                let kill_type = data.kill_type();
+                let state = if kill_type.is_reboot() {
+                    ProcessState::Rebooting
+                } else {
+                    ProcessState::Down
+                };
+                (address, state)
+            }
+            _ => return,
+        };
+        self.transitions
+            .entry(address.to_string())
+            .or_default()
+            .push((event.timestamp(), state));
+    }
+
+    /// The availability of every process, the last interval of each ending at
+    /// `run_end`. A process killed before any start is logged was up from time zero.
+    pub fn finish(self, run_end: f64) -> BTreeMap<String, Availability> {
+        self.transitions
+            .into_iter()
+            .map(|(address, mut transitions)| {
+                // Rolled trace files may be read out of order
+                transitions.sort_by(|a, b| a.0.total_cmp(&b.0));
+                let mut since = match transitions.first() {
+                    Some((time, ProcessState::Up)) => *time,
+                    _ => 0.0,
+                };
+                let mut state = ProcessState::Up;
+                let mut intervals = Vec::new();
+                for (time, next) in transitions {
+                    if next == state || (state != ProcessState::Up && next != ProcessState::Up) {
+                        continue;
+                    }
+                    if time > since {
+                        intervals.push((since, time, state));
+                    }
+                    since = time;
+                    state = next;
+                }
+                intervals.push((since, run_end.max(since), state));
+                (address, intervals)
+            })
+            .collect()
+    }
+}
+
+/// The part of an availability between `from` and `to` seconds.
+pub fn clip(availability: &Availability, from: f64, to: f64) -> Availability {
+    availability
+        .iter()
+        .filter(|(start, end, _)| *start < to && *end > from)
+        .map(|(start, end, state)| (start.max(from), end.min(to), *state))
+        .collect()
+}
+
+/// Share of the time a process was up over its availability, `None` when it covers
+/// no time.
+pub fn uptime(availability: &Availability) -> Option<f64> {
+    let (up, total) = availability
+        .iter()
+        .fold((0.0, 0.0), |(up, total), (start, end, state)| {
+            let seconds = end - start;
+            let up = if *state == ProcessState::Up {
+                up + seconds
+            } else {
+                up
+            };
+            (up, total + seconds)
+        });
+    (total > 0.0).then(|| up / total)
+}
+
+/// A period during which a process of a machine was down.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DowntimeInterval {
+    /// IP of the machine, e.g. "2.2.1.2".
+    pub machine: String,
+    /// Address of the killed process, e.g. "2.2.1.2:1".
+    pub address: String,
+    pub dc_id: Option<String>,
+    pub zone_id: Option<String>,
+    /// Kill time, in simulated seconds.
+    pub start: f64,
+    /// Restart time, or the end of the run when the process never restarted.
+    pub end: f64,
+    /// Kill type of the kill that took the process down.
+    pub cause: KillType,
+    /// Whether the process restarted before the end of the run.
+    pub restarted: bool,
+}
+
+impl DowntimeInterval {
+    /// Length of the interval, in simulated seconds, rounded to the microsecond
+    /// precision of trace timestamps.
+    pub fn duration(&self) -> f64 {
+        ((self.end - self.start) * 1e6).round() / 1e6
+    }
+}
+
+/// Downtime of the processes taken down by a kill type, see `downtime_by_kill_type`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DowntimeStats {
+    /// Number of kills whose process restarted.
+    pub count: usize,
+    pub min_seconds: f64,
+    pub mean_seconds: f64,
+    pub max_seconds: f64,
+}
+
+impl DowntimeStats {
+    /// Statistics of downtimes given in seconds, `None` without any.
+    fn from_durations(durations: &[f64]) -> Option<Self> {
+        Some(DowntimeStats {
+            count: durations.len(),
+            min_seconds: durations.iter().copied().reduce(f64::min)?,
+            mean_seconds: durations.iter().sum::<f64>() / durations.len() as f64,
+            max_seconds: durations.iter().copied().reduce(f64::max)?,
+        })
+    }
+}
+
+/// Downtime statistics of each kill type, over the intervals closed by a restart:
+/// the downtime of processes still down at the end of the run is unknown.
+pub fn downtime_by_kill_type(report: &SimulationReport) -> BTreeMap<KillType, DowntimeStats> {
+    let mut durations: BTreeMap<KillType, Vec<f64>> = BTreeMap::new();
+    for interval in downtime_intervals(report) {
+        if interval.restarted {
+            let duration = interval.duration();
+            durations.entry(interval.cause).or_default().push(duration);
+        }
+    }
+    durations
+        .into_iter()
+        .filter_map(|(kill_type, durations)| {
+            Some((kill_type, DowntimeStats::from_durations(&durations)?))
+        })
+        .collect()
+}
+
+/// Computes the downtime intervals of a run, sorted by start time.
+pub fn downtime_intervals(report: &SimulationReport) -> Vec<DowntimeInterval> {
+    let cluster = ClusterIndex::new(report);
+    let run_end: f64 = report
+        .elapsed_time
+        .as_deref()
+        .and_then(|t| t.parse().ok())
+        .unwrap_or(0.0);
+
+    // Restart times per process address, in time order as in the report
+    let mut restarts: HashMap<&str, Vec<f64>> = HashMap::new();
+    for restart in &report.process_restarts {
+        restarts
+            .entry(restart.process_address())
+            .or_default()
+            .push(restart.timestamp.parse().unwrap_or(0.0));
+    }
+
+    let mut intervals: Vec<DowntimeInterval> = Vec::new();
+    // End of the last interval per address, to fold kills of a process already down
+    let mut down_until: HashMap<&str, f64> = HashMap::new();
+    for kill in &report.kill_machine_processes {
+        let Some(address) = kill.address() else {
+            continue;
+        };
+        let start: f64 = kill.timestamp.parse().unwrap_or(0.0);
+        if down_until.get(address).is_some_and(|end| start < *end) {
+            continue;
+        }
+        let restart = restarts
+            .get(address)
+            .and_then(|times| times.iter().find(|t| **t >= start).copied());
+        let end = restart.unwrap_or(run_end.max(start));
+        down_until.insert(address, end);
+
+        let machine = kill.ip_address().unwrap_or(address);
+        let info = cluster.by_address(machine);
+        intervals.push(DowntimeInterval {
+            machine: machine.to_string(),
+            address: address.to_string(),
+            dc_id: info.and_then(|info| info.dc_id.clone()),
+            zone_id: info.and_then(|info| info.zone_id.clone()),
+            start,
+            end,
+            cause: kill.kill_type(),
+            restarted: restart.is_some(),
+        });
+    }
+    intervals
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{ElapsedTimeData, KillMachineProcessData, RebooterStartData};
+    use crate::report::create_simulation_report;
+    use crate::time_format::TimeFormat;
+
+    fn start(timestamp: &str, address: &str, cycles: &str) -> Event {
+        Event::RebooterStart(RebooterStartData {
+            timestamp: timestamp.to_string(),
+            address: address.to_string(),
+            zone_id: None,
+            cycles: cycles.to_string(),
+        })
+    }
+
+    fn kill(timestamp: &str, address: &str, kill_type: KillType) -> Event {
+        Event::KillMachineProcess(KillMachineProcessData {
+            timestamp: timestamp.to_string(),
+            raw_kill_type: (kill_type as u8).to_string(),
+            process: format!("name: Server address: {} zone: z", address),
+            starting_class: "storage".to_string(),
+            failed: "0".to_string(),
+            excluded: "0".to_string(),
+            cleared: "0".to_string(),
+            rebooting: "0".to_string(),
+        })
+    }
+
+    #[test]
+    fn test_lifecycle_transitions() {
+        let mut tracker = LifecycleTracker::default();
+        // Out of order, as when reading rolled trace files
+        for event in [
+            kill("100.0", "2.0.1.0:1", KillType::Reboot),
+            start("4.0", "2.0.1.0:1:tls", "1"),
+            start("110.0", "2.0.1.0:1:tls", "2"),
+            kill("200.0", "2.0.1.0:1", KillType::KillInstantly),
+            kill("250.0", "2.0.1.0:1", KillType::Reboot),
+            kill("50.0", "2.0.1.1:1", KillType::FailDisk),
+        ] {
+            tracker.add(&event);
+        }
+        let availability = tracker.finish(300.0);
+
+        assert_eq!(
+            availability["2.0.1.0:1"],
+            [
+                (4.0, 100.0, ProcessState::Up),
+                (100.0, 110.0, ProcessState::Rebooting),
+                (110.0, 200.0, ProcessState::Up),
+                (200.0, 300.0, ProcessState::Down),
+            ]
+        );
+        assert_eq!(
+            availability["2.0.1.1:1"],
+            [
+                (0.0, 50.0, ProcessState::Up),
+                (50.0, 300.0, ProcessState::Down)
+            ]
+        );
+        assert_eq!(uptime(&availability["2.0.1.1:1"]), Some(50.0 / 300.0));
+        assert_eq!(
+            clip(&availability["2.0.1.0:1"], 105.0, 150.0),
+            [
+                (105.0, 110.0, ProcessState::Rebooting),
+                (110.0, 150.0, ProcessState::Up),
+            ]
+        );
+        assert_eq!(uptime(&Vec::new()), None);
+    }
+
+    #[test]
+    fn test_downtime_by_kill_type() {
+        let events = vec![
+            kill("10", "2.0.1.0:1", KillType::Reboot),
+            start("14", "2.0.1.0:1:tls", "2"),
+            kill("20", "2.0.1.1:1", KillType::Reboot),
+            start("30", "2.0.1.1:1:tls", "2"),
+            kill("40", "2.0.1.0:1", KillType::KillInstantly),
+            start("41.5", "2.0.1.0:1:tls", "3"),
+            // Never restarted: its downtime is unknown
+            kill("50", "2.0.1.1:1", KillType::Reboot),
+            Event::ElapsedTime(ElapsedTimeData {
+                timestamp: "60".to_string(),
+                sim_time: "60".to_string(),
+                real_time: "1".to_string(),
+            }),
+        ];
+        let report = create_simulation_report(&events);
+
+        let stats = downtime_by_kill_type(&report);
+        assert_eq!(
+            stats[&KillType::Reboot],
+            DowntimeStats {
+                count: 2,
+                min_seconds: 4.0,
+                mean_seconds: 7.0,
+                max_seconds: 10.0,
+            }
+        );
+        assert_eq!(
+            stats[&KillType::KillInstantly],
+            DowntimeStats {
+                count: 1,
+                min_seconds: 1.5,
+                mean_seconds: 1.5,
+                max_seconds: 1.5,
+            }
+        );
+        assert_eq!(stats.len(), 2);
+        assert_eq!(DowntimeStats::from_durations(&[]), None);
+
+        // The report's kill table renders them as durations in the chosen format
+        let text = report.display_with(TimeFormat::Humanized).to_string();
+        assert!(text.contains("Mean Down"));
+        assert!(text.contains("1s 500ms"));
+        assert!(!text.contains("Down (s)"));
+    }
+}
@@ -0,0 +1,241 @@
+//! Random access to the lines of large trace files.
+//!
+//! `LineIndex` records the byte offset of every `LINE_INDEX_STRIDE`-th line of a
+//! trace, so that a range of lines can be re-read and parsed on demand by seeking
+//! close to it, instead of keeping every event of a multi-gigabyte trace in memory.
+//! The index of `trace.json` is cached in `trace.json.lineindex` and rebuilt when the
+//! trace changes.
+
+use crate::parser::{Event, ParsingError};
+use serde::{Deserialize, Serialize};
+use std::ffi::OsString;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Seek, SeekFrom};
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, UNIX_EPOCH};
+
+/// Number of lines between two indexed offsets. At most this many lines are skipped
+/// after seeking.
+pub const LINE_INDEX_STRIDE: usize = 1024;
+
+/// Byte offsets of regularly spaced lines of a trace file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LineIndex {
+    /// Length of the indexed file, in bytes.
+    file_len: u64,
+    /// Modification time of the indexed file, since the Unix epoch.
+    modified: Option<Duration>,
+    stride: usize,
+    /// Offset of lines 1, 1 + stride, 1 + 2 * stride, ...
+    offsets: Vec<u64>,
+    /// Number of lines of the file.
+    line_count: usize,
+}
+
+/// A line of a trace, as read by `LineIndex::read_lines`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceLine {
+    /// Line number, starting at 1 like in parsing errors.
+    pub number: usize,
+    /// The line without its terminator, invalid UTF-8 replaced.
+    pub text: String,
+    /// The event parsed from the line, `None` for event types not tracked.
+    pub event: Option<Event>,
+}
+
+/// Path of the cached index of a trace.
+pub fn index_path(trace_path: &Path) -> PathBuf {
+    let mut path = OsString::from(trace_path.as_os_str());
+    path.push(".lineindex");
+    PathBuf::from(path)
+}
+
+/// Parses a line number or an inclusive range of line numbers, e.g. "120" or
+/// "120-130".
+pub fn parse_line_range(s: &str) -> Result<RangeInclusive<usize>, String> {
+    let invalid = || format!("Invalid line range '{}', expected e.g. 120 or 120-130", s);
+    let (first, last) = s.split_once('-').unwrap_or((s, s));
+    let first: usize = first.trim().parse().map_err(|_| invalid())?;
+    let last: usize = last.trim().parse().map_err(|_| invalid())?;
+    if first == 0 || last < first {
+        return Err(invalid());
+    }
+    Ok(first..=last)
+}
+
+/// Length and modification time identifying a version of a file.
+pub(crate) fn file_version(path: &Path) -> io::Result<(u64, Option<Duration>)> {
+    let metadata = fs::metadata(path)?;
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok());
+    Ok((metadata.len(), modified))
+}
+
+impl LineIndex {
+    /// Indexes a trace file in a single pass.
+    pub fn build<P: AsRef<Path>>(trace_path: P) -> io::Result<Self> {
+        let _span = tracing::info_span!("build_line_index").entered();
+        let trace_path = trace_path.as_ref();
+        let (file_len, modified) = file_version(trace_path)?;
+        let mut reader = BufReader::new(File::open(trace_path)?);
+        let mut offsets = Vec::new();
+        let mut line_count = 0;
+        let mut offset = 0;
+        let mut line = Vec::new();
+        loop {
+            line.clear();
+            let read = reader.read_until(b'\n', &mut line)?;
+            if read == 0 {
+                break;
+            }
+            if line_count % LINE_INDEX_STRIDE == 0 {
+                offsets.push(offset);
+            }
+            line_count += 1;
+            offset += read as u64;
+        }
+        Ok(Self {
+            file_len,
+            modified,
+            stride: LINE_INDEX_STRIDE,
+            offsets,
+            line_count,
+        })
+    }
+
+    /// Loads the cached index of a trace, or builds it and tries to cache it when it is
+    /// missing or stale. Failing to write the cache, e.g. in a read-only directory, is
+    /// not an error.
+    pub fn open<P: AsRef<Path>>(trace_path: P) -> io::Result<Self> {
+        let trace_path = trace_path.as_ref();
+        let cache_path = index_path(trace_path);
+        let version = file_version(trace_path)?;
+        let cached = File::open(&cache_path)
+            .ok()
+            .and_then(|file| serde_json::from_reader::<_, Self>(BufReader::new(file)).ok())
+            .filter(|index| (index.file_len, index.modified) == version);
+        if let Some(index) = cached {
+            return Ok(index);
+        }
+
+        let index = Self::build(trace_path)?;
+        if let Ok(json) = serde_json::to_vec(&index) {
+            if let Err(e) = fs::write(&cache_path, json) {
+                tracing::debug!(path = %cache_path.display(), error = %e, "line index not cached");
+            }
+        }
+        Ok(index)
+    }
+
+    /// Number of lines of the indexed trace.
+    pub fn line_count(&self) -> usize {
+        self.line_count
+    }
+
+    /// Reads and parses the given lines of the indexed trace. Lines past the end of the
+    /// file are ignored.
+    pub fn read_lines<P: AsRef<Path>>(
+        &self,
+        trace_path: P,
+        lines: RangeInclusive<usize>,
+    ) -> Result<Vec<TraceLine>, ParsingError> {
+        let (first, last) = ((*lines.start()).max(1), *lines.end());
+        let checkpoint = (first - 1) / self.stride;
+        let Some(&offset) = self.offsets.get(checkpoint) else {
+            return Ok(Vec::new());
+        };
+
+        let mut file = File::open(trace_path)?;
+        file.seek(SeekFrom::Start(offset))?;
+        let mut reader = BufReader::new(file);
+        let mut number = checkpoint * self.stride;
+        let mut raw_line = Vec::new();
+        let mut result = Vec::new();
+        while number < last {
+            raw_line.clear();
+            if reader.read_until(b'\n', &mut raw_line)? == 0 {
+                break;
+            }
+            number += 1;
+            if number < first {
+                continue;
+            }
+            let text = String::from_utf8_lossy(&raw_line)
+                .trim_end_matches(['\r', '\n'])
+                .to_string();
+            let event = Event::try_from(text.as_str()).ok();
+            result.push(TraceLine {
+                number,
+                text,
+                event,
+            });
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_log_file;
+
+    #[test]
+    fn test_read_lines() {
+        let trace = "logs/combined_trace.0.0.0.0.24.1745498878.p7Loj0.json";
+        let index = LineIndex::build(trace).unwrap();
+        let all_lines: Vec<String> = fs::read_to_string(trace)
+            .unwrap()
+            .lines()
+            .map(str::to_string)
+            .collect();
+        assert_eq!(index.line_count(), all_lines.len());
+        assert!(index.line_count() > 2 * LINE_INDEX_STRIDE);
+
+        // A range spanning a checkpoint
+        let first = LINE_INDEX_STRIDE - 2;
+        let lines = index.read_lines(trace, first..=first + 4).unwrap();
+        assert_eq!(lines.len(), 5);
+        assert_eq!(lines[0].number, first);
+        for line in &lines {
+            assert_eq!(line.text, all_lines[line.number - 1]);
+        }
+
+        // Same events as a full parse
+        let events: Vec<Event> = index
+            .read_lines(trace, 1..=index.line_count())
+            .unwrap()
+            .into_iter()
+            .filter_map(|line| line.event)
+            .collect();
+        assert_eq!(events, parse_log_file(trace).unwrap());
+
+        let past_end = index.line_count() + 1;
+        assert!(index
+            .read_lines(trace, past_end..=past_end + 10)
+            .unwrap()
+            .is_empty());
+
+        assert_eq!(parse_line_range("120"), Ok(120..=120));
+        assert_eq!(parse_line_range("120-130"), Ok(120..=130));
+        assert!(parse_line_range("130-120").is_err());
+        assert!(parse_line_range("0").is_err());
+    }
+
+    #[test]
+    fn test_cached_index() {
+        let dir = tempfile::tempdir().unwrap();
+        let trace = dir.path().join("trace.json");
+        fs::write(&trace, "{\"Type\": \"A\"}\n{\"Type\": \"B\"}\n").unwrap();
+
+        let index = LineIndex::open(&trace).unwrap();
+        assert!(index_path(&trace).exists());
+        assert_eq!(LineIndex::open(&trace).unwrap(), index);
+
+        // A changed trace invalidates the cache
+        fs::write(&trace, "{\"Type\": \"A\"}\n").unwrap();
+        assert_eq!(LineIndex::open(&trace).unwrap().line_count(), 1);
+    }
+}
@@ -0,0 +1,92 @@
+//! Rendering of counts and decimals in report tables.
+//!
+//! Numbers are formatted by Rust's own formatting machinery and parsed with
+//! `str::parse`, neither of which depends on the locale, so traces and reports read
+//! the same everywhere. Grouping of thousands is therefore opt-in and always uses
+//! commas, e.g. "1,234,567".
+
+/// How counts and decimals are displayed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NumberFormat {
+    /// Group the digits of integer parts by thousands with commas.
+    pub group_thousands: bool,
+    /// Number of decimals of every decimal column, instead of each column's own
+    /// precision.
+    pub decimals: Option<usize>,
+}
+
+impl NumberFormat {
+    /// Formats a count, e.g. "1234567" or "1,234,567".
+    pub fn count(&self, count: usize) -> String {
+        let digits = count.to_string();
+        if self.group_thousands {
+            group_thousands(&digits)
+        } else {
+            digits
+        }
+    }
+
+    /// Formats a decimal with `default_decimals` decimals unless `decimals` is set.
+    pub fn decimal(&self, value: f64, default_decimals: usize) -> String {
+        let formatted = format!("{:.*}", self.decimals.unwrap_or(default_decimals), value);
+        if !self.group_thousands || !value.is_finite() {
+            return formatted;
+        }
+        let (sign, unsigned) = match formatted.strip_prefix('-') {
+            Some(unsigned) => ("-", unsigned),
+            None => ("", formatted.as_str()),
+        };
+        let (integer, fraction) = match unsigned.split_once('.') {
+            Some((integer, fraction)) => (integer, Some(fraction)),
+            None => (unsigned, None),
+        };
+        let mut grouped = format!("{}{}", sign, group_thousands(integer));
+        if let Some(fraction) = fraction {
+            grouped.push('.');
+            grouped.push_str(fraction);
+        }
+        grouped
+    }
+}
+
+/// Inserts a comma every three digits of a string of digits, from the right.
+fn group_thousands(digits: &str) -> String {
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_number_format() {
+        let plain = NumberFormat::default();
+        assert_eq!(plain.count(1234567), "1234567");
+        assert_eq!(plain.decimal(1234.5, 3), "1234.500");
+
+        let grouped = NumberFormat {
+            group_thousands: true,
+            decimals: Some(1),
+        };
+        assert_eq!(grouped.count(1234567), "1,234,567");
+        assert_eq!(grouped.count(999), "999");
+        assert_eq!(grouped.count(0), "0");
+        assert_eq!(grouped.decimal(-1234567.25, 6), "-1,234,567.2");
+        assert_eq!(grouped.decimal(f64::NAN, 6), "NaN");
+        assert_eq!(
+            NumberFormat {
+                group_thousands: true,
+                decimals: Some(0),
+            }
+            .decimal(1000.4, 3),
+            "1,000"
+        );
+    }
+}
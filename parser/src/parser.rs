@@ -1,11 +1,24 @@
+use crate::analysis::AnalysisRegistry;
+use crate::ensemble::trace_files;
+use crate::plugin::{CustomEvent, DynamicEvent};
+use crate::remote::{self, remote_location, RemoteError};
+use crate::sampling::Sampling;
+use crate::xml;
+use rayon::prelude::*;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonNode;
-use std::collections::HashMap;
-use std::fs::File;
-use std::io::{self, BufRead, BufReader};
-use std::path::Path;
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap};
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::sync::Arc;
+use std::thread;
+use std::time::Instant;
 use thiserror::Error;
 
 /// Represents different types of log events.
@@ -33,6 +46,40 @@ pub enum Event {
     KillMachineProcess(KillMachineProcessData),
     /// Represents a SimulatorConfig event.
     SimulatorConfig(SimulatorConfigData),
+    /// Represents a StorageServerAddedToRunningStorage event.
+    StorageServerAdded(StorageServerAddedData),
+    /// Represents a StorageServerRemoveFromRunningStorage event.
+    StorageServerRemoved(StorageServerRemovedData),
+    /// Represents a failure to recruit workers (e.g. RecruitStorageNotAvailable).
+    RecruitmentFailure(RecruitmentFailureData),
+    /// Represents a BuggifySection event.
+    BuggifySection(BuggifySectionData),
+    /// Represents a Knob event, logging the value of a knob at startup.
+    Knob(KnobData),
+    /// Represents an Assassination event, where a workload kills a whole zone.
+    Assassination(AssassinationData),
+    /// Represents a SimulatedRebooterStarting event, logged whenever a simulated
+    /// process starts, including restarts after kills and reboots.
+    RebooterStart(RebooterStartData),
+    /// Represents a Role event, logged when a process starts, refreshes or stops
+    /// acting as a role such as TLog or StorageServer.
+    Role(RoleData),
+    /// Represents a TestResults event, logged by the tester with the outcome of a test.
+    TestResults(TestResultsData),
+    /// Represents a TestComplete event, logged by every tester client once it is done
+    /// running a workload.
+    TestComplete(TestCompleteData),
+    /// Represents a MasterRecoveryState event, logged by the master at every step of a
+    /// recovery of the transaction system.
+    RecoveryState(RecoveryStateData),
+    /// Represents any other event logged with Severity 30 (warning) or above.
+    SevereEvent(SevereEventData),
+    /// Represents an event of any other type, only captured with
+    /// `ParseOptions::capture_other`.
+    Other(OtherEventData),
+    /// Represents an event of a type defined outside of this crate, see
+    /// `ParseOptions::register_event_parser`.
+    Custom(CustomEvent),
     // Add other specific event variants here
 }
 
@@ -50,9 +97,9 @@ pub struct CloggingPairData {
     pub seconds: String, // Match JSON string type
 }
 
-impl Into<Event> for CloggingPairData {
-    fn into(self) -> Event {
-        Event::CloggingPair(self)
+impl From<CloggingPairData> for Event {
+    fn from(data: CloggingPairData) -> Self {
+        Event::CloggingPair(data)
     }
 }
 
@@ -70,9 +117,9 @@ pub struct ClogInterfaceData {
     // Severity, DateTime, ID, ThreadID, LogGroup, Roles ignored
 }
 
-impl Into<Event> for ClogInterfaceData {
-    fn into(self) -> Event {
-        Event::ClogInterface(self)
+impl From<ClogInterfaceData> for Event {
+    fn from(data: ClogInterfaceData) -> Self {
+        Event::ClogInterface(data)
     }
 }
 
@@ -89,9 +136,9 @@ pub struct ElapsedTimeData {
     // Severity, DateTime, Machine, ID, ThreadID, LogGroup ignored
 }
 
-impl Into<Event> for ElapsedTimeData {
-    fn into(self) -> Event {
-        Event::ElapsedTime(self)
+impl From<ElapsedTimeData> for Event {
+    fn from(data: ElapsedTimeData) -> Self {
+        Event::ElapsedTime(data)
     }
 }
 
@@ -137,9 +184,9 @@ impl SimulatedMachineStartData {
     }
 }
 
-impl Into<Event> for SimulatedMachineStartData {
-    fn into(self) -> Event {
-        Event::SimulatedMachineStart(self)
+impl From<SimulatedMachineStartData> for Event {
+    fn from(data: SimulatedMachineStartData) -> Self {
+        Event::SimulatedMachineStart(data)
     }
 }
 
@@ -153,9 +200,68 @@ pub struct CoordinatorsChangeData {
     // Other fields ignored: Severity, DateTime, Machine, ID, Auto, ThreadID, LogGroup, Roles
 }
 
-impl Into<Event> for CoordinatorsChangeData {
-    fn into(self) -> Event {
-        Event::CoordinatorsChange(self)
+impl CoordinatorsChangeData {
+    /// The coordinators of the new connection string, see `parse_coordinators`.
+    pub fn coordinators(&self) -> Vec<CoordinatorAddress> {
+        parse_coordinators(&self.new_coordinators_key)
+    }
+}
+
+/// Address of a coordinator in a connection string, e.g. "2.0.1.0:1:tls".
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct CoordinatorAddress {
+    /// IP, without brackets for IPv6, or hostname.
+    pub ip: String,
+    pub port: u16,
+    /// Whether the coordinator is reached over TLS.
+    pub tls: bool,
+}
+
+impl fmt::Display for CoordinatorAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.ip.contains(':') {
+            write!(f, "[{}]:{}", self.ip, self.port)?;
+        } else {
+            write!(f, "{}:{}", self.ip, self.port)?;
+        }
+        if self.tls {
+            write!(f, ":tls")?;
+        }
+        Ok(())
+    }
+}
+
+/// Decodes the coordinators of a connection string such as
+/// "TestCluster:0@2.0.1.0:1:tls,2.0.1.1:1:tls", sorted. Entries that are not
+/// `<ip>:<port>[:tls]` are skipped; an empty string has no coordinators.
+pub fn parse_coordinators(connection_string: &str) -> Vec<CoordinatorAddress> {
+    let addresses = connection_string
+        .rsplit_once('@')
+        .map_or(connection_string, |(_, addresses)| addresses);
+    let mut coordinators: Vec<CoordinatorAddress> = addresses
+        .split(',')
+        .filter_map(|address| {
+            let address = address.trim();
+            let (address, tls) = match address.strip_suffix(":tls") {
+                Some(address) => (address, true),
+                None => (address, false),
+            };
+            let (ip, port) = address.rsplit_once(':')?;
+            let ip = ip.trim_start_matches('[').trim_end_matches(']');
+            Some(CoordinatorAddress {
+                ip: (!ip.is_empty()).then(|| ip.to_string())?,
+                port: port.parse().ok()?,
+                tls,
+            })
+        })
+        .collect();
+    coordinators.sort();
+    coordinators
+}
+
+impl From<CoordinatorsChangeData> for Event {
+    fn from(data: CoordinatorsChangeData) -> Self {
+        Event::CoordinatorsChange(data)
     }
 }
 
@@ -168,11 +274,19 @@ pub struct ProgramStartData {
     pub machine: String,
     #[serde(rename = "RandomSeed")]
     pub random_seed: Option<String>, // Seed might not be present in all ProgramStart events
+    #[serde(rename = "DateTime")]
+    pub date_time: Option<String>, // Wall-clock time the process started
+    #[serde(rename = "Version", default)]
+    pub version: Option<String>, // fdbserver version, e.g. "7.3.43"
+    #[serde(rename = "CommandLine", default)]
+    pub command_line: Option<String>, // fdbserver command line of the run
+    #[serde(rename = "ConnectionString", default)]
+    pub connection_string: Option<String>, // Coordinators when the process started
 }
 
-impl Into<Event> for ProgramStartData {
-    fn into(self) -> Event {
-        Event::ProgramStart(self)
+impl From<ProgramStartData> for Event {
+    fn from(data: ProgramStartData) -> Self {
+        Event::ProgramStart(data)
     }
 }
 
@@ -181,14 +295,24 @@ impl Into<Event> for ProgramStartData {
 pub struct DiskSwapData {
     #[serde(rename = "Time")]
     pub timestamp: String,
+    /// Addresses of the swapped machines as logged, a bracketed list; see `ips`.
     #[serde(rename = "MachineIPs")]
-    pub machine_ips: String, // Assuming this is a single string like "[ip1,ip2,...]"
-                             // Consider parsing this further if needed
+    pub machine_ips: String,
 }
 
-impl Into<Event> for DiskSwapData {
-    fn into(self) -> Event {
-        Event::DiskSwap(self)
+impl DiskSwapData {
+    /// IPs of the swapped machines, parsed from the `MachineIPs` list.
+    pub fn ips(&self) -> Vec<String> {
+        split_ip_list(&self.machine_ips)
+            .into_iter()
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+impl From<DiskSwapData> for Event {
+    fn from(data: DiskSwapData) -> Self {
+        Event::DiskSwap(data)
     }
 }
 
@@ -212,9 +336,9 @@ pub struct SetDiskFailureData {
     // Other fields ignored for now: Severity, DateTime, ID, Now, ThreadID, LogGroup, Roles
 }
 
-impl Into<Event> for SetDiskFailureData {
-    fn into(self) -> Event {
-        Event::SetDiskFailure(self)
+impl From<SetDiskFailureData> for Event {
+    fn from(data: SetDiskFailureData) -> Self {
+        Event::SetDiskFailure(data)
     }
 }
 
@@ -241,9 +365,9 @@ pub struct CorruptedBlockData {
     pub roles: Option<String>,
 }
 
-impl Into<Event> for CorruptedBlockData {
-    fn into(self) -> Event {
-        Event::CorruptedBlock(self)
+impl From<CorruptedBlockData> for Event {
+    fn from(data: CorruptedBlockData) -> Self {
+        Event::CorruptedBlock(data)
     }
 }
 
@@ -261,6 +385,57 @@ pub enum KillType {
     Unknown,                // Added for parsing errors
 }
 
+impl KillType {
+    /// Kill types the simulator can inject, in KillType order.
+    pub const INJECTABLE: [KillType; 8] = [
+        KillType::KillInstantly,
+        KillType::InjectFaults,
+        KillType::FailDisk,
+        KillType::RebootAndDelete,
+        KillType::RebootProcessAndDelete,
+        KillType::RebootProcessAndSwitch,
+        KillType::Reboot,
+        KillType::RebootProcess,
+    ];
+
+    /// Short explanation of what the simulator does for this kill type, for legends
+    /// and tooltips.
+    pub fn description(&self) -> &'static str {
+        match self {
+            KillType::KillInstantly => {
+                "Kills the machine on the spot; it does not come back and its data is lost"
+            }
+            KillType::InjectFaults => {
+                "Makes the processes fail random I/O and network operations until they crash"
+            }
+            KillType::FailDisk => "Fails the machine's disk; processes die and lose their data",
+            KillType::RebootAndDelete => "Reboots the whole machine and deletes its data files",
+            KillType::RebootProcessAndDelete => {
+                "Reboots a single process and deletes its data files"
+            }
+            KillType::RebootProcessAndSwitch => {
+                "Reboots a process onto a different cluster file; data is kept"
+            }
+            KillType::Reboot => "Cleanly reboots the whole machine; durable data is kept",
+            KillType::RebootProcess => "Cleanly reboots a single process; durable data is kept",
+            KillType::None => "No kill was performed",
+            KillType::Unknown => "Kill type not recognized by this tool",
+        }
+    }
+
+    /// Whether the killed processes are rebooted, rather than left down.
+    pub fn is_reboot(&self) -> bool {
+        matches!(
+            self,
+            KillType::RebootAndDelete
+                | KillType::RebootProcessAndDelete
+                | KillType::RebootProcessAndSwitch
+                | KillType::Reboot
+                | KillType::RebootProcess
+        )
+    }
+}
+
 impl FromStr for KillType {
     type Err = std::num::ParseIntError;
 
@@ -300,9 +475,31 @@ pub struct KillMachineProcessData {
     pub rebooting: String,
 }
 
-impl Into<Event> for KillMachineProcessData {
-    fn into(self) -> Event {
-        Event::KillMachineProcess(self)
+impl KillMachineProcessData {
+    /// Decodes the numeric `KillType` field, `KillType::Unknown` for values of other
+    /// FoundationDB versions.
+    pub fn kill_type(&self) -> KillType {
+        KillType::from_str(&self.raw_kill_type).unwrap_or(KillType::Unknown)
+    }
+
+    /// Returns the address of the killed process (e.g. "2.2.1.2:1"), extracted from
+    /// the `Process` description (e.g. "name: Server address: 2.2.1.2:1 zone: ...").
+    pub fn address(&self) -> Option<&str> {
+        self.process
+            .split("address: ")
+            .nth(1)
+            .and_then(|addr_part| addr_part.split(' ').next())
+    }
+
+    /// Returns the IP address of the killed process.
+    pub fn ip_address(&self) -> Option<&str> {
+        self.address().map(ip_from_address)
+    }
+}
+
+impl From<KillMachineProcessData> for Event {
+    fn from(data: KillMachineProcessData) -> Self {
+        Event::KillMachineProcess(data)
     }
 }
 
@@ -316,7 +513,7 @@ pub struct SimulatorConfigData {
     #[serde(rename = "ConfigString")]
     pub config_string: String,
     #[serde(default)]
-    pub config: HashMap<String, String>,
+    pub config: BTreeMap<String, String>,
 }
 
 impl SimulatorConfigData {
@@ -342,145 +539,1679 @@ impl SimulatorConfigData {
     }
 }
 
-impl Into<Event> for SimulatorConfigData {
-    fn into(self) -> Event {
-        Event::SimulatorConfig(self)
-    }
-}
+impl From<SimulatorConfigData> for Event {
+    fn from(data: SimulatorConfigData) -> Self {
+        Event::SimulatorConfig(data)
+    }
+}
+
+/// Data specific to a StorageServerAddedToRunningStorage event.
+#[derive(Debug, Deserialize, PartialEq, Clone, Serialize)]
+pub struct StorageServerAddedData {
+    #[serde(rename = "Time")]
+    pub timestamp: String,
+    #[serde(rename = "Machine")]
+    pub machine: String,
+    #[serde(rename = "ID")]
+    pub server_id: String, // The storage server's UID
+}
+
+impl From<StorageServerAddedData> for Event {
+    fn from(data: StorageServerAddedData) -> Self {
+        Event::StorageServerAdded(data)
+    }
+}
+
+/// Data specific to a StorageServerRemoveFromRunningStorage event.
+#[derive(Debug, Deserialize, PartialEq, Clone, Serialize)]
+pub struct StorageServerRemovedData {
+    #[serde(rename = "Time")]
+    pub timestamp: String,
+    #[serde(rename = "Machine")]
+    pub machine: String,
+    #[serde(rename = "ID")]
+    pub server_id: String,
+}
+
+impl From<StorageServerRemovedData> for Event {
+    fn from(data: StorageServerRemovedData) -> Self {
+        Event::StorageServerRemoved(data)
+    }
+}
+
+/// Data specific to recruitment failure events, such as RecruitStorageNotAvailable
+/// or ClusterRecoveryRetrying with `no_more_servers`.
+#[derive(Debug, Deserialize, PartialEq, Clone, Serialize)]
+pub struct RecruitmentFailureData {
+    #[serde(rename = "Time")]
+    pub timestamp: String,
+    #[serde(rename = "Severity", default)]
+    pub severity: Option<String>, // Usually 30, these are warnings
+    #[serde(rename = "Type")]
+    pub event_type: String, // Keeps track of which recruitment failed
+    #[serde(rename = "Machine")]
+    pub machine: String,
+    #[serde(rename = "Error")]
+    pub error: Option<String>,
+    #[serde(rename = "ErrorDescription")]
+    pub error_description: Option<String>,
+    #[serde(rename = "IsCriticalRecruitment")]
+    pub is_critical: Option<String>, // Only present on RecruitStorageNotAvailable
+}
+
+impl From<RecruitmentFailureData> for Event {
+    fn from(data: RecruitmentFailureData) -> Self {
+        Event::RecruitmentFailure(data)
+    }
+}
+
+/// Data specific to a BuggifySection event, logged the first time a buggify point
+/// is evaluated.
+#[derive(Debug, Deserialize, PartialEq, Clone, Serialize)]
+pub struct BuggifySectionData {
+    #[serde(rename = "Time")]
+    pub timestamp: String,
+    #[serde(rename = "Activated")]
+    pub activated: String, // "1" if the buggify point was enabled for this run
+    #[serde(rename = "File")]
+    pub file: String,
+    #[serde(rename = "Line")]
+    pub line: String,
+}
+
+impl BuggifySectionData {
+    /// Returns whether the buggify point was enabled for this run.
+    pub fn is_activated(&self) -> bool {
+        self.activated == "1"
+    }
+
+    /// Identifies the buggify point as "file:line", keeping only the file name.
+    pub fn location(&self) -> String {
+        let file = self.file.rsplit('/').next().unwrap_or(&self.file);
+        format!("{}:{}", file, self.line)
+    }
+}
+
+impl From<BuggifySectionData> for Event {
+    fn from(data: BuggifySectionData) -> Self {
+        Event::BuggifySection(data)
+    }
+}
+
+/// Data specific to a Knob event. Every knob is logged with its effective value,
+/// including those randomized or overridden for the run.
+#[derive(Debug, Deserialize, PartialEq, Clone, Serialize)]
+pub struct KnobData {
+    #[serde(rename = "Time")]
+    pub timestamp: String,
+    #[serde(rename = "Name")]
+    pub name: String,
+    #[serde(rename = "Value")]
+    pub value: String,
+}
+
+impl From<KnobData> for Event {
+    fn from(data: KnobData) -> Self {
+        Event::Knob(data)
+    }
+}
+
+/// Data specific to an Assassination event, logged by the Attrition workload when it
+/// kills or reboots every machine of a zone.
+#[derive(Debug, Deserialize, PartialEq, Clone, Serialize)]
+pub struct AssassinationData {
+    #[serde(rename = "Time")]
+    pub timestamp: String,
+    #[serde(rename = "TargetMachine")]
+    pub target_machine: String, // e.g. "zoneid=... processid=[unset] machineid=... dcid=2 data_hall=2"
+    #[serde(rename = "ZoneId")]
+    pub zone_id: String,
+    #[serde(rename = "Reboot")]
+    pub reboot: String, // "1" when machines are rebooted rather than killed
+}
+
+impl AssassinationData {
+    /// Returns whether the zone's machines are rebooted rather than killed.
+    pub fn is_reboot(&self) -> bool {
+        self.reboot == "1"
+    }
+
+    /// Returns the machine ID from the `TargetMachine` locality description.
+    pub fn target_machine_id(&self) -> Option<&str> {
+        self.target_machine
+            .split(' ')
+            .find_map(|part| part.strip_prefix("machineid="))
+    }
+}
+
+impl From<AssassinationData> for Event {
+    fn from(data: AssassinationData) -> Self {
+        Event::Assassination(data)
+    }
+}
+
+/// Data specific to a SimulatedRebooterStarting event.
+#[derive(Debug, Deserialize, PartialEq, Clone, Serialize)]
+pub struct RebooterStartData {
+    #[serde(rename = "Time")]
+    pub timestamp: String,
+    #[serde(rename = "Address")]
+    pub address: String, // e.g. "2.1.1.4:1:tls"
+    #[serde(rename = "ZoneId", default)]
+    pub zone_id: Option<String>,
+    #[serde(rename = "Cycles")]
+    pub cycles: String, // "1" on the first start, incremented on every restart
+}
+
+impl RebooterStartData {
+    /// Returns whether the process is starting again after a kill or reboot.
+    pub fn is_restart(&self) -> bool {
+        self.cycles.parse::<u32>().is_ok_and(|cycles| cycles > 1)
+    }
+
+    /// Returns the process address without the ":tls" suffix, e.g. "2.1.1.4:1".
+    pub fn process_address(&self) -> &str {
+        self.address.strip_suffix(":tls").unwrap_or(&self.address)
+    }
+}
+
+impl From<RebooterStartData> for Event {
+    fn from(data: RebooterStartData) -> Self {
+        Event::RebooterStart(data)
+    }
+}
+
+/// Data specific to a Role event.
+#[derive(Debug, Deserialize, PartialEq, Clone, Serialize)]
+pub struct RoleData {
+    #[serde(rename = "Time")]
+    pub timestamp: String,
+    #[serde(rename = "Machine")]
+    pub machine: String, // Address of the process, e.g. "2.0.1.2:1"
+    #[serde(rename = "ID")]
+    pub id: String, // ID of the role instance, shared by its Begin and End events
+    #[serde(rename = "As")]
+    pub role: String, // e.g. "TLog", "StorageServer", "Worker"
+    #[serde(rename = "Transition")]
+    pub transition: String, // "Begin", "Refresh" or "End"
+    #[serde(rename = "Roles", default)]
+    pub roles: Option<String>, // Roles held by the process, e.g. "SS,TL", see `role_name`
+                               // Other fields ignored: Severity, DateTime, Origination, OnWorker, ThreadID, LogGroup
+}
+
+/// Returns the name of a role abbreviation of the `Roles` trace field, e.g.
+/// "Storage Server" for "SS", or `None` for abbreviations this tool does not know.
+pub fn role_name(abbreviation: &str) -> Option<&'static str> {
+    Some(match abbreviation {
+        "BK" => "Backup Worker",
+        "BM" => "Blob Manager",
+        "BW" => "Blob Worker",
+        "CC" => "Cluster Controller",
+        "CD" => "Coordinator",
+        "CP" => "Commit Proxy",
+        "CS" => "Consistency Scan",
+        "DD" => "Data Distributor",
+        "EK" => "Encrypt Key Proxy",
+        "GP" => "GRV Proxy",
+        "LR" => "Log Router",
+        "MS" => "Master",
+        "RK" => "Ratekeeper",
+        "RV" => "Resolver",
+        "SC" => "Storage Cache",
+        "SS" => "Storage Server",
+        "TL" => "TLog",
+        "TS" => "Tester",
+        _ => return None,
+    })
+}
+
+impl RoleData {
+    /// Returns whether the process stops acting as the role.
+    pub fn is_end(&self) -> bool {
+        self.transition == "End"
+    }
+}
+
+impl From<RoleData> for Event {
+    fn from(data: RoleData) -> Self {
+        Event::Role(data)
+    }
+}
+
+/// Data specific to a TestResults event.
+#[derive(Debug, Deserialize, PartialEq, Clone, Serialize)]
+pub struct TestResultsData {
+    #[serde(rename = "Time")]
+    pub timestamp: String,
+    #[serde(rename = "Workload")]
+    pub workload: String, // Title of the test, e.g. "LogicalDBWorkload"
+    #[serde(rename = "Passed")]
+    pub passed: String, // "1" when the test passed
+    #[serde(rename = "Severity", default)]
+    pub severity: Option<String>, // 30 for failed tests
+}
+
+impl TestResultsData {
+    /// Returns whether the test passed.
+    pub fn is_passed(&self) -> bool {
+        self.passed == "1"
+    }
+}
+
+impl From<TestResultsData> for Event {
+    fn from(data: TestResultsData) -> Self {
+        Event::TestResults(data)
+    }
+}
+
+/// Data specific to a TestComplete event.
+#[derive(Debug, Deserialize, PartialEq, Clone, Serialize)]
+pub struct TestCompleteData {
+    #[serde(rename = "Time")]
+    pub timestamp: String,
+    #[serde(rename = "Machine")]
+    pub machine: String, // Address of the tester client
+    #[serde(rename = "Workload")]
+    pub workload: String, // e.g. "ConsistencyCheck" or "External;RandomClogging;Attrition"
+    #[serde(rename = "OK")]
+    pub ok: String, // "1" when the workload succeeded on this client
+}
+
+impl TestCompleteData {
+    /// Returns whether the workload succeeded on this client.
+    pub fn is_ok(&self) -> bool {
+        self.ok == "1"
+    }
+}
+
+impl From<TestCompleteData> for Event {
+    fn from(data: TestCompleteData) -> Self {
+        Event::TestComplete(data)
+    }
+}
+
+/// Status code of the last step of a recovery, once the cluster is fully recovered.
+pub const RECOVERY_STATUS_FULLY_RECOVERED: &str = "14";
+
+/// Data specific to a MasterRecoveryState event.
+#[derive(Debug, Deserialize, PartialEq, Clone, Serialize)]
+pub struct RecoveryStateData {
+    #[serde(rename = "Time")]
+    pub timestamp: String,
+    #[serde(rename = "Machine")]
+    pub machine: String, // Address of the master (or cluster controller) process
+    #[serde(rename = "ID")]
+    pub id: String, // ID of the recovery, shared by all of its steps
+    #[serde(rename = "StatusCode", default)]
+    pub status_code: Option<String>, // e.g. "0", missing on periodic refreshes
+    #[serde(rename = "Status", default)]
+    pub status: Option<String>, // e.g. "reading_coordinated_state", "fully_recovered"
+}
+
+impl RecoveryStateData {
+    /// Returns whether the cluster is fully recovered at this step.
+    pub fn is_fully_recovered(&self) -> bool {
+        self.status_code.as_deref() == Some(RECOVERY_STATUS_FULLY_RECOVERED)
+    }
+}
+
+impl From<RecoveryStateData> for Event {
+    fn from(data: RecoveryStateData) -> Self {
+        Event::RecoveryState(data)
+    }
+}
+
+/// Lowest severity of events captured as `Event::SevereEvent`, i.e. warnings.
+pub const SEVERITY_WARNING: u32 = 30;
+
+/// Severity of errors, which fail the test.
+pub const SEVERITY_ERROR: u32 = 40;
+
+/// Data of an event of an otherwise unparsed type logged with Severity 30 or above.
+#[derive(Debug, Deserialize, PartialEq, Clone, Serialize)]
+pub struct SevereEventData {
+    #[serde(rename = "Time")]
+    pub timestamp: String,
+    #[serde(rename = "Severity")]
+    pub severity: String,
+    #[serde(rename = "Type")]
+    pub event_type: String,
+    #[serde(rename = "Machine", default)]
+    pub machine: Option<String>,
+    #[serde(rename = "Error", default)]
+    pub error: Option<String>,
+}
+
+impl SevereEventData {
+    /// Returns the numeric severity, 0 if it does not parse.
+    pub fn severity(&self) -> u32 {
+        self.severity.parse().unwrap_or(0)
+    }
+}
+
+impl From<SevereEventData> for Event {
+    fn from(data: SevereEventData) -> Self {
+        Event::SevereEvent(data)
+    }
+}
+
+/// Data of an event of a type the parser does not model, with all of its fields.
+#[derive(Debug, Deserialize, PartialEq, Clone, Serialize)]
+pub struct OtherEventData {
+    #[serde(rename = "Time", default)]
+    pub timestamp: String,
+    #[serde(rename = "Type")]
+    pub event_type: String,
+    /// Every other field of the event, e.g. "Severity" and "Machine".
+    #[serde(flatten)]
+    pub fields: BTreeMap<String, String>,
+}
+
+impl From<OtherEventData> for Event {
+    fn from(data: OtherEventData) -> Self {
+        Event::Other(data)
+    }
+}
+
+impl Event {
+    /// Returns the timestamp associated with the event, parsed from string.
+    /// Returns 0.0 if parsing fails.
+    /// For ElapsedTime events, this uses the SimTime field.
+    pub fn timestamp(&self) -> f64 {
+        match self {
+            // Parse the timestamp string to f64
+            Event::CloggingPair(data) => data.timestamp.parse().unwrap_or(0.0),
+            Event::ClogInterface(data) => data.timestamp.parse().unwrap_or(0.0),
+            Event::ElapsedTime(data) => data.timestamp.parse().unwrap_or(0.0),
+            Event::SimulatedMachineStart(data) => data.timestamp.parse().unwrap_or(0.0),
+            Event::CoordinatorsChange(data) => data.timestamp.parse().unwrap_or(0.0),
+            Event::ProgramStart(data) => data.timestamp.parse().unwrap_or(0.0),
+            Event::DiskSwap(data) => data.timestamp.parse().unwrap_or(0.0),
+            Event::SetDiskFailure(data) => data.timestamp.parse().unwrap_or(0.0),
+            Event::CorruptedBlock(data) => data.time.parse().unwrap_or(0.0),
+            Event::KillMachineProcess(data) => data.timestamp.parse().unwrap_or(0.0),
+            Event::SimulatorConfig(data) => data.timestamp.parse().unwrap_or(0.0),
+            Event::StorageServerAdded(data) => data.timestamp.parse().unwrap_or(0.0),
+            Event::StorageServerRemoved(data) => data.timestamp.parse().unwrap_or(0.0),
+            Event::RecruitmentFailure(data) => data.timestamp.parse().unwrap_or(0.0),
+            Event::BuggifySection(data) => data.timestamp.parse().unwrap_or(0.0),
+            Event::Knob(data) => data.timestamp.parse().unwrap_or(0.0),
+            Event::Assassination(data) => data.timestamp.parse().unwrap_or(0.0),
+            Event::RebooterStart(data) => data.timestamp.parse().unwrap_or(0.0),
+            Event::Role(data) => data.timestamp.parse().unwrap_or(0.0),
+            Event::TestResults(data) => data.timestamp.parse().unwrap_or(0.0),
+            Event::TestComplete(data) => data.timestamp.parse().unwrap_or(0.0),
+            Event::RecoveryState(data) => data.timestamp.parse().unwrap_or(0.0),
+            Event::SevereEvent(data) => data.timestamp.parse().unwrap_or(0.0),
+            Event::Other(data) => data.timestamp.parse().unwrap_or(0.0),
+            Event::Custom(event) => event.timestamp(),
+        }
+    }
+
+    /// Returns whether the event is chaos injected by the simulator: clogs, kills,
+    /// disk faults and assassinations.
+    pub fn is_chaos(&self) -> bool {
+        matches!(
+            self,
+            Event::CloggingPair(_)
+                | Event::ClogInterface(_)
+                | Event::KillMachineProcess(_)
+                | Event::SetDiskFailure(_)
+                | Event::CorruptedBlock(_)
+                | Event::DiskSwap(_)
+                | Event::Assassination(_)
+        )
+    }
+
+    /// Returns the IPs of the machines the event is about: both ends of a clog, the
+    /// target of a kill and the logging process otherwise. Assassinations designate a
+    /// zone rather than machines and have none.
+    pub fn machine_ips(&self) -> Vec<&str> {
+        match self {
+            Event::CloggingPair(data) => {
+                vec![ip_from_address(&data.from_id), ip_from_address(&data.to_id)]
+            }
+            Event::ClogInterface(data) => vec![ip_from_address(&data.ip)],
+            Event::SimulatedMachineStart(data) => {
+                split_ip_list(data.machine_ips.as_deref().unwrap_or(""))
+            }
+            Event::DiskSwap(data) => split_ip_list(&data.machine_ips),
+            Event::KillMachineProcess(data) => data.ip_address().into_iter().collect(),
+            Event::RebooterStart(data) => vec![ip_from_address(&data.address)],
+            Event::SevereEvent(data) => data
+                .machine
+                .as_deref()
+                .map(ip_from_address)
+                .into_iter()
+                .collect(),
+            Event::Other(data) => data
+                .fields
+                .get("Machine")
+                .map(|machine| ip_from_address(machine))
+                .into_iter()
+                .collect(),
+            Event::Custom(event) => event.machine().map(ip_from_address).into_iter().collect(),
+            Event::ProgramStart(ProgramStartData { machine, .. })
+            | Event::SetDiskFailure(SetDiskFailureData { machine, .. })
+            | Event::CorruptedBlock(CorruptedBlockData { machine, .. })
+            | Event::SimulatorConfig(SimulatorConfigData { machine, .. })
+            | Event::StorageServerAdded(StorageServerAddedData { machine, .. })
+            | Event::StorageServerRemoved(StorageServerRemovedData { machine, .. })
+            | Event::RecruitmentFailure(RecruitmentFailureData { machine, .. })
+            | Event::Role(RoleData { machine, .. })
+            | Event::TestComplete(TestCompleteData { machine, .. })
+            | Event::RecoveryState(RecoveryStateData { machine, .. }) => {
+                vec![ip_from_address(machine)]
+            }
+            Event::TestResults(_)
+            | Event::ElapsedTime(_)
+            | Event::CoordinatorsChange(_)
+            | Event::BuggifySection(_)
+            | Event::Knob(_)
+            | Event::Assassination(_) => Vec::new(),
+        }
+    }
+
+    /// Returns the address of the process that logged the event with the `Roles` it
+    /// held at the time, e.g. ("2.0.1.2:1", "SS,TL"), for events carrying that field.
+    /// Most unmodeled events carry it, see `ParseOptions::capture_other`.
+    pub fn process_roles(&self) -> Option<(&str, &str)> {
+        match self {
+            Event::Role(data) => Some((data.machine.as_str(), data.roles.as_deref()?)),
+            Event::CorruptedBlock(data) => Some((data.machine.as_str(), data.roles.as_deref()?)),
+            Event::Other(data) => Some((
+                data.fields.get("Machine")?.as_str(),
+                data.fields.get("Roles")?.as_str(),
+            )),
+            _ => None,
+        }
+    }
+
+    /// Returns the event as a Severity 30+ event, whatever its type, or `None` for
+    /// events logged below `SEVERITY_WARNING`. Most modeled types are informational;
+    /// recruitment failures and the results of failed tests are logged as warnings.
+    pub fn severe_event(&self) -> Option<SevereEventData> {
+        let severe = match self {
+            Event::SevereEvent(data) => return Some(data.clone()),
+            Event::RecruitmentFailure(data) => SevereEventData {
+                timestamp: data.timestamp.clone(),
+                severity: data.severity.clone()?,
+                event_type: data.event_type.clone(),
+                machine: Some(data.machine.clone()),
+                error: data.error.clone(),
+            },
+            Event::TestResults(data) => SevereEventData {
+                timestamp: data.timestamp.clone(),
+                severity: data.severity.clone()?,
+                event_type: "TestResults".to_string(),
+                machine: None,
+                error: None,
+            },
+            _ => return None,
+        };
+        (severe.severity() >= SEVERITY_WARNING).then_some(severe)
+    }
+
+    /// Returns the name of the event variant, e.g. "KillMachineProcess".
+    pub fn event_type(&self) -> &'static str {
+        match self {
+            Event::CloggingPair(_) => "CloggingPair",
+            Event::ClogInterface(_) => "ClogInterface",
+            Event::ElapsedTime(_) => "ElapsedTime",
+            Event::SimulatedMachineStart(_) => "SimulatedMachineStart",
+            Event::CoordinatorsChange(_) => "CoordinatorsChange",
+            Event::ProgramStart(_) => "ProgramStart",
+            Event::DiskSwap(_) => "DiskSwap",
+            Event::SetDiskFailure(_) => "SetDiskFailure",
+            Event::CorruptedBlock(_) => "CorruptedBlock",
+            Event::KillMachineProcess(_) => "KillMachineProcess",
+            Event::SimulatorConfig(_) => "SimulatorConfig",
+            Event::StorageServerAdded(_) => "StorageServerAdded",
+            Event::StorageServerRemoved(_) => "StorageServerRemoved",
+            Event::RecruitmentFailure(_) => "RecruitmentFailure",
+            Event::BuggifySection(_) => "BuggifySection",
+            Event::Knob(_) => "Knob",
+            Event::Assassination(_) => "Assassination",
+            Event::RebooterStart(_) => "RebooterStart",
+            Event::Role(_) => "Role",
+            Event::TestResults(_) => "TestResults",
+            Event::TestComplete(_) => "TestComplete",
+            Event::RecoveryState(_) => "MasterRecoveryState",
+            Event::SevereEvent(_) => "SevereEvent",
+            Event::Other(_) => "Other",
+            Event::Custom(_) => "Custom",
+        }
+    }
+}
+
+/// IPs of a MachineIPs list, space or comma separated and sometimes in brackets.
+fn split_ip_list(ips: &str) -> Vec<&str> {
+    ips.split(['[', ']', ',', ' '])
+        .filter(|ip| !ip.is_empty())
+        .map(ip_from_address)
+        .collect()
+}
+
+/// Strips the port (and any `:tls` suffix) from a trace address, handling both
+/// IPv4 ("2.1.1.0:1:tls") and bracketed IPv6 ("[::1]:80") forms.
+pub fn ip_from_address(address: &str) -> &str {
+    if let Some(rest) = address.strip_prefix('[') {
+        return rest.split(']').next().unwrap_or(rest);
+    }
+    address.split(':').next().unwrap_or(address)
+}
+
+/// Errors that can occur during log parsing.
+#[derive(Error, Debug)]
+pub enum ParsingError {
+    #[error("I/O error reading file: {0}")]
+    Io(#[from] io::Error),
+    // Use serde_json::Error directly for JSON issues when parsing the initial line
+    #[error("JSON parsing error on line {line}: {source}")]
+    Json {
+        line: usize,
+        source: serde_json::Error,
+    },
+    // Specific error for when the data of a known event type fails to deserialize,
+    // only raised in strict mode
+    #[error("Failed to parse {event_type} event data on line {line}: {source}")]
+    EventDataParsing {
+        line: usize,
+        event_type: String,
+        source: serde_json::Error,
+    },
+    #[error("Invalid UTF-8 on line {line} (lossy reading skips such lines)")]
+    InvalidUtf8 { line: usize },
+    #[error("Failed to open remote trace: {0}")]
+    Remote(#[from] RemoteError),
+    #[error("No .json or .xml trace files in {0}")]
+    NoTraceFiles(PathBuf),
+}
+
+/// Errors that can occur when converting a single JSON line into an `Event`.
+#[derive(Error, Debug)]
+pub enum EventConversionError {
+    #[error("JSON parsing error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Missing or non-string \"Type\" field")]
+    MissingType,
+    #[error("Not an XML <Event> element")]
+    NotAnEvent,
+    #[error("Failed to parse event data: {event_type}")]
+    EventData { event_type: String },
+}
+
+/// The "Type" field of an event node, empty when missing.
+fn event_type_of(node: &JsonNode) -> &str {
+    node.get("Type").and_then(|v| v.as_str()).unwrap_or("")
+}
+
+/// Helper function to reduce repetition in deserialization
+fn try_parse_event_data<T>(node: &JsonNode) -> Result<Option<Event>, serde_json::Error>
+where
+    T: DeserializeOwned + Into<Event>,
+{
+    T::deserialize(node).map(|data| Some(data.into()))
+}
+
+/// Parses a single JSON log line represented as a `serde_json::Value` node into an Event.
+///
+/// Lines that are not events we track and events whose fields fail to deserialize
+/// both give `None`, see `decode_event_from_node` to tell them apart.
+pub(crate) fn parse_event_from_node(node: &JsonNode) -> Option<Event> {
+    decode_event_from_node(node).ok().flatten()
+}
+
+/// Parses a JSON log line node into an Event, like `parse_event_from_node`, but
+/// returns the deserialization error of events of a known type whose fields do not
+/// match it. `Ok(None)` for lines that are not events we track.
+///
+/// This function centralizes the logic for identifying the event type and deserializing
+/// the corresponding data structure.
+fn decode_event_from_node(node: &JsonNode) -> Result<Option<Event>, serde_json::Error> {
+    let Some(event_type) = node.get("Type").and_then(|v| v.as_str()) else {
+        return Ok(None);
+    };
+
+    match event_type {
+        "CloggingPair" => try_parse_event_data::<CloggingPairData>(node),
+        "ClogInterface" => try_parse_event_data::<ClogInterfaceData>(node),
+        "ElapsedTime" => try_parse_event_data::<ElapsedTimeData>(node),
+        "SimulatedMachineStart" => {
+            let mut data = SimulatedMachineStartData::deserialize(node)?;
+            data.populate_from_locality();
+            Ok(Some(Event::SimulatedMachineStart(data)))
+        }
+        "CoordinatorsChangeBeforeCommit" => try_parse_event_data::<CoordinatorsChangeData>(node),
+        "ProgramStart" => try_parse_event_data::<ProgramStartData>(node),
+        "SimulatedMachineFolderSwap" => try_parse_event_data::<DiskSwapData>(node), // Use DiskSwapData struct
+        "SetDiskFailure" => try_parse_event_data::<SetDiskFailureData>(node),
+        "CorruptedBlock" => try_parse_event_data::<CorruptedBlockData>(node),
+        "KillMachineProcess" => try_parse_event_data::<KillMachineProcessData>(node),
+        "SimulatorConfig" => {
+            let mut data = SimulatorConfigData::deserialize(node)?;
+            data.populate_config();
+            Ok(Some(Event::SimulatorConfig(data)))
+        }
+        "StorageServerAddedToRunningStorage" => {
+            try_parse_event_data::<StorageServerAddedData>(node)
+        }
+        "StorageServerRemoveFromRunningStorage" => {
+            try_parse_event_data::<StorageServerRemovedData>(node)
+        }
+        "RecruitStorageNotAvailable"
+        | "RecruitFromConfigurationNotAvailable"
+        | "RecruitRemoteFromConfigurationNotAvailable" => {
+            try_parse_event_data::<RecruitmentFailureData>(node)
+        }
+        // Recoveries retry for many reasons, only keep the ones lacking workers
+        "ClusterRecoveryRetrying" => Ok(try_parse_event_data::<RecruitmentFailureData>(node)?
+            .filter(|event| {
+                matches!(event, Event::RecruitmentFailure(data) if data.error.as_deref() == Some("no_more_servers"))
+            })),
+        "BuggifySection" => try_parse_event_data::<BuggifySectionData>(node),
+        "Knob" => try_parse_event_data::<KnobData>(node),
+        "Assassination" => try_parse_event_data::<AssassinationData>(node),
+        "SimulatedRebooterStarting" => try_parse_event_data::<RebooterStartData>(node),
+        "Role" => try_parse_event_data::<RoleData>(node),
+        "TestResults" => try_parse_event_data::<TestResultsData>(node),
+        "TestComplete" => try_parse_event_data::<TestCompleteData>(node),
+        // Named ClusterRecoveryState by releases where the cluster controller recovers
+        "MasterRecoveryState" | "ClusterRecoveryState" => {
+            try_parse_event_data::<RecoveryStateData>(node)
+        }
+        // Unknown event types are only kept when they are warnings or errors
+        _ => {
+            let severe = node
+                .get("Severity")
+                .and_then(|v| v.as_str())
+                .and_then(|severity| severity.parse::<u32>().ok())
+                .is_some_and(|severity| severity >= SEVERITY_WARNING);
+            if severe {
+                try_parse_event_data::<SevereEventData>(node)
+            } else {
+                Ok(None)
+            }
+        }
+    }
+}
+
+impl TryFrom<&str> for Event {
+    type Error = EventConversionError;
+
+    /// Parses a single JSON or XML trace line into an `Event`.
+    ///
+    /// ```
+    /// use parser::parser::Event;
+    ///
+    /// let line = r#"{"Type": "ProgramStart", "Time": "0.000000", "Machine": "0.0.0.0:0", "RandomSeed": "42"}"#;
+    /// let event = Event::try_from(line).unwrap();
+    /// assert!(matches!(event, Event::ProgramStart(_)));
+    /// ```
+    fn try_from(line: &str) -> Result<Self, Self::Error> {
+        let node: JsonNode = if xml::is_xml_line(line) {
+            xml::event_node(line).ok_or(EventConversionError::NotAnEvent)?
+        } else {
+            serde_json::from_str(line)?
+        };
+        let event_type = node
+            .get("Type")
+            .and_then(|v| v.as_str())
+            .ok_or(EventConversionError::MissingType)?;
+        parse_event_from_node(&node).ok_or_else(|| EventConversionError::EventData {
+            event_type: event_type.to_string(),
+        })
+    }
+}
+
+/// Options controlling how trace files are parsed.
+#[derive(Debug, Clone, Default)]
+pub struct ParseOptions {
+    /// Sampling applied to high-volume event types.
+    pub sampling: Sampling,
+    /// Approximate number of bytes of raw events a report may retain before it
+    /// switches to streaming aggregation. Unlimited when `None`.
+    ///
+    /// Only events the report keeps count towards the budget, and only clog events,
+    /// by far the most numerous, are dropped once it is exceeded: kills, disk faults,
+    /// severe events (capped per type, see `full_errors`) and the other raw events
+    /// are kept whatever the budget.
+    pub max_memory: Option<usize>,
+    /// Replace invalid UTF-8 and strip NUL bytes instead of failing, for traces of
+    /// crashed runs. Lines that still do not parse are skipped.
+    pub lossy: bool,
+    /// Keep every Severity 30+ event in the report instead of a few samples per type.
+    pub full_errors: bool,
+    /// Analysis passes run over the report, the built-in ones by default.
+    pub analyses: AnalysisRegistry,
+    /// Capture events of types the parser does not model as `Event::Other` instead of
+    /// dropping them. Such events of Severity 30+ remain `Event::SevereEvent`s.
+    pub capture_other: bool,
+    /// Notified of the bytes of trace read, e.g. to draw a progress bar.
+    pub progress: Option<ProgressHook>,
+    /// Fail on events of a known type whose fields do not deserialize instead of
+    /// dropping them and recording the failure in `ParseStats::failures`.
+    pub strict: bool,
+    /// Trace event types parsed, e.g. "CloggingPair"; every type when `None`. Lines
+    /// of other types are skipped before being deserialized, see `ParserBuilder`.
+    pub event_types: Option<BTreeSet<String>>,
+    /// Handlers decoding events by trace event type, in place of the parser's own
+    /// decoding, e.g. for types it does not model.
+    pub handlers: BTreeMap<String, EventHandler>,
+}
+
+impl ParseOptions {
+    /// Parses the events of a trace event type the crate does not model with
+    /// `parser`, into `Event::Custom`. `parser` returns `None` for events to drop.
+    ///
+    /// Like other handlers, it replaces the parser's own decoding of the type.
+    pub fn register_event_parser<T, F>(&mut self, event_type: &str, parser: F)
+    where
+        T: DynamicEvent,
+        F: Fn(&JsonNode) -> Option<T> + Send + Sync + 'static,
+    {
+        let handler =
+            move |node: &JsonNode| parser(node).map(|event| Event::Custom(CustomEvent::new(event)));
+        self.handlers
+            .insert(event_type.to_string(), EventHandler(Arc::new(handler)));
+    }
+
+    /// Whether events of a trace event type are parsed, see `event_types`.
+    fn parses(&self, event_type: &str) -> bool {
+        self.event_types
+            .as_ref()
+            .is_none_or(|types| types.contains(event_type))
+            || self.handlers.contains_key(event_type)
+    }
+}
+
+/// Trace event types of the chaos events, see `Event::is_chaos`.
+pub const CHAOS_EVENT_TYPES: [&str; 7] = [
+    "CloggingPair",
+    "ClogInterface",
+    "KillMachineProcess",
+    "SetDiskFailure",
+    "CorruptedBlock",
+    "SimulatedMachineFolderSwap",
+    "Assassination",
+];
+
+/// Function decoding an event node, see `EventHandler`.
+type DecodeFn = dyn Fn(&JsonNode) -> Option<Event> + Send + Sync;
+
+/// Decodes the events of a trace event type, see `ParseOptions::handlers`. Returns
+/// `None` for events to drop.
+#[derive(Clone)]
+pub struct EventHandler(Arc<DecodeFn>);
+
+impl fmt::Debug for EventHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("EventHandler")
+    }
+}
+
+/// Builds the `ParseOptions` of tools that only need some events, e.g. only chaos
+/// events: lines of other types are skipped without being deserialized.
+///
+/// ```
+/// use parser::parser::{Event, OtherEventData, ParserBuilder};
+///
+/// let options = ParserBuilder::new()
+///     .chaos_only()
+///     .handler("PingLatency", |node| {
+///         serde_json::from_value::<OtherEventData>(node.clone()).ok().map(Event::Other)
+///     })
+///     .build();
+/// assert!(options.event_types.unwrap().contains("CloggingPair"));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ParserBuilder {
+    options: ParseOptions,
+}
+
+impl ParserBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts from existing options, e.g. with sampling or lossy reading set.
+    pub fn with_options(options: ParseOptions) -> Self {
+        Self { options }
+    }
+
+    /// Also parses events of the given trace event types. Once called, events of
+    /// other types are skipped, except those with a handler.
+    pub fn event_types<I, S>(mut self, event_types: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.options
+            .event_types
+            .get_or_insert_with(BTreeSet::new)
+            .extend(event_types.into_iter().map(Into::into));
+        self
+    }
+
+    /// Also parses the chaos events, see `CHAOS_EVENT_TYPES`.
+    pub fn chaos_only(self) -> Self {
+        self.event_types(CHAOS_EVENT_TYPES)
+    }
+
+    /// Decodes events of a trace event type with `handler`, whether or not the
+    /// parser models the type.
+    pub fn handler<F>(mut self, event_type: &str, handler: F) -> Self
+    where
+        F: Fn(&JsonNode) -> Option<Event> + Send + Sync + 'static,
+    {
+        self.options
+            .handlers
+            .insert(event_type.to_string(), EventHandler(Arc::new(handler)));
+        self
+    }
+
+    /// Parses the events of a custom trace event type, see
+    /// `ParseOptions::register_event_parser`.
+    pub fn register_event_parser<T, F>(mut self, event_type: &str, parser: F) -> Self
+    where
+        T: DynamicEvent,
+        F: Fn(&JsonNode) -> Option<T> + Send + Sync + 'static,
+    {
+        self.options.register_event_parser(event_type, parser);
+        self
+    }
+
+    pub fn build(self) -> ParseOptions {
+        self.options
+    }
+}
+
+/// The "Type" field of a JSON trace line, read without deserializing the other
+/// fields.
+#[derive(Deserialize)]
+struct LineType<'a> {
+    #[serde(rename = "Type", borrow)]
+    event_type: Cow<'a, str>,
+}
+
+/// Bytes of trace read between two calls of a `ProgressHook`.
+const PROGRESS_BYTES: u64 = 1024 * 1024;
+
+/// Callback receiving the number of bytes of trace read since its previous call,
+/// about every megabyte. Files parsed concurrently share the hook, so it must sum
+/// the bytes rather than assume a single reader.
+#[derive(Clone)]
+pub struct ProgressHook(Arc<dyn Fn(u64) + Send + Sync>);
+
+impl ProgressHook {
+    pub fn new(on_progress: impl Fn(u64) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(on_progress))
+    }
+}
+
+impl fmt::Debug for ProgressHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ProgressHook")
+    }
+}
+
+/// What a parse read and left out: lines that are not events and events of types
+/// the parser does not model.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParseStats {
+    /// Lines read.
+    pub lines: usize,
+    /// Events parsed and kept after sampling.
+    pub events: usize,
+    /// Lines skipped because they are not valid JSON, e.g. cut off by a crash.
+    pub skipped_lines: usize,
+    /// Events of types the parser does not model, when not captured as
+    /// `Event::Other`, by type.
+    pub unknown_types: BTreeMap<String, usize>,
+    /// Events skipped because their type is not in `ParseOptions::event_types`.
+    pub filtered_events: usize,
+    /// Number of events of a known type dropped because their fields failed to
+    /// deserialize.
+    pub failed_events: usize,
+    /// The first `MAX_PARSE_FAILURES` of these failures.
+    pub failures: Vec<EventDataFailure>,
+    /// Lines whose invalid UTF-8 sequences were replaced, in lossy mode.
+    pub replaced_utf8_lines: usize,
+    /// Lines whose NUL bytes were dropped, in lossy mode.
+    pub nul_stripped_lines: usize,
+}
+
+/// Number of deserialization failures kept by `ParseStats`.
+pub const MAX_PARSE_FAILURES: usize = 100;
+
+/// An event of a known type dropped because its fields failed to deserialize.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventDataFailure {
+    /// Line of the event in its trace file.
+    pub line: usize,
+    pub event_type: String,
+    /// The deserialization error, e.g. a missing field.
+    pub message: String,
+}
+
+impl fmt::Display for EventDataFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}: dropped {} event: {}",
+            self.line, self.event_type, self.message
+        )
+    }
+}
+
+impl ParseStats {
+    /// Adds the statistics of another trace file, e.g. of a rolled trace.
+    pub fn merge(&mut self, other: &ParseStats) {
+        self.lines += other.lines;
+        self.events += other.events;
+        self.skipped_lines += other.skipped_lines;
+        self.filtered_events += other.filtered_events;
+        for (event_type, count) in &other.unknown_types {
+            *self.unknown_types.entry(event_type.clone()).or_insert(0) += count;
+        }
+        self.failed_events += other.failed_events;
+        self.replaced_utf8_lines += other.replaced_utf8_lines;
+        self.nul_stripped_lines += other.nul_stripped_lines;
+        let room = MAX_PARSE_FAILURES.saturating_sub(self.failures.len());
+        self.failures
+            .extend(other.failures.iter().take(room).cloned());
+    }
+
+    fn add_failure(&mut self, line: usize, event_type: &str, error: &serde_json::Error) {
+        self.failed_events += 1;
+        if self.failures.len() < MAX_PARSE_FAILURES {
+            self.failures.push(EventDataFailure {
+                line,
+                event_type: event_type.to_string(),
+                message: error.to_string(),
+            });
+        }
+    }
+
+    fn add_unknown(&mut self, node: &JsonNode) {
+        let event_type = event_type_of(node);
+        match self.unknown_types.get_mut(event_type) {
+            Some(count) => *count += 1,
+            None => {
+                self.unknown_types.insert(event_type.to_string(), 1);
+            }
+        }
+    }
+}
+
+/// Size in bytes of a trace file, or of the trace files of a directory, e.g. to
+/// measure parsing progress. `None` for remote locations and unreadable paths.
+pub fn trace_size(path: &Path) -> Option<u64> {
+    if remote_location(path).is_some() {
+        return None;
+    }
+    if path.is_dir() {
+        let files = trace_files(path).ok()?;
+        return files
+            .iter()
+            .map(|file| fs::metadata(file).map(|metadata| metadata.len()).ok())
+            .sum();
+    }
+    fs::metadata(path).map(|metadata| metadata.len()).ok()
+}
+
+/// Number of unknown event types listed by the `Display` of `ParseStats`.
+const TOP_UNKNOWN_TYPES: usize = 5;
+
+impl fmt::Display for ParseStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Read {} lines: {} events, {} lines skipped",
+            self.lines, self.events, self.skipped_lines
+        )?;
+        if self.failed_events > 0 {
+            write!(f, ", {} events failed to parse", self.failed_events)?;
+        }
+        if self.filtered_events > 0 {
+            write!(f, ", {} events filtered out", self.filtered_events)?;
+        }
+        if self.replaced_utf8_lines > 0 {
+            write!(f, ", {} lines with invalid UTF-8", self.replaced_utf8_lines)?;
+        }
+        if self.nul_stripped_lines > 0 {
+            write!(f, ", {} lines with NUL bytes", self.nul_stripped_lines)?;
+        }
+        if self.unknown_types.is_empty() {
+            return Ok(());
+        }
+        let mut by_count: Vec<(&String, &usize)> = self.unknown_types.iter().collect();
+        by_count.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+        let top: Vec<String> = by_count
+            .iter()
+            .take(TOP_UNKNOWN_TYPES)
+            .map(|(event_type, count)| format!("{} ({})", event_type, count))
+            .collect();
+        write!(
+            f,
+            "\n{} events of {} unknown types, most frequent: {}",
+            self.unknown_types.values().sum::<usize>(),
+            self.unknown_types.len(),
+            top.join(", ")
+        )
+    }
+}
+
+/// Parses a human-readable byte size such as "512MB", "2GiB" or "1048576".
+///
+/// Suffixes are case-insensitive powers of 1024, with or without the "i".
+pub fn parse_byte_size(s: &str) -> Result<usize, String> {
+    let s = s.trim();
+    let split = s
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(s.len());
+    let (number, unit) = s.split_at(split);
+    let multiplier: f64 = match unit.trim().to_ascii_uppercase().as_str() {
+        "" | "B" => 1.0,
+        "K" | "KB" | "KIB" => 1024.0,
+        "M" | "MB" | "MIB" => 1024.0 * 1024.0,
+        "G" | "GB" | "GIB" => 1024.0 * 1024.0 * 1024.0,
+        other => return Err(format!("Unknown size unit '{}' in '{}'", other, s)),
+    };
+    let number: f64 = number
+        .parse()
+        .map_err(|_| format!("Invalid size '{}', expected e.g. 512MB", s))?;
+    Ok((number * multiplier) as usize)
+}
+
+/// Parses a FoundationDB trace log file in JSON or XML format.
+///
+/// Takes a path to the log file and returns a `Result` containing either a vector
+/// of parsed `Event`s or a `ParsingError`.
+pub fn parse_log_file<P: AsRef<Path>>(file_path: P) -> Result<Vec<Event>, ParsingError> {
+    parse_log_file_with_options(file_path, &ParseOptions::default())
+}
+
+/// Parses a FoundationDB trace log file in JSON or XML format using the given options.
+pub fn parse_log_file_with_options<P: AsRef<Path>>(
+    file_path: P,
+    options: &ParseOptions,
+) -> Result<Vec<Event>, ParsingError> {
+    let _span = tracing::info_span!("parse_log_file").entered();
+    let mut events = Vec::new();
+    for_each_event(file_path, options, |event, _| events.push(event))?;
+    Ok(events)
+}
+
+/// Minimum number of bytes of a trace parsed by each task of `parse_log_file_parallel`.
+const PARALLEL_CHUNK_BYTES: u64 = 8 * 1024 * 1024;
+
+/// Parses a trace log file like `parse_log_file_with_options`, splitting it into
+/// chunks of lines deserialized concurrently. Events are returned in file order.
+///
+/// Small files, remote locations and traces read on a single core are parsed on the
+/// current thread.
+pub fn parse_log_file_parallel<P: AsRef<Path>>(
+    file_path: P,
+    options: &ParseOptions,
+) -> Result<Vec<Event>, ParsingError> {
+    // On a single core, splitting only adds the pass counting lines
+    if rayon::current_num_threads() == 1 {
+        return parse_log_file_with_options(file_path, options);
+    }
+    parse_log_file_in_chunks(file_path.as_ref(), options, PARALLEL_CHUNK_BYTES)
+}
+
+fn parse_log_file_in_chunks(
+    file_path: &Path,
+    options: &ParseOptions,
+    min_chunk_bytes: u64,
+) -> Result<Vec<Event>, ParsingError> {
+    if remote_location(file_path).is_some() {
+        return parse_log_file_with_options(file_path, options);
+    }
+    let _span =
+        tracing::info_span!("parse_log_file_parallel", path = %file_path.display()).entered();
+    let len = fs::metadata(file_path)?.len();
+    let chunks = (len / min_chunk_bytes.max(1)).clamp(1, 4 * rayon::current_num_threads() as u64);
+    if chunks == 1 {
+        return parse_log_file_with_options(file_path, options);
+    }
+
+    // Chunks start right after a line break, so that every line is in a single chunk
+    let mut file = File::open(file_path)?;
+    let mut starts = vec![0];
+    for i in 1..chunks {
+        file.seek(SeekFrom::Start(len * i / chunks))?;
+        let mut reader = BufReader::new(&mut file);
+        let skipped = reader.read_until(b'\n', &mut Vec::new())? as u64;
+        let start = len * i / chunks + skipped;
+        if start > *starts.last().unwrap_or(&0) && start < len {
+            starts.push(start);
+        }
+    }
+    let ranges: Vec<(u64, u64)> = starts
+        .iter()
+        .zip(starts.iter().skip(1).chain([&len]))
+        .map(|(start, end)| (*start, end - start))
+        .collect();
+    let open_chunk = |(start, len): (u64, u64)| -> io::Result<BufReader<io::Take<File>>> {
+        let mut file = File::open(file_path)?;
+        file.seek(SeekFrom::Start(start))?;
+        Ok(BufReader::new(file.take(len)))
+    };
+
+    // Line numbers, which sampling and error messages depend on, need the number of
+    // lines before each chunk
+    let line_counts = ranges
+        .par_iter()
+        .map(|range| {
+            let mut reader = open_chunk(*range)?;
+            let mut lines = 0;
+            loop {
+                let buffer = reader.fill_buf()?;
+                if buffer.is_empty() {
+                    return Ok(lines);
+                }
+                lines += buffer.iter().filter(|b| **b == b'\n').count();
+                let read = buffer.len();
+                reader.consume(read);
+            }
+        })
+        .collect::<io::Result<Vec<usize>>>()?;
+    let lines_before = line_counts.iter().scan(0, |total, count| {
+        let before = *total;
+        *total += count;
+        Some(before)
+    });
+
+    let parts = ranges
+        .into_par_iter()
+        .zip(lines_before.collect::<Vec<_>>())
+        .map(|(range, lines_before)| {
+            EventIterator::new(open_chunk(range)?, options.clone())
+                .starting_after_line(lines_before)
+                .collect::<Result<Vec<Event>, ParsingError>>()
+        })
+        .collect::<Result<Vec<_>, ParsingError>>()?;
+    Ok(parts.concat())
+}
+
+/// Parses every trace file directly inside `dir`, e.g. the rolled parts
+/// `trace.0.0.0.0.*.json` of a run, and returns their events merged and sorted by
+/// timestamp.
+pub fn parse_log_directory<P: AsRef<Path>>(dir: P) -> Result<Vec<Event>, ParsingError> {
+    parse_log_directory_with_options(dir, &ParseOptions::default())
+}
+
+/// Parses every trace file directly inside `dir` using the given options, see
+/// `parse_log_directory`. Files are parsed concurrently, see `stream_log_directory`
+/// to read directories of traces larger than memory.
+pub fn parse_log_directory_with_options<P: AsRef<Path>>(
+    dir: P,
+    options: &ParseOptions,
+) -> Result<Vec<Event>, ParsingError> {
+    let dir = dir.as_ref();
+    let _span = tracing::info_span!("parse_log_directory", path = %dir.display()).entered();
+    let paths = trace_files(dir)?;
+    if paths.is_empty() {
+        return Err(ParsingError::NoTraceFiles(dir.to_path_buf()));
+    }
+    let threads = thread::available_parallelism()
+        .map_or(1, usize::from)
+        .min(paths.len());
+    let parts: Vec<Result<Vec<Event>, ParsingError>> = thread::scope(|scope| {
+        let handles: Vec<_> = paths
+            .chunks(paths.len().div_ceil(threads))
+            .map(|chunk| {
+                scope.spawn(move || {
+                    let mut events = Vec::new();
+                    for path in chunk {
+                        events.extend(parse_log_file_with_options(path, options)?);
+                    }
+                    Ok(events)
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("trace parsing thread panicked"))
+            .collect()
+    });
+
+    let mut events = Vec::new();
+    for part in parts {
+        events.extend(part?);
+    }
+    // Stable, so events of the same time keep their order within a file
+    events.sort_by(|a, b| a.timestamp().total_cmp(&b.timestamp()));
+    Ok(events)
+}
+
+/// Streams the events of every trace file directly inside `dir`, merged by timestamp
+/// like `parse_log_directory_with_options` but without loading whole files: a single
+/// event per file is buffered at any time, see `MergedEvents`.
+pub fn stream_log_directory<P: AsRef<Path>>(
+    dir: P,
+    options: &ParseOptions,
+) -> Result<MergedEvents<EventIterator<BufReader<File>>, Event>, ParsingError> {
+    let dir = dir.as_ref();
+    let paths = trace_files(dir)?;
+    if paths.is_empty() {
+        return Err(ParsingError::NoTraceFiles(dir.to_path_buf()));
+    }
+    let sources = paths
+        .iter()
+        .map(|path| {
+            let file = File::open(path)?;
+            Ok(EventIterator::new(BufReader::new(file), options.clone()))
+        })
+        .collect::<Result<Vec<_>, ParsingError>>()?;
+    Ok(MergedEvents::new(sources))
+}
+
+/// Items that can be merged by simulated time, see `MergedEvents`.
+pub trait Timestamped {
+    /// Simulated time of the item, in seconds.
+    fn timestamp(&self) -> f64;
+}
+
+impl Timestamped for Event {
+    fn timestamp(&self) -> f64 {
+        Event::timestamp(self)
+    }
+}
+
+/// The next item of a source of `MergedEvents`.
+struct Head<T> {
+    timestamp: f64,
+    source: usize,
+    item: T,
+}
+
+impl<T> PartialEq for Head<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T> Eq for Head<T> {}
+
+impl<T> PartialOrd for Head<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for Head<T> {
+    /// Reversed, so that the max-heap `BinaryHeap` pops the earliest item first, and
+    /// the first source among items of the same time.
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .timestamp
+            .total_cmp(&self.timestamp)
+            .then_with(|| other.source.cmp(&self.source))
+    }
+}
+
+/// K-way merge of event streams that are each sorted by timestamp, e.g. the rolled
+/// files of a trace, into a single sorted stream.
+///
+/// Only the next item of each source is buffered, in a min-heap keyed by timestamp, so
+/// memory stays bounded by the number of sources rather than their length. Items of
+/// the same time come out in source order, then in their order within the source, as
+/// a stable sort of the concatenated sources would. A source is dropped after
+/// returning an error.
+pub struct MergedEvents<I, T> {
+    sources: Vec<I>,
+    heads: BinaryHeap<Head<T>>,
+    /// Sources whose next item must be read before the earliest head is known.
+    to_read: Vec<usize>,
+}
+
+impl<I, T> MergedEvents<I, T>
+where
+    I: Iterator<Item = Result<T, ParsingError>>,
+    T: Timestamped,
+{
+    pub fn new(sources: Vec<I>) -> Self {
+        Self {
+            heads: BinaryHeap::with_capacity(sources.len()),
+            to_read: (0..sources.len()).rev().collect(),
+            sources,
+        }
+    }
+
+    /// The merged sources, e.g. to read their statistics once merged.
+    pub fn sources(&self) -> &[I] {
+        &self.sources
+    }
+
+    /// Returns the next item along with the index of the source it came from.
+    pub fn next_with_source(&mut self) -> Option<(usize, Result<T, ParsingError>)> {
+        while let Some(source) = self.to_read.pop() {
+            match self.sources[source].next() {
+                Some(Ok(item)) => self.heads.push(Head {
+                    timestamp: item.timestamp(),
+                    source,
+                    item,
+                }),
+                Some(Err(e)) => return Some((source, Err(e))),
+                None => {}
+            }
+        }
+        let head = self.heads.pop()?;
+        self.to_read.push(head.source);
+        Some((head.source, Ok(head.item)))
+    }
+}
+
+impl<I, T> Iterator for MergedEvents<I, T>
+where
+    I: Iterator<Item = Result<T, ParsingError>>,
+    T: Timestamped,
+{
+    type Item = Result<T, ParsingError>;
 
-impl Event {
-    /// Returns the timestamp associated with the event, parsed from string.
-    /// Returns 0.0 if parsing fails.
-    /// For ElapsedTime events, this uses the SimTime field.
-    pub fn timestamp(&self) -> f64 {
-        match self {
-            // Parse the timestamp string to f64
-            Event::CloggingPair(data) => data.timestamp.parse().unwrap_or(0.0),
-            Event::ClogInterface(data) => data.timestamp.parse().unwrap_or(0.0),
-            Event::ElapsedTime(data) => data.timestamp.parse().unwrap_or(0.0),
-            Event::SimulatedMachineStart(data) => data.timestamp.parse().unwrap_or(0.0),
-            Event::CoordinatorsChange(data) => data.timestamp.parse().unwrap_or(0.0),
-            Event::ProgramStart(data) => data.timestamp.parse().unwrap_or(0.0),
-            Event::DiskSwap(data) => data.timestamp.parse().unwrap_or(0.0),
-            Event::SetDiskFailure(data) => data.timestamp.parse().unwrap_or(0.0),
-            Event::CorruptedBlock(data) => data.time.parse().unwrap_or(0.0),
-            Event::KillMachineProcess(data) => data.timestamp.parse().unwrap_or(0.0),
-            Event::SimulatorConfig(data) => data.timestamp.parse().unwrap_or(0.0),
-        }
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_with_source().map(|(_, item)| item)
     }
 }
 
-/// Errors that can occur during log parsing.
-#[derive(Error, Debug)]
-pub enum ParsingError {
-    #[error("I/O error reading file: {0}")]
-    Io(#[from] io::Error),
-    // Use serde_json::Error directly for JSON issues when parsing the initial line
-    #[error("JSON parsing error on line {line}: {source}")]
-    Json {
-        line: usize,
-        source: serde_json::Error,
-    },
-    // Specific error for when an event type is unknown or parsing its data fails
-    #[error("Failed to parse event data on line {line}: {event_type}")]
-    EventDataParsing { line: usize, event_type: String },
+/// Streams the events of a FoundationDB trace log file to `visit` without retaining
+/// them, along with the length in bytes of the line each event was parsed from, and
+/// returns the statistics of the parse.
+///
+/// The path may also be a remote location, see `remote::remote_location`.
+pub fn for_each_event<P, F>(
+    file_path: P,
+    options: &ParseOptions,
+    visit: F,
+) -> Result<ParseStats, ParsingError>
+where
+    P: AsRef<Path>,
+    F: FnMut(Event, usize),
+{
+    let file_path = file_path.as_ref();
+    let _span = tracing::info_span!("parse_file", path = %file_path.display()).entered();
+    let reader: Box<dyn Read> = match remote_location(file_path) {
+        Some(location) => remote::open(location)?,
+        None => Box::new(File::open(file_path)?),
+    };
+    for_each_event_in_reader(BufReader::new(reader), options, visit)
 }
 
-/// Helper function to reduce repetition in deserialization
-fn try_parse_event_data<T>(node: &JsonNode) -> Option<Event>
+/// Streams the events of a trace read from `reader` to `visit`, see `for_each_event`.
+pub fn for_each_event_in_reader<R, F>(
+    reader: R,
+    options: &ParseOptions,
+    mut visit: F,
+) -> Result<ParseStats, ParsingError>
 where
-    T: DeserializeOwned + Into<Event>,
+    R: BufRead,
+    F: FnMut(Event, usize),
 {
-    serde_json::from_value::<T>(node.clone())
-        .ok()
-        .map(Into::into)
+    let started = Instant::now();
+    let mut events_by_type: BTreeMap<&'static str, usize> = BTreeMap::new();
+    let mut events = EventIterator::new(reader, options.clone());
+    while let Some((event, line_len)) = events.next_with_line_len().transpose()? {
+        *events_by_type.entry(event.event_type()).or_insert(0) += 1;
+        visit(event, line_len);
+    }
+    let lines = events.lines();
+    let stats = events.stats();
+    if stats.replaced_utf8_lines > 0 || stats.nul_stripped_lines > 0 {
+        tracing::warn!(
+            replaced_utf8_lines = stats.replaced_utf8_lines,
+            nul_stripped_lines = stats.nul_stripped_lines,
+            "decoded damaged trace lines"
+        );
+    }
+
+    let elapsed = started.elapsed().as_secs_f64();
+    tracing::info!(
+        lines,
+        events = events_by_type.values().sum::<usize>(),
+        lines_per_sec = (lines as f64 / elapsed.max(f64::EPSILON)) as u64,
+        events_by_type = ?events_by_type,
+        "parsed trace file"
+    );
+    Ok(stats.clone())
 }
 
-/// Parses a single JSON log line represented as a `serde_json::Value` node into an Event.
+/// Lazily parses the events of a trace read from a `BufRead`, one line at a time, so
+/// that traces larger than memory can be processed.
 ///
-/// This function centralizes the logic for identifying the event type and deserializing
-/// the corresponding data structure.
-fn parse_event_from_node(node: &JsonNode) -> Option<Event> {
-    let event_type = node.get("Type")?.as_str()?;
+/// Lines that are not events we track are skipped. Iteration stops after the first
+/// error, e.g. an I/O error or invalid UTF-8 outside of lossy mode.
+pub struct EventIterator<R> {
+    reader: R,
+    options: ParseOptions,
+    lines: usize,
+    raw_line: Vec<u8>,
+    failed: bool,
+    stats: ParseStats,
+    /// Bytes read since the last call of the progress hook.
+    unreported_bytes: u64,
+}
 
-    match event_type {
-        "CloggingPair" => try_parse_event_data::<CloggingPairData>(node),
-        "ClogInterface" => try_parse_event_data::<ClogInterfaceData>(node),
-        "ElapsedTime" => try_parse_event_data::<ElapsedTimeData>(node),
-        "SimulatedMachineStart" => {
-            match serde_json::from_value::<SimulatedMachineStartData>(node.clone()) {
-                Ok(mut data) => {
-                    data.populate_from_locality();
-                    Some(Event::SimulatedMachineStart(data))
-                }
-                Err(_) => None,
-            }
+impl<R: BufRead> EventIterator<R> {
+    pub fn new(reader: R, options: ParseOptions) -> Self {
+        Self {
+            reader,
+            options,
+            lines: 0,
+            raw_line: Vec::new(),
+            failed: false,
+            stats: ParseStats::default(),
+            unreported_bytes: 0,
         }
-        "CoordinatorsChangeBeforeCommit" => try_parse_event_data::<CoordinatorsChangeData>(node),
-        "ProgramStart" => try_parse_event_data::<ProgramStartData>(node).map(|e| e.into()),
-        "SimulatedMachineFolderSwap" => {
-            try_parse_event_data::<DiskSwapData>(node).map(|e| e.into())
-        } // Use DiskSwapData struct
-        "SetDiskFailure" => try_parse_event_data::<SetDiskFailureData>(node).map(|e| e.into()),
-        "CorruptedBlock" => try_parse_event_data::<CorruptedBlockData>(node).map(|e| e.into()),
-        "KillMachineProcess" => {
-            try_parse_event_data::<KillMachineProcessData>(node).map(|e| e.into())
+    }
+
+    /// Numbers lines as if `lines_before` lines preceded the reader, for readers
+    /// starting in the middle of a trace.
+    fn starting_after_line(mut self, lines_before: usize) -> Self {
+        self.lines = lines_before;
+        self
+    }
+
+    /// Number of lines read so far.
+    pub fn lines(&self) -> usize {
+        self.lines
+    }
+
+    /// Statistics of the lines read so far.
+    pub fn stats(&self) -> &ParseStats {
+        &self.stats
+    }
+
+    /// Passes the bytes read to the progress hook, if any, once they add up to
+    /// `PROGRESS_BYTES` or when `flush` is set.
+    fn report_progress(&mut self, bytes: usize, flush: bool) {
+        let Some(progress) = &self.options.progress else {
+            return;
+        };
+        self.unreported_bytes += bytes as u64;
+        if self.unreported_bytes >= PROGRESS_BYTES || (flush && self.unreported_bytes > 0) {
+            (progress.0)(self.unreported_bytes);
+            self.unreported_bytes = 0;
         }
-        "SimulatorConfig" => match serde_json::from_value::<SimulatorConfigData>(node.clone()) {
-            Ok(mut data) => {
-                data.populate_config();
-                Some(Event::SimulatorConfig(data))
-            }
-            Err(_) => None,
-        },
-        _ => None, // Unknown event type
     }
-}
 
-/// Parses a FoundationDB trace log file in JSON format.
-///
-/// Takes a path to the log file and returns a `Result` containing either a vector
-/// of parsed `Event`s or a `ParsingError`.
-pub fn parse_log_file<P: AsRef<Path>>(file_path: P) -> Result<Vec<Event>, ParsingError> {
-    let file = File::open(file_path)?;
-    let reader = BufReader::new(file);
-    let mut events = Vec::new();
+    /// Returns the next event along with the length in bytes of its line.
+    fn next_with_line_len(&mut self) -> Option<Result<(Event, usize), ParsingError>> {
+        if self.failed {
+            return None;
+        }
+        let result = self.read_next();
+        self.failed = matches!(result, Some(Err(_)));
+        result
+    }
 
-    for (index, line_result) in reader.lines().enumerate() {
-        let line = line_result?; // Propagate IO errors
-        let line_number = index + 1;
+    fn read_next(&mut self) -> Option<Result<(Event, usize), ParsingError>> {
+        loop {
+            self.raw_line.clear();
+            match self.reader.read_until(b'\n', &mut self.raw_line) {
+                Ok(0) => {
+                    self.report_progress(0, true);
+                    return None;
+                }
+                Ok(bytes) => self.report_progress(bytes, false),
+                Err(e) => return Some(Err(e.into())),
+            }
+            self.lines += 1;
+            self.stats.lines += 1;
+            let line_number = self.lines;
+            let line =
+                match decode_line(&self.raw_line, &self.options, line_number, &mut self.stats) {
+                    Ok(line) => line,
+                    Err(e) => return Some(Err(e)),
+                };
 
-        // Attempt to parse the line as a JSON Value
-        let node: JsonNode = match serde_json::from_str(&line) {
-            Ok(val) => val,
-            Err(e) => {
-                // Consider logging this error instead of returning immediately
-                // to allow parsing potentially valid lines later in the file.
-                eprintln!("Skipping line {}: JSON parsing error - {}", line_number, e);
-                continue; // Skip this line and continue with the next
-                          // Or return Err(ParsingError::Json { line: line_number, source: e });
+            // Lines of types not parsed are skipped before building their JSON tree
+            let is_xml = xml::is_xml_line(&line);
+            if self.options.event_types.is_some() && !is_xml {
+                if let Ok(LineType { event_type }) = serde_json::from_str(&line) {
+                    if !self.options.parses(&event_type) {
+                        self.stats.filtered_events += 1;
+                        continue;
+                    }
+                }
             }
-        };
 
-        // Attempt to parse the JSON Value into a specific Event type
-        if let Some(event) = parse_event_from_node(&node) {
-            events.push(event);
-        } else {
-            // Log or handle cases where a valid JSON object doesn't match a known Event type
-            // let event_type = node
-            //     .get("Type")
-            //     .and_then(|v| v.as_str())
-            //     .unwrap_or("Unknown");
-            // eprintln!(
-            //     "Skipping line {}: Failed to parse event data for type '{}'",
-            //     line_number, event_type
-            // );
-            // Optionally, you could store these as a generic 'UnknownEvent' type
-            // Or return Err(ParsingError::EventDataParsing { line: line_number, event_type: event_type.to_string() });
+            // XML traces hold one event element per line, other lines are skipped
+            let parsed = if is_xml {
+                match xml::event_node(&line) {
+                    Some(node) => Ok(node),
+                    None => continue,
+                }
+            } else {
+                serde_json::from_str(&line)
+            };
+            let node: JsonNode = match parsed {
+                Ok(val) => val,
+                Err(e) => {
+                    // Skip the line so that the rest of a damaged trace is still read
+                    eprintln!("Skipping line {}: JSON parsing error - {}", line_number, e);
+                    self.stats.skipped_lines += 1;
+                    continue;
+                }
+            };
+
+            if is_xml && !self.options.parses(event_type_of(&node)) {
+                self.stats.filtered_events += 1;
+                continue;
+            }
+            let decoded = match self.options.handlers.get(event_type_of(&node)) {
+                Some(handler) => Ok((handler.0)(&node)),
+                None => decode_event_from_node(&node),
+            };
+            let (event, failure) = match decoded {
+                Ok(event) => (event, None),
+                Err(source) if self.options.strict => {
+                    return Some(Err(ParsingError::EventDataParsing {
+                        line: line_number,
+                        event_type: event_type_of(&node).to_string(),
+                        source,
+                    }));
+                }
+                Err(source) => (None, Some(source)),
+            };
+            // Valid JSON objects that are not a known Event type are skipped, unless
+            // captured as `Event::Other`
+            let event = event.or_else(|| {
+                self.options
+                    .capture_other
+                    .then(|| try_parse_event_data::<OtherEventData>(&node).ok())
+                    .flatten()
+                    .flatten()
+            });
+            match (event, failure) {
+                (None, Some(source)) => {
+                    self.stats
+                        .add_failure(line_number, event_type_of(&node), &source);
+                }
+                (Some(event), _) => {
+                    if self.options.sampling.keep(&event, line_number) {
+                        self.stats.events += 1;
+                        return Some(Ok((event, line.len())));
+                    }
+                }
+                (None, None) => self.stats.add_unknown(&node),
+            }
         }
     }
+}
+
+impl<R: BufRead> Iterator for EventIterator<R> {
+    type Item = Result<Event, ParsingError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_with_line_len()
+            .map(|result| result.map(|(event, _)| event))
+    }
+}
 
-    Ok(events)
+/// Lazily parses the events of a trace read from `reader` with the default options,
+/// see `EventIterator`.
+pub fn parse_log_stream<R: BufRead>(reader: R) -> EventIterator<R> {
+    EventIterator::new(reader, ParseOptions::default())
+}
+
+/// Number of bytes at the end of a trace searched for its last line.
+const TAIL_BYTES: u64 = 64 * 1024;
+
+/// Returns whether the last line of a trace file is cut off, as left behind by a run
+/// that crashed or was killed while writing it. Trailing NUL bytes of preallocated
+/// trace files are ignored.
+pub fn ends_with_incomplete_line(file_path: &Path) -> io::Result<bool> {
+    let mut file = File::open(file_path)?;
+    let len = file.metadata()?.len();
+    file.seek(SeekFrom::Start(len.saturating_sub(TAIL_BYTES)))?;
+    let mut tail = Vec::new();
+    file.read_to_end(&mut tail)?;
+
+    let tail = String::from_utf8_lossy(&tail);
+    let tail = tail.trim_end_matches(['\0', '\n', '\r']);
+    let last_line = tail.rsplit('\n').next().unwrap_or(tail).trim();
+    if last_line.is_empty() {
+        return Ok(false);
+    }
+    Ok(if xml::is_xml_line(last_line) {
+        !last_line.ends_with('>')
+    } else {
+        serde_json::from_str::<JsonNode>(last_line).is_err()
+    })
+}
+
+/// Decodes a raw line, without its line terminator.
+///
+/// In lossy mode invalid UTF-8 sequences are replaced and NUL bytes, which crashed
+/// runs leave behind in preallocated trace files, are dropped. Such lines are counted
+/// in `stats`.
+fn decode_line<'a>(
+    raw: &'a [u8],
+    options: &ParseOptions,
+    line_number: usize,
+    stats: &mut ParseStats,
+) -> Result<Cow<'a, str>, ParsingError> {
+    let raw = raw.strip_suffix(b"\n").unwrap_or(raw);
+    let raw = raw.strip_suffix(b"\r").unwrap_or(raw);
+    if !options.lossy {
+        return std::str::from_utf8(raw)
+            .map(Cow::Borrowed)
+            .map_err(|_| ParsingError::InvalidUtf8 { line: line_number });
+    }
+
+    let decoded = String::from_utf8_lossy(raw);
+    if !matches!(decoded, Cow::Borrowed(_)) {
+        stats.replaced_utf8_lines += 1;
+    }
+    if decoded.contains('\0') {
+        stats.nul_stripped_lines += 1;
+        return Ok(Cow::Owned(decoded.replace('\0', "")));
+    }
+    Ok(decoded)
 }
 
 #[cfg(test)]
@@ -489,8 +2220,143 @@ mod tests {
     use pretty_assertions::assert_eq;
     // Import items from outer module
     use serde_json::json;
+    use std::io::Write;
     use std::path::Path;
 
+    #[test]
+    fn test_parse_stats_and_progress() {
+        let trace = concat!(
+            r#"{"Type": "ProgramStart", "Time": "0.000000", "Machine": "0.0.0.0:0", "RandomSeed": "42"}"#,
+            "\n",
+            r#"{"Type": "Role", "Time": "1.0"#,
+            "\n",
+            r#"{"Type": "PingLatency", "Time": "2.0"}"#,
+            "\n",
+            r#"{"Type": "PingLatency", "Time": "3.0"}"#,
+            "\n",
+            r#"{"Type": "KillMachineProcess", "Time": "4.0"}"#,
+            "\n",
+        );
+        let read = Arc::new(std::sync::atomic::AtomicU64::new(0));
+        let counter = Arc::clone(&read);
+        let options = ParseOptions {
+            progress: Some(ProgressHook::new(move |bytes| {
+                counter.fetch_add(bytes, std::sync::atomic::Ordering::Relaxed);
+            })),
+            ..ParseOptions::default()
+        };
+
+        let stats = for_each_event_in_reader(trace.as_bytes(), &options, |_, _| {}).unwrap();
+        assert_eq!(stats.lines, 5);
+        assert_eq!(stats.events, 1);
+        assert_eq!(stats.skipped_lines, 1);
+        assert_eq!(stats.unknown_types["PingLatency"], 2);
+        assert_eq!(stats.failed_events, 1);
+        assert_eq!(stats.failures[0].line, 5);
+        assert_eq!(stats.failures[0].event_type, "KillMachineProcess");
+        assert!(stats.failures[0].to_string().contains("missing field"));
+        assert_eq!(
+            read.load(std::sync::atomic::Ordering::Relaxed),
+            trace.len() as u64
+        );
+        assert_eq!(
+            stats.to_string(),
+            "Read 5 lines: 1 events, 1 lines skipped, 1 events failed to parse\n\
+             2 events of 1 unknown types, most frequent: PingLatency (2)"
+        );
+
+        // Strict parsing stops at the event that fails to deserialize
+        let options = ParseOptions {
+            strict: true,
+            ..ParseOptions::default()
+        };
+        match for_each_event_in_reader(trace.as_bytes(), &options, |_, _| {}) {
+            Err(ParsingError::EventDataParsing {
+                line, event_type, ..
+            }) => assert_eq!((line, event_type.as_str()), (5, "KillMachineProcess")),
+            other => panic!("Expected an event data error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_event_data_failures_strict_and_lenient() {
+        let kill = r#"{"Type": "KillMachineProcess", "Time": "2.0", "KillType": "6", "Process": "name: Server address: 2.2.1.2:1 zone: z", "StartingClass": "storage", "Failed": "0", "Excluded": "0", "Cleared": "0", "Rebooting": "0"}"#;
+        let malformed = r#"{"Type": "KillMachineProcess", "Time": "3.0"}"#;
+        let failures = MAX_PARSE_FAILURES + 20;
+        let mut trace = format!("{}\n", kill);
+        for _ in 0..failures {
+            trace.push_str(malformed);
+            trace.push('\n');
+        }
+        trace.push_str(kill);
+
+        // Strict parsing yields the events before the malformed one, then its error,
+        // then stops
+        let options = ParseOptions {
+            strict: true,
+            ..ParseOptions::default()
+        };
+        let mut events = EventIterator::new(trace.as_bytes(), options);
+        assert!(matches!(
+            events.next(),
+            Some(Ok(Event::KillMachineProcess(_)))
+        ));
+        match events.next() {
+            Some(Err(error @ ParsingError::EventDataParsing { .. })) => {
+                assert!(error.to_string().starts_with(
+                    "Failed to parse KillMachineProcess event data on line 2: missing field"
+                ));
+            }
+            other => panic!("Expected an event data error, got {:?}", other),
+        }
+        assert!(events.next().is_none());
+        assert_eq!(events.stats().failed_events, 0);
+
+        // Lenient parsing skips malformed events, keeping the first of their failures
+        let mut events = parse_log_stream(trace.as_bytes());
+        assert_eq!(events.by_ref().filter(Result::is_ok).count(), 2);
+        let stats = events.stats().clone();
+        assert_eq!(stats.failed_events, failures);
+        assert_eq!(stats.failures.len(), MAX_PARSE_FAILURES);
+        assert_eq!(stats.failures[0].line, 2);
+        assert_eq!(
+            stats.failures[MAX_PARSE_FAILURES - 1].line,
+            MAX_PARSE_FAILURES + 1
+        );
+
+        // Merged statistics keep the cap
+        let mut merged = stats.clone();
+        merged.merge(&stats);
+        assert_eq!(merged.failed_events, 2 * failures);
+        assert_eq!(merged.failures, stats.failures);
+    }
+
+    #[test]
+    fn test_parser_builder_selects_event_types() {
+        let log_path = Path::new("logs/combined_trace.0.0.0.0.24.1745498878.p7Loj0.json");
+        let chaos: Vec<Event> = parse_log_file(log_path)
+            .unwrap()
+            .into_iter()
+            .filter(Event::is_chaos)
+            .collect();
+
+        let options = ParserBuilder::new()
+            .chaos_only()
+            .handler("PingLatency", |node| {
+                try_parse_event_data::<OtherEventData>(node).ok().flatten()
+            })
+            .build();
+        let mut events = Vec::new();
+        let stats = for_each_event(log_path, &options, |event, _| events.push(event)).unwrap();
+        let (pings, selected): (Vec<Event>, Vec<Event>) = events
+            .into_iter()
+            .partition(|event| matches!(event, Event::Other(_)));
+        assert_eq!(selected, chaos);
+        assert_eq!(pings.len(), 1895);
+        assert_eq!(stats.events, chaos.len() + pings.len());
+        assert_eq!(stats.events + stats.filtered_events, stats.lines);
+    }
+
     #[test]
     fn test_parse_valid_log_file() {
         // Define the path relative to the crate root (parser directory)
@@ -502,7 +2368,7 @@ mod tests {
 
         // Parse the log file
         let events = parse_log_file(log_path)
-            .expect(&format!("Failed to parse log file \"{}\"", log_path_str));
+            .unwrap_or_else(|e| panic!("Failed to parse log file \"{}\": {}", log_path_str, e));
         assert!(!events.is_empty(), "Parser returned no events.");
         // Add more specific assertions based on expected events if needed
     }
@@ -513,6 +2379,95 @@ mod tests {
         assert!(matches!(result, Err(ParsingError::Io(_))));
     }
 
+    #[test]
+    fn test_lossy_reading_skips_garbage() {
+        let kill = r#"{"Type": "KillMachineProcess", "Time": "2.0", "KillType": "6", "Process": "name: Server address: 2.2.1.2:1 zone: z", "StartingClass": "storage", "Failed": "0", "Excluded": "0", "Cleared": "0", "Rebooting": "0"}"#;
+        let mut trace = tempfile::NamedTempFile::new().unwrap();
+        trace.write_all(kill.as_bytes()).unwrap();
+        trace.write_all(b"\n{\"Type\": \"Kill\xff\xfe\n").unwrap();
+        trace.write_all(kill.as_bytes()).unwrap();
+        trace.write_all(b"\0\0\0\n\0\0\0\0").unwrap();
+
+        assert!(matches!(
+            parse_log_file(trace.path()),
+            Err(ParsingError::InvalidUtf8 { line: 2 })
+        ));
+
+        let options = ParseOptions {
+            lossy: true,
+            ..ParseOptions::default()
+        };
+        let events = parse_log_file_with_options(trace.path(), &options).unwrap();
+        assert_eq!(events.len(), 2);
+
+        let reader = BufReader::new(File::open(trace.path()).unwrap());
+        let stats = for_each_event_in_reader(reader, &options, |_, _| {}).unwrap();
+        assert_eq!(stats.replaced_utf8_lines, 1);
+        assert_eq!(stats.nul_stripped_lines, 2);
+    }
+
+    #[test]
+    fn test_event_iterator() {
+        let log_path = "logs/combined_trace.0.0.0.0.24.1745498878.p7Loj0.json";
+        let reader = BufReader::new(File::open(log_path).unwrap());
+        let events: Vec<Event> = parse_log_stream(reader).collect::<Result<_, _>>().unwrap();
+        assert_eq!(events, parse_log_file(log_path).unwrap());
+
+        // Iteration stops after an error
+        let kill = r#"{"Type": "KillMachineProcess", "Time": "2.0", "KillType": "6", "Process": "name: Server address: 2.2.1.2:1 zone: z", "StartingClass": "storage", "Failed": "0", "Excluded": "0", "Cleared": "0", "Rebooting": "0"}"#;
+        let trace = [kill.as_bytes(), b"\n\xff\n", kill.as_bytes()].concat();
+        let mut events = parse_log_stream(&trace[..]);
+        assert!(matches!(
+            events.next(),
+            Some(Ok(Event::KillMachineProcess(_)))
+        ));
+        assert!(matches!(
+            events.next(),
+            Some(Err(ParsingError::InvalidUtf8 { line: 2 }))
+        ));
+        assert!(events.next().is_none());
+        assert_eq!(events.lines(), 2);
+    }
+
+    #[test]
+    fn test_parse_log_file_parallel() {
+        let log_path = Path::new("logs/combined_trace.0.0.0.0.24.1745498878.p7Loj0.json");
+        let options = ParseOptions {
+            sampling: "clog_interface=0.5".parse().unwrap(),
+            ..ParseOptions::default()
+        };
+        let sequential = parse_log_file_with_options(log_path, &options).unwrap();
+        // Chunks of a few lines each, starting in the middle of lines
+        let parallel = parse_log_file_in_chunks(log_path, &options, 1000).unwrap();
+        assert_eq!(parallel, sequential);
+        assert_eq!(
+            parse_log_file_parallel(log_path, &options).unwrap(),
+            sequential
+        );
+    }
+
+    #[test]
+    fn test_capture_other_events() {
+        let line = r#"{"Severity": "10", "Time": "3.5", "Type": "MasterRecoveryState", "Machine": "2.0.1.0:1", "StatusCode": "0"}"#;
+        let options = ParseOptions {
+            capture_other: true,
+            ..ParseOptions::default()
+        };
+        let events: Vec<Event> = EventIterator::new(line.as_bytes(), options)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        let Event::Other(data) = &events[0] else {
+            panic!("Expected an Other event, got {:?}", events);
+        };
+        assert_eq!(data.event_type, "MasterRecoveryState");
+        assert_eq!(data.fields["StatusCode"], "0");
+        assert_eq!(events[0].timestamp(), 3.5);
+        assert_eq!(events[0].machine_ips(), ["2.0.1.0"]);
+
+        // Dropped by default
+        assert_eq!(parse_log_stream(line.as_bytes()).count(), 0);
+    }
+
     #[test]
     fn test_parse_program_start_event_with_seed() {
         let json_line = json!({
@@ -528,6 +2483,7 @@ mod tests {
                 assert_eq!(data.timestamp, "0.000000");
                 assert_eq!(data.machine, "0.0.0.0:0");
                 assert_eq!(data.random_seed.as_deref(), Some("2837976339"));
+                assert_eq!(data.version.as_deref(), Some("7.3.43"));
             }
             _ => panic!("Parsed event is not a ProgramStart event"),
         }
@@ -613,6 +2569,23 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_disk_swap_event() {
+        let json_line = json!({
+          "Severity": "10", "Time": "75.120000", "Type": "SimulatedMachineFolderSwap", "Machine": "0.0.0.0:0", "MachineIPs": "[2.0.1.1:1, 2.0.1.2:1,2.0.1.3]"
+        });
+        let node: JsonNode =
+            serde_json::from_str(&json_line.to_string()).expect("Failed to parse JSON line");
+
+        match parse_event_from_node(&node) {
+            Some(Event::DiskSwap(data)) => {
+                assert_eq!(data.timestamp, "75.120000");
+                assert_eq!(data.ips(), ["2.0.1.1", "2.0.1.2", "2.0.1.3"]);
+            }
+            other => panic!("Parsed event is not a DiskSwap event: {:?}", other),
+        }
+    }
+
     #[test]
     fn test_parse_kill_machine_process_event() {
         let json_data = json!({
@@ -649,6 +2622,178 @@ mod tests {
         assert_eq!(event, Event::KillMachineProcess(expected_data));
     }
 
+    #[test]
+    fn test_event_try_from_str() {
+        let line = r#"{"Severity": "10", "Time": "12.5", "Type": "CloggingPair", "From": "2.0.1.0", "To": "2.0.1.1", "Seconds": "3.2"}"#;
+        let event = Event::try_from(line).expect("Line should convert into an Event");
+        match event {
+            Event::CloggingPair(data) => {
+                assert_eq!(data.timestamp, "12.5");
+                assert_eq!(data.from_id, "2.0.1.0");
+                assert_eq!(data.to_id, "2.0.1.1");
+                assert_eq!(data.seconds, "3.2");
+            }
+            _ => panic!("Converted event is not a CloggingPair event"),
+        }
+
+        assert!(matches!(
+            Event::try_from("not json"),
+            Err(EventConversionError::Json(_))
+        ));
+        assert!(matches!(
+            Event::try_from(r#"{"Time": "1.0"}"#),
+            Err(EventConversionError::MissingType)
+        ));
+        assert!(matches!(
+            Event::try_from(r#"{"Time": "1.0", "Type": "SomethingElse"}"#),
+            Err(EventConversionError::EventData { event_type }) if event_type == "SomethingElse"
+        ));
+    }
+
+    #[test]
+    fn test_parse_storage_server_membership_events() {
+        let added = json!({
+          "Severity": "10", "Time": "9.073994", "DateTime": "2025-04-24T12:47:58Z", "Type": "StorageServerAddedToRunningStorage", "Machine": "2.1.1.0:1", "ID": "283481919f0d686d", "ThreadID": "4687316415922983387", "LogGroup": "default", "Roles": "MS,SS"
+        });
+        let removed = json!({
+          "Severity": "10", "Time": "59.921404", "DateTime": "2025-04-24T12:47:59Z", "Type": "StorageServerRemoveFromRunningStorage", "Machine": "2.0.1.0:1", "ID": "3764f6c9c5051cfb", "ThreadID": "4687316415922983387", "LogGroup": "default"
+        });
+
+        assert_eq!(
+            parse_event_from_node(&added),
+            Some(Event::StorageServerAdded(StorageServerAddedData {
+                timestamp: "9.073994".to_string(),
+                machine: "2.1.1.0:1".to_string(),
+                server_id: "283481919f0d686d".to_string(),
+            }))
+        );
+        assert_eq!(
+            parse_event_from_node(&removed),
+            Some(Event::StorageServerRemoved(StorageServerRemovedData {
+                timestamp: "59.921404".to_string(),
+                machine: "2.0.1.0:1".to_string(),
+                server_id: "3764f6c9c5051cfb".to_string(),
+            }))
+        );
+    }
+
+    #[test]
+    fn test_parse_recruitment_failure_events() {
+        let not_available = json!({
+          "Severity": "20", "Time": "10.872543", "DateTime": "2025-04-24T12:47:58Z", "Type": "RecruitStorageNotAvailable", "Machine": "2.1.1.2:1", "ID": "ebe1e78f70837579", "Error": "no_more_servers", "ErrorDescription": "Not enough physical servers available", "ErrorCode": "1008", "IsCriticalRecruitment": "0", "ThreadID": "4687316415922983387", "LogGroup": "default", "Roles": "CC,CP,GP,SS"
+        });
+        match parse_event_from_node(&not_available) {
+            Some(Event::RecruitmentFailure(data)) => {
+                assert_eq!(data.timestamp, "10.872543");
+                assert_eq!(data.event_type, "RecruitStorageNotAvailable");
+                assert_eq!(data.machine, "2.1.1.2:1");
+                assert_eq!(data.error.as_deref(), Some("no_more_servers"));
+                assert_eq!(data.is_critical.as_deref(), Some("0"));
+            }
+            other => panic!("Unexpected parse result: {:?}", other),
+        }
+
+        let retrying = json!({
+          "Severity": "20", "Time": "4.980765", "Type": "ClusterRecoveryRetrying", "Machine": "2.1.1.2:1", "Error": "no_more_servers", "ErrorDescription": "Not enough physical servers available"
+        });
+        assert!(matches!(
+            parse_event_from_node(&retrying),
+            Some(Event::RecruitmentFailure(_))
+        ));
+
+        let unrelated_retry = json!({
+          "Severity": "20", "Time": "5.0", "Type": "ClusterRecoveryRetrying", "Machine": "2.1.1.2:1", "Error": "tlog_failed"
+        });
+        assert!(parse_event_from_node(&unrelated_retry).is_none());
+    }
+
+    #[test]
+    fn test_parse_buggify_section() {
+        let node = json!({
+          "Severity": "10", "Time": "0.000000", "DateTime": "1970-01-01T00:00:00Z", "Type": "BuggifySection", "Activated": "1", "File": "/home/foundationdb_ci/src/foundationdb/flow/Knobs.cpp", "Line": "51", "Machine": "0.0.0.0:0", "LogGroup": "default"
+        });
+        match parse_event_from_node(&node) {
+            Some(Event::BuggifySection(data)) => {
+                assert!(data.is_activated());
+                assert_eq!(data.location(), "Knobs.cpp:51");
+            }
+            other => panic!("Unexpected parse result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_rebooter_start() {
+        let node = json!({
+          "Severity": "10", "Time": "75.053886", "Type": "SimulatedRebooterStarting", "Machine": "0.0.0.0:0", "ID": "0000000000000000", "Cycles": "2", "RandomId": "a21588fba90226b3", "ZoneId": "20fc497ed5a1efc4ae829f", "DataHall": "1", "Address": "2.0.1.1:1:tls", "Excluded": "0", "UsingSSL": "1", "ProcessMode": "3", "LogGroup": "default"
+        });
+        match parse_event_from_node(&node) {
+            Some(Event::RebooterStart(data)) => {
+                assert!(data.is_restart());
+                assert_eq!(data.process_address(), "2.0.1.1:1");
+            }
+            other => panic!("Unexpected parse result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_role() {
+        let node = json!({
+          "Severity": "10", "Time": "9.101267", "DateTime": "2025-04-24T12:47:58Z", "Type": "Role", "Machine": "2.0.1.2:1", "ID": "73c3ff7694d34462", "As": "TLog", "Transition": "Begin", "Origination": "Recruited", "OnWorker": "c6abbeec3dc1a293", "SharedTLog": "f205d64ba1d7eb87", "ThreadID": "4687316415922983387", "LogGroup": "default", "Roles": "SS,TL", "TrackLatestType": "Original"
+        });
+        match parse_event_from_node(&node) {
+            Some(Event::Role(data)) => {
+                assert_eq!(data.role, "TLog");
+                assert_eq!(data.machine, "2.0.1.2:1");
+                assert!(!data.is_end());
+            }
+            other => panic!("Unexpected parse result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_severe_event() {
+        let node = json!({
+          "Severity": "30", "Time": "120.5", "Type": "TransactionTooManyRetries", "Machine": "2.0.1.0:1", "ID": "0000000000000000", "LogGroup": "default"
+        });
+        match parse_event_from_node(&node) {
+            Some(Event::SevereEvent(data)) => {
+                assert_eq!(data.event_type, "TransactionTooManyRetries");
+                assert_eq!(data.severity(), 30);
+                assert_eq!(data.machine.as_deref(), Some("2.0.1.0:1"));
+                assert_eq!(data.error, None);
+            }
+            other => panic!("Unexpected parse result: {:?}", other),
+        }
+
+        let info = json!({ "Severity": "20", "Time": "1.0", "Type": "SomethingNoisy" });
+        assert!(parse_event_from_node(&info).is_none());
+    }
+
+    #[test]
+    fn test_parse_assassination() {
+        let node = json!({
+          "Severity": "10", "Time": "54.523646", "DateTime": "2025-04-24T12:47:59Z", "Type": "Assassination", "Machine": "3.4.3.5:1", "ID": "0000000000000000", "TargetMachine": "zoneid=af0447e31d137a87afdea1539e414288 processid=[unset] machineid=5835a37723cc1d589117ac73d898ed97 dcid=2 data_hall=2", "ZoneId": "af0447e31d137a87afdea1539e414288", "Reboot": "1", "KilledMachines": "0", "MachinesToKill": "10", "MachinesToLeave": "3", "Machines": "17", "Replace": "0", "ThreadID": "4687316415922983387", "LogGroup": "default", "Roles": "TS"
+        });
+        match parse_event_from_node(&node) {
+            Some(Event::Assassination(data)) => {
+                assert!(data.is_reboot());
+                assert_eq!(
+                    data.target_machine_id(),
+                    Some("5835a37723cc1d589117ac73d898ed97")
+                );
+            }
+            other => panic!("Unexpected parse result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_ip_from_address() {
+        assert_eq!(ip_from_address("2.1.1.0:1:tls"), "2.1.1.0");
+        assert_eq!(ip_from_address("2.1.1.0:1"), "2.1.1.0");
+        assert_eq!(ip_from_address("2.1.1.0"), "2.1.1.0");
+        assert_eq!(ip_from_address("[::1]:80"), "::1");
+    }
+
     #[test]
     fn test_kill_type_from_str() {
         assert_eq!(KillType::from_str("0").unwrap(), KillType::KillInstantly);
@@ -668,6 +2813,16 @@ mod tests {
         assert_eq!(KillType::from_str("8").unwrap(), KillType::Unknown);
     }
 
+    #[test]
+    fn test_kill_type_descriptions_are_distinct() {
+        let descriptions: std::collections::HashSet<_> = KillType::INJECTABLE
+            .iter()
+            .map(KillType::description)
+            .collect();
+        assert_eq!(descriptions.len(), KillType::INJECTABLE.len());
+        assert!(KillType::RebootAndDelete.description().contains("deletes"));
+    }
+
     #[test]
     fn test_parse_simulator_config_event() {
         let json_str = r#"
@@ -686,7 +2841,7 @@ mod tests {
         let node: JsonNode = serde_json::from_str(json_str).unwrap();
         let event = parse_event_from_node(&node).unwrap();
 
-        let mut expected_config = HashMap::new();
+        let mut expected_config = BTreeMap::new();
         expected_config.insert("backup_worker_enabled".to_string(), "0".to_string());
         expected_config.insert("blob_granules_enabled".to_string(), "0".to_string());
         expected_config.insert("commit_proxies".to_string(), "4".to_string());
@@ -726,4 +2881,69 @@ mod tests {
             _ => panic!("Parsed event is not a SimulatorConfig event"),
         }
     }
+
+    #[test]
+    fn test_parse_coordinators() {
+        let coordinators = parse_coordinators("TestCluster:0@2.0.1.1:1:tls,2.0.1.0:1:tls");
+        assert_eq!(
+            coordinators
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            ["2.0.1.0:1:tls", "2.0.1.1:1:tls"]
+        );
+        assert_eq!(
+            parse_coordinators("db:id@[2001:db8::1]:4500,bad,10.0.0.1:x"),
+            [CoordinatorAddress {
+                ip: "2001:db8::1".to_string(),
+                port: 4500,
+                tls: false
+            }]
+        );
+        assert!(parse_coordinators("").is_empty());
+    }
+
+    #[test]
+    fn test_parse_byte_size() {
+        assert_eq!(parse_byte_size("1048576"), Ok(1_048_576));
+        assert_eq!(parse_byte_size("512KB"), Ok(512 * 1024));
+        assert_eq!(parse_byte_size("1.5gib"), Ok(3 * 512 * 1024 * 1024));
+        assert!(parse_byte_size("12 parsecs").is_err());
+        assert!(parse_byte_size("MB").is_err());
+    }
+
+    #[test]
+    fn test_parse_log_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let line = |time: &str| {
+            format!(
+                r#"{{"Type": "CoordinatorsChangeBeforeCommit", "Time": "{}", "NewCoordinatorsKey": "k"}}"#,
+                time
+            )
+        };
+        let part_0 = [line("1.0"), line("5.0")].join("\n");
+        let part_1 = [line("2.0"), line("3.0")].join("\n");
+        std::fs::write(dir.path().join("trace.0.0.0.0.1.json"), part_0).unwrap();
+        std::fs::write(dir.path().join("trace.0.0.0.0.2.json"), part_1).unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "not a trace").unwrap();
+
+        let times: Vec<f64> = parse_log_directory(dir.path())
+            .unwrap()
+            .iter()
+            .map(Event::timestamp)
+            .collect();
+        assert_eq!(times, [1.0, 2.0, 3.0, 5.0]);
+
+        let streamed: Vec<Event> = stream_log_directory(dir.path(), &ParseOptions::default())
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(streamed, parse_log_directory(dir.path()).unwrap());
+
+        let empty = tempfile::tempdir().unwrap();
+        assert!(matches!(
+            parse_log_directory(empty.path()),
+            Err(ParsingError::NoTraceFiles(_))
+        ));
+    }
 }
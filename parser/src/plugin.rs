@@ -0,0 +1,190 @@
+//! Custom event types, for downstream crates parsing workload-specific trace events
+//! without forking `Event`.
+//!
+//! A custom type implements `DynamicEvent` and is registered with
+//! `ParseOptions::register_event_parser`; its events come out of the parser as
+//! `Event::Custom`. Once serialized, a custom event can only be read back as a
+//! `JsonEvent`, since the parser of its type is not known to the deserializer.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value as JsonNode;
+use std::any::Any;
+use std::fmt;
+use std::sync::Arc;
+
+/// An event of a type defined outside of this crate.
+pub trait DynamicEvent: Any + fmt::Debug + Send + Sync {
+    /// Trace event type, e.g. "ConsistencyCheck_FinishedCheck".
+    fn event_type(&self) -> &str;
+
+    /// Simulated time of the event, in seconds.
+    fn timestamp(&self) -> f64;
+
+    /// Address of the process that logged the event, e.g. "2.0.1.0:1", if any.
+    fn machine(&self) -> Option<&str> {
+        None
+    }
+
+    /// The fields of the event as a JSON object, used to serialize and compare it.
+    fn to_json(&self) -> JsonNode;
+}
+
+/// A `DynamicEvent` carried by `Event::Custom`. Cloning shares the event.
+#[derive(Debug, Clone)]
+pub struct CustomEvent(Arc<dyn DynamicEvent>);
+
+impl CustomEvent {
+    pub fn new(event: impl DynamicEvent) -> Self {
+        Self(Arc::new(event))
+    }
+
+    /// The event as its concrete type, `None` when it is of another type.
+    pub fn downcast_ref<T: DynamicEvent>(&self) -> Option<&T> {
+        let event: &dyn Any = self.0.as_ref();
+        event.downcast_ref()
+    }
+}
+
+impl std::ops::Deref for CustomEvent {
+    type Target = dyn DynamicEvent;
+
+    fn deref(&self) -> &Self::Target {
+        self.0.as_ref()
+    }
+}
+
+impl PartialEq for CustomEvent {
+    fn eq(&self, other: &Self) -> bool {
+        self.event_type() == other.event_type() && self.to_json() == other.to_json()
+    }
+}
+
+impl Serialize for CustomEvent {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        JsonEvent {
+            event_type: self.event_type().to_string(),
+            timestamp: self.timestamp(),
+            machine: self.machine().map(str::to_string),
+            fields: self.to_json(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for CustomEvent {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        JsonEvent::deserialize(deserializer).map(CustomEvent::new)
+    }
+}
+
+/// A custom event read back from its serialized form, see `CustomEvent`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JsonEvent {
+    #[serde(rename = "Type")]
+    pub event_type: String,
+    #[serde(rename = "Time")]
+    pub timestamp: f64,
+    #[serde(rename = "Machine")]
+    pub machine: Option<String>,
+    /// The fields of the event, see `DynamicEvent::to_json`.
+    #[serde(rename = "Fields")]
+    pub fields: JsonNode,
+}
+
+impl DynamicEvent for JsonEvent {
+    fn event_type(&self) -> &str {
+        &self.event_type
+    }
+
+    fn timestamp(&self) -> f64 {
+        self.timestamp
+    }
+
+    fn machine(&self) -> Option<&str> {
+        self.machine.as_deref()
+    }
+
+    fn to_json(&self) -> JsonNode {
+        self.fields.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{Event, EventIterator, ParseOptions};
+    use crate::record::to_record;
+
+    #[derive(Debug)]
+    struct WorkloadProgress {
+        time: f64,
+        machine: String,
+        percent: u32,
+    }
+
+    impl DynamicEvent for WorkloadProgress {
+        fn event_type(&self) -> &str {
+            "WorkloadProgress"
+        }
+
+        fn timestamp(&self) -> f64 {
+            self.time
+        }
+
+        fn machine(&self) -> Option<&str> {
+            Some(&self.machine)
+        }
+
+        fn to_json(&self) -> JsonNode {
+            serde_json::json!({ "Percent": self.percent })
+        }
+    }
+
+    fn parse_progress(node: &JsonNode) -> Option<WorkloadProgress> {
+        let field = |name: &str| node.get(name)?.as_str();
+        Some(WorkloadProgress {
+            time: field("Time")?.parse().ok()?,
+            machine: field("Machine")?.to_string(),
+            percent: field("Percent")?.parse().ok()?,
+        })
+    }
+
+    #[test]
+    fn test_register_event_parser() {
+        let trace = concat!(
+            r#"{"Type": "WorkloadProgress", "Time": "12.5", "Machine": "2.0.1.0:1", "Percent": "40"}"#,
+            "\n",
+            r#"{"Type": "WorkloadProgress", "Time": "13.0", "Machine": "2.0.1.0:1"}"#,
+            "\n",
+        );
+        let mut options = ParseOptions::default();
+        options.register_event_parser("WorkloadProgress", parse_progress);
+        let events: Vec<Event> = EventIterator::new(trace.as_bytes(), options)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(events.len(), 1);
+        let Event::Custom(custom) = &events[0] else {
+            panic!("Expected a custom event, got {:?}", events);
+        };
+        assert_eq!(
+            custom.downcast_ref::<WorkloadProgress>().unwrap().percent,
+            40
+        );
+        assert_eq!(events[0].timestamp(), 12.5);
+        assert_eq!(events[0].machine_ips(), ["2.0.1.0"]);
+        assert_eq!(to_record(&events[0])["type"], "WorkloadProgress");
+
+        // Read back without its parser, the event keeps its fields
+        let json = serde_json::to_string(&events[0]).unwrap();
+        let read: Event = serde_json::from_str(&json).unwrap();
+        assert_eq!(read, events[0]);
+        let Event::Custom(read) = read else {
+            unreachable!()
+        };
+        assert!(read.downcast_ref::<WorkloadProgress>().is_none());
+        assert_eq!(
+            read.downcast_ref::<JsonEvent>().unwrap().fields["Percent"],
+            40
+        );
+    }
+}
@@ -0,0 +1,128 @@
+//! Flat records: events as maps of snake_case field names to string values, the
+//! common ground of the tabular text and CSV outputs.
+//!
+//! A record holds the fields of the event as logged, renamed to snake_case (e.g.
+//! `KillType` becomes `kill_type`), plus `time`, `type` and `machine` for every event:
+//! `machine` is the address of the logging process, or the IPs of the machines
+//! involved for events without one. Kill types are given by name rather than code.
+
+use crate::export::escape_csv;
+use crate::parser::Event;
+use serde_json::Value as JsonNode;
+use std::collections::BTreeMap;
+
+/// Columns printed when none are selected.
+pub const DEFAULT_COLUMNS: &[&str] = &["time", "type", "machine"];
+
+/// A flattened event, keyed by snake_case field name.
+pub type Record = BTreeMap<String, String>;
+
+/// Flattens an event into a record.
+pub fn to_record(event: &Event) -> Record {
+    let mut record = Record::new();
+    // Events serialize as { "<Variant>": { <fields> } }
+    if let Ok(JsonNode::Object(variant)) = serde_json::to_value(event) {
+        if let Some(JsonNode::Object(fields)) = variant.into_iter().next().map(|(_, v)| v) {
+            for (name, value) in fields {
+                let value = match value {
+                    JsonNode::Null => continue,
+                    JsonNode::String(value) => value,
+                    other => other.to_string(),
+                };
+                record.insert(snake_case(&name), value);
+            }
+        }
+    }
+    // Other and severe events keep the type they were logged with
+    record
+        .entry("type".to_string())
+        .or_insert_with(|| event.event_type().to_string());
+    record
+        .entry("time".to_string())
+        .or_insert_with(|| event.timestamp().to_string());
+    let ips = event.machine_ips();
+    if !ips.is_empty() {
+        record
+            .entry("machine".to_string())
+            .or_insert_with(|| ips.join(" "));
+    }
+    if let Event::KillMachineProcess(data) = event {
+        let kill_type = data.kill_type();
+        record.insert("kill_type".to_string(), format!("{:?}", kill_type));
+    }
+    record
+}
+
+/// Converts a trace field name to snake_case, keeping acronyms together, e.g.
+/// "MachineIPs" to "machine_ips" and "DCId" to "dc_id".
+fn snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut out = String::with_capacity(name.len() + 4);
+    for (i, c) in chars.iter().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            let previous = chars[i - 1];
+            // A lowercase letter after an acronym starts a new word, unless it is the
+            // plural "s" of the acronym as in "IPs"
+            let next_is_lower = chars.get(i + 1).is_some_and(|n| n.is_lowercase())
+                && !(chars[i + 1] == 's' && chars.get(i + 2).is_none_or(|n| n.is_uppercase()));
+            if previous.is_lowercase()
+                || previous.is_ascii_digit()
+                || (previous.is_uppercase() && next_is_lower)
+            {
+                out.push('_');
+            }
+        }
+        out.extend(c.to_lowercase());
+    }
+    out
+}
+
+/// Renders the selected columns of a record as a tab-separated line, "-" standing for
+/// missing fields.
+pub fn to_text_row(record: &Record, columns: &[String]) -> String {
+    columns
+        .iter()
+        .map(|column| record.get(column).map_or("-", String::as_str))
+        .collect::<Vec<_>>()
+        .join("\t")
+}
+
+/// Renders the selected columns of a record as a CSV line, missing fields empty.
+pub fn to_csv_row(record: &Record, columns: &[String]) -> String {
+    columns
+        .iter()
+        .map(|column| escape_csv(record.get(column).map_or("", String::as_str)))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_record() {
+        let event = Event::try_from(
+            r#"{"Severity": "10", "Time": "54.5", "Type": "KillMachineProcess", "Machine": "3.4.3.5:1", "KillType": "6", "Process": "name: Server address: 2.2.1.2:1 zone: z", "StartingClass": "storage", "Failed": "0", "Excluded": "0", "Cleared": "0", "Rebooting": "0"}"#,
+        )
+        .unwrap();
+        let record = to_record(&event);
+        assert_eq!(record["time"], "54.5");
+        assert_eq!(record["type"], "KillMachineProcess");
+        assert_eq!(record["machine"], "2.2.1.2");
+        assert_eq!(record["kill_type"], "Reboot");
+        assert_eq!(record["starting_class"], "storage");
+
+        let columns: Vec<String> = ["time", "kill_type", "zone"]
+            .iter()
+            .map(|c| c.to_string())
+            .collect();
+        assert_eq!(to_text_row(&record, &columns), "54.5\tReboot\t-");
+        assert_eq!(to_csv_row(&record, &columns), "54.5,Reboot,");
+
+        assert_eq!(snake_case("MachineIPs"), "machine_ips");
+        assert_eq!(snake_case("DCId"), "dc_id");
+        assert_eq!(snake_case("ID"), "id");
+        assert_eq!(snake_case("SimTime"), "sim_time");
+    }
+}
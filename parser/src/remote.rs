@@ -0,0 +1,132 @@
+//! Reading traces from object storage and HTTP servers.
+//!
+//! Ensemble traces usually live in S3 rather than on the machine used for triage, so
+//! the parser also accepts `s3://<bucket>/<key>`, `http://` and `https://` locations
+//! wherever it accepts a trace path. The content is streamed through the line reader,
+//! never downloaded whole. Reading remote traces needs the `remote` feature; S3
+//! credentials and region come from the usual `AWS_*` environment variables.
+
+use std::io::Read;
+use std::path::Path;
+use thiserror::Error;
+
+/// URL schemes of the trace locations read remotely.
+pub const REMOTE_SCHEMES: &[&str] = &["s3://", "http://", "https://"];
+
+#[derive(Error, Debug)]
+pub enum RemoteError {
+    #[error("Reading '{0}' requires building with the `remote` feature")]
+    Unsupported(String),
+    #[error("Invalid S3 location '{0}', expected s3://<bucket>/<key>")]
+    InvalidS3Location(String),
+    #[cfg(feature = "remote")]
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+    #[cfg(feature = "remote")]
+    #[error("Object store error: {0}")]
+    ObjectStore(#[from] object_store::Error),
+    #[cfg(feature = "remote")]
+    #[error("Failed to start the I/O runtime: {0}")]
+    Runtime(#[from] std::io::Error),
+}
+
+/// Returns the URL of a trace location that must be read remotely, `None` for local
+/// files.
+pub fn remote_location(path: &Path) -> Option<&str> {
+    let location = path.to_str()?;
+    REMOTE_SCHEMES
+        .iter()
+        .any(|scheme| location.starts_with(scheme))
+        .then_some(location)
+}
+
+/// Splits an `s3://<bucket>/<key>` location into its bucket and key.
+fn split_s3_location(location: &str) -> Result<(&str, &str), RemoteError> {
+    location
+        .strip_prefix("s3://")
+        .and_then(|rest| rest.split_once('/'))
+        .filter(|(bucket, key)| !bucket.is_empty() && !key.is_empty())
+        .ok_or_else(|| RemoteError::InvalidS3Location(location.to_string()))
+}
+
+/// Opens a remote trace, see `remote_location`, as a stream of bytes.
+#[cfg(feature = "remote")]
+pub fn open(location: &str) -> Result<Box<dyn Read + Send>, RemoteError> {
+    if !location.starts_with("s3://") {
+        let response = reqwest::blocking::get(location)?.error_for_status()?;
+        return Ok(Box::new(response));
+    }
+
+    use object_store::{aws::AmazonS3Builder, ObjectStore};
+    let (bucket, key) = split_s3_location(location)?;
+    let store = AmazonS3Builder::from_env()
+        .with_bucket_name(bucket)
+        .build()?;
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?;
+    let object = runtime.block_on(store.get(&object_store::path::Path::from(key)))?;
+    Ok(Box::new(ObjectReader {
+        stream: object.into_stream(),
+        runtime,
+        chunk: bytes::Bytes::new(),
+    }))
+}
+
+/// Opens a remote trace, see `remote_location`. Always fails without the `remote`
+/// feature.
+#[cfg(not(feature = "remote"))]
+pub fn open(location: &str) -> Result<Box<dyn Read + Send>, RemoteError> {
+    if location.starts_with("s3://") {
+        split_s3_location(location)?;
+    }
+    Err(RemoteError::Unsupported(location.to_string()))
+}
+
+/// Blocking reader over the chunks of an object store download.
+#[cfg(feature = "remote")]
+struct ObjectReader {
+    stream: futures::stream::BoxStream<'static, object_store::Result<bytes::Bytes>>,
+    runtime: tokio::runtime::Runtime,
+    /// Unread part of the last chunk.
+    chunk: bytes::Bytes,
+}
+
+#[cfg(feature = "remote")]
+impl Read for ObjectReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        use futures::StreamExt;
+        while self.chunk.is_empty() {
+            match self.runtime.block_on(self.stream.next()) {
+                Some(chunk) => self.chunk = chunk.map_err(std::io::Error::other)?,
+                None => return Ok(0),
+            }
+        }
+        let read = buf.len().min(self.chunk.len());
+        buf[..read].copy_from_slice(&self.chunk.split_to(read));
+        Ok(read)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remote_location() {
+        let remote = Path::new("s3://traces/ensemble-42/trace.json");
+        assert_eq!(
+            remote_location(remote),
+            Some("s3://traces/ensemble-42/trace.json")
+        );
+        assert!(remote_location(Path::new("https://example.com/trace.json")).is_some());
+        assert_eq!(remote_location(Path::new("logs/trace.json")), None);
+
+        assert_eq!(
+            split_s3_location("s3://traces/ensemble-42/trace.json").unwrap(),
+            ("traces", "ensemble-42/trace.json")
+        );
+        assert!(split_s3_location("s3://traces").is_err());
+        assert!(split_s3_location("s3:///trace.json").is_err());
+    }
+}
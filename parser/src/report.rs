@@ -1,10 +1,28 @@
+pub mod html;
+pub mod markdown;
+
+use crate::analysis::{
+    AnalysisRegistry, AnalysisSection, AnalysisTable, AnalysisValue, Finding, FindingLevel,
+};
+use crate::annotations::Annotation;
+use crate::cluster::ClusterIndex;
+use crate::lifecycle::{
+    self, downtime_by_kill_type, Availability, DowntimeStats, LifecycleTracker,
+};
+use crate::number_format::NumberFormat;
 use crate::parser::*;
+use crate::sampling::Sampling;
+use crate::sketch::{CountMinSketch, HyperLogLog};
+use crate::time_format::{TimeFormat, TimeFormatter};
 use colored::Colorize; // Import colored functionality
 use comfy_table::{presets::UTF8_FULL, Cell, ContentArrangement, Table}; // Import comfy-table
-use humantime::format_duration;
 use serde::{Deserialize, Serialize}; // Add this back
-use std::collections::{HashMap, HashSet};
+use std::borrow::Borrow;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fmt;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::Path;
 use std::str::FromStr; // Add this import back
 
 // --- Struct Definitions ---
@@ -26,6 +44,268 @@ pub struct ClogInterfaceSummary {
     pub max_seconds: f64,
 }
 
+/// Holds summary statistics for SetDiskFailure events.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiskFailureSummary {
+    pub count: usize,
+    /// Seconds of disk stalls, summed over all failures.
+    pub total_stall_seconds: f64,
+    /// Seconds of disk throttling, summed over all failures.
+    pub total_throttle_seconds: f64,
+    /// The same statistics for each machine, keyed by IP address.
+    pub by_machine: BTreeMap<String, MachineDiskFailures>,
+}
+
+/// Disk failures of a single machine, see `DiskFailureSummary`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MachineDiskFailures {
+    pub count: usize,
+    pub stall_seconds: f64,
+    pub throttle_seconds: f64,
+}
+
+/// Holds churn statistics for storage server membership changes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StorageServerChurnSummary {
+    /// Number of storage servers added to the running storage set.
+    pub added: usize,
+    /// Number of storage servers removed from the running storage set.
+    pub removed: usize,
+    /// Number of distinct storage server IDs seen during the run.
+    pub unique_servers: usize,
+}
+
+/// Aggregated recruitment failures sharing the same event type and error.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecruitmentFailureSummary {
+    pub event_type: String,
+    pub error: String,
+    pub count: usize,
+    pub first_seen: f64,
+    pub last_seen: f64,
+}
+
+/// A recovery of the transaction system, from its MasterRecoveryState events.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecoveryInfo {
+    /// ID shared by the events of the recovery.
+    pub id: String,
+    /// Address of the process running the recovery.
+    pub machine: String,
+    /// Simulated time of the first step of the recovery.
+    pub start: f64,
+    /// Seconds until the cluster was fully recovered, `None` if it never was, e.g.
+    /// because a newer recovery superseded this one.
+    pub duration: Option<f64>,
+    /// Last status reached, e.g. "fully_recovered".
+    pub status: String,
+    /// Closest kill or clog preceding the recovery, its likely trigger.
+    pub cause: Option<RecoveryCause>,
+}
+
+/// A chaos event preceding a recovery.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecoveryCause {
+    pub timestamp: f64,
+    /// e.g. "KillMachineProcess 2.0.1.0" or "CloggingPair 2.0.1.0 -> 2.0.1.1".
+    pub description: String,
+}
+
+impl RecoveryCause {
+    /// Describes a kill or clog, `None` for other events.
+    fn from_event(event: &Event) -> Option<Self> {
+        let targets = match event {
+            Event::KillMachineProcess(_) | Event::ClogInterface(_) => {
+                event.machine_ips().join(", ")
+            }
+            Event::CloggingPair(data) => format!("{} -> {}", data.from_id, data.to_id),
+            Event::Assassination(data) => format!("zone {}", data.zone_id),
+            _ => return None,
+        };
+        Some(Self {
+            timestamp: event.timestamp(),
+            description: format!("{} {}", event.event_type(), targets),
+        })
+    }
+}
+
+/// Severity 30+ events of a single type, aggregated over the whole trace.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SevereEventSummary {
+    pub event_type: String,
+    /// Highest severity logged for this type.
+    pub severity: u32,
+    pub count: usize,
+    pub first_seen: f64,
+    pub last_seen: f64,
+    /// Machines logging this type the most, as (address, count), most frequent first.
+    pub top_machines: Vec<(String, usize)>,
+    /// Time and severity of every event of this type, sorted by time, so that the
+    /// events of a time window are summarized exactly, see `SimulationReport::window`.
+    #[serde(default)]
+    pub occurrences: Vec<(f64, u32)>,
+}
+
+impl SevereEventSummary {
+    /// The summary of the events of this type between `from` and `to` seconds, `None`
+    /// when there are none. Machines are not kept per event, so `top_machines` is
+    /// left empty.
+    fn window(&self, from: f64, to: f64) -> Option<SevereEventSummary> {
+        let start = self.occurrences.partition_point(|(t, _)| *t < from);
+        let end = self
+            .occurrences
+            .partition_point(|(t, _)| *t <= to)
+            .max(start);
+        let occurrences = &self.occurrences[start..end];
+        Some(SevereEventSummary {
+            event_type: self.event_type.clone(),
+            severity: occurrences.iter().map(|(_, severity)| *severity).max()?,
+            count: occurrences.len(),
+            first_seen: occurrences.first()?.0,
+            last_seen: occurrences.last()?.0,
+            top_machines: Vec::new(),
+            occurrences: occurrences.to_vec(),
+        })
+    }
+}
+
+/// Estimates of statistics that need the raw events to be exact, see `sketch`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ApproximateCounts {
+    /// Distinct machine IPs in CloggingPair and ClogInterface events.
+    pub clogged_machines: usize,
+    /// Distinct pairs of machines clogged by CloggingPair events, in either direction.
+    pub clogged_pairs: usize,
+    /// Number of events of each type, keyed by `Event::event_type` name.
+    pub events_by_type: BTreeMap<String, usize>,
+}
+
+/// Raw Severity 30+ events kept per event type unless all of them are requested.
+pub const SEVERE_EVENT_SAMPLES_PER_TYPE: usize = 5;
+
+/// Event types listed in the errors and warnings table when raw events are capped.
+pub const TOP_SEVERE_EVENT_TYPES: usize = 10;
+
+/// Machines listed per event type in `SevereEventSummary::top_machines`.
+const TOP_SEVERE_EVENT_MACHINES: usize = 3;
+
+/// Chaos injected into a single machine, attributed by IP address.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MachineChaosSummary {
+    /// Number of process kills on this machine, grouped by KillType.
+    pub kills_by_type: BTreeMap<KillType, usize>,
+    /// Number of SetDiskFailure events targeting this machine.
+    pub disk_failures: usize,
+    /// Number of CorruptedBlock events reported by this machine.
+    pub corrupted_blocks: usize,
+    /// Number of CloggingPair events with this machine on either end.
+    pub clogging_pairs: usize,
+    /// Number of ClogInterface events targeting this machine's interface.
+    pub clogged_interfaces: usize,
+}
+
+/// The coordinators before and after a CoordinatorsChange event.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CoordinatorSetChange {
+    /// Simulated time of the change.
+    pub timestamp: f64,
+    /// Coordinators after the previous change, or from the connection string of the
+    /// first processes for the first change. Empty when unknown.
+    pub before: Vec<CoordinatorAddress>,
+    /// Coordinators of the new connection string, `before` when the event logs none.
+    pub after: Vec<CoordinatorAddress>,
+}
+
+impl CoordinatorSetChange {
+    /// Coordinators in `after` but not in `before`.
+    pub fn added(&self) -> Vec<&CoordinatorAddress> {
+        self.after
+            .iter()
+            .filter(|address| !self.before.contains(address))
+            .collect()
+    }
+
+    /// Coordinators in `before` but not in `after`.
+    pub fn removed(&self) -> Vec<&CoordinatorAddress> {
+        self.before
+            .iter()
+            .filter(|address| !self.after.contains(address))
+            .collect()
+    }
+}
+
+/// Chaos injected into the machines of a datacenter, see `SimulationReport::dc_impact`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct DcImpact {
+    /// Number of process kills on the DC's machines.
+    pub kills: usize,
+    /// Seconds of CloggingPair clogging with the DC on either end.
+    pub clog_seconds: f64,
+    /// Number of SetDiskFailure events targeting the DC's machines.
+    pub disk_failures: usize,
+}
+
+/// CorruptedBlock events grouped by machine and by kind of file, see
+/// `SimulationReport::corrupted_block_summary`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CorruptedBlockSummary {
+    /// Corrupted blocks of each machine, keyed by IP address.
+    pub by_machine: BTreeMap<String, CorruptedBlockGroup>,
+    /// Corrupted blocks of each kind of file, keyed by `file_category`.
+    pub by_file: BTreeMap<String, CorruptedBlockGroup>,
+}
+
+/// Number and time span of a group of CorruptedBlock events.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CorruptedBlockGroup {
+    pub count: usize,
+    pub first_seen: f64,
+    pub last_seen: f64,
+}
+
+impl CorruptedBlockGroup {
+    fn add(&mut self, timestamp: f64) {
+        self.count += 1;
+        self.first_seen = self.first_seen.min(timestamp);
+        self.last_seen = self.last_seen.max(timestamp);
+    }
+}
+
+impl Default for CorruptedBlockGroup {
+    fn default() -> Self {
+        Self {
+            count: 0,
+            first_seen: f64::MAX,
+            last_seen: f64::MIN,
+        }
+    }
+}
+
+/// Kind of file a simulated fdbserver writes, from its name: the storage engine
+/// files, the TLog queues and stores, and the coordination state.
+pub fn file_category(filename: &str) -> &'static str {
+    let name = filename.rsplit('/').next().unwrap_or(filename);
+    if name.starts_with("log") {
+        "tlog"
+    } else if name.starts_with("coordination") {
+        "coordination"
+    } else if name.starts_with("storage") {
+        if name.contains(".sqlite") {
+            "storage (sqlite)"
+        } else if name.contains("redwood") {
+            "storage (redwood)"
+        } else if name.contains("rocksdb") {
+            "storage (rocksdb)"
+        } else if name.ends_with(".fdq") {
+            "storage (memory)"
+        } else {
+            "storage"
+        }
+    } else {
+        "other"
+    }
+}
+
 /// Holds details about a specific machine gathered from events.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct MachineInfo {
@@ -37,48 +317,852 @@ pub struct MachineInfo {
     pub class_type: Option<String>,
 }
 
+/// Process classes of the simulated helper processes running next to the cluster,
+/// e.g. the HTTP server used by backup and blob workloads. They are reported in
+/// `SimulationReport::auxiliary_processes` rather than with the cluster machines.
+pub const AUXILIARY_PROCESS_CLASSES: &[&str] = &["sim_http_server"];
+
+impl MachineInfo {
+    /// Whether the machine runs an auxiliary process, see `AUXILIARY_PROCESS_CLASSES`.
+    pub fn is_auxiliary(&self) -> bool {
+        self.class_type
+            .as_deref()
+            .is_some_and(|class| AUXILIARY_PROCESS_CLASSES.contains(&class))
+    }
+}
+
+/// A clog event involving a given machine, see `SimulationReport::clogs_for_machine`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(tag = "type", content = "data")]
+pub enum MachineClog<'a> {
+    /// The machine is one of the two clogged endpoints.
+    Pair(&'a CloggingPairData),
+    /// One of the machine's network interfaces was clogged.
+    Interface(&'a ClogInterfaceData),
+}
+
+impl MachineClog<'_> {
+    /// Start of the clog, in simulated seconds.
+    pub fn timestamp(&self) -> f64 {
+        let timestamp = match self {
+            MachineClog::Pair(data) => &data.timestamp,
+            MachineClog::Interface(data) => &data.timestamp,
+        };
+        timestamp.parse().unwrap_or(0.0)
+    }
+}
+
+/// Simulated seconds between the last event of a trace and its ElapsedTime beyond
+/// which a trace ending with an incomplete line is considered truncated.
+pub const TRUNCATION_GAP_SECONDS: f64 = 1.0;
+
+/// DC of machines without a known dcid, see `SimulationReport::dc_clog_seconds`.
+pub const UNKNOWN_DC: &str = "N/A";
+
+/// Key of `SimulationReport::clog_interface_roles` for interfaces clogged while
+/// hosting no role besides Worker.
+pub const NO_ROLE: &str = "(none)";
+
+/// Version of the serialized `SimulationReport` layout, bumped whenever fields are
+/// renamed, removed or change meaning. Every bump needs a shim in `migrate_report`.
+/// Added fields need no bump: they are `#[serde(default)]` or optional, so reports
+/// saved without them still load.
+pub const REPORT_SCHEMA_VERSION: u32 = 1;
+
+/// Errors that can occur when saving or loading a report file.
+#[derive(thiserror::Error, Debug)]
+pub enum ReportFileError {
+    #[error("I/O error on report file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid report file: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Report schema version {found} is newer than the supported version {supported}")]
+    UnsupportedVersion { found: u64, supported: u32 },
+}
+
 /// Represents the overall simulation report.
+///
+/// All maps are ordered so that serialized reports are stable and can be diffed.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SimulationReport {
+    /// Version of the report layout, see `REPORT_SCHEMA_VERSION`. Reports written
+    /// before versioning was introduced deserialize as version 0.
+    #[serde(default)]
+    pub schema_version: u32,
     /// The random seed used for the simulation run.
     pub seed: Option<String>,
+    /// Wall-clock DateTime of the first ProgramStart, anchoring simulated time zero.
+    pub start_date_time: Option<String>,
+    /// fdbserver version of the first ProgramStart logging one.
+    pub fdb_version: Option<String>,
+    /// fdbserver command line of the first ProgramStart logging one, see
+    /// `triage::repro_command`.
+    pub command_line: Option<String>,
     /// The total elapsed time reported by the simulation.
     pub elapsed_time: Option<String>,
     /// The total real time reported by the simulation.
     pub real_time: Option<String>,
     /// Simulator configuration parameters.
-    pub simulator_config: Option<HashMap<String, String>>,
+    pub simulator_config: Option<BTreeMap<String, String>>,
     /// List of CloggingPair events, sorted by timestamp.
     pub clogging_pairs: Vec<CloggingPairData>,
     /// Summary statistics for CloggingPair events.
     pub clogging_pair_summary: Option<CloggingPairSummary>,
+    /// Seconds of CloggingPair clogging between datacenters, summed over machine pairs
+    /// and keyed by source then destination DC. Machines of unknown DC count as
+    /// `UNKNOWN_DC`.
+    #[serde(default)]
+    pub dc_clog_seconds: BTreeMap<String, BTreeMap<String, f64>>,
     /// List of ClogInterface events, sorted by timestamp.
     pub clog_interfaces: Vec<ClogInterfaceData>,
     /// Summary statistics for ClogInterface events, grouped by queue name.
-    pub clog_interface_summary: HashMap<String, ClogInterfaceSummary>,
+    pub clog_interface_summary: BTreeMap<String, ClogInterfaceSummary>,
+    /// Summary statistics for ClogInterface events, grouped by the roles (see
+    /// `RoleData`) active on the clogged IP at the time. An interface hosting several
+    /// roles counts for each of them, one hosting none counts for `NO_ROLE`.
+    #[serde(default)]
+    pub clog_interface_roles: BTreeMap<String, ClogInterfaceSummary>,
     /// List of CoordinatorsChange events, sorted by timestamp.
     pub coordinators_changes: Vec<CoordinatorsChangeData>,
     /// Total count of coordinator changes.
     pub coordinators_change_count: usize,
+    /// Coordinators before and after each of `coordinators_changes`, in the same
+    /// order.
+    #[serde(default)]
+    pub coordinator_sets: Vec<CoordinatorSetChange>,
     /// Details of machines involved in the simulation.
-    pub machine_details: HashMap<String, MachineInfo>,
+    pub machine_details: BTreeMap<String, MachineInfo>,
+    /// Auxiliary simulated processes such as HTTP servers, sorted by machine ID. They
+    /// are not part of `machine_details`.
+    #[serde(default)]
+    pub auxiliary_processes: Vec<MachineInfo>,
+    /// Roles held by each process address, as abbreviated in the `Roles` trace field
+    /// (see `role_name`), with the simulated time the process was first seen holding
+    /// them. Built from the events carrying that field, see `Event::process_roles`.
+    #[serde(default)]
+    pub roles_by_process: BTreeMap<String, BTreeMap<String, f64>>,
+    /// Role events of every role but Worker, sorted by timestamp. Kept to attribute the
+    /// clogged interfaces of a time window to the roles held at the time, see
+    /// `window`.
+    #[serde(default)]
+    pub role_changes: Vec<RoleData>,
     /// List of DiskSwap events, sorted by timestamp.
     pub disk_swaps: Vec<DiskSwapData>,
     /// List of SetDiskFailure events, sorted by timestamp.
     pub set_disk_failures: Vec<SetDiskFailureData>,
+    /// Summary statistics for SetDiskFailure events.
+    pub disk_failure_summary: Option<DiskFailureSummary>,
     /// List of CorruptedBlock events, sorted by timestamp.
     pub corrupted_blocks: Vec<CorruptedBlockData>,
     /// List of KillMachineProcess events, sorted by timestamp.
     pub kill_machine_processes: Vec<KillMachineProcessData>,
     /// Summary statistics for KillMachineProcess events, grouped by KillType.
-    pub kill_machine_process_summary: HashMap<KillType, usize>,
+    pub kill_machine_process_summary: BTreeMap<KillType, usize>,
+    /// List of storage servers joining the running storage set, sorted by timestamp.
+    pub storage_servers_added: Vec<StorageServerAddedData>,
+    /// List of storage servers leaving the running storage set, sorted by timestamp.
+    pub storage_servers_removed: Vec<StorageServerRemovedData>,
+    /// Churn statistics for storage server membership changes.
+    pub storage_server_churn: Option<StorageServerChurnSummary>,
+    /// Chaos absorbed by each machine, keyed by IP address.
+    pub machine_chaos: BTreeMap<String, MachineChaosSummary>,
+    /// List of recruitment failure events, sorted by timestamp.
+    pub recruitment_failures: Vec<RecruitmentFailureData>,
+    /// Recruitment failures grouped by event type and error, sorted by first occurrence.
+    pub recruitment_failure_summary: Vec<RecruitmentFailureSummary>,
+    /// List of BuggifySection events, sorted by timestamp.
+    pub buggify_sections: Vec<BuggifySectionData>,
+    /// Effective knob values by knob name, as logged at startup.
+    #[serde(default)]
+    pub knobs: BTreeMap<String, String>,
+    /// List of Assassination events, sorted by timestamp.
+    #[serde(default)]
+    pub assassinations: Vec<AssassinationData>,
+    /// Processes starting again after a kill or reboot, sorted by timestamp.
+    #[serde(default)]
+    pub process_restarts: Vec<RebooterStartData>,
+    /// Up, rebooting and down intervals of every process, keyed by address, e.g.
+    /// "2.0.1.0:1", see `lifecycle`.
+    #[serde(default)]
+    pub availability: BTreeMap<String, Availability>,
+    /// Recoveries of the transaction system, sorted by start time.
+    #[serde(default)]
+    pub recoveries: Vec<RecoveryInfo>,
+    /// Severity 30+ events of any type (see `Event::severe_event`), sorted by
+    /// timestamp. Capped to `SEVERE_EVENT_SAMPLES_PER_TYPE` per type unless
+    /// `severe_events_capped` is false.
+    #[serde(default)]
+    pub severe_events: Vec<SevereEventData>,
+    /// Severity 30+ events grouped by type, most severe and most frequent first.
+    #[serde(default)]
+    pub errors_and_warnings: Vec<SevereEventSummary>,
+    /// Whether `severe_events` only holds the first events of each type.
+    #[serde(default)]
+    pub severe_events_capped: bool,
+    /// Number of Severity 40+ events, which fail the test.
+    #[serde(default)]
+    pub error_count: usize,
+    /// List of TestResults events, one per test of the run, sorted by timestamp.
+    #[serde(default)]
+    pub test_results: Vec<TestResultsData>,
+    /// Name of the test, i.e. the workloads of its TestResults events, comma-separated.
+    pub test_name: Option<String>,
+    /// Whether the run passed: every TestResults event reported success and no
+    /// Severity 40+ event was logged. False for runs that never logged their results,
+    /// e.g. crashed or truncated ones. `None` when the outcome is unknown, for reports
+    /// saved before test outcomes were parsed.
+    pub passed: Option<bool>,
+    /// TestComplete events of workloads that failed on a tester client, sorted by
+    /// timestamp.
+    #[serde(default)]
+    pub failed_workloads: Vec<TestCompleteData>,
+    /// Output of the analysis passes, in run order (see `analysis::AnalysisRegistry`).
+    #[serde(default)]
+    pub analyses: Vec<AnalysisSection>,
+    /// User annotations from the trace's sidecar file, sorted by timestamp. Attached
+    /// by the caller, see `annotations::AnnotationStore`.
+    #[serde(default)]
+    pub annotations: Vec<Annotation>,
+    /// User metadata of the run, e.g. `branch=main`, to group and filter stored
+    /// reports by build. Attached by the caller, see `parse_tag`.
+    #[serde(default)]
+    pub tags: BTreeMap<String, String>,
+    /// Sampling rate of each sampled event type. Their event lists only hold the
+    /// sampled events, while summary counts are scaled up to estimate the full trace.
+    pub sampling: BTreeMap<String, f64>,
+    /// Whether raw CloggingPair and ClogInterface events were dropped to stay within
+    /// the memory budget. Their summaries still cover the whole trace.
+    pub aggregate_only: bool,
+    /// Approximate statistics of the whole trace, only set when `aggregate_only`: the
+    /// raw events to count them exactly were dropped.
+    pub approximate_counts: Option<ApproximateCounts>,
+    /// Simulated time of the last event when the trace looks cut off mid-run: it ends
+    /// with an incomplete line, and the run's ElapsedTime is missing or far later.
+    /// The report then only covers part of the run.
+    pub truncated_at: Option<f64>,
 }
 
 impl fmt::Display for SimulationReport {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_with(
+            f,
+            &TimeFormatter::new(TimeFormat::default(), self),
+            &NumberFormat::default(),
+        )
+    }
+}
+
+/// Displays a report with times rendered in a chosen `TimeFormat`.
+pub struct ReportDisplay<'a> {
+    report: &'a SimulationReport,
+    time: TimeFormatter,
+    numbers: NumberFormat,
+}
+
+impl ReportDisplay<'_> {
+    /// Sets how counts and decimals are rendered, plain by default.
+    pub fn with_numbers(mut self, numbers: NumberFormat) -> Self {
+        self.numbers = numbers;
+        self
+    }
+}
+
+impl fmt::Display for ReportDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.report.fmt_with(f, &self.time, &self.numbers)
+    }
+}
+
+impl SimulationReport {
+    /// Processes that held each role of `roles_by_process`, keyed by role abbreviation,
+    /// with the time they were first seen holding it, in order of that time.
+    pub fn processes_by_role(&self) -> BTreeMap<&str, Vec<(&str, f64)>> {
+        let mut by_role: BTreeMap<&str, Vec<(&str, f64)>> = BTreeMap::new();
+        for (address, roles) in &self.roles_by_process {
+            for (role, first_seen) in roles {
+                by_role
+                    .entry(role.as_str())
+                    .or_default()
+                    .push((address.as_str(), *first_seen));
+            }
+        }
+        for processes in by_role.values_mut() {
+            processes.sort_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(b.0)));
+        }
+        by_role
+    }
+
+    /// Why the run did not pass, one line per failed check, empty for passing runs and
+    /// runs of unknown outcome.
+    pub fn failure_reasons(&self, time: &TimeFormatter, numbers: &NumberFormat) -> Vec<String> {
+        let mut reasons = Vec::new();
+        if self.passed != Some(false) {
+            return reasons;
+        }
+        if self.test_results.is_empty() {
+            reasons.push("no TestResults logged".to_string());
+        }
+        for result in self.test_results.iter().filter(|r| !r.is_passed()) {
+            reasons.push(format!("{} did not pass", result.workload));
+        }
+        if self.error_count > 0 {
+            reasons.push(format!("{} error(s)", numbers.count(self.error_count)));
+        }
+        for failed in &self.failed_workloads {
+            reasons.push(format!(
+                "{} failed on {} at {}",
+                failed.workload,
+                failed.machine,
+                time.timestamp_str(&failed.timestamp)
+            ));
+        }
+        reasons
+    }
+
+    /// Simulated time of the first chaos event (see `Event::is_chaos`), or `None` for
+    /// runs without chaos.
+    pub fn chaos_onset(&self) -> Option<f64> {
+        let timestamps = self
+            .clogging_pairs
+            .iter()
+            .map(|e| e.timestamp.as_str())
+            .chain(self.clog_interfaces.iter().map(|e| e.timestamp.as_str()))
+            .chain(
+                self.kill_machine_processes
+                    .iter()
+                    .map(|e| e.timestamp.as_str()),
+            )
+            .chain(self.set_disk_failures.iter().map(|e| e.timestamp.as_str()))
+            .chain(self.corrupted_blocks.iter().map(|e| e.time.as_str()))
+            .chain(self.disk_swaps.iter().map(|e| e.timestamp.as_str()))
+            .chain(self.assassinations.iter().map(|e| e.timestamp.as_str()));
+        timestamps
+            .filter_map(|timestamp| timestamp.parse::<f64>().ok())
+            .reduce(f64::min)
+    }
+
+    /// The chaos events of the run, i.e. kills, assassinations, clogs and disk faults
+    /// and swaps, as events, e.g. to index them by machine with `events::Events`.
+    /// Assassinations designate a zone, so they are not indexed by machine.
+    pub fn chaos_events(&self) -> Vec<Event> {
+        let mut events: Vec<Event> = Vec::new();
+        events.extend(self.kill_machine_processes.iter().cloned().map(Event::from));
+        events.extend(self.assassinations.iter().cloned().map(Event::from));
+        events.extend(self.clogging_pairs.iter().cloned().map(Event::from));
+        events.extend(self.clog_interfaces.iter().cloned().map(Event::from));
+        events.extend(self.set_disk_failures.iter().cloned().map(Event::from));
+        events.extend(self.corrupted_blocks.iter().cloned().map(Event::from));
+        events.extend(self.disk_swaps.iter().cloned().map(Event::from));
+        events
+    }
+
+    /// The report restricted to the simulated time window `[from, to]`: chaos, kills,
+    /// errors, recoveries and their summaries only cover the events of the window,
+    /// as well as the annotations, while the run metadata, machines, test outcome and
+    /// analyses are those of the whole run.
+    ///
+    /// Errors are counted from the time of every severe event, so they are exact even
+    /// when `severe_events_capped`; `severe_events` and the `top_machines` of each type
+    /// only cover the kept samples of the window.
+    pub fn window(&self, from: f64, to: f64) -> SimulationReport {
+        // Entries are sorted by timestamp, so the window is found by binary search and
+        // only its entries are filtered
+        let in_window = |timestamp: &str| {
+            timestamp
+                .parse::<f64>()
+                .is_ok_and(|t| (from..=to).contains(&t))
+        };
+        let mut builder = ReportBuilder {
+            analyses: AnalysisRegistry::empty(),
+            keep_all_severe_events: true,
+            ..ReportBuilder::default()
+        };
+        for machine in self
+            .machine_details
+            .values()
+            .chain(&self.auxiliary_processes)
+        {
+            if let (Some(dc_id), Some(ips)) = (&machine.dc_id, &machine.ip_address) {
+                for ip in ips.split([' ', ',']).filter(|ip| !ip.is_empty()) {
+                    builder.dc_by_ip.insert(ip.to_string(), dc_id.clone());
+                }
+            }
+        }
+
+        let events = time_range(&self.clogging_pairs, |e| &e.timestamp, from, to)
+            .iter()
+            .filter(|e| in_window(&e.timestamp))
+            .cloned()
+            .map(Event::from)
+            .chain(
+                time_range(&self.coordinators_changes, |e| &e.timestamp, from, to)
+                    .iter()
+                    .filter(|e| in_window(&e.timestamp))
+                    .cloned()
+                    .map(Event::from),
+            )
+            .chain(
+                time_range(&self.disk_swaps, |e| &e.timestamp, from, to)
+                    .iter()
+                    .filter(|e| in_window(&e.timestamp))
+                    .cloned()
+                    .map(Event::from),
+            )
+            .chain(
+                time_range(&self.set_disk_failures, |e| &e.timestamp, from, to)
+                    .iter()
+                    .filter(|e| in_window(&e.timestamp))
+                    .cloned()
+                    .map(Event::from),
+            )
+            .chain(
+                time_range(&self.corrupted_blocks, |e| &e.time, from, to)
+                    .iter()
+                    .filter(|e| in_window(&e.time))
+                    .cloned()
+                    .map(Event::from),
+            )
+            .chain(
+                time_range(&self.kill_machine_processes, |e| &e.timestamp, from, to)
+                    .iter()
+                    .filter(|e| in_window(&e.timestamp))
+                    .cloned()
+                    .map(Event::from),
+            )
+            .chain(
+                time_range(&self.storage_servers_added, |e| &e.timestamp, from, to)
+                    .iter()
+                    .filter(|e| in_window(&e.timestamp))
+                    .cloned()
+                    .map(Event::from),
+            )
+            .chain(
+                time_range(&self.storage_servers_removed, |e| &e.timestamp, from, to)
+                    .iter()
+                    .filter(|e| in_window(&e.timestamp))
+                    .cloned()
+                    .map(Event::from),
+            )
+            .chain(
+                time_range(&self.buggify_sections, |e| &e.timestamp, from, to)
+                    .iter()
+                    .filter(|e| in_window(&e.timestamp))
+                    .cloned()
+                    .map(Event::from),
+            )
+            .chain(
+                time_range(&self.assassinations, |e| &e.timestamp, from, to)
+                    .iter()
+                    .filter(|e| in_window(&e.timestamp))
+                    .cloned()
+                    .map(Event::from),
+            )
+            .chain(
+                time_range(&self.process_restarts, |e| &e.timestamp, from, to)
+                    .iter()
+                    .filter(|e| in_window(&e.timestamp))
+                    .cloned()
+                    .map(Event::from),
+            );
+        for event in events {
+            builder.add(&event);
+        }
+        // Clogged interfaces are attributed to the roles held when they were clogged, so
+        // the role changes and restarts since the start of the run are replayed up to
+        // each of them
+        let time = |timestamp: &str| timestamp.parse::<f64>().unwrap_or(0.0);
+        let mut role_changes = self.role_changes.iter().peekable();
+        let mut restarts = self.process_restarts.iter().peekable();
+        for clog in time_range(&self.clog_interfaces, |e| &e.timestamp, from, to)
+            .iter()
+            .filter(|e| in_window(&e.timestamp))
+        {
+            let clogged_at = time(&clog.timestamp);
+            loop {
+                let role_at = role_changes.peek().map(|role| time(&role.timestamp));
+                let restart_at = restarts.peek().map(|restart| time(&restart.timestamp));
+                match (role_at, restart_at) {
+                    (Some(role_at), restart_at)
+                        if role_at <= clogged_at && restart_at.is_none_or(|t| role_at <= t) =>
+                    {
+                        builder.track_role(role_changes.next().expect("peeked"));
+                    }
+                    (_, Some(restart_at)) if restart_at <= clogged_at => {
+                        builder.end_roles(restarts.next().expect("peeked").process_address());
+                    }
+                    _ => break,
+                }
+            }
+            builder.add(&Event::from(clog.clone()));
+        }
+        // Recruitment failures are also severe events, which are counted below
+        builder.recruitment_failures =
+            time_range(&self.recruitment_failures, |e| &e.timestamp, from, to)
+                .iter()
+                .filter(|e| in_window(&e.timestamp))
+                .cloned()
+                .collect();
+        for severe in time_range(&self.severe_events, |e| &e.timestamp, from, to)
+            .iter()
+            .filter(|e| in_window(&e.timestamp))
+        {
+            builder.add_severe_event(severe.clone());
+        }
+        builder.recoveries = self
+            .recoveries
+            .iter()
+            .filter(|recovery| (from..=to).contains(&recovery.start))
+            .cloned()
+            .collect();
+
+        let mut window = builder.finish();
+        let mut sampling = Sampling::default();
+        for (event_type, rate) in &self.sampling {
+            // The rates were validated when the report was built
+            let _ = sampling.set_rate(event_type, *rate);
+        }
+        window.apply_sampling(&sampling);
+        // Summarized from every severe event, the kept samples may miss some
+        let mut sampled_machines: BTreeMap<String, Vec<(String, usize)>> = window
+            .errors_and_warnings
+            .drain(..)
+            .map(|summary| (summary.event_type, summary.top_machines))
+            .collect();
+        window.errors_and_warnings = self
+            .errors_and_warnings
+            .iter()
+            .filter_map(|summary| summary.window(from, to))
+            .map(|mut summary| {
+                summary.top_machines = sampled_machines
+                    .remove(&summary.event_type)
+                    .unwrap_or_default();
+                summary
+            })
+            .collect();
+        sort_severe_event_summaries(&mut window.errors_and_warnings);
+        window.error_count = window
+            .errors_and_warnings
+            .iter()
+            .flat_map(|summary| &summary.occurrences)
+            .filter(|(_, severity)| *severity >= SEVERITY_ERROR)
+            .count();
+        window.severe_events_capped = self.severe_events_capped;
+        window.seed = self.seed.clone();
+        window.start_date_time = self.start_date_time.clone();
+        window.fdb_version = self.fdb_version.clone();
+        window.command_line = self.command_line.clone();
+        window.elapsed_time = self.elapsed_time.clone();
+        window.real_time = self.real_time.clone();
+        window.simulator_config = self.simulator_config.clone();
+        window.machine_details = self.machine_details.clone();
+        window.auxiliary_processes = self.auxiliary_processes.clone();
+        window.roles_by_process = self.roles_by_process.clone();
+        window.role_changes = self.role_changes.clone();
+        window.coordinator_sets = self
+            .coordinator_sets
+            .iter()
+            .filter(|change| (from..=to).contains(&change.timestamp))
+            .cloned()
+            .collect();
+        window.availability = self
+            .availability
+            .iter()
+            .map(|(address, intervals)| (address.clone(), lifecycle::clip(intervals, from, to)))
+            .filter(|(_, intervals)| !intervals.is_empty())
+            .collect();
+        window.knobs = self.knobs.clone();
+        window.test_results = self.test_results.clone();
+        window.test_name = self.test_name.clone();
+        window.passed = self.passed;
+        window.failed_workloads = self.failed_workloads.clone();
+        window.analyses = self.analyses.clone();
+        window.annotations = self
+            .annotations
+            .iter()
+            .filter(|annotation| (from..=to).contains(&annotation.timestamp))
+            .cloned()
+            .collect();
+        window.tags = self.tags.clone();
+        window.aggregate_only = self.aggregate_only;
+        window.approximate_counts = self.approximate_counts.clone();
+        window.truncated_at = self.truncated_at;
+        window
+    }
+
+    /// Saves the report as pretty-printed JSON, tagged with its schema version.
+    pub fn to_json_file<P: AsRef<Path>>(&self, path: P) -> Result<(), ReportFileError> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(&mut writer, self)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Loads a report saved by `to_json_file`, migrating older schema versions.
+    pub fn from_json_file<P: AsRef<Path>>(path: P) -> Result<Self, ReportFileError> {
+        let mut value: serde_json::Value =
+            serde_json::from_reader(BufReader::new(File::open(path)?))?;
+        migrate_report(&mut value)?;
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// Chaos aggregated by datacenter, the machines of unknown DCs under
+    /// `UNKNOWN_DC`, from the per-machine chaos and the DC clog matrix. Clogs between
+    /// two DCs count for both.
+    pub fn dc_impact(&self) -> BTreeMap<String, DcImpact> {
+        let cluster = ClusterIndex::new(self);
+        let mut impact: BTreeMap<String, DcImpact> = BTreeMap::new();
+        for (ip, chaos) in &self.machine_chaos {
+            let dc = impact
+                .entry(cluster.dc_id(ip).unwrap_or(UNKNOWN_DC).to_string())
+                .or_default();
+            dc.kills += chaos.kills_by_type.values().sum::<usize>();
+            dc.disk_failures += chaos.disk_failures;
+        }
+        for (from, to, seconds) in self
+            .dc_clog_seconds
+            .iter()
+            .flat_map(|(from, to)| to.iter().map(move |(to, seconds)| (from, to, seconds)))
+        {
+            impact.entry(from.clone()).or_default().clog_seconds += seconds;
+            if to != from {
+                impact.entry(to.clone()).or_default().clog_seconds += seconds;
+            }
+        }
+        impact
+    }
+
+    /// CorruptedBlock events grouped by machine and by `file_category` of the
+    /// corrupted file. `None` when no block was reported corrupted.
+    pub fn corrupted_block_summary(&self) -> Option<CorruptedBlockSummary> {
+        if self.corrupted_blocks.is_empty() {
+            return None;
+        }
+        let mut summary = CorruptedBlockSummary::default();
+        for block in &self.corrupted_blocks {
+            let timestamp = block.time.parse().unwrap_or(0.0);
+            summary
+                .by_machine
+                .entry(ip_from_address(&block.machine).to_string())
+                .or_default()
+                .add(timestamp);
+            summary
+                .by_file
+                .entry(file_category(&block.filename).to_string())
+                .or_default()
+                .add(timestamp);
+        }
+        Some(summary)
+    }
+
+    /// Share of the time the processes of the machine at `ip` were up, over their
+    /// availability. `None` when none of its processes was seen starting or killed.
+    pub fn uptime(&self, ip: &str) -> Option<f64> {
+        let intervals: Availability = self
+            .availability
+            .iter()
+            .filter(|(address, _)| ip_from_address(address) == ip)
+            .flat_map(|(_, intervals)| intervals.iter().copied())
+            .collect();
+        lifecycle::uptime(&intervals)
+    }
+
+    /// Seconds of CloggingPair clogging between machines, keyed by source then
+    /// destination IP, like `dc_clog_seconds` between datacenters.
+    ///
+    /// Computed from the CloggingPair events kept in the report, so it is empty for
+    /// aggregate-only reports and only covers the sampled events of sampled ones.
+    pub fn machine_clog_seconds(&self) -> BTreeMap<String, BTreeMap<String, f64>> {
+        let mut matrix: BTreeMap<String, BTreeMap<String, f64>> = BTreeMap::new();
+        for clog in &self.clogging_pairs {
+            if let Ok(seconds) = clog.seconds.parse::<f64>() {
+                *matrix
+                    .entry(ip_from_address(&clog.from_id).to_string())
+                    .or_default()
+                    .entry(ip_from_address(&clog.to_id).to_string())
+                    .or_default() += seconds;
+            }
+        }
+        matrix
+    }
+
+    /// Returns the clog events involving a machine, sorted by timestamp. The machine
+    /// is given by any identifier `ClusterIndex::resolve` accepts; unknown IPs and
+    /// addresses are matched as-is.
+    ///
+    /// Only events kept in the report are returned, so the result is partial for
+    /// sampled or aggregate-only reports.
+    pub fn clogs_for_machine(&self, ip_or_id: &str) -> Vec<MachineClog<'_>> {
+        let machines = ClusterIndex::new(self).resolve(ip_or_id);
+        let ips: HashSet<&str> = if machines.is_empty() {
+            HashSet::from([ip_from_address(ip_or_id)])
+        } else {
+            machines.iter().flat_map(|machine| machine.ips()).collect()
+        };
+
+        let pairs = self
+            .clogging_pairs
+            .iter()
+            .filter(|pair| {
+                ips.contains(ip_from_address(&pair.from_id))
+                    || ips.contains(ip_from_address(&pair.to_id))
+            })
+            .map(MachineClog::Pair);
+        let interfaces = self
+            .clog_interfaces
+            .iter()
+            .filter(|interface| ips.contains(ip_from_address(&interface.ip)))
+            .map(MachineClog::Interface);
+        let mut clogs: Vec<MachineClog> = pairs.chain(interfaces).collect();
+        clogs.sort_by(|a, b| a.timestamp().total_cmp(&b.timestamp()));
+        clogs
+    }
+
+    /// Records the sampling used while parsing and scales sampled counts back up to
+    /// estimates of the full trace. Call once, on a report built from sampled events.
+    pub fn apply_sampling(&mut self, sampling: &Sampling) {
+        let scale = |count: usize, rate: f64| (count as f64 / rate).round() as usize;
+        let clogging_pair_rate = sampling.rate("CloggingPair");
+        let clog_interface_rate = sampling.rate("ClogInterface");
+
+        if let Some(summary) = &mut self.clogging_pair_summary {
+            summary.count = scale(summary.count, clogging_pair_rate);
+        }
+        for summary in self
+            .clog_interface_summary
+            .values_mut()
+            .chain(self.clog_interface_roles.values_mut())
+        {
+            summary.count = scale(summary.count, clog_interface_rate);
+        }
+        for seconds in self
+            .dc_clog_seconds
+            .values_mut()
+            .flat_map(|to| to.values_mut())
+        {
+            *seconds /= clogging_pair_rate;
+        }
+        for chaos in self.machine_chaos.values_mut() {
+            chaos.clogging_pairs = scale(chaos.clogging_pairs, clogging_pair_rate);
+            chaos.clogged_interfaces = scale(chaos.clogged_interfaces, clog_interface_rate);
+        }
+        self.sampling = sampling.rates().clone();
+    }
+
+    /// Suffix marking a section whose counts are estimated from sampled events.
+    pub fn sampling_note(&self, event_type: &str) -> Option<String> {
+        self.sampling
+            .get(event_type)
+            .map(|rate| format!("sampled at {:.1}%, counts scaled", rate * 100.0))
+    }
+
+    /// Returns a `Display` adapter rendering times in the given format.
+    pub fn display_with(&self, time_format: TimeFormat) -> ReportDisplay<'_> {
+        ReportDisplay {
+            report: self,
+            time: TimeFormatter::new(time_format, self),
+            numbers: NumberFormat::default(),
+        }
+    }
+
+    fn fmt_with(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        time: &TimeFormatter,
+        numbers: &NumberFormat,
+    ) -> fmt::Result {
         writeln!(f, "{}", "Simulation Report".bold().underline())?;
         writeln!(f)?;
 
+        let test_name = self.test_name.as_deref().unwrap_or("unknown test");
+        match self.passed {
+            Some(true) => writeln!(f, "{} {}", "PASS".green().bold(), test_name)?,
+            Some(false) => {
+                writeln!(f, "{} {}", "FAIL".red().bold(), test_name)?;
+                for reason in self.failure_reasons(time, numbers) {
+                    writeln!(f, "  - {}", reason)?;
+                }
+            }
+            None => writeln!(f, "{} {}", "UNKNOWN".yellow().bold(), test_name)?,
+        }
+        if !self.tags.is_empty() {
+            let tags: Vec<String> = self
+                .tags
+                .iter()
+                .map(|(key, value)| format!("{}={}", key, value))
+                .collect();
+            writeln!(f, "{} {}", "Tags:".green(), tags.join(", "))?;
+        }
+        writeln!(f)?;
+
+        if let Some(truncated_at) = self.truncated_at {
+            writeln!(
+                f,
+                "{}",
+                format!(
+                    "WARNING: truncated run, the trace stops mid-line at {}; the report only covers part of the run",
+                    time.timestamp(truncated_at)
+                )
+                .red()
+                .bold()
+            )?;
+            writeln!(f)?;
+        }
+        if self.aggregate_only {
+            writeln!(
+                f,
+                "{}",
+                "NOTE: memory budget exceeded while parsing, raw clog events were dropped and only their summaries are shown"
+                    .yellow()
+            )?;
+            writeln!(f)?;
+        }
+
+        // Analyses with warnings, e.g. the likely trigger of a failure, come first
+        for section in self.analyses.iter().filter(|section| is_alert(section)) {
+            self.fmt_analysis_section(f, section, time)?;
+            writeln!(f)?;
+        }
+
+        // Recruitment failures usually explain timed-out runs, so flag them first
+        if !self.recruitment_failure_summary.is_empty() {
+            writeln!(
+                f,
+                "{}",
+                format!(
+                    "WARNING: {} recruitment failure(s) detected, the cluster lacked workers to recruit",
+                    numbers.count(self.recruitment_failures.len())
+                )
+                .red()
+                .bold()
+            )?;
+            let mut table = Table::new();
+            table
+                .load_preset(UTF8_FULL)
+                .set_content_arrangement(ContentArrangement::Dynamic)
+                .set_header(vec![
+                    "Event Type".to_string(),
+                    "Error".to_string(),
+                    "Count".to_string(),
+                    time.header("First Seen"),
+                    time.header("Last Seen"),
+                ]);
+            for summary in &self.recruitment_failure_summary {
+                table.add_row(vec![
+                    Cell::new(&summary.event_type),
+                    Cell::new(&summary.error),
+                    Cell::new(numbers.count(summary.count)),
+                    Cell::new(time.timestamp(summary.first_seen)),
+                    Cell::new(time.timestamp(summary.last_seen)),
+                ]);
+            }
+            writeln!(f, "{}", table)?;
+            writeln!(f)?;
+        }
+
+        if !self.errors_and_warnings.is_empty() {
+            self.fmt_errors_and_warnings(f, time, numbers)?;
+        }
+
         // --- Combined Overview Table (Horizontal) ---
         writeln!(f, "{}", "Simulation Overview".bold())?;
         let mut ordered_headers: Vec<String> = Vec::new();
@@ -144,9 +1228,7 @@ impl fmt::Display for SimulationReport {
             |elapsed| {
                 elapsed.parse::<f64>().map_or_else(
                     |_| format!("{} (Invalid format)", elapsed),
-                    |duration| {
-                        format_duration(std::time::Duration::from_secs_f64(duration)).to_string()
-                    },
+                    |duration| time.duration(duration),
                 )
             },
         ));
@@ -158,9 +1240,7 @@ impl fmt::Display for SimulationReport {
             |real| {
                 real.parse::<f64>().map_or_else(
                     |_| format!("{} (Invalid format)", real),
-                    |duration| {
-                        format_duration(std::time::Duration::from_secs_f64(duration)).to_string()
-                    },
+                    |duration| time.duration(duration),
                 )
             },
         ));
@@ -185,14 +1265,8 @@ impl fmt::Display for SimulationReport {
         }
 
         // Create and print the overview table using the ordered vectors
-        let headers = ordered_headers
-            .iter()
-            .map(|h| Cell::new(h))
-            .collect::<Vec<_>>();
-        let row = ordered_values
-            .iter()
-            .map(|v| Cell::new(v))
-            .collect::<Vec<_>>();
+        let headers = ordered_headers.iter().map(Cell::new).collect::<Vec<_>>();
+        let row = ordered_values.iter().map(Cell::new).collect::<Vec<_>>();
 
         let mut overview_table = Table::new();
         overview_table
@@ -244,7 +1318,7 @@ impl fmt::Display for SimulationReport {
 
                     topology_table.add_row(vec![
                         Cell::new(&dc_id),
-                        Cell::new(machine_count),
+                        Cell::new(numbers.count(machine_count)),
                         Cell::new(summary_str),
                     ]);
                 }
@@ -264,6 +1338,7 @@ impl fmt::Display for SimulationReport {
                     Cell::new("IP Address"),
                     Cell::new("DC ID"),
                     Cell::new("Class Type"),
+                    Cell::new("Uptime"),
                 ]);
 
             // Collect machine details into a Vec to sort them
@@ -282,12 +1357,73 @@ impl fmt::Display for SimulationReport {
                     Cell::new(machine_info.ip_address.as_deref().unwrap_or("N/A")),
                     Cell::new(machine_info.dc_id.as_deref().unwrap_or("N/A")),
                     Cell::new(machine_info.class_type.as_deref().unwrap_or("N/A")),
+                    Cell::new(
+                        machine_info
+                            .ips()
+                            .next()
+                            .and_then(|ip| self.uptime(ip))
+                            .map_or_else(
+                                || "N/A".to_string(),
+                                |uptime| format!("{:.1}%", uptime * 100.0),
+                            ),
+                    ),
                 ]);
             }
 
             writeln!(f, "{}", machine_table)?;
             writeln!(f)?; // Add extra newline for spacing
         }
+        if !self.auxiliary_processes.is_empty() {
+            let processes: Vec<String> = self
+                .auxiliary_processes
+                .iter()
+                .map(|process| {
+                    format!(
+                        "{} ({}, DC {})",
+                        process.ip_address.as_deref().unwrap_or("N/A"),
+                        process.class_type.as_deref().unwrap_or("N/A"),
+                        process.dc_id.as_deref().unwrap_or("N/A")
+                    )
+                })
+                .collect();
+            writeln!(
+                f,
+                "{} {}",
+                "Auxiliary Processes:".green(),
+                processes.join(", ")
+            )?;
+            writeln!(f)?;
+        }
+
+        // --- Role Assignment Table ---
+        if !self.roles_by_process.is_empty() {
+            writeln!(f, "{}", "--- Role Assignment --- ".bright_blue())?;
+            let mut role_table = Table::new();
+            role_table
+                .load_preset(UTF8_FULL)
+                .set_content_arrangement(ContentArrangement::Dynamic)
+                .set_header(vec![
+                    Cell::new("Role"),
+                    Cell::new("Processes"),
+                    Cell::new(time.header("First Assigned")),
+                    Cell::new("Addresses"),
+                ]);
+            for (role, processes) in self.processes_by_role() {
+                let name = match role_name(role) {
+                    Some(name) => format!("{} ({})", name, role),
+                    None => role.to_string(),
+                };
+                let addresses: Vec<&str> = processes.iter().map(|(address, _)| *address).collect();
+                role_table.add_row(vec![
+                    Cell::new(name),
+                    Cell::new(numbers.count(processes.len())),
+                    Cell::new(time.timestamp(processes[0].1)),
+                    Cell::new(addresses.join(", ")),
+                ]);
+            }
+            writeln!(f, "{}", role_table)?;
+            writeln!(f)?;
+        }
 
         // --- Chaos Summary Section ---
         writeln!(f, "{}", "--- Chaos injection Summary ---".bright_yellow())?;
@@ -295,7 +1431,11 @@ impl fmt::Display for SimulationReport {
         // Clogging Pairs (Table)
         if let Some(summary) = &self.clogging_pair_summary {
             if summary.count > 0 {
-                writeln!(f, "  {}:", "Clogging Pairs".green())?;
+                write!(f, "  {}:", "Clogging Pairs".green())?;
+                match self.sampling_note("CloggingPair") {
+                    Some(note) => writeln!(f, " {}", format!("({})", note).yellow())?,
+                    None => writeln!(f)?,
+                }
                 let mut table = Table::new();
                 table
                     .load_preset(UTF8_FULL)
@@ -307,18 +1447,27 @@ impl fmt::Display for SimulationReport {
                         "Max Duration (s)",
                     ]);
                 table.add_row(vec![
-                    Cell::new(summary.count),
-                    Cell::new(format!("{:.6}", summary.min_seconds)),
-                    Cell::new(format!("{:.6}", summary.mean_seconds)),
-                    Cell::new(format!("{:.6}", summary.max_seconds)),
+                    Cell::new(numbers.count(summary.count)),
+                    Cell::new(numbers.decimal(summary.min_seconds, 6)),
+                    Cell::new(numbers.decimal(summary.mean_seconds, 6)),
+                    Cell::new(numbers.decimal(summary.max_seconds, 6)),
                 ]);
                 writeln!(f, "{}", table)?;
             }
         }
 
+        // Clogging between DCs (Matrix)
+        if !self.dc_clog_seconds.is_empty() {
+            self.fmt_dc_clog_matrix(f, numbers)?;
+        }
+
         // Clogged Interfaces (Table)
         if !self.clog_interface_summary.is_empty() {
-            writeln!(f, "  {}:", "Clogged Interfaces (by Queue)".green())?;
+            write!(f, "  {}:", "Clogged Interfaces (by Queue)".green())?;
+            match self.sampling_note("ClogInterface") {
+                Some(note) => writeln!(f, " {}", format!("({})", note).yellow())?,
+                None => writeln!(f)?,
+            }
             let mut table = Table::new();
             table
                 .load_preset(UTF8_FULL)
@@ -338,10 +1487,10 @@ impl fmt::Display for SimulationReport {
                     if summary.count > 0 {
                         table.add_row(vec![
                             Cell::new(queue_name),
-                            Cell::new(summary.count),
-                            Cell::new(format!("{:.6}", summary.min_seconds)),
-                            Cell::new(format!("{:.6}", summary.mean_seconds)),
-                            Cell::new(format!("{:.6}", summary.max_seconds)),
+                            Cell::new(numbers.count(summary.count)),
+                            Cell::new(numbers.decimal(summary.min_seconds, 6)),
+                            Cell::new(numbers.decimal(summary.mean_seconds, 6)),
+                            Cell::new(numbers.decimal(summary.max_seconds, 6)),
                         ]);
                     }
                 }
@@ -352,6 +1501,36 @@ impl fmt::Display for SimulationReport {
             }
         }
 
+        // Clogged Interfaces by Role (Table)
+        if !self.clog_interface_roles.is_empty() {
+            write!(f, "  {}:", "Clogged Interfaces (by Role)".green())?;
+            match self.sampling_note("ClogInterface") {
+                Some(note) => writeln!(f, " {}", format!("({})", note).yellow())?,
+                None => writeln!(f)?,
+            }
+            let mut table = Table::new();
+            table
+                .load_preset(UTF8_FULL)
+                .set_content_arrangement(ContentArrangement::Dynamic)
+                .set_header(vec![
+                    "Role",
+                    "Count",
+                    "Min Delay (s)",
+                    "Mean Delay (s)",
+                    "Max Delay (s)",
+                ]);
+            for (role, summary) in &self.clog_interface_roles {
+                table.add_row(vec![
+                    Cell::new(role),
+                    Cell::new(numbers.count(summary.count)),
+                    Cell::new(numbers.decimal(summary.min_seconds, 6)),
+                    Cell::new(numbers.decimal(summary.mean_seconds, 6)),
+                    Cell::new(numbers.decimal(summary.max_seconds, 6)),
+                ]);
+            }
+            writeln!(f, "{}", table)?;
+        }
+
         // Coordinator Changes (Table)
         writeln!(f, "  Coordinator Changes:")?;
         if !self.coordinators_changes.is_empty() {
@@ -360,16 +1539,35 @@ impl fmt::Display for SimulationReport {
                 .load_preset(UTF8_FULL)
                 .set_content_arrangement(ContentArrangement::Dynamic)
                 .set_header(vec![
-                    Cell::new("Timestamp (s)").add_attribute(comfy_table::Attribute::Bold),
-                    Cell::new("Coordinator Count").add_attribute(comfy_table::Attribute::Bold), // Updated Header
+                    Cell::new(time.header("Timestamp")).add_attribute(comfy_table::Attribute::Bold),
+                    Cell::new("Coordinator Count").add_attribute(comfy_table::Attribute::Bold),
+                    Cell::new("Added").add_attribute(comfy_table::Attribute::Bold),
+                    Cell::new("Removed").add_attribute(comfy_table::Attribute::Bold),
                 ]);
 
-            for change in &self.coordinators_changes {
-                // Count coordinators by splitting the string
-                let count = change.new_coordinators_key.split(',').count();
+            let join = |addresses: Vec<&CoordinatorAddress>| {
+                let addresses: Vec<String> = addresses.iter().map(ToString::to_string).collect();
+                addresses.join(", ")
+            };
+            for (i, change) in self.coordinators_changes.iter().enumerate() {
+                // Reports saved before coordinator sets were decoded have none
+                let (count, added, removed) = match self.coordinator_sets.get(i) {
+                    Some(set) => (
+                        set.after.len().to_string(),
+                        join(set.added()),
+                        join(set.removed()),
+                    ),
+                    None => (
+                        change.coordinators().len().to_string(),
+                        "-".to_string(),
+                        "-".to_string(),
+                    ),
+                };
                 coord_table.add_row(vec![
-                    Cell::new(&change.timestamp),
-                    Cell::new(count.to_string()), // Display count
+                    Cell::new(time.timestamp_str(&change.timestamp)),
+                    Cell::new(count),
+                    Cell::new(added),
+                    Cell::new(removed),
                 ]);
             }
             writeln!(f, "{}", coord_table)?;
@@ -384,16 +1582,34 @@ impl fmt::Display for SimulationReport {
             table
                 .load_preset(UTF8_FULL)
                 .set_content_arrangement(ContentArrangement::Dynamic)
-                .set_header(vec!["Kill Type", "Count"]);
-
+                .set_header(vec![
+                    "Kill Type",
+                    "Count",
+                    "Min Down",
+                    "Mean Down",
+                    "Max Down",
+                    "Meaning",
+                ]);
+
+            let downtime = downtime_by_kill_type(self);
             let mut sorted_kill_types: Vec<_> = self.kill_machine_process_summary.keys().collect();
             sorted_kill_types.sort();
             for kill_type in sorted_kill_types {
                 if let Some(count) = self.kill_machine_process_summary.get(kill_type) {
                     if *count > 0 {
+                        let stat = |value: fn(&DowntimeStats) -> f64| {
+                            downtime
+                                .get(kill_type)
+                                .map(|stats| time.duration(value(stats)))
+                                .unwrap_or_else(|| "-".to_string())
+                        };
                         table.add_row(vec![
                             Cell::new(format!("{:?}", kill_type)),
-                            Cell::new(*count),
+                            Cell::new(numbers.count(*count)),
+                            Cell::new(stat(|stats| stats.min_seconds)),
+                            Cell::new(stat(|stats| stats.mean_seconds)),
+                            Cell::new(stat(|stats| stats.max_seconds)),
+                            Cell::new(kill_type.description()),
                         ]);
                     }
                 }
@@ -403,225 +1619,1681 @@ impl fmt::Display for SimulationReport {
                 writeln!(f, "{}", table)?;
             }
         }
+
+        // Per-DC Chaos (Table)
+        let dc_impact = self.dc_impact();
+        if !dc_impact.is_empty() {
+            writeln!(f, "  {}:", "Chaos by DC".green())?;
+            let mut table = Table::new();
+            table
+                .load_preset(UTF8_FULL)
+                .set_content_arrangement(ContentArrangement::Dynamic)
+                .set_header(vec!["DC ID", "Kills", "Clog Seconds", "Disk Failures"]);
+            for (dc_id, impact) in &dc_impact {
+                table.add_row(vec![
+                    Cell::new(dc_id),
+                    Cell::new(numbers.count(impact.kills)),
+                    Cell::new(numbers.decimal(impact.clog_seconds, 3)),
+                    Cell::new(numbers.count(impact.disk_failures)),
+                ]);
+            }
+            writeln!(f, "{}", table)?;
+        }
+
+        // Per-Machine Chaos (Table)
+        if !self.machine_chaos.is_empty() {
+            writeln!(f, "  {}:", "Chaos by Machine".green())?;
+
+            // One column per kill type observed anywhere in the run
+            let mut kill_types: Vec<&KillType> = self
+                .machine_chaos
+                .values()
+                .flat_map(|chaos| chaos.kills_by_type.keys())
+                .collect::<HashSet<_>>()
+                .into_iter()
+                .collect();
+            kill_types.sort();
+
+            let mut headers = vec!["IP Address".to_string(), "DC ID".to_string()];
+            headers.extend(
+                kill_types
+                    .iter()
+                    .map(|kill_type| format!("{:?}", kill_type)),
+            );
+            headers.extend(
+                [
+                    "Disk Failures",
+                    "Corrupted Blocks",
+                    "Clogging Pairs",
+                    "Clogged Interfaces",
+                ]
+                .iter()
+                .map(|h| h.to_string()),
+            );
+
+            let mut table = Table::new();
+            table
+                .load_preset(UTF8_FULL)
+                .set_content_arrangement(ContentArrangement::Dynamic)
+                .set_header(headers);
+
+            let cluster = ClusterIndex::new(self);
+
+            let mut sorted_ips: Vec<_> = self.machine_chaos.keys().collect();
+            sorted_ips.sort();
+            for ip in sorted_ips {
+                let chaos = &self.machine_chaos[ip];
+                let mut row = vec![Cell::new(ip), Cell::new(cluster.dc_id(ip).unwrap_or("N/A"))];
+                row.extend(kill_types.iter().map(|kill_type| {
+                    Cell::new(
+                        numbers.count(chaos.kills_by_type.get(*kill_type).copied().unwrap_or(0)),
+                    )
+                }));
+                row.push(Cell::new(numbers.count(chaos.disk_failures)));
+                row.push(Cell::new(numbers.count(chaos.corrupted_blocks)));
+                row.push(Cell::new(numbers.count(chaos.clogging_pairs)));
+                row.push(Cell::new(numbers.count(chaos.clogged_interfaces)));
+                table.add_row(row);
+            }
+            writeln!(f, "{}", table)?;
+        }
+
+        // Disk Failures (Table)
+        if let Some(summary) = &self.disk_failure_summary {
+            writeln!(f, "  {}:", "Disk Failures (SetDiskFailure)".green())?;
+            let mut table = Table::new();
+            table
+                .load_preset(UTF8_FULL)
+                .set_content_arrangement(ContentArrangement::Dynamic)
+                .set_header(vec!["Machine", "Count", "Stall", "Throttle"]);
+            for (ip, machine) in &summary.by_machine {
+                table.add_row(vec![
+                    Cell::new(ip),
+                    Cell::new(numbers.count(machine.count)),
+                    Cell::new(time.duration(machine.stall_seconds)),
+                    Cell::new(time.duration(machine.throttle_seconds)),
+                ]);
+            }
+            table.add_row(vec![
+                Cell::new("Total").add_attribute(comfy_table::Attribute::Bold),
+                Cell::new(numbers.count(summary.count)),
+                Cell::new(time.duration(summary.total_stall_seconds)),
+                Cell::new(time.duration(summary.total_throttle_seconds)),
+            ]);
+            writeln!(f, "{}", table)?;
+        }
+
+        // Corrupted Blocks (Table)
+        if let Some(summary) = self.corrupted_block_summary() {
+            writeln!(f, "  {}:", "Corrupted Blocks".green())?;
+            let mut table = Table::new();
+            table
+                .load_preset(UTF8_FULL)
+                .set_content_arrangement(ContentArrangement::Dynamic)
+                .set_header(vec![
+                    "Machine / File".to_string(),
+                    "Count".to_string(),
+                    time.header("First Seen"),
+                    time.header("Last Seen"),
+                ]);
+            for (name, group) in summary.by_machine.iter().chain(&summary.by_file) {
+                table.add_row(vec![
+                    Cell::new(name),
+                    Cell::new(numbers.count(group.count)),
+                    Cell::new(time.timestamp(group.first_seen)),
+                    Cell::new(time.timestamp(group.last_seen)),
+                ]);
+            }
+            writeln!(f, "{}", table)?;
+        }
+
+        // Disk Swaps (Table)
+        if !self.disk_swaps.is_empty() {
+            writeln!(f, "  {}:", "Disk Swaps".green())?;
+            let mut table = Table::new();
+            table
+                .load_preset(UTF8_FULL)
+                .set_content_arrangement(ContentArrangement::Dynamic)
+                .set_header(vec![
+                    time.header("Timestamp"),
+                    "Machines".to_string(),
+                    "IPs".to_string(),
+                ]);
+            for swap in &self.disk_swaps {
+                let ips = swap.ips();
+                table.add_row(vec![
+                    Cell::new(time.timestamp_str(&swap.timestamp)),
+                    Cell::new(numbers.count(ips.len())),
+                    Cell::new(ips.join(", ")),
+                ]);
+            }
+            writeln!(f, "{}", table)?;
+        }
+
+        // Storage Server Churn (Table)
+        if let Some(churn) = &self.storage_server_churn {
+            writeln!(f, "  {}:", "Storage Server Churn".green())?;
+            let mut table = Table::new();
+            table
+                .load_preset(UTF8_FULL)
+                .set_content_arrangement(ContentArrangement::Dynamic)
+                .set_header(vec!["Added", "Removed", "Unique Servers"]);
+            table.add_row(vec![
+                Cell::new(numbers.count(churn.added)),
+                Cell::new(numbers.count(churn.removed)),
+                Cell::new(numbers.count(churn.unique_servers)),
+            ]);
+            writeln!(f, "{}", table)?;
+        }
+
+        // Approximate Counts (Table)
+        if let Some(approximate) = &self.approximate_counts {
+            writeln!(
+                f,
+                "  {} (estimated from streamed events, ~ marks approximate numbers):",
+                "Approximate Counts".green()
+            )?;
+            let mut table = Table::new();
+            table
+                .load_preset(UTF8_FULL)
+                .set_content_arrangement(ContentArrangement::Dynamic)
+                .set_header(vec!["Statistic", "Value"]);
+            let approximate_row = |name: String, count: usize| {
+                vec![
+                    Cell::new(name),
+                    Cell::new(format!("~{}", numbers.count(count))),
+                ]
+            };
+            table.add_row(approximate_row(
+                "Distinct clogged machines".to_string(),
+                approximate.clogged_machines,
+            ));
+            table.add_row(approximate_row(
+                "Distinct clogged pairs".to_string(),
+                approximate.clogged_pairs,
+            ));
+            for (event_type, count) in &approximate.events_by_type {
+                table.add_row(approximate_row(format!("{} events", event_type), *count));
+            }
+            writeln!(f, "{}", table)?;
+        }
+
+        // Recoveries (Table)
+        if !self.recoveries.is_empty() {
+            writeln!(f, "  {}:", "Recoveries".green())?;
+            let mut table = Table::new();
+            table
+                .load_preset(UTF8_FULL)
+                .set_content_arrangement(ContentArrangement::Dynamic)
+                .set_header(vec![
+                    time.header("Start"),
+                    "Duration (s)".to_string(),
+                    "Status".to_string(),
+                    "Machine".to_string(),
+                    "Likely Cause".to_string(),
+                ]);
+            for recovery in &self.recoveries {
+                let cause = recovery.cause.as_ref().map_or_else(String::new, |cause| {
+                    format!(
+                        "{} at {}",
+                        cause.description,
+                        time.timestamp(cause.timestamp)
+                    )
+                });
+                table.add_row(vec![
+                    Cell::new(time.timestamp(recovery.start)),
+                    Cell::new(
+                        recovery
+                            .duration
+                            .map_or_else(|| "-".to_string(), |d| numbers.decimal(d, 3)),
+                    ),
+                    Cell::new(&recovery.status),
+                    Cell::new(&recovery.machine),
+                    Cell::new(cause),
+                ]);
+            }
+            writeln!(f, "{}", table)?;
+        }
+
+        // Analyses without warnings
+        if self.analyses.iter().any(|section| !is_alert(section)) {
+            writeln!(f)?;
+            writeln!(f, "{}", "Analyses".bold())?;
+            for section in self.analyses.iter().filter(|section| !is_alert(section)) {
+                self.fmt_analysis_section(f, section, time)?;
+            }
+        }
+
+        // Annotations (Table)
+        if !self.annotations.is_empty() {
+            writeln!(f)?;
+            writeln!(f, "{}", "Annotations".bold())?;
+            let mut table = Table::new();
+            table
+                .load_preset(UTF8_FULL)
+                .set_content_arrangement(ContentArrangement::Dynamic)
+                .set_header(vec![
+                    time.header("Timestamp"),
+                    "Event Type".to_string(),
+                    "Note".to_string(),
+                    "Author".to_string(),
+                ]);
+            for annotation in &self.annotations {
+                table.add_row(vec![
+                    Cell::new(time.timestamp(annotation.timestamp)),
+                    Cell::new(annotation.event_type.as_deref().unwrap_or("")),
+                    Cell::new(&annotation.text),
+                    Cell::new(annotation.author.as_deref().unwrap_or("")),
+                ]);
+            }
+            writeln!(f, "{}", table)?;
+        }
         writeln!(f)?; // Add a final newline for spacing
 
-        Ok(())
+        Ok(())
+    }
+}
+
+impl SimulationReport {
+    /// Writes the DC x DC matrix of clogging seconds, noting clogged WAN links.
+    fn fmt_dc_clog_matrix(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        numbers: &NumberFormat,
+    ) -> fmt::Result {
+        write!(f, "  {}:", "Clogging Between DCs (s)".green())?;
+        match self.sampling_note("CloggingPair") {
+            Some(note) => writeln!(f, " {}", format!("({})", note).yellow())?,
+            None => writeln!(f)?,
+        }
+        let dcs: BTreeSet<&str> = self
+            .dc_clog_seconds
+            .iter()
+            .flat_map(|(from, to)| std::iter::once(from).chain(to.keys()))
+            .map(String::as_str)
+            .collect();
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_header(std::iter::once("From \\ To").chain(dcs.iter().copied()));
+        for from in &dcs {
+            let row = self.dc_clog_seconds.get(*from);
+            table.add_row(std::iter::once(Cell::new(from)).chain(dcs.iter().map(|to| {
+                match row.and_then(|row| row.get(*to)) {
+                    Some(seconds) => Cell::new(numbers.decimal(*seconds, 3)),
+                    None => Cell::new("-"),
+                }
+            })));
+        }
+        writeln!(f, "{}", table)?;
+
+        let wan_seconds: f64 = self
+            .dc_clog_seconds
+            .iter()
+            .flat_map(|(from, to)| to.iter().map(move |(to, seconds)| (from, to, seconds)))
+            .filter(|(from, to, _)| from != to && *from != UNKNOWN_DC && *to != UNKNOWN_DC)
+            .map(|(_, _, seconds)| seconds)
+            .sum();
+        if wan_seconds > 0.0 {
+            writeln!(
+                f,
+                "{}",
+                format!(
+                    "  Links between DCs were clogged for {}s in total",
+                    numbers.decimal(wan_seconds, 3)
+                )
+                .yellow()
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Writes the findings and table of an analysis section.
+    fn fmt_analysis_section(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        section: &AnalysisSection,
+        time: &TimeFormatter,
+    ) -> fmt::Result {
+        writeln!(f, "  {}:", section.title.green())?;
+        for finding in &section.findings {
+            let line = finding_text(finding, time);
+            let line = match finding.level {
+                FindingLevel::Error => line.red().bold(),
+                FindingLevel::Warning => line.yellow(),
+                FindingLevel::Info => line.normal(),
+            };
+            writeln!(f, "{}", line)?;
+        }
+        let Some(analysis_table) = &section.table else {
+            return Ok(());
+        };
+        let (headers, rows) = analysis_table_cells(analysis_table, time);
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_header(headers);
+        for row in rows {
+            table.add_row(row);
+        }
+        writeln!(f, "{}", table)
+    }
+
+    /// Writes the errors and warnings section. When raw events were capped only the
+    /// top offending types are listed, otherwise every type and event is.
+    fn fmt_errors_and_warnings(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        time: &TimeFormatter,
+        numbers: &NumberFormat,
+    ) -> fmt::Result {
+        let total: usize = self.errors_and_warnings.iter().map(|s| s.count).sum();
+        writeln!(
+            f,
+            "{}",
+            format!(
+                "Errors & Warnings: {} event(s) of {} type(s) with Severity >= {}",
+                numbers.count(total),
+                self.errors_and_warnings.len(),
+                SEVERITY_WARNING
+            )
+            .bold()
+        )?;
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_header(vec![
+                "Event Type".to_string(),
+                "Severity".to_string(),
+                "Count".to_string(),
+                time.header("First Seen"),
+                time.header("Last Seen"),
+                "Top Machines".to_string(),
+            ]);
+        let shown = if self.severe_events_capped {
+            TOP_SEVERE_EVENT_TYPES
+        } else {
+            self.errors_and_warnings.len()
+        };
+        for summary in self.errors_and_warnings.iter().take(shown) {
+            let machines: Vec<String> = summary
+                .top_machines
+                .iter()
+                .map(|(machine, count)| format!("{} ({})", machine, count))
+                .collect();
+            table.add_row(vec![
+                Cell::new(&summary.event_type),
+                Cell::new(summary.severity),
+                Cell::new(numbers.count(summary.count)),
+                Cell::new(time.timestamp(summary.first_seen)),
+                Cell::new(time.timestamp(summary.last_seen)),
+                Cell::new(machines.join(", ")),
+            ]);
+        }
+        writeln!(f, "{}", table)?;
+
+        if self.severe_events_capped {
+            let hidden = self.errors_and_warnings.len().saturating_sub(shown);
+            let note = if hidden > 0 {
+                format!(
+                    "Showing the top {} of {} event types. Rerun with --full-errors to list every type and event.",
+                    shown,
+                    self.errors_and_warnings.len()
+                )
+            } else {
+                "Individual events are omitted. Rerun with --full-errors to list them.".to_string()
+            };
+            writeln!(f, "{}", note.yellow())?;
+        } else {
+            let mut events_table = Table::new();
+            events_table
+                .load_preset(UTF8_FULL)
+                .set_content_arrangement(ContentArrangement::Dynamic)
+                .set_header(vec![
+                    time.header("Time"),
+                    "Severity".to_string(),
+                    "Event Type".to_string(),
+                    "Machine".to_string(),
+                    "Error".to_string(),
+                ]);
+            for event in &self.severe_events {
+                events_table.add_row(vec![
+                    Cell::new(time.timestamp_str(&event.timestamp)),
+                    Cell::new(&event.severity),
+                    Cell::new(&event.event_type),
+                    Cell::new(event.machine.as_deref().unwrap_or("N/A")),
+                    Cell::new(event.error.as_deref().unwrap_or("")),
+                ]);
+            }
+            writeln!(f, "{}", events_table)?;
+        }
+        writeln!(f)
+    }
+}
+
+/// Parses a `KEY=VALUE` run tag, see `SimulationReport::tags`.
+pub fn parse_tag(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((key, value)) if !key.trim().is_empty() => {
+            Ok((key.trim().to_string(), value.trim().to_string()))
+        }
+        _ => Err(format!("Invalid tag '{}', expected KEY=VALUE", s)),
+    }
+}
+
+/// Upgrades a serialized report in place to `REPORT_SCHEMA_VERSION`.
+fn migrate_report(value: &mut serde_json::Value) -> Result<(), ReportFileError> {
+    let version = value
+        .get("schema_version")
+        .and_then(serde_json::Value::as_u64)
+        .unwrap_or(0);
+    if version > u64::from(REPORT_SCHEMA_VERSION) {
+        return Err(ReportFileError::UnsupportedVersion {
+            found: version,
+            supported: REPORT_SCHEMA_VERSION,
+        });
+    }
+    let Some(report) = value.as_object_mut() else {
+        // Let deserialization report the type mismatch
+        return Ok(());
+    };
+
+    if version < 1 {
+        // Unversioned reports predate sections added since the first release
+        for field in [
+            "storage_servers_added",
+            "storage_servers_removed",
+            "recruitment_failures",
+            "recruitment_failure_summary",
+            "buggify_sections",
+        ] {
+            report.entry(field).or_insert_with(|| serde_json::json!([]));
+        }
+        for field in ["machine_chaos", "sampling"] {
+            report.entry(field).or_insert_with(|| serde_json::json!({}));
+        }
+        report
+            .entry("aggregate_only")
+            .or_insert(serde_json::Value::Bool(false));
+
+        // Auxiliary processes used to be listed with the cluster machines
+        let mut auxiliary = Vec::new();
+        if let Some(machines) = report
+            .get_mut("machine_details")
+            .and_then(|m| m.as_object_mut())
+        {
+            let ids: Vec<String> = machines
+                .iter()
+                .filter(|(_, machine)| {
+                    machine["class_type"]
+                        .as_str()
+                        .is_some_and(|class| AUXILIARY_PROCESS_CLASSES.contains(&class))
+                })
+                .map(|(id, _)| id.clone())
+                .collect();
+            auxiliary.extend(ids.iter().filter_map(|id| machines.remove(id)));
+        }
+        report
+            .entry("auxiliary_processes")
+            .or_insert_with(|| serde_json::Value::Array(auxiliary));
+    }
+
+    report.insert(
+        "schema_version".to_string(),
+        serde_json::Value::from(REPORT_SCHEMA_VERSION),
+    );
+    Ok(())
+}
+
+/// The entries of `entries`, sorted by timestamp as `ReportBuilder::finish` leaves
+/// them, whose timestamp is between `from` and `to` seconds, found by binary search.
+/// Timestamps that do not parse sort as 0.
+fn time_range<T>(entries: &[T], timestamp: impl Fn(&T) -> &str, from: f64, to: f64) -> &[T] {
+    let time = |entry: &T| timestamp(entry).parse::<f64>().unwrap_or(0.0);
+    let start = entries.partition_point(|entry| time(entry) < from);
+    let end = entries
+        .partition_point(|entry| time(entry) <= to)
+        .max(start);
+    &entries[start..end]
+}
+
+/// Whether an analysis section has warnings or errors, which are shown first.
+fn is_alert(section: &AnalysisSection) -> bool {
+    section
+        .level()
+        .is_some_and(|level| level >= FindingLevel::Warning)
+}
+
+/// Rows of the overview table of the HTML and Markdown reports: seed, times, cluster
+/// size, errors and tags.
+fn overview_rows(
+    report: &SimulationReport,
+    time: &TimeFormatter,
+    numbers: &NumberFormat,
+) -> Vec<Vec<String>> {
+    let duration = |seconds: &Option<String>| {
+        seconds.as_deref().map_or_else(
+            || "N/A".to_string(),
+            |value| {
+                value
+                    .parse()
+                    .map_or(value.to_string(), |d| time.duration(d))
+            },
+        )
+    };
+    let mut rows = vec![
+        vec![
+            "Seed".to_string(),
+            report.seed.clone().unwrap_or_else(|| "N/A".to_string()),
+        ],
+        vec!["Simulated Time".to_string(), duration(&report.elapsed_time)],
+        vec!["Real Time".to_string(), duration(&report.real_time)],
+        vec![
+            "Machines".to_string(),
+            numbers.count(report.machine_details.len()),
+        ],
+        vec!["Errors".to_string(), numbers.count(report.error_count)],
+    ];
+    for (key, value) in &report.tags {
+        rows.push(vec![format!("Tag {}", key), value.clone()]);
+    }
+    rows
+}
+
+/// Whether a table cell holds a number, possibly grouped with commas.
+fn is_numeric(cell: &str) -> bool {
+    !cell.is_empty() && cell.replace(',', "").parse::<f64>().is_ok()
+}
+
+/// A finding as a line of text, prefixed with its time when it has one.
+fn finding_text(finding: &Finding, time: &TimeFormatter) -> String {
+    match finding.time {
+        Some(seconds) => format!("[{}] {}", time.timestamp(seconds), finding.message),
+        None => finding.message.clone(),
+    }
+}
+
+/// Headers and cells of an analysis table, with times rendered by `time` and time
+/// columns labelled with the unit of the time format.
+fn analysis_table_cells(
+    table: &AnalysisTable,
+    time: &TimeFormatter,
+) -> (Vec<String>, Vec<Vec<String>>) {
+    let first_row = table.rows.first();
+    let headers = table
+        .headers
+        .iter()
+        .enumerate()
+        .map(
+            |(column, header)| match first_row.and_then(|row| row.get(column)) {
+                Some(AnalysisValue::Time(_)) => time.header(header),
+                _ => header.clone(),
+            },
+        )
+        .collect();
+    let rows = table
+        .rows
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|value| match value {
+                    AnalysisValue::Text(text) => text.clone(),
+                    AnalysisValue::Number(number) => number.to_string(),
+                    AnalysisValue::Time(seconds) => time.timestamp(*seconds),
+                })
+                .collect()
+        })
+        .collect();
+    (headers, rows)
+}
+
+/// Creates a `SimulationReport` by processing `Event`s, given by value or by reference,
+/// e.g. a slice of events.
+///
+/// Extracts the seed, a list of unique machine identifiers (from ProgramStart events),
+/// the last reported elapsed time, and groups specific events into time-ordered vectors.
+pub fn create_simulation_report<I>(events: I) -> SimulationReport
+where
+    I: IntoIterator,
+    I::Item: Borrow<Event>,
+{
+    let events = events.into_iter();
+    let _span = tracing::info_span!("build_report", events = events.size_hint().0).entered();
+    let mut builder = ReportBuilder::default();
+    for event in events {
+        builder.add(event.borrow());
+    }
+    builder.finish()
+}
+
+/// Creates a `SimulationReport` from a stream of parsed events, such as an
+/// `EventIterator`, without retaining them. Stops at the first parsing error.
+pub fn create_simulation_report_from_stream<I>(events: I) -> Result<SimulationReport, ParsingError>
+where
+    I: IntoIterator<Item = Result<Event, ParsingError>>,
+{
+    let _span = tracing::info_span!("build_report").entered();
+    let mut builder = ReportBuilder::default();
+    for event in events {
+        builder.add(&event?);
+    }
+    Ok(builder.finish())
+}
+
+/// Creates a `SimulationReport` straight from a trace file without retaining the
+/// parsed events. A directory is read as the rolled trace files of a single run, see
+/// `parse_log_directory`.
+///
+/// When `options.max_memory` is set and the raw events kept by the report exceed it,
+/// the report switches to streaming aggregation: raw clog events are dropped and only
+/// their summaries are maintained. Events the report does not keep, e.g. ElapsedTime
+/// or Knob, do not count towards the budget. Sampling from `options` is applied to the report.
+pub fn create_simulation_report_from_file<P: AsRef<Path>>(
+    file_path: P,
+    options: &ParseOptions,
+) -> Result<SimulationReport, ParsingError> {
+    create_simulation_report_with_stats(file_path, options).map(|(report, _)| report)
+}
+
+/// Creates a `SimulationReport` like `create_simulation_report_from_file`, along with
+/// the statistics of the parse, summed over the files of a directory.
+pub fn create_simulation_report_with_stats<P: AsRef<Path>>(
+    file_path: P,
+    options: &ParseOptions,
+) -> Result<(SimulationReport, ParseStats), ParsingError> {
+    let file_path = file_path.as_ref();
+    let mut builder = ReportBuilder::default();
+    if options.full_errors {
+        builder.keep_all_severe_events();
+    }
+    builder.analyses(options.analyses.clone());
+    let mut retained_bytes: usize = 0;
+    let mut add = |event: Event, line_len: usize| {
+        let retained_before = builder.retained_event_count();
+        builder.add(&event);
+        if builder.is_aggregate_only() || builder.retained_event_count() == retained_before {
+            return;
+        }
+        retained_bytes += line_len + std::mem::size_of::<Event>();
+        if options.max_memory.is_some_and(|max| retained_bytes > max) {
+            eprintln!(
+                "Memory budget exceeded after ~{} bytes of events, switching to streaming aggregation",
+                retained_bytes
+            );
+            builder.drop_raw_events();
+        }
+    };
+    let stats = if file_path.is_dir() {
+        // Line lengths are not tracked across files, only the events kept count
+        let mut events = stream_log_directory(file_path, options)?;
+        for event in events.by_ref() {
+            add(event?, 0);
+        }
+        let mut stats = ParseStats::default();
+        for source in events.sources() {
+            stats.merge(source.stats());
+        }
+        stats
+    } else {
+        for_each_event(file_path, options, add)?
+    };
+
+    let last_event_time = builder.last_event_time;
+    let mut report = tracing::info_span!("build_report").in_scope(|| builder.finish());
+    report.apply_sampling(&options.sampling);
+    if file_path.is_file() && ends_with_incomplete_line(file_path)? {
+        report.truncated_at = truncation_time(&report, last_event_time);
+    }
+    Ok((report, stats))
+}
+
+/// Returns the time of the last event of a trace ending with an incomplete line, unless
+/// the run reached its ElapsedTime anyway.
+fn truncation_time(report: &SimulationReport, last_event_time: f64) -> Option<f64> {
+    let elapsed: Option<f64> = report
+        .elapsed_time
+        .as_deref()
+        .and_then(|elapsed| elapsed.parse().ok());
+    elapsed
+        .is_none_or(|elapsed| elapsed - last_event_time > TRUNCATION_GAP_SECONDS)
+        .then_some((last_event_time * 1e6).round() / 1e6)
+}
+
+/// Running count, sum, min and max of a series of durations.
+#[derive(Debug, Clone, Copy)]
+struct DurationStats {
+    count: usize,
+    sum: f64,
+    min: f64,
+    max: f64,
+}
+
+impl Default for DurationStats {
+    fn default() -> Self {
+        DurationStats {
+            count: 0,
+            sum: 0.0,
+            min: f64::MAX,
+            max: f64::MIN,
+        }
+    }
+}
+
+impl DurationStats {
+    /// Adds a duration if it parses as seconds.
+    fn add(&mut self, seconds: &str) {
+        if let Ok(seconds) = seconds.parse::<f64>() {
+            self.min = self.min.min(seconds);
+            self.max = self.max.max(seconds);
+            self.sum += seconds;
+            self.count += 1;
+        }
+    }
+
+    fn mean(&self) -> f64 {
+        if self.count > 0 {
+            self.sum / self.count as f64
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Turns grouped ClogInterface delay statistics into report summaries.
+fn clog_interface_summaries(
+    stats: BTreeMap<String, DurationStats>,
+) -> BTreeMap<String, ClogInterfaceSummary> {
+    stats
+        .into_iter()
+        .map(|(key, stats)| {
+            let summary = ClogInterfaceSummary {
+                count: stats.count,
+                min_seconds: stats.min,
+                // Rounded to the nanosecond so the mean survives a JSON round trip
+                mean_seconds: (stats.mean() * 1e9).round() / 1e9,
+                max_seconds: stats.max,
+            };
+            (key, summary)
+        })
+        .collect()
+}
+
+/// Running aggregation of the Severity 30+ events of one type.
+#[derive(Debug, Clone)]
+struct SevereEventStats {
+    severity: u32,
+    count: usize,
+    first_seen: f64,
+    last_seen: f64,
+    machines: BTreeMap<String, usize>,
+    occurrences: Vec<(f64, u32)>,
+}
+
+impl Default for SevereEventStats {
+    fn default() -> Self {
+        SevereEventStats {
+            severity: 0,
+            count: 0,
+            first_seen: f64::MAX,
+            last_seen: f64::MIN,
+            machines: BTreeMap::new(),
+            occurrences: Vec::new(),
+        }
+    }
+}
+
+impl SevereEventStats {
+    fn add(&mut self, data: &SevereEventData) {
+        let timestamp = data.timestamp.parse::<f64>().unwrap_or(0.0);
+        self.severity = self.severity.max(data.severity());
+        self.count += 1;
+        self.first_seen = self.first_seen.min(timestamp);
+        self.last_seen = self.last_seen.max(timestamp);
+        if let Some(machine) = &data.machine {
+            *self.machines.entry(machine.clone()).or_insert(0) += 1;
+        }
+        self.occurrences.push((timestamp, data.severity()));
+    }
+
+    fn into_summary(mut self, event_type: String) -> SevereEventSummary {
+        let mut top_machines: Vec<(String, usize)> = self.machines.into_iter().collect();
+        // Stable sort keeps ties in address order
+        top_machines.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+        top_machines.truncate(TOP_SEVERE_EVENT_MACHINES);
+        // Rolled trace files may be read out of order
+        self.occurrences.sort_by(|a, b| a.0.total_cmp(&b.0));
+        SevereEventSummary {
+            event_type,
+            severity: self.severity,
+            count: self.count,
+            first_seen: self.first_seen,
+            last_seen: self.last_seen,
+            top_machines,
+            occurrences: self.occurrences,
+        }
+    }
+}
+
+/// Sorts severe event summaries most severe first, then most frequent first.
+fn sort_severe_event_summaries(summaries: &mut [SevereEventSummary]) {
+    summaries.sort_by(|a, b| {
+        b.severity
+            .cmp(&a.severity)
+            .then(b.count.cmp(&a.count))
+            .then_with(|| a.event_type.cmp(&b.event_type))
+    });
+}
+
+/// Incrementally builds a `SimulationReport`, one event at a time.
+///
+/// Clog summaries and per-machine chaos are aggregated as events arrive, so raw clog
+/// events can be dropped mid-stream with `drop_raw_events` without losing them.
+#[derive(Debug, Clone, Default)]
+pub struct ReportBuilder {
+    seed: Option<String>,
+    start_date_time: Option<String>,
+    fdb_version: Option<String>,
+    command_line: Option<String>,
+    /// Earliest non-empty ProgramStart connection string, with its time.
+    initial_connection_string: Option<(f64, String)>,
+    elapsed_time: Option<String>,
+    real_time: Option<String>,
+    simulator_config: Option<BTreeMap<String, String>>,
+    machine_details: BTreeMap<String, MachineInfo>,
+    auxiliary_processes: BTreeMap<String, MachineInfo>,
+    clogging_pairs: Vec<CloggingPairData>,
+    clog_interfaces: Vec<ClogInterfaceData>,
+    coordinators_changes: Vec<CoordinatorsChangeData>,
+    disk_swaps: Vec<DiskSwapData>,
+    set_disk_failures: Vec<SetDiskFailureData>,
+    corrupted_blocks: Vec<CorruptedBlockData>,
+    kill_machine_processes: Vec<KillMachineProcessData>,
+    storage_servers_added: Vec<StorageServerAddedData>,
+    storage_servers_removed: Vec<StorageServerRemovedData>,
+    recruitment_failures: Vec<RecruitmentFailureData>,
+    buggify_sections: Vec<BuggifySectionData>,
+    knobs: BTreeMap<String, String>,
+    assassinations: Vec<AssassinationData>,
+    process_restarts: Vec<RebooterStartData>,
+    lifecycle: LifecycleTracker,
+    kill_machine_process_summary: BTreeMap<KillType, usize>,
+    clogging_pair_stats: DurationStats,
+    dc_clog_seconds: BTreeMap<String, BTreeMap<String, f64>>,
+    /// DC of every machine IP seen in SimulatedMachineStart events.
+    dc_by_ip: HashMap<String, String>,
+    clog_interface_stats: BTreeMap<String, DurationStats>,
+    clog_interface_role_stats: BTreeMap<String, DurationStats>,
+    /// Roles currently held, keyed by (role ID, role) with the process address.
+    active_roles: HashMap<(String, String), String>,
+    roles_by_process: BTreeMap<String, BTreeMap<String, f64>>,
+    role_changes: Vec<RoleData>,
+    recoveries: Vec<RecoveryInfo>,
+    /// Latest kill or clog added so far, the likely cause of the next recovery.
+    last_recovery_cause: Option<RecoveryCause>,
+    machine_chaos: BTreeMap<String, MachineChaosSummary>,
+    aggregate_only: bool,
+    /// Sketches fed with every event, reported when `aggregate_only`.
+    clogged_machines: HyperLogLog,
+    clogged_pairs: HyperLogLog,
+    event_type_counts: CountMinSketch,
+    /// Keys of `event_type_counts`, few as there are few event types.
+    event_types: BTreeSet<&'static str>,
+    /// Latest timestamp of the events added so far.
+    last_event_time: f64,
+    severe_events: Vec<SevereEventData>,
+    severe_event_stats: BTreeMap<String, SevereEventStats>,
+    keep_all_severe_events: bool,
+    severe_events_capped: bool,
+    error_count: usize,
+    test_results: Vec<TestResultsData>,
+    failed_workloads: Vec<TestCompleteData>,
+    analyses: AnalysisRegistry,
+}
+
+impl ReportBuilder {
+    /// Folds a single event into the report.
+    pub fn add(&mut self, event: &Event) {
+        self.last_event_time = self.last_event_time.max(event.timestamp());
+        self.event_types.insert(event.event_type());
+        self.event_type_counts.add(event.event_type(), 1);
+        if let Some(severe) = event.severe_event() {
+            self.add_severe_event(severe);
+        }
+        if let Some(cause) = RecoveryCause::from_event(event) {
+            self.last_recovery_cause = Some(cause);
+        }
+        self.lifecycle.add(event);
+        if let Some((address, roles)) = event.process_roles() {
+            let timestamp = event.timestamp();
+            let held = self
+                .roles_by_process
+                .entry(address.to_string())
+                .or_default();
+            for role in roles.split(',').filter(|role| !role.is_empty()) {
+                // Rolled trace files may be read out of order
+                let first_seen = held.entry(role.to_string()).or_insert(timestamp);
+                *first_seen = first_seen.min(timestamp);
+            }
+        }
+        match event {
+            Event::ProgramStart(data) => {
+                // Only take the seed from the first ProgramStart event
+                if self.seed.is_none() && data.random_seed.is_some() {
+                    self.seed = data.random_seed.clone();
+                }
+                if self.start_date_time.is_none() {
+                    self.start_date_time = data.date_time.clone();
+                }
+                if self.fdb_version.is_none() {
+                    self.fdb_version = data.version.clone();
+                }
+                if self.command_line.is_none() {
+                    self.command_line = data.command_line.clone();
+                }
+                if let Some(connection_string) =
+                    data.connection_string.as_ref().filter(|s| !s.is_empty())
+                {
+                    // Rolled trace files may be read out of order
+                    let timestamp = event.timestamp();
+                    if self
+                        .initial_connection_string
+                        .as_ref()
+                        .is_none_or(|(earliest, _)| timestamp < *earliest)
+                    {
+                        self.initial_connection_string =
+                            Some((timestamp, connection_string.clone()));
+                    }
+                }
+            }
+            Event::ElapsedTime(data) => {
+                self.elapsed_time = Some(data.sim_time.clone());
+                self.real_time = Some(data.real_time.clone());
+            }
+            Event::SimulatorConfig(data) => {
+                // Assume only one SimulatorConfig event exists
+                if self.simulator_config.is_none() {
+                    self.simulator_config = Some(data.config.clone());
+                }
+            }
+            Event::CloggingPair(data) => {
+                self.clogging_pair_stats.add(&data.seconds);
+                let (from, to) = (ip_from_address(&data.from_id), ip_from_address(&data.to_id));
+                self.clogged_machines.insert(from);
+                self.clogged_machines.insert(to);
+                self.clogged_pairs
+                    .insert(&format!("{} {}", from.min(to), from.max(to)));
+                if let Ok(seconds) = data.seconds.parse::<f64>() {
+                    let dc_of = |address: &str| {
+                        self.dc_by_ip
+                            .get(ip_from_address(address))
+                            .map_or(UNKNOWN_DC, String::as_str)
+                            .to_string()
+                    };
+                    let (from_dc, to_dc) = (dc_of(&data.from_id), dc_of(&data.to_id));
+                    *self
+                        .dc_clog_seconds
+                        .entry(from_dc)
+                        .or_default()
+                        .entry(to_dc)
+                        .or_default() += seconds;
+                }
+                self.chaos_for(&data.from_id).clogging_pairs += 1;
+                if data.to_id != data.from_id {
+                    self.chaos_for(&data.to_id).clogging_pairs += 1;
+                }
+                if !self.aggregate_only {
+                    self.clogging_pairs.push(data.clone());
+                }
+            }
+            Event::ClogInterface(data) => {
+                self.clogged_machines.insert(ip_from_address(&data.ip));
+                self.clog_interface_stats
+                    .entry(data.queue.clone())
+                    .or_default()
+                    .add(&data.delay);
+                self.chaos_for(&data.ip).clogged_interfaces += 1;
+                let ip = ip_from_address(&data.ip);
+                let mut roles: Vec<&str> = self
+                    .active_roles
+                    .iter()
+                    .filter(|(_, address)| ip_from_address(address) == ip)
+                    .map(|((_, role), _)| role.as_str())
+                    .collect();
+                roles.sort_unstable();
+                roles.dedup();
+                if roles.is_empty() {
+                    roles.push(NO_ROLE);
+                }
+                for role in roles {
+                    self.clog_interface_role_stats
+                        .entry(role.to_string())
+                        .or_default()
+                        .add(&data.delay);
+                }
+                if !self.aggregate_only {
+                    self.clog_interfaces.push(data.clone());
+                }
+            }
+            Event::SimulatedMachineStart(data) => {
+                if data.process_class == "test" {
+                    return;
+                }
+
+                if let (Some(dc_id), Some(ips)) = (&data.dc_id, &data.machine_ips) {
+                    for ip in ips.split([' ', ',']).filter(|ip| !ip.is_empty()) {
+                        self.dc_by_ip.insert(ip.to_string(), dc_id.clone());
+                    }
+                }
+
+                // Ensure machine_id exists before inserting
+                if let Some(machine_id) = &data.machine_id {
+                    let machine = MachineInfo {
+                        dc_id: data.dc_id.clone(),
+                        data_hall_id: data.data_hall.clone(),
+                        zone_id: data.zone_id.clone(),
+                        machine_id: data.machine_id.clone(), // Store the machine_id itself
+                        ip_address: data.machine_ips.clone(), // Use the machine_ips field here
+                        class_type: Some(data.process_class.clone()),
+                    };
+                    let machines = if machine.is_auxiliary() {
+                        &mut self.auxiliary_processes
+                    } else {
+                        &mut self.machine_details
+                    };
+                    machines.insert(machine_id.clone(), machine);
+                } else {
+                    // Optionally log a warning if machine_id is missing
+                    eprintln!(
+                        "Warning: SimulatedMachineStart event at timestamp {} is missing machine_id",
+                        data.timestamp
+                    );
+                }
+            }
+            Event::CoordinatorsChange(data) => self.coordinators_changes.push(data.clone()),
+            Event::DiskSwap(data) => self.disk_swaps.push(data.clone()),
+            Event::SetDiskFailure(data) => {
+                self.chaos_for(&data.machine).disk_failures += 1;
+                self.set_disk_failures.push(data.clone());
+            }
+            Event::CorruptedBlock(data) => {
+                self.chaos_for(&data.machine).corrupted_blocks += 1;
+                self.corrupted_blocks.push(data.clone());
+            }
+            Event::KillMachineProcess(event_data) => {
+                self.kill_machine_processes.push(event_data.clone());
+                let kill_type = match KillType::from_str(&event_data.raw_kill_type) {
+                    Ok(kill_type) => kill_type,
+                    Err(e) => {
+                        eprintln!(
+                            "Warning: Unknown KillType '{}' at timestamp {}: {}",
+                            event_data.raw_kill_type, event_data.timestamp, e
+                        );
+                        KillType::Unknown // Count unknowns
+                    }
+                };
+                *self
+                    .kill_machine_process_summary
+                    .entry(kill_type.clone())
+                    .or_insert(0) += 1;
+                if let Some(ip) = event_data.ip_address() {
+                    *self
+                        .machine_chaos
+                        .entry(ip.to_string())
+                        .or_default()
+                        .kills_by_type
+                        .entry(kill_type)
+                        .or_insert(0) += 1;
+                }
+            }
+            Event::StorageServerAdded(data) => self.storage_servers_added.push(data.clone()),
+            Event::StorageServerRemoved(data) => self.storage_servers_removed.push(data.clone()),
+            Event::RecruitmentFailure(data) => self.recruitment_failures.push(data.clone()),
+            Event::BuggifySection(data) => self.buggify_sections.push(data.clone()),
+            Event::Knob(data) => {
+                self.knobs.insert(data.name.clone(), data.value.clone());
+            }
+            Event::Assassination(data) => self.assassinations.push(data.clone()),
+            Event::RebooterStart(data) => {
+                if data.is_restart() {
+                    self.end_roles(data.process_address());
+                    self.process_restarts.push(data.clone());
+                }
+            }
+            // Every process is a Worker, only the roles recruited on top of it matter
+            Event::Role(data) if data.role == "Worker" => {}
+            Event::Role(data) => {
+                self.track_role(data);
+                self.role_changes.push(data.clone());
+            }
+            // Collected with the Severity 30+ events of every other type above
+            Event::SevereEvent(_) => {}
+            Event::TestResults(data) => self.test_results.push(data.clone()),
+            Event::TestComplete(data) => {
+                if !data.is_ok() {
+                    self.failed_workloads.push(data.clone());
+                }
+            }
+            Event::RecoveryState(data) => {
+                let timestamp = event.timestamp();
+                let index = match self.recoveries.iter().rposition(|r| r.id == data.id) {
+                    Some(index) => index,
+                    None => {
+                        self.recoveries.push(RecoveryInfo {
+                            id: data.id.clone(),
+                            machine: data.machine.clone(),
+                            start: timestamp,
+                            duration: None,
+                            status: String::new(),
+                            cause: self.last_recovery_cause.clone(),
+                        });
+                        self.recoveries.len() - 1
+                    }
+                };
+                let recovery = &mut self.recoveries[index];
+                if let Some(status) = &data.status {
+                    recovery.status = status.clone();
+                }
+                if data.is_fully_recovered() && recovery.duration.is_none() {
+                    recovery.duration = Some(((timestamp - recovery.start) * 1e6).round() / 1e6);
+                }
+            }
+            // Events of types the report does not model are only of interest raw
+            Event::Other(_) | Event::Custom(_) => {}
+        }
+    }
+
+    /// Counts a Severity 30+ event of any type into the errors and warnings, keeping
+    /// it raw unless its type already has enough samples.
+    fn add_severe_event(&mut self, data: SevereEventData) {
+        let stats = self
+            .severe_event_stats
+            .entry(data.event_type.clone())
+            .or_default();
+        stats.add(&data);
+        if data.severity() >= SEVERITY_ERROR {
+            self.error_count += 1;
+        }
+        if self.keep_all_severe_events || stats.count <= SEVERE_EVENT_SAMPLES_PER_TYPE {
+            self.severe_events.push(data);
+        } else {
+            self.severe_events_capped = true;
+        }
+    }
+
+    /// Updates the roles currently held with a Role event.
+    fn track_role(&mut self, data: &RoleData) {
+        let key = (data.id.clone(), data.role.clone());
+        if data.is_end() {
+            self.active_roles.remove(&key);
+        } else {
+            self.active_roles.insert(key, data.machine.clone());
+        }
+    }
+
+    /// Forgets the roles of a restarted process, which starts over without any.
+    fn end_roles(&mut self, address: &str) {
+        self.active_roles.retain(|_, held_by| held_by != address);
+    }
+
+    /// Keeps every Severity 30+ event instead of the first
+    /// `SEVERE_EVENT_SAMPLES_PER_TYPE` of each type. Must be called before adding events.
+    pub fn keep_all_severe_events(&mut self) {
+        self.keep_all_severe_events = true;
+    }
+
+    /// Sets the analysis passes run over the finished report, the built-in ones by
+    /// default.
+    pub fn analyses(&mut self, registry: AnalysisRegistry) {
+        self.analyses = registry;
+    }
+
+    /// Switches to streaming aggregation: raw CloggingPair and ClogInterface events
+    /// collected so far are freed and later ones are only counted in the summaries.
+    pub fn drop_raw_events(&mut self) {
+        self.aggregate_only = true;
+        self.clogging_pairs = Vec::new();
+        self.clog_interfaces = Vec::new();
+    }
+
+    /// Returns whether raw clog events are being dropped.
+    pub fn is_aggregate_only(&self) -> bool {
+        self.aggregate_only
+    }
+
+    /// Latest timestamp of the events added so far, 0 before the first one.
+    pub fn last_event_time(&self) -> f64 {
+        self.last_event_time
+    }
+
+    /// Number of CloggingPair events kept so far.
+    pub fn clogging_pair_count(&self) -> usize {
+        self.clogging_pairs.len()
+    }
+
+    /// Number of ClogInterface events kept so far.
+    pub fn clog_interface_count(&self) -> usize {
+        self.clog_interfaces.len()
+    }
+
+    /// Number of KillMachineProcess events added so far.
+    pub fn kill_count(&self) -> usize {
+        self.kill_machine_processes.len()
+    }
+
+    /// Number of SetDiskFailure events added so far.
+    pub fn disk_failure_count(&self) -> usize {
+        self.set_disk_failures.len()
+    }
+
+    /// Number of CorruptedBlock events added so far.
+    pub fn corrupted_block_count(&self) -> usize {
+        self.corrupted_blocks.len()
+    }
+
+    /// Number of CoordinatorsChange events added so far.
+    pub fn coordinators_change_count(&self) -> usize {
+        self.coordinators_changes.len()
     }
-}
 
-/// Creates a `SimulationReport` by processing a slice of `Event`s.
-///
-/// Extracts the seed, a list of unique machine identifiers (from ProgramStart events),
-/// the last reported elapsed time, and groups specific events into time-ordered vectors.
-pub fn create_simulation_report(events: &[Event]) -> SimulationReport {
-    let mut seed = None;
-    let mut elapsed_time = None;
-    let mut real_time = None;
-    let mut simulator_config = None;
-
-    // Use HashMaps to collect unique machine details
-    let mut machine_details: HashMap<String, MachineInfo> = HashMap::new();
-
-    // Initialize vectors for other event types
-    let mut clogging_pairs = Vec::new();
-    let mut clog_interfaces = Vec::new();
-    let mut coordinators_changes = Vec::new();
-    let mut disk_swaps = Vec::new();
-    let mut set_disk_failures = Vec::new();
-    let mut corrupted_blocks = Vec::new();
-    let mut kill_machine_processes = Vec::new();
-
-    // Summaries (initialized before loop)
-    let mut kill_machine_process_summary: HashMap<KillType, usize> = HashMap::new();
+    /// Number of RecruitmentFailure events added so far.
+    pub fn recruitment_failure_count(&self) -> usize {
+        self.recruitment_failures.len()
+    }
 
-    for event in events {
-        match event {
-            Event::ProgramStart(data) => {
-                // Only take the seed from the first ProgramStart event
-                if seed.is_none() && data.random_seed.is_some() {
-                    seed = data.random_seed.clone();
-                }
-            }
-            Event::ElapsedTime(data) => {
-                elapsed_time = Some(data.sim_time.clone());
-                real_time = Some(data.real_time.clone());
-            }
-            Event::SimulatorConfig(data) => {
-                // Assume only one SimulatorConfig event exists
-                if simulator_config.is_none() {
-                    simulator_config = Some(data.config.clone());
-                }
-            }
-            Event::CloggingPair(data) => clogging_pairs.push(data.clone()),
-            Event::ClogInterface(data) => clog_interfaces.push(data.clone()),
-            Event::SimulatedMachineStart(data) => {
-                if data.process_class == "test" {
-                    continue;
-                }
+    /// Number of raw events kept so far, the ones the memory budget is charged for.
+    fn retained_event_count(&self) -> usize {
+        self.clogging_pairs.len()
+            + self.clog_interfaces.len()
+            + self.coordinators_changes.len()
+            + self.disk_swaps.len()
+            + self.set_disk_failures.len()
+            + self.corrupted_blocks.len()
+            + self.kill_machine_processes.len()
+            + self.storage_servers_added.len()
+            + self.storage_servers_removed.len()
+            + self.recruitment_failures.len()
+            + self.buggify_sections.len()
+            + self.assassinations.len()
+            + self.process_restarts.len()
+            + self.role_changes.len()
+            + self.severe_events.len()
+            + self.test_results.len()
+            + self.failed_workloads.len()
+    }
 
-                // Ensure machine_id exists before inserting
-                if let Some(machine_id) = &data.machine_id {
-                    machine_details.insert(
-                        machine_id.clone(), // Use the actual machine_id
-                        MachineInfo {
-                            dc_id: data.dc_id.clone(),
-                            data_hall_id: data.data_hall.clone(),
-                            zone_id: data.zone_id.clone(),
-                            machine_id: data.machine_id.clone(), // Store the machine_id itself
-                            ip_address: data.machine_ips.clone(), // Use the machine_ips field here
-                            class_type: Some(data.process_class.clone()),
-                        },
-                    );
-                } else {
-                    // Optionally log a warning if machine_id is missing
-                    eprintln!(
-                        "Warning: SimulatedMachineStart event at timestamp {} is missing machine_id",
-                        data.timestamp
-                    );
-                }
-            }
-            Event::CoordinatorsChange(data) => coordinators_changes.push(data.clone()),
-            Event::DiskSwap(data) => disk_swaps.push(data.clone()),
-            Event::SetDiskFailure(data) => set_disk_failures.push(data.clone()),
-            Event::CorruptedBlock(data) => corrupted_blocks.push(data.clone()),
-            Event::KillMachineProcess(event_data) => {
-                kill_machine_processes.push(event_data.clone());
-                match KillType::from_str(&event_data.raw_kill_type) {
-                    Ok(kill_type) => {
-                        *kill_machine_process_summary.entry(kill_type).or_insert(0) += 1;
-                    }
-                    Err(e) => {
-                        eprintln!(
-                            "Warning: Unknown KillType '{}' at timestamp {}: {}",
-                            event_data.raw_kill_type, event_data.timestamp, e
-                        );
-                        *kill_machine_process_summary
-                            .entry(KillType::Unknown) // Count unknowns
-                            .or_insert(0) += 1;
-                    }
-                }
-            }
-        }
+    /// Returns the per-machine chaos summary of the machine at `address`.
+    fn chaos_for(&mut self, address: &str) -> &mut MachineChaosSummary {
+        self.machine_chaos
+            .entry(ip_from_address(address).to_string())
+            .or_default()
     }
 
-    // --- Sorting Logic for Vecs ---
-    let parse_ts = |ts_str: &str| ts_str.parse::<f64>().unwrap_or(0.0);
+    /// Returns the report of the events added so far, leaving the builder able to take
+    /// more. Live views use this to refresh their report without folding every event
+    /// again.
+    pub fn snapshot(&self) -> SimulationReport {
+        self.clone().finish()
+    }
 
-    clogging_pairs.sort_by(|a, b| {
-        parse_ts(&a.timestamp)
-            .partial_cmp(&parse_ts(&b.timestamp))
-            .unwrap_or(std::cmp::Ordering::Equal)
-    });
-    clog_interfaces.sort_by(|a, b| {
-        parse_ts(&a.timestamp)
-            .partial_cmp(&parse_ts(&b.timestamp))
-            .unwrap_or(std::cmp::Ordering::Equal)
-    });
-    coordinators_changes.sort_by(|a, b| {
-        parse_ts(&a.timestamp)
-            .partial_cmp(&parse_ts(&b.timestamp))
-            .unwrap_or(std::cmp::Ordering::Equal)
-    });
-    disk_swaps.sort_by(|a, b| {
-        parse_ts(&a.timestamp)
-            .partial_cmp(&parse_ts(&b.timestamp))
-            .unwrap_or(std::cmp::Ordering::Equal)
-    });
-    set_disk_failures.sort_by(|a, b| {
-        parse_ts(&a.timestamp)
-            .partial_cmp(&parse_ts(&b.timestamp))
-            .unwrap_or(std::cmp::Ordering::Equal)
-    });
-    corrupted_blocks.sort_by(|a, b| {
-        parse_ts(&a.time)
-            .partial_cmp(&parse_ts(&b.time))
-            .unwrap_or(std::cmp::Ordering::Equal)
-    });
+    /// Sorts the collected events and computes the remaining summaries.
+    pub fn finish(self) -> SimulationReport {
+        let ReportBuilder {
+            seed,
+            start_date_time,
+            fdb_version,
+            command_line,
+            initial_connection_string,
+            elapsed_time,
+            real_time,
+            simulator_config,
+            machine_details,
+            auxiliary_processes,
+            mut clogging_pairs,
+            mut clog_interfaces,
+            mut coordinators_changes,
+            mut disk_swaps,
+            mut set_disk_failures,
+            mut corrupted_blocks,
+            mut kill_machine_processes,
+            mut storage_servers_added,
+            mut storage_servers_removed,
+            mut recruitment_failures,
+            mut buggify_sections,
+            knobs,
+            mut assassinations,
+            mut process_restarts,
+            lifecycle,
+            kill_machine_process_summary,
+            clogging_pair_stats,
+            mut dc_clog_seconds,
+            dc_by_ip: _,
+            clog_interface_stats,
+            clog_interface_role_stats,
+            active_roles: _,
+            roles_by_process,
+            mut role_changes,
+            mut recoveries,
+            last_recovery_cause: _,
+            machine_chaos,
+            aggregate_only,
+            clogged_machines,
+            clogged_pairs,
+            event_type_counts,
+            event_types,
+            last_event_time,
+            mut severe_events,
+            severe_event_stats,
+            keep_all_severe_events: _,
+            severe_events_capped,
+            error_count,
+            mut test_results,
+            mut failed_workloads,
+            analyses,
+        } = self;
 
-    kill_machine_processes.sort_by(|a, b| {
-        parse_ts(&a.timestamp)
-            .partial_cmp(&parse_ts(&b.timestamp))
-            .unwrap_or(std::cmp::Ordering::Equal)
-    });
+        // --- Sorting Logic for Vecs ---
+        let parse_ts = |ts_str: &str| ts_str.parse::<f64>().unwrap_or(0.0);
+
+        clogging_pairs.sort_by(|a, b| {
+            parse_ts(&a.timestamp)
+                .partial_cmp(&parse_ts(&b.timestamp))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        clog_interfaces.sort_by(|a, b| {
+            parse_ts(&a.timestamp)
+                .partial_cmp(&parse_ts(&b.timestamp))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        coordinators_changes.sort_by(|a, b| {
+            parse_ts(&a.timestamp)
+                .partial_cmp(&parse_ts(&b.timestamp))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        disk_swaps.sort_by(|a, b| {
+            parse_ts(&a.timestamp)
+                .partial_cmp(&parse_ts(&b.timestamp))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        set_disk_failures.sort_by(|a, b| {
+            parse_ts(&a.timestamp)
+                .partial_cmp(&parse_ts(&b.timestamp))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        corrupted_blocks.sort_by(|a, b| {
+            parse_ts(&a.time)
+                .partial_cmp(&parse_ts(&b.time))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        kill_machine_processes.sort_by(|a, b| {
+            parse_ts(&a.timestamp)
+                .partial_cmp(&parse_ts(&b.timestamp))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        storage_servers_added.sort_by(|a, b| {
+            parse_ts(&a.timestamp)
+                .partial_cmp(&parse_ts(&b.timestamp))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        storage_servers_removed.sort_by(|a, b| {
+            parse_ts(&a.timestamp)
+                .partial_cmp(&parse_ts(&b.timestamp))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        recruitment_failures.sort_by(|a, b| {
+            parse_ts(&a.timestamp)
+                .partial_cmp(&parse_ts(&b.timestamp))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        buggify_sections.sort_by(|a, b| {
+            parse_ts(&a.timestamp)
+                .partial_cmp(&parse_ts(&b.timestamp))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        assassinations.sort_by(|a, b| {
+            parse_ts(&a.timestamp)
+                .partial_cmp(&parse_ts(&b.timestamp))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        process_restarts.sort_by(|a, b| {
+            parse_ts(&a.timestamp)
+                .partial_cmp(&parse_ts(&b.timestamp))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        role_changes.sort_by(|a, b| {
+            parse_ts(&a.timestamp)
+                .partial_cmp(&parse_ts(&b.timestamp))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        severe_events.sort_by(|a, b| {
+            parse_ts(&a.timestamp)
+                .partial_cmp(&parse_ts(&b.timestamp))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        test_results.sort_by(|a, b| {
+            parse_ts(&a.timestamp)
+                .partial_cmp(&parse_ts(&b.timestamp))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        failed_workloads.sort_by(|a, b| {
+            parse_ts(&a.timestamp)
+                .partial_cmp(&parse_ts(&b.timestamp))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        recoveries.sort_by(|a, b| a.start.total_cmp(&b.start));
 
-    // --- Calculate Clogging Summary ---
-    let mut min_seconds = f64::MAX;
-    let mut max_seconds = f64::MIN;
-    let mut sum_seconds = 0.0;
-    let mut count = 0;
+        // --- Test Outcome ---
+        let test_name = (!test_results.is_empty()).then(|| {
+            test_results
+                .iter()
+                .map(|result| result.workload.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        });
+        let passed = Some(
+            !test_results.is_empty()
+                && test_results.iter().all(TestResultsData::is_passed)
+                && error_count == 0,
+        );
+
+        // --- Clogging Summary ---
+        let clogging_pair_summary = if clogging_pair_stats.count > 0 {
+            Some(CloggingPairSummary {
+                count: clogging_pair_stats.count,
+                min_seconds: clogging_pair_stats.min,
+                mean_seconds: clogging_pair_stats.mean(),
+                max_seconds: clogging_pair_stats.max,
+            })
+        } else {
+            None
+        };
 
-    for pair in &clogging_pairs {
-        if let Ok(seconds) = pair.seconds.parse::<f64>() {
-            min_seconds = min_seconds.min(seconds);
-            max_seconds = max_seconds.max(seconds);
-            sum_seconds += seconds;
-            count += 1;
+        // Rounded to the microsecond so the sums survive a JSON round trip
+        for seconds in dc_clog_seconds.values_mut().flat_map(|to| to.values_mut()) {
+            *seconds = (*seconds * 1e6).round() / 1e6;
         }
-    }
 
-    let clogging_pair_summary = if count > 0 {
-        Some(CloggingPairSummary {
-            count,
-            min_seconds,
-            mean_seconds: sum_seconds / count as f64,
-            max_seconds,
-        })
-    } else {
-        None
-    };
+        // --- Clog Interface Summary (Grouped by Queue and by Role) ---
+        let clog_interface_summary = clog_interface_summaries(clog_interface_stats);
+        let clog_interface_roles = clog_interface_summaries(clog_interface_role_stats);
 
-    // --- Calculate Clog Interface Summary (Grouped by Queue) ---
-    let mut interface_stats: HashMap<String, (f64, f64, f64, usize)> = HashMap::new(); // (sum, min, max, count)
+        // --- Calculate Coordinator Change Count ---
+        let coordinators_change_count = coordinators_changes.len();
+        let mut coordinators = initial_connection_string
+            .map(|(_, connection_string)| parse_coordinators(&connection_string))
+            .unwrap_or_default();
+        let coordinator_sets = coordinators_changes
+            .iter()
+            .map(|change| {
+                let before = coordinators.clone();
+                let after = change.coordinators();
+                if !after.is_empty() {
+                    coordinators = after;
+                }
+                CoordinatorSetChange {
+                    timestamp: parse_ts(&change.timestamp),
+                    before,
+                    after: coordinators.clone(),
+                }
+            })
+            .collect();
 
-    for interface in &clog_interfaces {
-        if let Ok(seconds) = interface.delay.parse::<f64>() {
-            let queue_name = interface.queue.clone();
-            let entry = interface_stats
-                .entry(queue_name)
-                .or_insert((0.0, f64::MAX, f64::MIN, 0));
-            entry.0 += seconds; // sum
-            entry.1 = entry.1.min(seconds); // min
-            entry.2 = entry.2.max(seconds); // max
-            entry.3 += 1; // count
+        // --- Calculate Recruitment Failure Summary ---
+        // recruitment_failures is sorted, so the first entry of a group is its first occurrence
+        let mut recruitment_failure_summary: Vec<RecruitmentFailureSummary> = Vec::new();
+        for failure in &recruitment_failures {
+            let error = failure.error.as_deref().unwrap_or("N/A");
+            let ts = parse_ts(&failure.timestamp);
+            match recruitment_failure_summary
+                .iter_mut()
+                .find(|s| s.event_type == failure.event_type && s.error == error)
+            {
+                Some(summary) => {
+                    summary.count += 1;
+                    summary.last_seen = ts;
+                }
+                None => recruitment_failure_summary.push(RecruitmentFailureSummary {
+                    event_type: failure.event_type.clone(),
+                    error: error.to_string(),
+                    count: 1,
+                    first_seen: ts,
+                    last_seen: ts,
+                }),
+            }
         }
-    }
 
-    let clog_interface_summary: HashMap<String, ClogInterfaceSummary> = interface_stats
-        .into_iter()
-        .map(|(queue, (sum, min_val, max_val, count))| {
-            let summary = ClogInterfaceSummary {
-                count,
-                min_seconds: min_val,
-                mean_seconds: if count > 0 { sum / count as f64 } else { 0.0 },
-                max_seconds: max_val,
-            };
-            (queue, summary)
-        })
-        .collect();
+        // --- Errors and Warnings Summary ---
+        let mut errors_and_warnings: Vec<SevereEventSummary> = severe_event_stats
+            .into_iter()
+            .map(|(event_type, stats)| stats.into_summary(event_type))
+            .collect();
+        sort_severe_event_summaries(&mut errors_and_warnings);
+
+        // --- Calculate Disk Failure Summary ---
+        let disk_failure_summary = if set_disk_failures.is_empty() {
+            None
+        } else {
+            let mut by_machine: BTreeMap<String, MachineDiskFailures> = BTreeMap::new();
+            for failure in &set_disk_failures {
+                let machine = by_machine
+                    .entry(ip_from_address(&failure.machine).to_string())
+                    .or_default();
+                machine.count += 1;
+                machine.stall_seconds += failure.stall_period.parse().unwrap_or(0.0);
+                machine.throttle_seconds += failure.throttle_period.parse().unwrap_or(0.0);
+            }
+            Some(DiskFailureSummary {
+                count: set_disk_failures.len(),
+                total_stall_seconds: by_machine.values().map(|m| m.stall_seconds).sum(),
+                total_throttle_seconds: by_machine.values().map(|m| m.throttle_seconds).sum(),
+                by_machine,
+            })
+        };
 
-    // --- Calculate Coordinator Change Count ---
-    let coordinators_change_count = coordinators_changes.len();
+        // --- Calculate Storage Server Churn ---
+        let storage_server_churn =
+            if storage_servers_added.is_empty() && storage_servers_removed.is_empty() {
+                None
+            } else {
+                let unique_servers = storage_servers_added
+                    .iter()
+                    .map(|data| data.server_id.as_str())
+                    .chain(
+                        storage_servers_removed
+                            .iter()
+                            .map(|data| data.server_id.as_str()),
+                    )
+                    .collect::<HashSet<_>>()
+                    .len();
+                Some(StorageServerChurnSummary {
+                    added: storage_servers_added.len(),
+                    removed: storage_servers_removed.len(),
+                    unique_servers,
+                })
+            };
 
-    // --- Calculate Kill Machine Process Summary ---
-    // kill_machine_process_summary is already populated in the event loop
+        let run_end = elapsed_time
+            .as_deref()
+            .and_then(|t| t.parse().ok())
+            .unwrap_or(last_event_time);
+        let availability = lifecycle.finish(run_end);
 
-    SimulationReport {
-        seed,
-        elapsed_time,
-        real_time,
-        simulator_config,
-        clogging_pairs,
-        clogging_pair_summary,
-        clog_interfaces,
-        clog_interface_summary,
-        coordinators_changes,
-        coordinators_change_count,
-        machine_details,
-        disk_swaps,
-        set_disk_failures,
-        corrupted_blocks,
-        kill_machine_processes,
-        kill_machine_process_summary,
+        let mut report = SimulationReport {
+            schema_version: REPORT_SCHEMA_VERSION,
+            seed,
+            start_date_time,
+            fdb_version,
+            command_line,
+            elapsed_time,
+            real_time,
+            simulator_config,
+            clogging_pairs,
+            clogging_pair_summary,
+            dc_clog_seconds,
+            clog_interfaces,
+            clog_interface_summary,
+            clog_interface_roles,
+            coordinators_changes,
+            coordinators_change_count,
+            coordinator_sets,
+            machine_details,
+            // Keyed by machine ID, so already sorted
+            auxiliary_processes: auxiliary_processes.into_values().collect(),
+            roles_by_process,
+            role_changes,
+            disk_swaps,
+            set_disk_failures,
+            disk_failure_summary,
+            corrupted_blocks,
+            kill_machine_processes,
+            kill_machine_process_summary,
+            storage_servers_added,
+            storage_servers_removed,
+            storage_server_churn,
+            machine_chaos,
+            recruitment_failures,
+            recruitment_failure_summary,
+            buggify_sections,
+            knobs,
+            assassinations,
+            process_restarts,
+            availability,
+            recoveries,
+            severe_events,
+            errors_and_warnings,
+            severe_events_capped,
+            error_count,
+            test_results,
+            test_name,
+            passed,
+            failed_workloads,
+            analyses: Vec::new(),
+            annotations: Vec::new(),
+            tags: BTreeMap::new(),
+            sampling: BTreeMap::new(),
+            aggregate_only,
+            approximate_counts: aggregate_only.then(|| ApproximateCounts {
+                clogged_machines: clogged_machines.estimate(),
+                clogged_pairs: clogged_pairs.estimate(),
+                events_by_type: event_types
+                    .into_iter()
+                    .map(|event_type| {
+                        (
+                            event_type.to_string(),
+                            event_type_counts.estimate(event_type),
+                        )
+                    })
+                    .collect(),
+            }),
+            truncated_at: None,
+        };
+        report.analyses = analyses.run(&report);
+        report
     }
 }
 
@@ -629,10 +3301,349 @@ pub fn create_simulation_report(events: &[Event]) -> SimulationReport {
 #[cfg(test)]
 mod tests {
     use super::*; // Import items from outer module (report)
-    use crate::parser::{parse_log_file, Event};
+    use crate::parser::{parse_log_file, parse_log_file_with_options, Event, ParseOptions};
+    use serde_json::json;
     // use crate::parser::KillType; // Remove unused import
     // use std::collections::HashMap; // Remove unused import
 
+    #[test]
+    fn test_apply_sampling_scales_counts() {
+        let options = ParseOptions {
+            sampling: "clog_interface=0.5".parse().unwrap(),
+            ..ParseOptions::default()
+        };
+        let events = parse_log_file_with_options(
+            "logs/combined_trace.0.0.0.0.24.1745498878.p7Loj0.json",
+            &options,
+        )
+        .expect("Failed to parse log file");
+        let mut report = create_simulation_report(&events);
+        let sampled = report.clog_interfaces.len();
+        assert!(sampled > 150 && sampled < 330, "sampled {}", sampled);
+        assert_eq!(report.clogging_pairs.len(), 396);
+
+        report.apply_sampling(&options.sampling);
+
+        let estimated: usize = report
+            .clog_interface_summary
+            .values()
+            .map(|s| s.count)
+            .sum();
+        assert!((estimated as i64 - sampled as i64 * 2).abs() <= 3);
+        assert_eq!(report.clogging_pair_summary.as_ref().unwrap().count, 396);
+        assert_eq!(
+            report.sampling_note("ClogInterface").as_deref(),
+            Some("sampled at 50.0%, counts scaled")
+        );
+        assert!(report.to_string().contains("sampled at 50.0%"));
+    }
+
+    #[test]
+    fn test_truncated_run() {
+        let file_path = "logs/combined_trace.0.0.0.0.24.1745498878.p7Loj0.json";
+        let report = create_simulation_report_from_file(file_path, &ParseOptions::default())
+            .expect("Failed to parse log file");
+        assert_eq!(report.truncated_at, None);
+
+        let start = r#"{"Severity": "10", "Time": "0.000000", "Type": "ProgramStart", "Machine": "0.0.0.0:0", "RandomSeed": "42"}"#;
+        let clog = r#"{"Severity": "10", "Time": "12.500000", "Type": "CloggingPair", "Machine": "3.4.3.1:1", "From": "2.0.1.0", "To": "2.0.1.1", "Seconds": "3.5"}"#;
+        let elapsed = r#"{"Severity": "10", "Time": "12.600000", "Type": "ElapsedTime", "SimTime": "12.6", "RealTime": "1.0"}"#;
+        let report_of = |content: String| {
+            let mut trace = tempfile::NamedTempFile::new().unwrap();
+            trace.write_all(content.as_bytes()).unwrap();
+            create_simulation_report_from_file(trace.path(), &ParseOptions::default()).unwrap()
+        };
+
+        let cut = r#"{"Severity": "10", "Time": "13.0"#;
+        let report = report_of(format!("{}\n{}\n{}", start, clog, cut));
+        assert_eq!(report.truncated_at, Some(12.5));
+        assert!(report.to_string().contains("truncated run"));
+        // Complete lines, or an incomplete one after the end of the run, are fine
+        assert_eq!(
+            report_of(format!("{}\n{}\n", start, clog)).truncated_at,
+            None
+        );
+        let report = report_of(format!("{}\n{}\n{}\n{}", start, clog, elapsed, cut));
+        assert_eq!(report.truncated_at, None);
+    }
+
+    #[test]
+    fn test_recoveries() {
+        let state = |ts: &str, id: &str, code: Option<&str>, status: Option<&str>| {
+            Event::RecoveryState(RecoveryStateData {
+                timestamp: ts.to_string(),
+                machine: "2.0.1.2:1".to_string(),
+                id: id.to_string(),
+                status_code: code.map(str::to_string),
+                status: status.map(str::to_string),
+            })
+        };
+        let events = vec![
+            state("5.0", "r1", Some("0"), Some("reading_coordinated_state")),
+            state("9.5", "r1", Some("14"), Some("fully_recovered")),
+            state("9.8", "r1", None, None),
+            Event::KillMachineProcess(KillMachineProcessData {
+                timestamp: "60.0".to_string(),
+                raw_kill_type: "2".to_string(),
+                process: "name: Server address: 2.1.1.2:1 zone: z".to_string(),
+                starting_class: "stateless".to_string(),
+                failed: "0".to_string(),
+                excluded: "0".to_string(),
+                cleared: "0".to_string(),
+                rebooting: "0".to_string(),
+            }),
+            state("67.25", "r2", Some("0"), Some("reading_coordinated_state")),
+            state(
+                "70.5",
+                "r2",
+                Some("7"),
+                Some("recruiting_transaction_servers"),
+            ),
+        ];
+
+        let report = create_simulation_report(&events);
+
+        let recoveries = &report.recoveries;
+        assert_eq!(recoveries.len(), 2);
+        assert_eq!(recoveries[0].duration, Some(4.5));
+        assert_eq!(recoveries[0].status, "fully_recovered");
+        assert_eq!(recoveries[0].cause, None);
+        assert_eq!(recoveries[1].duration, None);
+        assert_eq!(recoveries[1].status, "recruiting_transaction_servers");
+        assert_eq!(
+            recoveries[1].cause,
+            Some(RecoveryCause {
+                timestamp: 60.0,
+                description: "KillMachineProcess 2.1.1.2".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_test_outcome() {
+        let file_path = "logs/combined_trace.0.0.0.0.24.1745498878.p7Loj0.json";
+        let report = create_simulation_report_from_file(file_path, &ParseOptions::default())
+            .expect("Failed to parse log file");
+        assert_eq!(report.passed, Some(true));
+        assert_eq!(report.test_name.as_deref(), Some("LogicalDBWorkload"));
+        assert_eq!(report.error_count, 0);
+        assert!(report.failed_workloads.is_empty());
+        assert!(report.to_string().contains("PASS"));
+
+        let events: Vec<Event> = [
+            r#"{"Severity": "10", "Time": "8.0", "Type": "TestComplete", "Machine": "3.4.3.3:1", "Workload": "ChangeConfig", "OK": "0"}"#,
+            r#"{"Severity": "40", "Time": "9.0", "Type": "InternalError", "Machine": "2.0.1.0:1"}"#,
+            r#"{"Severity": "30", "Time": "10.0", "Type": "TestResults", "Machine": "0.0.0.0:0", "Workload": "ChangeConfig", "Passed": "0"}"#,
+        ]
+        .into_iter()
+        .map(|line| Event::try_from(line).unwrap())
+        .collect();
+        let report = create_simulation_report(&events);
+        assert_eq!(report.passed, Some(false));
+        assert_eq!(report.error_count, 1);
+        assert_eq!(report.failed_workloads.len(), 1);
+        assert!(report.to_string().contains("ChangeConfig did not pass"));
+
+        // Runs that never logged their results did not pass
+        assert_eq!(create_simulation_report(&events[..0]).passed, Some(false));
+    }
+
+    #[test]
+    fn test_memory_budget_switches_to_aggregation() {
+        let file_path = "logs/combined_trace.0.0.0.0.24.1745498878.p7Loj0.json";
+        let full = create_simulation_report_from_file(file_path, &ParseOptions::default())
+            .expect("Failed to parse log file");
+        assert!(!full.aggregate_only);
+        let from_events = create_simulation_report(parse_log_file(file_path).unwrap());
+        assert_eq!(full.clog_interfaces, from_events.clog_interfaces);
+        assert_eq!(full.machine_chaos, from_events.machine_chaos);
+
+        let options = ParseOptions {
+            max_memory: Some(64 * 1024),
+            ..ParseOptions::default()
+        };
+        let budgeted = create_simulation_report_from_file(file_path, &options)
+            .expect("Failed to parse log file");
+        assert!(budgeted.aggregate_only);
+        assert!(budgeted.clog_interfaces.len() < full.clog_interfaces.len());
+        assert_eq!(budgeted.clog_interface_summary, full.clog_interface_summary);
+        assert_eq!(budgeted.clogging_pair_summary, full.clogging_pair_summary);
+        assert_eq!(budgeted.machine_chaos, full.machine_chaos);
+        assert_eq!(budgeted.kill_machine_processes, full.kill_machine_processes);
+        assert!(budgeted.to_string().contains("memory budget exceeded"));
+
+        // Distinct counts of the dropped events are estimated, small ones exactly
+        assert!(full.approximate_counts.is_none());
+        let approximate = budgeted.approximate_counts.as_ref().unwrap();
+        let clogged: HashSet<&str> =
+            full.clog_interfaces
+                .iter()
+                .map(|clog| ip_from_address(&clog.ip))
+                .chain(full.clogging_pairs.iter().flat_map(|pair| {
+                    [ip_from_address(&pair.from_id), ip_from_address(&pair.to_id)]
+                }))
+                .collect();
+        assert_eq!(approximate.clogged_machines, clogged.len());
+        assert!(
+            approximate.events_by_type["KillMachineProcess"] >= full.kill_machine_processes.len()
+        );
+        assert!(budgeted.to_string().contains("Approximate Counts"));
+
+        // Events the report does not keep are not charged to the budget
+        let mut trace = tempfile::NamedTempFile::new().unwrap();
+        for i in 0..2000 {
+            writeln!(
+                trace,
+                r#"{{"Severity": "10", "Time": "{}.0", "Type": "ElapsedTime", "SimTime": "{}.0", "RealTime": "1.0"}}"#,
+                i, i
+            )
+            .unwrap();
+        }
+        let report = create_simulation_report_from_file(trace.path(), &options).unwrap();
+        assert!(!report.aggregate_only);
+    }
+
+    #[test]
+    fn test_serialized_report_is_stable() {
+        let file_path = "logs/combined_trace.0.0.0.0.24.1745498878.p7Loj0.json";
+        let events = parse_log_file(file_path).expect("Failed to parse log file");
+        let first = serde_json::to_string(&create_simulation_report(&events)).unwrap();
+        let second = serde_json::to_string(&create_simulation_report(&events)).unwrap();
+        assert_eq!(first, second);
+        assert!(first.starts_with(&format!("{{\"schema_version\":{}", REPORT_SCHEMA_VERSION)));
+
+        // Reports written before versioning load as version 0
+        let mut value: serde_json::Value = serde_json::from_str(&first).unwrap();
+        value.as_object_mut().unwrap().remove("schema_version");
+        let legacy: SimulationReport = serde_json::from_value(value).unwrap();
+        assert_eq!(legacy.schema_version, 0);
+    }
+
+    #[test]
+    fn test_snapshot_matches_full_report() {
+        let file_path = "logs/combined_trace.0.0.0.0.24.1745498878.p7Loj0.json";
+        let events = parse_log_file(file_path).expect("Failed to parse log file");
+        let (head, tail) = events.split_at(events.len() / 2);
+        let mut builder = ReportBuilder::default();
+        for event in head {
+            builder.add(event);
+        }
+        assert_eq!(
+            serde_json::to_string(&builder.snapshot()).unwrap(),
+            serde_json::to_string(&create_simulation_report(head)).unwrap()
+        );
+        for event in tail {
+            builder.add(event);
+        }
+        assert_eq!(
+            serde_json::to_string(&builder.finish()).unwrap(),
+            serde_json::to_string(&create_simulation_report(&events)).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_json_file_round_trip_and_migration() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("report.json");
+        let events = parse_log_file("logs/combined_trace.0.0.0.0.24.1745498878.p7Loj0.json")
+            .expect("Failed to parse log file");
+        let report = create_simulation_report(&events);
+
+        report.to_json_file(&path).unwrap();
+        let loaded = SimulationReport::from_json_file(&path).unwrap();
+        assert_eq!(
+            serde_json::to_value(&loaded).unwrap(),
+            serde_json::to_value(&report).unwrap()
+        );
+
+        // An unversioned report, written before most sections existed
+        let legacy = json!({
+            "seed": "42", "elapsed_time": null, "real_time": null, "simulator_config": null,
+            "clogging_pairs": [], "clogging_pair_summary": null, "clog_interfaces": [],
+            "clog_interface_summary": {}, "coordinators_changes": [],
+            "coordinators_change_count": 0, "disk_swaps": [],
+            "machine_details": {
+                "m1": { "machine_id": "m1", "class_type": "storage" },
+                "m2": { "machine_id": "m2", "class_type": "sim_http_server" }
+            },
+            "set_disk_failures": [], "corrupted_blocks": [], "kill_machine_processes": [],
+            "kill_machine_process_summary": {}
+        });
+        std::fs::write(&path, legacy.to_string()).unwrap();
+        let migrated = SimulationReport::from_json_file(&path).unwrap();
+        assert_eq!(migrated.schema_version, REPORT_SCHEMA_VERSION);
+        assert_eq!(migrated.seed.as_deref(), Some("42"));
+        assert!(migrated.buggify_sections.is_empty());
+        assert_eq!(migrated.machine_details.len(), 1);
+        assert_eq!(migrated.auxiliary_processes.len(), 1);
+        assert!(migrated.auxiliary_processes[0].is_auxiliary());
+        assert!(migrated.tags.is_empty());
+        // Whether the run passed was not recorded, so it is unknown rather than failed
+        assert_eq!(migrated.passed, None);
+        assert!(migrated.recoveries.is_empty());
+        assert!(migrated
+            .failure_reasons(
+                &TimeFormatter::new(TimeFormat::default(), &migrated),
+                &NumberFormat::default()
+            )
+            .is_empty());
+        assert!(migrated.to_string().contains("UNKNOWN"));
+
+        assert_eq!(
+            parse_tag("commit=abc123"),
+            Ok(("commit".to_string(), "abc123".to_string()))
+        );
+        assert!(parse_tag("=main").is_err());
+        assert!(parse_tag("main").is_err());
+
+        // Fields added since version 1 default when missing
+        let mut value = serde_json::to_value(&report).unwrap();
+        for field in ["recoveries", "tags", "availability", "disk_failure_summary"] {
+            value.as_object_mut().unwrap().remove(field);
+        }
+        std::fs::write(&path, value.to_string()).unwrap();
+        let loaded = SimulationReport::from_json_file(&path).unwrap();
+        assert!(loaded.recoveries.is_empty() && loaded.availability.is_empty());
+        assert_eq!(
+            loaded.kill_machine_processes.len(),
+            report.kill_machine_processes.len()
+        );
+
+        std::fs::write(&path, json!({ "schema_version": 99 }).to_string()).unwrap();
+        assert!(matches!(
+            SimulationReport::from_json_file(&path),
+            Err(ReportFileError::UnsupportedVersion { found: 99, .. })
+        ));
+    }
+
+    #[test]
+    fn test_clogs_for_machine() {
+        let events = parse_log_file("logs/combined_trace.0.0.0.0.24.1745498878.p7Loj0.json")
+            .expect("Failed to parse log file");
+        let report = create_simulation_report(&events);
+
+        let (machine_id, ip) = report
+            .machine_details
+            .iter()
+            .find_map(|(id, machine)| Some((id.clone(), machine.ip_address.clone()?)))
+            .unwrap();
+        let by_ip = report.clogs_for_machine(&ip);
+        assert_eq!(by_ip, report.clogs_for_machine(&machine_id));
+        assert_eq!(by_ip, report.clogs_for_machine(&format!("{}:1", ip)));
+
+        // Matches the per-machine chaos counts, which use the same endpoints
+        let chaos = &report.machine_chaos[&ip];
+        let (pairs, interfaces): (Vec<&MachineClog>, Vec<&MachineClog>) = by_ip
+            .iter()
+            .partition(|clog| matches!(clog, MachineClog::Pair(_)));
+        assert_eq!(pairs.len(), chaos.clogging_pairs);
+        assert_eq!(interfaces.len(), chaos.clogged_interfaces);
+        assert!(by_ip
+            .windows(2)
+            .all(|w| w[0].timestamp() <= w[1].timestamp()));
+        assert!(report.clogs_for_machine("unknown").is_empty());
+    }
+
     #[test]
     fn test_create_report_from_log() {
         let file_path = "logs/combined_trace.0.0.0.0.24.1745498878.p7Loj0.json";
@@ -748,5 +3759,507 @@ mod tests {
         assert!(report.corrupted_blocks.is_empty());
         assert!(report.kill_machine_processes.is_empty());
         assert!(report.kill_machine_process_summary.is_empty());
+        assert!(report.storage_server_churn.is_none());
+        assert_eq!(report.machine_chaos.len(), 1);
+        assert_eq!(report.machine_chaos["1.2.3.4"].disk_failures, 1);
+        let summary = report.disk_failure_summary.as_ref().unwrap();
+        assert_eq!(summary.count, 1);
+        assert_eq!(summary.total_stall_seconds, 10.0);
+        assert_eq!(summary.total_throttle_seconds, 60.0);
+        assert_eq!(summary.by_machine["1.2.3.4"].throttle_seconds, 60.0);
+        // Rendered as durations in the chosen time format
+        let text = report.display_with(TimeFormat::Humanized).to_string();
+        assert!(text.contains(" 10s ") && text.contains(" 1m "));
+        assert!(report
+            .display_with(TimeFormat::Seconds)
+            .to_string()
+            .contains("60.000000s"));
+    }
+
+    #[test]
+    fn test_create_report_machine_chaos() {
+        let events = vec![
+            Event::KillMachineProcess(KillMachineProcessData {
+                timestamp: "54.5".to_string(),
+                raw_kill_type: "6".to_string(),
+                process:
+                    "name: Server address: 2.2.1.2:1 zone: af04 datahall: 2 class: transaction"
+                        .to_string(),
+                starting_class: "transaction".to_string(),
+                failed: "0".to_string(),
+                excluded: "0".to_string(),
+                cleared: "0".to_string(),
+                rebooting: "0".to_string(),
+            }),
+            Event::CloggingPair(CloggingPairData {
+                timestamp: "52.7".to_string(),
+                from_id: "2.0.1.0".to_string(),
+                to_id: "2.2.1.2".to_string(),
+                seconds: "0.35".to_string(),
+            }),
+            Event::ClogInterface(ClogInterfaceData {
+                timestamp: "52.8".to_string(),
+                ip: "2.2.1.2".to_string(),
+                delay: "0.3".to_string(),
+                queue: "All".to_string(),
+            }),
+        ];
+
+        let report = create_simulation_report(&events);
+
+        let target = &report.machine_chaos["2.2.1.2"];
+        assert_eq!(target.kills_by_type.get(&KillType::Reboot), Some(&1));
+        assert_eq!(target.clogging_pairs, 1);
+        assert_eq!(target.clogged_interfaces, 1);
+        assert_eq!(target.disk_failures, 0);
+        assert_eq!(report.machine_chaos["2.0.1.0"].clogging_pairs, 1);
+        assert!(report.machine_chaos["2.0.1.0"].kills_by_type.is_empty());
+    }
+
+    #[test]
+    fn test_coordinator_sets() {
+        let start = |timestamp: &str, connection_string: &str| {
+            Event::ProgramStart(ProgramStartData {
+                timestamp: timestamp.to_string(),
+                machine: "0.0.0.0:0".to_string(),
+                random_seed: None,
+                date_time: None,
+                version: None,
+                command_line: None,
+                connection_string: Some(connection_string.to_string()),
+            })
+        };
+        let change = |timestamp: &str, key: &str| {
+            Event::CoordinatorsChange(CoordinatorsChangeData {
+                timestamp: timestamp.to_string(),
+                new_coordinators_key: key.to_string(),
+            })
+        };
+        let events = vec![
+            start("0.0", ""),
+            change("120.0", ""),
+            start("50.0", "db:1@2.0.1.0:1:tls,2.0.1.1:1:tls"),
+            start("4.0", "db:0@2.0.1.0:1:tls"),
+            change("100.0", "db:1@2.0.1.0:1:tls,2.0.1.1:1:tls"),
+        ];
+        let report = create_simulation_report(&events);
+
+        let sets = &report.coordinator_sets;
+        assert_eq!(sets.len(), 2);
+        assert_eq!(sets[0].timestamp, 100.0);
+        assert_eq!(sets[0].before.len(), 1);
+        let added: Vec<String> = sets[0].added().iter().map(|a| a.to_string()).collect();
+        assert_eq!(added, ["2.0.1.1:1:tls"]);
+        assert!(sets[0].removed().is_empty());
+        // A change logging no key keeps the coordinators
+        assert_eq!(sets[1].before, sets[1].after);
+        assert!(report.to_string().contains("2.0.1.1:1:tls"));
+    }
+
+    #[test]
+    fn test_report_window() {
+        let clog = |timestamp: &str, seconds: &str| {
+            Event::CloggingPair(CloggingPairData {
+                timestamp: timestamp.to_string(),
+                from_id: "2.0.1.0".to_string(),
+                to_id: "2.0.1.1".to_string(),
+                seconds: seconds.to_string(),
+            })
+        };
+        let events = vec![
+            clog("10.0", "1.0"),
+            clog("20.0", "3.0"),
+            clog("30.0", "5.0"),
+            Event::KillMachineProcess(KillMachineProcessData {
+                timestamp: "25.0".to_string(),
+                raw_kill_type: "6".to_string(),
+                process: "name: Server address: 2.0.1.1:1 zone: af04".to_string(),
+                starting_class: "storage".to_string(),
+                failed: "0".to_string(),
+                excluded: "0".to_string(),
+                cleared: "0".to_string(),
+                rebooting: "0".to_string(),
+            }),
+            Event::ProgramStart(ProgramStartData {
+                timestamp: "0.0".to_string(),
+                machine: "0.0.0.0:0".to_string(),
+                random_seed: Some("42".to_string()),
+                date_time: None,
+                version: None,
+                command_line: None,
+                connection_string: None,
+            }),
+        ];
+        let report = create_simulation_report(&events);
+
+        let window = report.window(15.0, 30.0);
+        let summary = window.clogging_pair_summary.as_ref().unwrap();
+        assert_eq!(summary.count, 2);
+        assert_eq!(summary.mean_seconds, 4.0);
+        assert_eq!(window.kill_machine_process_summary[&KillType::Reboot], 1);
+        assert_eq!(window.machine_chaos["2.0.1.0"].clogging_pairs, 2);
+        assert_eq!(window.seed.as_deref(), Some("42"));
+
+        let empty = report.window(0.0, 5.0);
+        assert_eq!(empty.clogging_pair_summary, None);
+        assert!(empty.kill_machine_process_summary.is_empty());
+        assert_eq!(report.clogging_pair_summary.unwrap().count, 3);
+    }
+
+    #[test]
+    fn test_severe_events_are_capped_per_type() {
+        let severe = |ts: usize, severity: &str, event_type: &str, machine: &str| {
+            Event::SevereEvent(SevereEventData {
+                timestamp: ts.to_string(),
+                severity: severity.to_string(),
+                event_type: event_type.to_string(),
+                machine: Some(machine.to_string()),
+                error: None,
+            })
+        };
+        let mut events: Vec<Event> = (0..20)
+            .map(|i| {
+                let machine = if i % 4 == 0 { "2.0.1.1:1" } else { "2.0.1.0:1" };
+                severe(i, "30", "TransactionTooManyRetries", machine)
+            })
+            .collect();
+        events.push(severe(50, "40", "StorageServerFailed", "2.0.1.2:1"));
+
+        let report = create_simulation_report(&events);
+        assert!(report.severe_events_capped);
+        assert_eq!(
+            report.severe_events.len(),
+            SEVERE_EVENT_SAMPLES_PER_TYPE + 1
+        );
+        assert_eq!(
+            report.errors_and_warnings[0].event_type,
+            "StorageServerFailed"
+        );
+        assert_eq!(
+            report.errors_and_warnings[1],
+            SevereEventSummary {
+                event_type: "TransactionTooManyRetries".to_string(),
+                severity: 30,
+                count: 20,
+                first_seen: 0.0,
+                last_seen: 19.0,
+                top_machines: vec![("2.0.1.0:1".to_string(), 15), ("2.0.1.1:1".to_string(), 5)],
+                occurrences: (0..20).map(|t| (f64::from(t), 30)).collect(),
+            }
+        );
+        assert!(report.to_string().contains("--full-errors"));
+
+        // Windows count every event, not only the kept samples
+        let window = report.window(10.0, 60.0);
+        assert_eq!(window.errors_and_warnings.len(), 2);
+        assert_eq!(window.errors_and_warnings[1].count, 10);
+        assert_eq!(window.errors_and_warnings[1].first_seen, 10.0);
+        assert_eq!(window.error_count, 1);
+        let window = report.window(0.0, 19.0);
+        assert_eq!(window.errors_and_warnings.len(), 1);
+        assert_eq!(window.errors_and_warnings[0].count, 20);
+        assert_eq!(
+            window.errors_and_warnings[0].top_machines,
+            [("2.0.1.0:1".to_string(), 3), ("2.0.1.1:1".to_string(), 2)]
+        );
+        assert_eq!(window.error_count, 0);
+
+        let mut builder = ReportBuilder::default();
+        builder.keep_all_severe_events();
+        events.iter().for_each(|event| builder.add(event));
+        let full = builder.finish();
+        assert!(!full.severe_events_capped);
+        assert_eq!(full.severe_events.len(), 21);
+        assert_eq!(full.errors_and_warnings, report.errors_and_warnings);
+    }
+
+    #[test]
+    fn test_create_report_recruitment_failure_summary() {
+        let failure = |ts: &str, event_type: &str| {
+            Event::RecruitmentFailure(RecruitmentFailureData {
+                timestamp: ts.to_string(),
+                severity: Some("30".to_string()),
+                event_type: event_type.to_string(),
+                machine: "2.1.1.2:1".to_string(),
+                error: Some("no_more_servers".to_string()),
+                error_description: None,
+                is_critical: None,
+            })
+        };
+        let events = vec![
+            failure("69.4", "RecruitStorageNotAvailable"),
+            failure("4.9", "ClusterRecoveryRetrying"),
+            failure("10.8", "RecruitStorageNotAvailable"),
+        ];
+
+        let report = create_simulation_report(&events);
+
+        assert_eq!(report.recruitment_failures.len(), 3);
+        // Recruitment failures are warnings, whatever their modeled type
+        let warnings: Vec<(&str, usize)> = report
+            .errors_and_warnings
+            .iter()
+            .map(|summary| (summary.event_type.as_str(), summary.count))
+            .collect();
+        assert_eq!(
+            warnings,
+            [
+                ("RecruitStorageNotAvailable", 2),
+                ("ClusterRecoveryRetrying", 1)
+            ]
+        );
+        assert_eq!(
+            report.recruitment_failure_summary,
+            vec![
+                RecruitmentFailureSummary {
+                    event_type: "ClusterRecoveryRetrying".to_string(),
+                    error: "no_more_servers".to_string(),
+                    count: 1,
+                    first_seen: 4.9,
+                    last_seen: 4.9,
+                },
+                RecruitmentFailureSummary {
+                    event_type: "RecruitStorageNotAvailable".to_string(),
+                    error: "no_more_servers".to_string(),
+                    count: 2,
+                    first_seen: 10.8,
+                    last_seen: 69.4,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_create_report_with_storage_server_churn() {
+        let added = |ts: &str, id: &str| {
+            Event::StorageServerAdded(StorageServerAddedData {
+                timestamp: ts.to_string(),
+                machine: "2.0.1.0:1".to_string(),
+                server_id: id.to_string(),
+            })
+        };
+        let events = vec![
+            added("20.0", "ss2"),
+            added("9.0", "ss1"),
+            Event::StorageServerRemoved(StorageServerRemovedData {
+                timestamp: "15.0".to_string(),
+                machine: "2.0.1.0:1".to_string(),
+                server_id: "ss1".to_string(),
+            }),
+        ];
+
+        let report = create_simulation_report(&events);
+
+        assert_eq!(report.storage_servers_added.len(), 2);
+        assert_eq!(report.storage_servers_added[0].server_id, "ss1");
+        assert_eq!(report.storage_servers_removed.len(), 1);
+        assert_eq!(
+            report.storage_server_churn,
+            Some(StorageServerChurnSummary {
+                added: 2,
+                removed: 1,
+                unique_servers: 2,
+            })
+        );
+    }
+
+    #[test]
+    fn test_clog_interfaces_by_role() {
+        let role = |ts: &str, machine: &str, as_role: &str, transition: &str| {
+            Event::Role(RoleData {
+                timestamp: ts.to_string(),
+                machine: machine.to_string(),
+                id: format!("{}-{}", machine, as_role),
+                role: as_role.to_string(),
+                transition: transition.to_string(),
+                roles: None,
+            })
+        };
+        let clog = |ts: &str, ip: &str, delay: &str| {
+            Event::ClogInterface(ClogInterfaceData {
+                timestamp: ts.to_string(),
+                ip: ip.to_string(),
+                delay: delay.to_string(),
+                queue: "All".to_string(),
+            })
+        };
+        let events = vec![
+            role("1", "2.0.1.0:1", "Worker", "Begin"),
+            role("2", "2.0.1.0:1", "TLog", "Begin"),
+            role("2", "2.0.1.0:2", "StorageServer", "Begin"),
+            clog("10", "2.0.1.0", "0.5"),
+            clog("11", "2.0.1.1", "0.1"),
+            role("20", "2.0.1.0:1", "TLog", "End"),
+            clog("30", "2.0.1.0", "0.2"),
+        ];
+
+        let report = create_simulation_report(&events);
+
+        let roles = &report.clog_interface_roles;
+        assert_eq!(
+            roles.keys().collect::<Vec<_>>(),
+            [NO_ROLE, "StorageServer", "TLog"]
+        );
+        assert_eq!(roles["TLog"].count, 1);
+        assert_eq!(roles["TLog"].max_seconds, 0.5);
+        assert_eq!(roles["StorageServer"].count, 2);
+        assert_eq!(roles[NO_ROLE].count, 1);
+
+        // Roles taken before a window still attribute the clogs inside it
+        let roles = report.window(5.0, 15.0).clog_interface_roles;
+        assert_eq!(
+            roles.keys().collect::<Vec<_>>(),
+            [NO_ROLE, "StorageServer", "TLog"]
+        );
+        assert_eq!(roles["TLog"].count, 1);
+        let roles = report.window(25.0, 35.0).clog_interface_roles;
+        assert_eq!(roles.keys().collect::<Vec<_>>(), ["StorageServer"]);
+    }
+
+    #[test]
+    fn test_roles_by_process() {
+        let events = vec![
+            Event::Role(RoleData {
+                timestamp: "2".to_string(),
+                machine: "2.0.1.0:1".to_string(),
+                id: "tlog1".to_string(),
+                role: "TLog".to_string(),
+                transition: "Begin".to_string(),
+                roles: Some("SS,TL".to_string()),
+            }),
+            Event::Other(OtherEventData {
+                timestamp: "1".to_string(),
+                event_type: "StorageMetrics".to_string(),
+                fields: BTreeMap::from([
+                    ("Machine".to_string(), "2.0.1.0:1".to_string()),
+                    ("Roles".to_string(), "SS".to_string()),
+                ]),
+            }),
+            Event::Other(OtherEventData {
+                timestamp: "3".to_string(),
+                event_type: "CommitDebug".to_string(),
+                fields: BTreeMap::from([
+                    ("Machine".to_string(), "2.0.1.1:1".to_string()),
+                    ("Roles".to_string(), "CP,SS".to_string()),
+                ]),
+            }),
+        ];
+
+        let report = create_simulation_report(&events);
+
+        assert_eq!(
+            report.roles_by_process["2.0.1.0:1"],
+            BTreeMap::from([("SS".to_string(), 1.0), ("TL".to_string(), 2.0)])
+        );
+        let by_role = report.processes_by_role();
+        assert_eq!(by_role["SS"], [("2.0.1.0:1", 1.0), ("2.0.1.1:1", 3.0)]);
+        assert_eq!(by_role["CP"], [("2.0.1.1:1", 3.0)]);
+        assert!(report.to_string().contains("Storage Server (SS)"));
+    }
+
+    #[test]
+    fn test_dc_clog_matrix() {
+        let machine = |ip: &str, dc: &str| {
+            Event::SimulatedMachineStart(SimulatedMachineStartData {
+                timestamp: "0.0".to_string(),
+                process_class: "storage".to_string(),
+                locality: format!("machineid={}", ip),
+                machine_ips: Some(ip.to_string()),
+                zone_id: None,
+                process_id: None,
+                machine_id: Some(ip.to_string()),
+                dc_id: Some(dc.to_string()),
+                data_hall: None,
+            })
+        };
+        let pair = |from: &str, to: &str, seconds: &str| {
+            Event::CloggingPair(CloggingPairData {
+                timestamp: "10".to_string(),
+                from_id: from.to_string(),
+                to_id: to.to_string(),
+                seconds: seconds.to_string(),
+            })
+        };
+        let events = vec![
+            machine("2.0.1.0", "0"),
+            machine("2.0.1.1", "0"),
+            machine("2.1.1.0", "1"),
+            pair("2.0.1.0:1", "2.1.1.0:1", "1.5"),
+            pair("2.0.1.1", "2.1.1.0", "0.5"),
+            pair("2.0.1.0", "2.0.1.1", "0.25"),
+            pair("3.4.3.1", "2.0.1.0", "1"),
+            Event::KillMachineProcess(KillMachineProcessData {
+                timestamp: "20".to_string(),
+                raw_kill_type: "6".to_string(),
+                process: "name: Server address: 2.1.1.0:1 zone: z".to_string(),
+                starting_class: "storage".to_string(),
+                failed: "0".to_string(),
+                excluded: "0".to_string(),
+                cleared: "0".to_string(),
+                rebooting: "0".to_string(),
+            }),
+        ];
+
+        let report = create_simulation_report(&events);
+
+        let matrix = &report.dc_clog_seconds;
+        assert_eq!(matrix["0"]["1"], 2.0);
+        assert_eq!(matrix["0"]["0"], 0.25);
+        assert_eq!(matrix[UNKNOWN_DC]["0"], 1.0);
+        assert!(!matrix.contains_key("1"));
+        assert!(report.to_string().contains("clogged for 2.000s"));
+
+        let matrix = report.machine_clog_seconds();
+        assert_eq!(matrix["2.0.1.0"]["2.1.1.0"], 1.5);
+        assert_eq!(matrix["2.0.1.0"]["2.0.1.1"], 0.25);
+        assert_eq!(matrix["3.4.3.1"]["2.0.1.0"], 1.0);
+        assert!(!matrix.contains_key("2.1.1.0"));
+
+        let impact = report.dc_impact();
+        assert_eq!(impact["0"].clog_seconds, 3.25);
+        assert_eq!(impact["0"].kills, 0);
+        assert_eq!(impact["1"].clog_seconds, 2.0);
+        assert_eq!(impact["1"].kills, 1);
+        assert_eq!(impact[UNKNOWN_DC].clog_seconds, 1.0);
+        assert!(report.to_string().contains("Chaos by DC"));
+    }
+
+    #[test]
+    fn test_corrupted_block_summary() {
+        let block = |time: &str, machine: &str, filename: &str| {
+            Event::CorruptedBlock(CorruptedBlockData {
+                severity: "10".to_string(),
+                time: time.to_string(),
+                date_time: String::new(),
+                event_type: "CorruptedBlock".to_string(),
+                machine: machine.to_string(),
+                filename: filename.to_string(),
+                block: "20".to_string(),
+                id: None,
+                roles: None,
+            })
+        };
+        assert_eq!(
+            create_simulation_report(&[]).corrupted_block_summary(),
+            None
+        );
+
+        let events = vec![
+            block("93.0", "2.0.1.3:1", "/sim/storage-0a1b.sqlite"),
+            block("120.5", "2.0.1.3:1", "/sim/logqueue-V_5_LS_2-0a1b-0.fdq"),
+            block("40.0", "2.0.1.4:1", "/sim/storage-0c2d.sqlite-wal"),
+        ];
+        let report = create_simulation_report(&events);
+        let summary = report.corrupted_block_summary().unwrap();
+
+        let machine = &summary.by_machine["2.0.1.3"];
+        assert_eq!(machine.count, 2);
+        assert_eq!((machine.first_seen, machine.last_seen), (93.0, 120.5));
+        let sqlite = &summary.by_file["storage (sqlite)"];
+        assert_eq!(sqlite.count, 2);
+        assert_eq!((sqlite.first_seen, sqlite.last_seen), (40.0, 93.0));
+        assert_eq!(summary.by_file["tlog"].count, 1);
+        assert_eq!(file_category("processId"), "other");
+        assert!(report.to_string().contains("Corrupted Blocks"));
     }
 }
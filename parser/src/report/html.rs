@@ -0,0 +1,726 @@
+//! Standalone HTML rendering of a report.
+//!
+//! The page is self-contained: styles are inlined and the timeline is an inline SVG,
+//! so it can be attached as a CI artifact and opened without network access. Colors
+//! follow the reader's light or dark preference.
+
+use super::{
+    analysis_table_cells, finding_text, is_alert, is_numeric, overview_rows, SimulationReport,
+};
+use crate::analysis::{AnalysisSection, FindingLevel};
+use crate::number_format::NumberFormat;
+use crate::parser::{role_name, KillType, SEVERITY_ERROR, SEVERITY_WARNING};
+use crate::time_format::TimeFormatter;
+use crate::timeline::timeline;
+use std::fmt::Write;
+
+/// Color variables, shared by the page and the standalone SVG timeline.
+const COLORS: &str = "\
+:root { color-scheme: light dark; --fg: #1e1e1e; --bg: #ffffff; --muted: #6e6e6e; --border: #d0d0d0; --pass: #1a7f37; --fail: #cf222e; --warn: #9a6700; }
+@media (prefers-color-scheme: dark) { :root { --fg: #e5e5e5; --bg: #1e1e1e; --muted: #9e9e9e; --border: #444444; --pass: #3fb950; --fail: #f85149; --warn: #d29922; } }
+";
+
+const STYLE: &str = "\
+body { font-family: system-ui, sans-serif; color: var(--fg); background: var(--bg); margin: 2em; }
+h1 { margin-bottom: 0.2em; }
+h2 { margin-top: 1.5em; border-bottom: 1px solid var(--border); }
+table { border-collapse: collapse; margin: 0.5em 0; }
+th, td { border: 1px solid var(--border); padding: 0.2em 0.6em; text-align: left; }
+td.num { text-align: right; font-variant-numeric: tabular-nums; }
+.pass { color: var(--pass); font-weight: bold; }
+.fail { color: var(--fail); font-weight: bold; }
+.warn { color: var(--warn); }
+.muted { color: var(--muted); }
+svg text { fill: var(--fg); font-size: 11px; }
+svg line { stroke: var(--border); }
+.timeline input { margin-left: 1em; }
+.swatch { display: inline-block; width: 0.8em; height: 0.8em; border-radius: 50%; margin: 0 0.3em; vertical-align: middle; }
+";
+
+/// Colors of the timeline lanes, cycled through in order of first appearance.
+const LANE_COLORS: &[&str] = &[
+    "#e6194b", "#3cb44b", "#4363d8", "#f58231", "#911eb4", "#42d4f4", "#f032e6", "#bfef45",
+    "#469990", "#9a6324", "#800000", "#808000",
+];
+
+/// Timeline lane of process kills, as named by `timeline`, whose markers are colored by
+/// kill type.
+const KILL_CATEGORY: &str = "Reboot";
+/// Timeline lane of Severity 30+ events, whose markers are colored by severity.
+const SEVERE_CATEGORY: &str = "Severity 30+";
+
+/// Geometry of the SVG timeline, in pixels.
+const LABEL_WIDTH: f64 = 130.0;
+const PLOT_WIDTH: f64 = 870.0;
+const LANE_HEIGHT: f64 = 22.0;
+const AXIS_HEIGHT: f64 = 24.0;
+
+/// Renders the report as a standalone HTML page, with times rendered by `time`.
+pub fn to_html(report: &SimulationReport, time: &TimeFormatter, numbers: &NumberFormat) -> String {
+    let title = match &report.seed {
+        Some(seed) => format!("Simulation seed {}", seed),
+        None => "Simulation".to_string(),
+    };
+    let mut body = String::new();
+    let _ = writeln!(body, "<h1>{}</h1>", escape_html(&title));
+    write_outcome(&mut body, report, time, numbers);
+    write_table(
+        &mut body,
+        "Overview",
+        &["Key", "Value"],
+        overview_rows(report, time, numbers),
+    );
+    // Analyses with warnings, e.g. the likely trigger of a failure, come first
+    for section in report.analyses.iter().filter(|section| is_alert(section)) {
+        write_analysis_section(&mut body, section, time);
+    }
+
+    let _ = writeln!(body, "<h2>Timeline</h2>");
+    body.push_str(&timeline_svg(report, time));
+
+    if !report.errors_and_warnings.is_empty() {
+        let rows = report
+            .errors_and_warnings
+            .iter()
+            .map(|summary| {
+                vec![
+                    summary.event_type.clone(),
+                    summary.severity.to_string(),
+                    numbers.count(summary.count),
+                    time.timestamp(summary.first_seen),
+                    time.timestamp(summary.last_seen),
+                ]
+            })
+            .collect();
+        write_table(
+            &mut body,
+            "Errors & Warnings",
+            &[
+                "Event Type",
+                "Severity",
+                "Count",
+                &time.header("First Seen"),
+                &time.header("Last Seen"),
+            ],
+            rows,
+        );
+    }
+
+    if !report.kill_machine_process_summary.is_empty() {
+        let rows = report
+            .kill_machine_process_summary
+            .iter()
+            .map(|(kill_type, count)| vec![format!("{:?}", kill_type), numbers.count(*count)])
+            .collect();
+        write_table(&mut body, "Process Kills", &["Kill Type", "Count"], rows);
+    }
+
+    let dc_impact = report.dc_impact();
+    if !dc_impact.is_empty() {
+        let rows = dc_impact
+            .iter()
+            .map(|(dc_id, impact)| {
+                vec![
+                    dc_id.clone(),
+                    numbers.count(impact.kills),
+                    numbers.decimal(impact.clog_seconds, 3),
+                    numbers.count(impact.disk_failures),
+                ]
+            })
+            .collect();
+        write_table(
+            &mut body,
+            "Chaos by DC",
+            &["DC ID", "Kills", "Clog Seconds", "Disk Failures"],
+            rows,
+        );
+    }
+
+    let clog_rows: Vec<Vec<String>> = report
+        .clogging_pair_summary
+        .iter()
+        .map(|s| {
+            (
+                "Clogging Pairs".to_string(),
+                s.count,
+                s.min_seconds,
+                s.mean_seconds,
+                s.max_seconds,
+            )
+        })
+        .chain(report.clog_interface_summary.iter().map(|(queue, s)| {
+            (
+                format!("Interfaces ({})", queue),
+                s.count,
+                s.min_seconds,
+                s.mean_seconds,
+                s.max_seconds,
+            )
+        }))
+        .map(|(kind, count, min, mean, max)| {
+            vec![
+                kind,
+                numbers.count(count),
+                numbers.decimal(min, 6),
+                numbers.decimal(mean, 6),
+                numbers.decimal(max, 6),
+            ]
+        })
+        .collect();
+    if !clog_rows.is_empty() {
+        write_table(
+            &mut body,
+            "Clogging",
+            &["Kind", "Count", "Min (s)", "Mean (s)", "Max (s)"],
+            clog_rows,
+        );
+    }
+
+    if !report.recoveries.is_empty() {
+        let rows = report
+            .recoveries
+            .iter()
+            .map(|recovery| {
+                vec![
+                    time.timestamp(recovery.start),
+                    recovery
+                        .duration
+                        .map_or_else(|| "-".to_string(), |d| numbers.decimal(d, 3)),
+                    recovery.status.clone(),
+                    recovery.machine.clone(),
+                    recovery
+                        .cause
+                        .as_ref()
+                        .map_or_else(String::new, |cause| cause.description.clone()),
+                ]
+            })
+            .collect();
+        write_table(
+            &mut body,
+            "Recoveries",
+            &[
+                &time.header("Start"),
+                "Duration (s)",
+                "Status",
+                "Machine",
+                "Likely Cause",
+            ],
+            rows,
+        );
+    }
+
+    let by_role = report.processes_by_role();
+    if !by_role.is_empty() {
+        let rows = by_role
+            .into_iter()
+            .map(|(role, processes)| {
+                let name = match role_name(role) {
+                    Some(name) => format!("{} ({})", name, role),
+                    None => role.to_string(),
+                };
+                let addresses: Vec<&str> = processes.iter().map(|(address, _)| *address).collect();
+                vec![name, numbers.count(processes.len()), addresses.join(", ")]
+            })
+            .collect();
+        write_table(
+            &mut body,
+            "Role Assignment",
+            &["Role", "Processes", "Addresses"],
+            rows,
+        );
+    }
+
+    if !report.machine_details.is_empty() {
+        let mut machines: Vec<_> = report.machine_details.values().collect();
+        machines.sort_by(|a, b| a.machine_id.cmp(&b.machine_id));
+        let rows = machines
+            .into_iter()
+            .map(|machine| {
+                [
+                    &machine.machine_id,
+                    &machine.ip_address,
+                    &machine.dc_id,
+                    &machine.class_type,
+                ]
+                .iter()
+                .map(|value| value.as_deref().unwrap_or("N/A").to_string())
+                .collect()
+            })
+            .collect();
+        write_table(
+            &mut body,
+            "Machines",
+            &["Machine ID", "IP Address", "DC ID", "Class Type"],
+            rows,
+        );
+    }
+
+    for section in report.analyses.iter().filter(|section| !is_alert(section)) {
+        write_analysis_section(&mut body, section, time);
+    }
+
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>\n{}{}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+        escape_html(&title),
+        COLORS,
+        STYLE,
+        body
+    )
+}
+
+/// Writes the PASS/FAIL line, its reasons and the run's tags.
+fn write_outcome(
+    out: &mut String,
+    report: &SimulationReport,
+    time: &TimeFormatter,
+    numbers: &NumberFormat,
+) {
+    let test_name = escape_html(report.test_name.as_deref().unwrap_or("unknown test"));
+    match report.passed {
+        Some(true) => {
+            let _ = writeln!(out, "<p><span class=\"pass\">PASS</span> {}</p>", test_name);
+        }
+        Some(false) => {
+            let _ = writeln!(out, "<p><span class=\"fail\">FAIL</span> {}</p>", test_name);
+            out.push_str("<ul>\n");
+            for reason in report.failure_reasons(time, numbers) {
+                let _ = writeln!(out, "<li>{}</li>", escape_html(&reason));
+            }
+            out.push_str("</ul>\n");
+        }
+        None => {
+            let _ = writeln!(
+                out,
+                "<p><span class=\"warn\">UNKNOWN</span> {}</p>",
+                test_name
+            );
+        }
+    }
+    if let Some(truncated_at) = report.truncated_at {
+        let _ = writeln!(
+            out,
+            "<p class=\"fail\">Truncated run: the trace stops mid-line at {}</p>",
+            escape_html(&time.timestamp(truncated_at))
+        );
+    }
+}
+
+/// Writes the findings of an analysis section, colored by level, and its table.
+fn write_analysis_section(out: &mut String, section: &AnalysisSection, time: &TimeFormatter) {
+    let _ = writeln!(out, "<h2>{}</h2>", escape_html(&section.title));
+    if !section.findings.is_empty() {
+        out.push_str("<ul>\n");
+        for finding in &section.findings {
+            let class = match finding.level {
+                FindingLevel::Error => " class=\"fail\"",
+                FindingLevel::Warning => " class=\"warn\"",
+                FindingLevel::Info => "",
+            };
+            let _ = writeln!(
+                out,
+                "<li{}>{}</li>",
+                class,
+                escape_html(&finding_text(finding, time))
+            );
+        }
+        out.push_str("</ul>\n");
+    }
+    if let Some(table) = &section.table {
+        let (headers, rows) = analysis_table_cells(table, time);
+        let headers: Vec<&str> = headers.iter().map(String::as_str).collect();
+        write_table_rows(out, &headers, rows);
+    }
+}
+
+/// Writes a titled table, escaping every cell. Cells holding numbers are right-aligned.
+fn write_table(out: &mut String, title: &str, headers: &[&str], rows: Vec<Vec<String>>) {
+    let _ = writeln!(out, "<h2>{}</h2>", escape_html(title));
+    write_table_rows(out, headers, rows);
+}
+
+/// Writes a table without a title, see `write_table`.
+fn write_table_rows(out: &mut String, headers: &[&str], rows: Vec<Vec<String>>) {
+    out.push_str("<table>\n<tr>");
+    for header in headers {
+        let _ = write!(out, "<th>{}</th>", escape_html(header));
+    }
+    out.push_str("</tr>\n");
+    for row in rows {
+        out.push_str("<tr>");
+        for cell in row {
+            let class = if is_numeric(&cell) {
+                " class=\"num\""
+            } else {
+                ""
+            };
+            let _ = write!(out, "<td{}>{}</td>", class, escape_html(&cell));
+        }
+        out.push_str("</tr>\n");
+    }
+    out.push_str("</table>\n");
+}
+
+/// A marker of the HTML timeline.
+struct Marker {
+    timestamp: f64,
+    /// Lane of the marker, toggled as a whole.
+    category: String,
+    /// Fill color, overriding the lane color.
+    color: Option<&'static str>,
+    tooltip: String,
+}
+
+/// Fill of severe event markers: red for errors and yellow for warnings, like the
+/// Errors & Warnings pane of the TUI.
+fn severity_color(severity: u32) -> &'static str {
+    if severity >= SEVERITY_ERROR {
+        "var(--fail)"
+    } else {
+        "var(--warn)"
+    }
+}
+
+/// Fill of kill markers, from mild to destructive: yellow for reboots keeping data,
+/// through orange, to red and dark red for kills losing data or the process.
+fn kill_color(kill_type: &KillType) -> &'static str {
+    match kill_type {
+        KillType::InjectFaults => "#42d4f4",
+        KillType::Reboot | KillType::RebootProcess => "var(--warn)",
+        KillType::RebootProcessAndSwitch => "#f58231",
+        KillType::RebootAndDelete | KillType::RebootProcessAndDelete | KillType::FailDisk => {
+            "var(--fail)"
+        }
+        KillType::KillInstantly => "#800000",
+        KillType::None | KillType::Unknown => "var(--muted)",
+    }
+}
+
+/// Collects the timeline markers: the events of `timeline` with kills colored by kill
+/// type, plus the Severity 30+ events colored by severity.
+fn markers(report: &SimulationReport, time: &TimeFormatter) -> Vec<Marker> {
+    let mut markers: Vec<Marker> = timeline(report)
+        .into_iter()
+        .filter(|event| event.event_type != KILL_CATEGORY)
+        .map(|event| Marker {
+            timestamp: event.timestamp,
+            tooltip: format!(
+                "{} {}: {}",
+                time.timestamp(event.timestamp),
+                event.event_type,
+                event.details
+            ),
+            category: event.event_type,
+            color: None,
+        })
+        .collect();
+    for kill in &report.kill_machine_processes {
+        let Ok(timestamp) = kill.timestamp.parse::<f64>() else {
+            continue;
+        };
+        let kill_type = kill.kill_type();
+        markers.push(Marker {
+            timestamp,
+            category: KILL_CATEGORY.to_string(),
+            color: Some(kill_color(&kill_type)),
+            tooltip: format!(
+                "{} {:?} {}",
+                time.timestamp(timestamp),
+                kill_type,
+                kill.ip_address().unwrap_or("?.?.?.?")
+            ),
+        });
+    }
+    for severe in &report.severe_events {
+        let Ok(timestamp) = severe.timestamp.parse::<f64>() else {
+            continue;
+        };
+        markers.push(Marker {
+            timestamp,
+            category: SEVERE_CATEGORY.to_string(),
+            color: Some(severity_color(severe.severity())),
+            tooltip: format!(
+                "{} {} (Severity {}) {}",
+                time.timestamp(timestamp),
+                severe.event_type,
+                severe.severity,
+                severe.machine.as_deref().unwrap_or_default()
+            ),
+        });
+    }
+    markers.sort_by(|a, b| a.timestamp.total_cmp(&b.timestamp));
+    markers
+}
+
+/// Draws the timeline as an SVG with one lane per category, in order of first
+/// appearance, preceded by a legend whose checkboxes show and hide each lane. Every
+/// event is a marker whose tooltip gives its time and details.
+///
+/// The toggles are plain CSS, so the page stays free of scripts.
+fn timeline_svg(report: &SimulationReport, time: &TimeFormatter) -> String {
+    let markers = markers(report, time);
+    if markers.is_empty() {
+        return "<p class=\"muted\">No events.</p>\n".to_string();
+    }
+    let lanes = lanes(&markers);
+
+    let mut out = String::from("<div class=\"timeline\">\n<style>\n");
+    for lane in 0..lanes.len() {
+        let _ = writeln!(
+            out,
+            "#lane-{0}:not(:checked) ~ svg .lane-{0} {{ display: none; }}",
+            lane
+        );
+    }
+    out.push_str("</style>\n");
+    // The checkboxes must be siblings preceding the SVG for the selectors above
+    for (lane, category) in lanes.iter().enumerate() {
+        let _ = writeln!(
+            out,
+            "<input type=\"checkbox\" id=\"lane-{0}\" checked><label for=\"lane-{0}\"><span class=\"swatch\" style=\"background: {1}\"></span>{2}</label>",
+            lane,
+            lane_color(lane),
+            escape_html(category)
+        );
+    }
+    out.push_str(&svg_element(report, time, &markers, &lanes, ""));
+    write_color_legend(&mut out, &markers);
+    out.push_str("</div>\n");
+    out
+}
+
+/// Draws the timeline as a standalone SVG document, e.g. to attach to a ticket, or
+/// `None` when the report has no events to draw. Unlike the HTML page, it has neither
+/// lane toggles nor color legend.
+pub fn timeline_svg_document(report: &SimulationReport, time: &TimeFormatter) -> Option<String> {
+    let markers = markers(report, time);
+    if markers.is_empty() {
+        return None;
+    }
+    let style = format!(
+        "<style>\n{}svg {{ background: var(--bg); font-family: system-ui, sans-serif; }}\ntext {{ fill: var(--fg); font-size: 11px; }}\nline {{ stroke: var(--border); }}\n</style>\n",
+        COLORS
+    );
+    Some(format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n{}",
+        svg_element(report, time, &markers, &lanes(&markers), &style)
+    ))
+}
+
+/// Timeline lanes, in order of first appearance.
+fn lanes(markers: &[Marker]) -> Vec<&str> {
+    let mut lanes: Vec<&str> = Vec::new();
+    for marker in markers {
+        if !lanes.contains(&marker.category.as_str()) {
+            lanes.push(&marker.category);
+        }
+    }
+    lanes
+}
+
+/// Color of a timeline lane.
+fn lane_color(lane: usize) -> &'static str {
+    LANE_COLORS[lane % LANE_COLORS.len()]
+}
+
+/// Draws the `<svg>` element of the timeline, with `style` as its first child.
+fn svg_element(
+    report: &SimulationReport,
+    time: &TimeFormatter,
+    markers: &[Marker],
+    lanes: &[&str],
+    style: &str,
+) -> String {
+    let end = report
+        .elapsed_time
+        .as_deref()
+        .and_then(|t| t.parse::<f64>().ok())
+        .into_iter()
+        .chain(markers.iter().map(|marker| marker.timestamp))
+        .fold(1.0_f64, f64::max);
+    let x = |seconds: f64| LABEL_WIDTH + seconds.max(0.0) / end * PLOT_WIDTH;
+    let height = lanes.len() as f64 * LANE_HEIGHT + AXIS_HEIGHT;
+
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" role=\"img\" aria-label=\"Timeline\">",
+        LABEL_WIDTH + PLOT_WIDTH + 10.0,
+        height
+    );
+    out.push_str(style);
+    for (lane, category) in lanes.iter().enumerate() {
+        let y = lane as f64 * LANE_HEIGHT + LANE_HEIGHT / 2.0;
+        let _ = writeln!(
+            out,
+            "<g class=\"lane-{}\"><text x=\"0\" y=\"{:.1}\" dominant-baseline=\"middle\">{}</text>\n<line x1=\"{}\" y1=\"{:.1}\" x2=\"{}\" y2=\"{:.1}\"/></g>",
+            lane,
+            y,
+            escape_html(category),
+            LABEL_WIDTH,
+            y,
+            LABEL_WIDTH + PLOT_WIDTH,
+            y
+        );
+    }
+    for marker in markers {
+        let lane = lanes
+            .iter()
+            .position(|category| *category == marker.category)
+            .unwrap_or(0);
+        let _ = writeln!(
+            out,
+            "<circle class=\"lane-{}\" cx=\"{:.1}\" cy=\"{:.1}\" r=\"4\" style=\"fill: {}\"><title>{}</title></circle>",
+            lane,
+            x(marker.timestamp),
+            lane as f64 * LANE_HEIGHT + LANE_HEIGHT / 2.0,
+            marker.color.unwrap_or_else(|| lane_color(lane)),
+            escape_html(&marker.tooltip)
+        );
+    }
+    // Axis with five evenly spaced ticks
+    let axis_y = lanes.len() as f64 * LANE_HEIGHT + 14.0;
+    for tick in 0..=4 {
+        let seconds = end * f64::from(tick) / 4.0;
+        let anchor = match tick {
+            0 => "start",
+            4 => "end",
+            _ => "middle",
+        };
+        let _ = writeln!(
+            out,
+            "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"{}\">{}</text>",
+            x(seconds),
+            axis_y,
+            anchor,
+            escape_html(&time.timestamp(seconds))
+        );
+    }
+    out.push_str("</svg>\n");
+    out
+}
+
+/// Explains the marker colors overriding lane colors, for the severities and kill
+/// types present.
+fn write_color_legend(out: &mut String, markers: &[Marker]) {
+    let has = |category: &str| markers.iter().any(|marker| marker.category == category);
+    let mut entries: Vec<(&str, String)> = Vec::new();
+    if has(SEVERE_CATEGORY) {
+        entries.push((severity_color(SEVERITY_ERROR), "Error".to_string()));
+        entries.push((severity_color(SEVERITY_WARNING), "Warning".to_string()));
+    }
+    if has(KILL_CATEGORY) {
+        for kill_type in &KillType::INJECTABLE {
+            entries.push((kill_color(kill_type), format!("{:?}", kill_type)));
+        }
+    }
+    if entries.is_empty() {
+        return;
+    }
+    out.push_str("<p class=\"legend muted\">");
+    for (color, label) in entries {
+        let _ = write!(
+            out,
+            "<span class=\"swatch\" style=\"background: {}\"></span>{} ",
+            color,
+            escape_html(&label)
+        );
+    }
+    out.push_str("</p>\n");
+}
+
+/// Escapes the characters of text that are special in HTML.
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::{AnalysisTable, AnalysisValue};
+    use crate::parser::{CloggingPairData, Event, ProgramStartData};
+    use crate::report::create_simulation_report;
+    use crate::time_format::TimeFormat;
+
+    #[test]
+    fn test_to_html() {
+        let events = vec![
+            Event::ProgramStart(ProgramStartData {
+                timestamp: "0.0".to_string(),
+                machine: "0.0.0.0:0".to_string(),
+                random_seed: Some("42".to_string()),
+                date_time: None,
+                version: None,
+                command_line: None,
+                connection_string: None,
+            }),
+            Event::CloggingPair(CloggingPairData {
+                timestamp: "10".to_string(),
+                from_id: "2.0.1.0".to_string(),
+                to_id: "2.0.1.1".to_string(),
+                seconds: "0.5".to_string(),
+            }),
+        ];
+        let mut report = create_simulation_report(&events);
+        report
+            .tags
+            .insert("branch".to_string(), "<main>".to_string());
+        let time = TimeFormatter::new(TimeFormat::Seconds, &report);
+
+        let html = to_html(&report, &time, &NumberFormat::default());
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<title>Simulation seed 42</title>"));
+        assert!(html.contains("<span class=\"fail\">FAIL</span>"));
+        assert!(html.contains("<td>Tag branch</td><td>&lt;main&gt;</td>"));
+        // One marker for each end of the clog window
+        assert_eq!(html.matches("<circle").count(), 2);
+        assert!(html.contains("2.0.1.0 -&gt; 2.0.1.1"));
+        assert!(!html.contains("<script"));
+    }
+
+    #[test]
+    fn test_analysis_sections() {
+        let mut report = create_simulation_report(&[]);
+        let mut section = AnalysisSection::new("custom", "Custom <Pass>");
+        section.push_finding(FindingLevel::Error, Some(12.5), "Storage server failed");
+        section.push_finding(FindingLevel::Info, None, "2 machines checked");
+        section.table = Some(AnalysisTable {
+            headers: vec!["Time".to_string(), "Machine".to_string()],
+            rows: vec![vec![
+                AnalysisValue::Time(12.5),
+                AnalysisValue::Text("2.0.1.0".to_string()),
+            ]],
+        });
+        report.analyses.push(section);
+        let time = TimeFormatter::new(TimeFormat::Seconds, &report);
+
+        let html = to_html(&report, &time, &NumberFormat::default());
+        assert!(html.contains("<h2>Custom &lt;Pass&gt;</h2>"));
+        assert!(html.contains("<li class=\"fail\">[12.500000] Storage server failed</li>"));
+        assert!(html.contains("<li>2 machines checked</li>"));
+        assert!(html.contains("<th>Time (s)</th><th>Machine</th>"));
+        assert!(html.contains("<td class=\"num\">12.500000</td><td>2.0.1.0</td>"));
+    }
+
+    #[test]
+    fn test_timeline_colors() {
+        let events: Vec<Event> = [
+            r#"{"Severity": "10", "Time": "5", "Type": "KillMachineProcess", "Machine": "3.4.3.5:1", "KillType": "6", "Process": "name: Server address: 2.2.1.2:1 zone: z", "StartingClass": "storage", "Failed": "0", "Excluded": "0", "Cleared": "0", "Rebooting": "0"}"#,
+            r#"{"Severity": "40", "Time": "7", "Type": "InternalError", "Machine": "2.2.1.2:1"}"#,
+        ]
+        .iter()
+        .map(|line| Event::try_from(*line).unwrap())
+        .collect();
+        let report = create_simulation_report(&events);
+        let time = TimeFormatter::new(TimeFormat::Seconds, &report);
+
+        let svg = timeline_svg(&report, &time);
+        assert!(svg.contains("<input type=\"checkbox\" id=\"lane-0\" checked>"));
+        assert!(svg.contains("#lane-1:not(:checked) ~ svg .lane-1 { display: none; }"));
+        assert!(svg.contains("style=\"fill: var(--warn)\"><title>5.000000 Reboot 2.2.1.2"));
+        assert!(svg.contains("style=\"fill: var(--fail)\"><title>7.000000 InternalError"));
+        assert!(svg.contains("</span>RebootProcessAndSwitch "));
+    }
+}
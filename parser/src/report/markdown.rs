@@ -0,0 +1,325 @@
+//! GitHub-flavored Markdown rendering of a report.
+//!
+//! Covers the outcome, the overview, the cluster topology, the chaos injected and the
+//! analyses, in a form a bot can post as a pull request comment.
+
+use super::{
+    analysis_table_cells, finding_text, is_alert, is_numeric, overview_rows, SimulationReport,
+};
+use crate::analysis::{AnalysisSection, FindingLevel};
+use crate::lifecycle::downtime_by_kill_type;
+use crate::number_format::NumberFormat;
+use crate::time_format::TimeFormatter;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// Renders the report as GitHub-flavored Markdown, with times rendered by `time`.
+pub fn to_markdown(
+    report: &SimulationReport,
+    time: &TimeFormatter,
+    numbers: &NumberFormat,
+) -> String {
+    let mut out = String::new();
+    let title = match &report.seed {
+        Some(seed) => format!("Simulation seed {}", seed),
+        None => "Simulation".to_string(),
+    };
+    let _ = writeln!(out, "### {}\n", escape_markdown(&title));
+
+    let test_name = escape_markdown(report.test_name.as_deref().unwrap_or("unknown test"));
+    match report.passed {
+        Some(true) => {
+            let _ = writeln!(out, ":white_check_mark: **PASS** {}", test_name);
+        }
+        Some(false) => {
+            let _ = writeln!(out, ":x: **FAIL** {}\n", test_name);
+            for reason in report.failure_reasons(time, numbers) {
+                let _ = writeln!(out, "- {}", escape_markdown(&reason));
+            }
+        }
+        None => {
+            let _ = writeln!(out, ":grey_question: **UNKNOWN** {}", test_name);
+        }
+    }
+    if let Some(truncated_at) = report.truncated_at {
+        let _ = writeln!(
+            out,
+            "\n> [!WARNING]\n> Truncated run: the trace stops mid-line at {}, the report only covers part of the run.",
+            time.timestamp(truncated_at)
+        );
+    }
+
+    write_table(
+        &mut out,
+        "Overview",
+        &["Key", "Value"],
+        &overview_rows(report, time, numbers),
+    );
+    // Analyses with warnings, e.g. the likely trigger of a failure, come first
+    for section in report.analyses.iter().filter(|section| is_alert(section)) {
+        write_analysis_section(&mut out, section, time);
+    }
+    write_topology(&mut out, report, numbers);
+    write_chaos(&mut out, report, time, numbers);
+    for section in report.analyses.iter().filter(|section| !is_alert(section)) {
+        write_analysis_section(&mut out, section, time);
+    }
+    out
+}
+
+/// Writes the findings of an analysis section, flagged by level, and its table.
+fn write_analysis_section(out: &mut String, section: &AnalysisSection, time: &TimeFormatter) {
+    let _ = writeln!(out, "\n#### {}\n", escape_markdown(&section.title));
+    for finding in &section.findings {
+        let flag = match finding.level {
+            FindingLevel::Error => ":x: ",
+            FindingLevel::Warning => ":warning: ",
+            FindingLevel::Info => "",
+        };
+        let _ = writeln!(
+            out,
+            "- {}{}",
+            flag,
+            escape_markdown(&finding_text(finding, time))
+        );
+    }
+    if let Some(table) = &section.table {
+        let (headers, rows) = analysis_table_cells(table, time);
+        let headers: Vec<&str> = headers.iter().map(String::as_str).collect();
+        if !section.findings.is_empty() {
+            out.push('\n');
+        }
+        write_table_rows(out, &headers, &rows);
+    }
+}
+
+/// Writes the machines of each DC, by class type.
+fn write_topology(out: &mut String, report: &SimulationReport, numbers: &NumberFormat) {
+    let mut by_dc: BTreeMap<&str, BTreeMap<&str, usize>> = BTreeMap::new();
+    for machine in report.machine_details.values() {
+        let classes = by_dc
+            .entry(machine.dc_id.as_deref().unwrap_or("N/A"))
+            .or_default();
+        *classes
+            .entry(machine.class_type.as_deref().unwrap_or("N/A"))
+            .or_insert(0) += 1;
+    }
+    if by_dc.is_empty() {
+        return;
+    }
+    let rows: Vec<Vec<String>> = by_dc
+        .into_iter()
+        .map(|(dc_id, classes)| {
+            let summary: Vec<String> = classes
+                .iter()
+                .map(|(class_type, count)| format!("{}: {}", class_type, numbers.count(*count)))
+                .collect();
+            vec![
+                dc_id.to_string(),
+                numbers.count(classes.values().sum()),
+                summary.join(", "),
+            ]
+        })
+        .collect();
+    write_table(
+        out,
+        "Cluster Topology",
+        &["DC ID", "Machines", "Class Types"],
+        &rows,
+    );
+}
+
+/// Writes the number of chaos events of each kind, then the kills by type and the
+/// chaos by DC.
+fn write_chaos(
+    out: &mut String,
+    report: &SimulationReport,
+    time: &TimeFormatter,
+    numbers: &NumberFormat,
+) {
+    let clogging_pairs = report
+        .clogging_pair_summary
+        .as_ref()
+        .map_or(0, |summary| summary.count);
+    let clogged_interfaces: usize = report
+        .clog_interface_summary
+        .values()
+        .map(|summary| summary.count)
+        .sum();
+    let rows: Vec<Vec<String>> = [
+        ("Clogging Pairs", clogging_pairs),
+        ("Clogged Interfaces", clogged_interfaces),
+        ("Process Kills", report.kill_machine_processes.len()),
+        ("Assassinations", report.assassinations.len()),
+        ("Disk Failures", report.set_disk_failures.len()),
+        ("Disk Swaps", report.disk_swaps.len()),
+        ("Corrupted Blocks", report.corrupted_blocks.len()),
+        ("Coordinator Changes", report.coordinators_change_count),
+        ("Recoveries", report.recoveries.len()),
+    ]
+    .into_iter()
+    .filter(|(_, count)| *count > 0)
+    .map(|(kind, count)| vec![kind.to_string(), numbers.count(count)])
+    .collect();
+    if rows.is_empty() {
+        let _ = writeln!(out, "\n#### Chaos Injection\n\nNo chaos injected.");
+        return;
+    }
+    write_table(out, "Chaos Injection", &["Kind", "Count"], &rows);
+
+    let downtime = downtime_by_kill_type(report);
+    let kills: Vec<Vec<String>> = report
+        .kill_machine_process_summary
+        .iter()
+        .filter(|(_, count)| **count > 0)
+        .map(|(kill_type, count)| {
+            let mean_downtime = downtime
+                .get(kill_type)
+                .map(|stats| time.duration(stats.mean_seconds))
+                .unwrap_or_else(|| "-".to_string());
+            vec![
+                format!("{:?}", kill_type),
+                numbers.count(*count),
+                mean_downtime,
+                kill_type.description().to_string(),
+            ]
+        })
+        .collect();
+    if !kills.is_empty() {
+        write_table(
+            out,
+            "Process Kills",
+            &["Kill Type", "Count", "Mean Down", "Meaning"],
+            &kills,
+        );
+    }
+
+    let by_dc: Vec<Vec<String>> = report
+        .dc_impact()
+        .into_iter()
+        .map(|(dc_id, impact)| {
+            vec![
+                dc_id,
+                numbers.count(impact.kills),
+                numbers.decimal(impact.clog_seconds, 3),
+                numbers.count(impact.disk_failures),
+            ]
+        })
+        .collect();
+    if !by_dc.is_empty() {
+        write_table(
+            out,
+            "Chaos by DC",
+            &["DC ID", "Kills", "Clog Seconds", "Disk Failures"],
+            &by_dc,
+        );
+    }
+}
+
+/// Writes a titled table, escaping every cell. Columns of numbers are right-aligned.
+fn write_table(out: &mut String, title: &str, headers: &[&str], rows: &[Vec<String>]) {
+    let _ = writeln!(out, "\n#### {}\n", escape_markdown(title));
+    write_table_rows(out, headers, rows);
+}
+
+/// Writes a table without a title, see `write_table`.
+fn write_table_rows(out: &mut String, headers: &[&str], rows: &[Vec<String>]) {
+    let cells: Vec<String> = headers.iter().map(|h| escape_markdown(h)).collect();
+    let _ = writeln!(out, "| {} |", cells.join(" | "));
+    let alignments: Vec<&str> = (0..headers.len())
+        .map(|column| {
+            let numeric = !rows.is_empty()
+                && rows
+                    .iter()
+                    .all(|row| row.get(column).is_some_and(|cell| is_numeric(cell)));
+            if numeric {
+                "---:"
+            } else {
+                "---"
+            }
+        })
+        .collect();
+    let _ = writeln!(out, "| {} |", alignments.join(" | "));
+    for row in rows {
+        let cells: Vec<String> = row.iter().map(|cell| escape_markdown(cell)).collect();
+        let _ = writeln!(out, "| {} |", cells.join(" | "));
+    }
+}
+
+/// Escapes the characters that would break a table row or turn into markup, and
+/// flattens line breaks.
+pub fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' | '|' | '*' | '_' | '[' | ']' | '<' | '>' | '#' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' | '\r' => escaped.push(' '),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::{AnalysisTable, AnalysisValue};
+    use crate::parser::{CloggingPairData, Event, ProgramStartData};
+    use crate::report::create_simulation_report;
+    use crate::time_format::TimeFormat;
+
+    #[test]
+    fn test_to_markdown() {
+        let events = vec![
+            Event::ProgramStart(ProgramStartData {
+                timestamp: "0.0".to_string(),
+                machine: "0.0.0.0:0".to_string(),
+                random_seed: Some("42".to_string()),
+                date_time: None,
+                version: None,
+                command_line: None,
+                connection_string: None,
+            }),
+            Event::CloggingPair(CloggingPairData {
+                timestamp: "10".to_string(),
+                from_id: "2.0.1.0".to_string(),
+                to_id: "2.0.1.1".to_string(),
+                seconds: "0.5".to_string(),
+            }),
+        ];
+        let mut report = create_simulation_report(&events);
+        report.tags.insert("branch".to_string(), "a|b".to_string());
+        let time = TimeFormatter::new(TimeFormat::Seconds, &report);
+
+        let markdown = to_markdown(&report, &time, &NumberFormat::default());
+        assert!(markdown.starts_with("### Simulation seed 42\n"));
+        assert!(markdown.contains(":x: **FAIL** unknown test"));
+        assert!(markdown.contains("- no TestResults logged"));
+        assert!(markdown.contains("| Tag branch | a\\|b |"));
+        assert!(markdown.contains("| Kind | Count |\n| --- | ---: |\n| Clogging Pairs | 1 |"));
+        assert!(!markdown.contains("Process Kills"));
+    }
+
+    #[test]
+    fn test_analysis_sections() {
+        let mut report = create_simulation_report(&[]);
+        let mut section = AnalysisSection::new("custom", "Custom Pass");
+        section.push_finding(FindingLevel::Warning, Some(12.5), "Coordinators churned");
+        section.table = Some(AnalysisTable {
+            headers: vec!["Time".to_string(), "Changes".to_string()],
+            rows: vec![vec![AnalysisValue::Time(12.5), AnalysisValue::Number(3.0)]],
+        });
+        report.analyses.push(section);
+        let time = TimeFormatter::new(TimeFormat::Seconds, &report);
+
+        let markdown = to_markdown(&report, &time, &NumberFormat::default());
+        assert!(markdown.contains(
+            "#### Custom Pass\n\n- :warning: \\[12.500000\\] Coordinators churned\n\n| Time (s) | Changes |\n| ---: | ---: |\n| 12.500000 | 3 |"
+        ));
+        // Alerts come before the topology and the chaos
+        assert!(markdown.find("Custom Pass") < markdown.find("Chaos Injection"));
+    }
+}
@@ -0,0 +1,121 @@
+//! Compressed cache of the reports of ensemble runs.
+//!
+//! Analyses over a whole ensemble parse hundreds of traces, which dominates their
+//! run time. The report of `trace.json` is saved next to it as the zstd-compressed
+//! `trace.json.report.zst`, so repeating an analysis over the same runs only reads
+//! the saved reports. A saved report is reused only for the version of the trace it
+//! was built from and by the release of the parser that built it: fields added since
+//! would load empty, as the `REPORT_SCHEMA_VERSION` is not bumped for them.
+
+use crate::line_index::file_version;
+use crate::parser::{parse_log_file, ParsingError};
+use crate::report::{create_simulation_report, SimulationReport};
+use serde::{Deserialize, Serialize};
+use std::ffi::OsString;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// zstd compression level of saved reports, favoring speed over size.
+const COMPRESSION_LEVEL: i32 = 3;
+
+/// Release of the parser, cached reports built by any other are rebuilt.
+const PARSER_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Content of a cache file, generic over owning the report or not.
+#[derive(Serialize, Deserialize)]
+struct CachedReport<R> {
+    /// Length of the trace the report was built from, in bytes.
+    file_len: u64,
+    /// Modification time of the trace, since the Unix epoch.
+    modified: Option<Duration>,
+    /// Release of the parser that built the report.
+    #[serde(default)]
+    parser_version: String,
+    report: R,
+}
+
+/// Path of the cached report of a trace.
+pub fn cache_path(trace_path: &Path) -> PathBuf {
+    let mut path = OsString::from(trace_path.as_os_str());
+    path.push(".report.zst");
+    PathBuf::from(path)
+}
+
+/// Reads a cached report, `None` if it is missing, unreadable or stale.
+fn read_cached(trace_path: &Path) -> Option<SimulationReport> {
+    let version = file_version(trace_path).ok()?;
+    let file = File::open(cache_path(trace_path)).ok()?;
+    let decoder = zstd::Decoder::new(file).ok()?;
+    let cached: CachedReport<SimulationReport> =
+        serde_json::from_reader(BufReader::new(decoder)).ok()?;
+    ((cached.file_len, cached.modified) == version && cached.parser_version == PARSER_VERSION)
+        .then_some(cached.report)
+}
+
+/// Saves the report of a trace to its cache file.
+fn write_cached(trace_path: &Path, report: &SimulationReport) -> io::Result<()> {
+    let (file_len, modified) = file_version(trace_path)?;
+    let cached = CachedReport {
+        file_len,
+        modified,
+        parser_version: PARSER_VERSION.to_string(),
+        report,
+    };
+    let file = File::create(cache_path(trace_path))?;
+    let mut encoder = zstd::Encoder::new(BufWriter::new(file), COMPRESSION_LEVEL)?;
+    serde_json::to_writer(&mut encoder, &cached)?;
+    encoder.finish()?.flush()
+}
+
+/// Returns the report of a trace, read from its cache file when up to date, else built
+/// and cached. Failing to write the cache, e.g. in a read-only directory, is not an
+/// error.
+pub fn load_or_create_report<P: AsRef<Path>>(
+    trace_path: P,
+) -> Result<SimulationReport, ParsingError> {
+    let trace_path = trace_path.as_ref();
+    if let Some(report) = read_cached(trace_path) {
+        tracing::debug!(path = %trace_path.display(), "report read from cache");
+        return Ok(report);
+    }
+
+    let events = parse_log_file(trace_path)?;
+    let report = create_simulation_report(&events);
+    if let Err(e) = write_cached(trace_path, &report) {
+        tracing::debug!(path = %trace_path.display(), error = %e, "report not cached");
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_report_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let trace = dir.path().join("trace.json");
+        fs::copy(
+            "logs/combined_trace.0.0.0.0.24.1745498878.p7Loj0.json",
+            &trace,
+        )
+        .unwrap();
+
+        let report = load_or_create_report(&trace).unwrap();
+        assert!(cache_path(&trace).exists());
+        let cached = read_cached(&trace).expect("report not cached");
+        assert_eq!(
+            serde_json::to_value(&cached).unwrap(),
+            serde_json::to_value(&report).unwrap()
+        );
+
+        // A changed trace invalidates the cache
+        fs::write(&trace, "").unwrap();
+        assert!(read_cached(&trace).is_none());
+        let report = load_or_create_report(&trace).unwrap();
+        assert!(report.clogging_pairs.is_empty());
+    }
+}
@@ -0,0 +1,139 @@
+//! Statistical sampling of high-volume, low-signal event types during parsing.
+//!
+//! Only event types listed in `Sampling::SAMPLEABLE` can be sampled, so kills, disk
+//! faults and other high-signal events are always kept in full. Whether an event is
+//! kept depends only on its line number, making sampled reports reproducible.
+
+use crate::parser::Event;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum SamplingError {
+    #[error("Invalid sampling spec '{0}', expected <type>=<rate>")]
+    InvalidSpec(String),
+    #[error("Event type '{0}' cannot be sampled (allowed: {allowed})", allowed = Sampling::SAMPLEABLE.map(|(key, _)| key).join(", "))]
+    NotSampleable(String),
+    #[error("Sampling rate {0} is not in (0, 1]")]
+    InvalidRate(f64),
+}
+
+/// Per event type sampling rates, keyed by `Event::event_type` name.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Sampling {
+    rates: BTreeMap<String, f64>,
+}
+
+impl Sampling {
+    /// Event types that may be sampled, as (CLI key, event type) pairs.
+    pub const SAMPLEABLE: [(&'static str, &'static str); 2] = [
+        ("clogging_pair", "CloggingPair"),
+        ("clog_interface", "ClogInterface"),
+    ];
+
+    /// Sets the fraction of events of the given type (CLI key such as
+    /// "clog_interface", or event type name) to keep.
+    pub fn set_rate(&mut self, key: &str, rate: f64) -> Result<(), SamplingError> {
+        let (_, event_type) = Self::SAMPLEABLE
+            .iter()
+            .find(|(cli_key, event_type)| *cli_key == key || *event_type == key)
+            .ok_or_else(|| SamplingError::NotSampleable(key.to_string()))?;
+        if !(rate > 0.0 && rate <= 1.0) {
+            return Err(SamplingError::InvalidRate(rate));
+        }
+        self.rates.insert(event_type.to_string(), rate);
+        Ok(())
+    }
+
+    /// Fraction of events of `event_type` that are kept, 1.0 when not sampled.
+    pub fn rate(&self, event_type: &str) -> f64 {
+        self.rates.get(event_type).copied().unwrap_or(1.0)
+    }
+
+    /// Sampling rates by event type, excluding types that are kept in full.
+    pub fn rates(&self) -> &BTreeMap<String, f64> {
+        &self.rates
+    }
+
+    /// Returns whether no event type is sampled.
+    pub fn is_empty(&self) -> bool {
+        self.rates.is_empty()
+    }
+
+    /// Decides whether the event parsed from the given line is kept.
+    pub fn keep(&self, event: &Event, line_number: usize) -> bool {
+        let rate = self.rate(event.event_type());
+        rate >= 1.0 || unit_hash(line_number as u64) < rate
+    }
+}
+
+impl FromStr for Sampling {
+    type Err = SamplingError;
+
+    /// Parses comma-separated `<type>=<rate>` specs, e.g. "clog_interface=0.1".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut sampling = Sampling::default();
+        for spec in s.split(',').filter(|spec| !spec.is_empty()) {
+            let (key, rate) = spec
+                .split_once('=')
+                .ok_or_else(|| SamplingError::InvalidSpec(spec.to_string()))?;
+            let rate: f64 = rate
+                .trim()
+                .parse()
+                .map_err(|_| SamplingError::InvalidSpec(spec.to_string()))?;
+            sampling.set_rate(key.trim(), rate)?;
+        }
+        Ok(sampling)
+    }
+}
+
+/// Maps a value to a uniformly distributed float in [0, 1) using SplitMix64.
+fn unit_hash(value: u64) -> f64 {
+    let mut z = value.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^= z >> 31;
+    (z >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{ClogInterfaceData, Event};
+
+    #[test]
+    fn test_sampling_from_str() {
+        let sampling: Sampling = "clog_interface=0.1".parse().unwrap();
+        assert_eq!(sampling.rate("ClogInterface"), 0.1);
+        assert_eq!(sampling.rate("KillMachineProcess"), 1.0);
+
+        assert_eq!(
+            "kill_machine_process=0.5".parse::<Sampling>(),
+            Err(SamplingError::NotSampleable(
+                "kill_machine_process".to_string()
+            ))
+        );
+        assert_eq!(
+            "clog_interface=0".parse::<Sampling>(),
+            Err(SamplingError::InvalidRate(0.0))
+        );
+        assert!("clog_interface".parse::<Sampling>().is_err());
+    }
+
+    #[test]
+    fn test_keep_rate_is_approximately_respected() {
+        let sampling: Sampling = "clog_interface=0.25".parse().unwrap();
+        let event = Event::ClogInterface(ClogInterfaceData {
+            timestamp: "1.0".to_string(),
+            ip: "2.0.1.0".to_string(),
+            delay: "0.1".to_string(),
+            queue: "All".to_string(),
+        });
+
+        let kept = (0..10_000)
+            .filter(|line| sampling.keep(&event, *line))
+            .count();
+        assert!((2_300..2_700).contains(&kept), "kept {}", kept);
+    }
+}
@@ -0,0 +1,151 @@
+//! Fixed-size approximate counters for traces too large to keep in memory.
+//!
+//! When parsing switches to streaming aggregation (see
+//! `ReportBuilder::drop_raw_events`), the raw events needed for exact distinct counts
+//! are gone. These sketches keep estimates within a few kilobytes whatever the size of
+//! the trace: `HyperLogLog` for the number of distinct values and `CountMinSketch`
+//! for the number of occurrences of each value.
+
+/// Bits of the hash selecting a `HyperLogLog` register.
+const HLL_PRECISION: u32 = 12;
+
+/// Counters per row of a `CountMinSketch`.
+const CMS_WIDTH: usize = 1024;
+
+/// Rows of a `CountMinSketch`, each hashing values with its own seed.
+const CMS_DEPTH: usize = 4;
+
+/// Estimates the number of distinct values added, with a standard error of about
+/// 1.6% (1.04 / sqrt(4096) registers). Small cardinalities are close to exact.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HyperLogLog {
+    registers: Vec<u8>,
+}
+
+impl Default for HyperLogLog {
+    fn default() -> Self {
+        Self {
+            registers: vec![0; 1 << HLL_PRECISION],
+        }
+    }
+}
+
+impl HyperLogLog {
+    /// Adds a value; adding it again does not change the estimate.
+    pub fn insert(&mut self, value: &str) {
+        let hash = hash(value, 0);
+        let index = (hash >> (64 - HLL_PRECISION)) as usize;
+        // Position of the first set bit after the index bits, the sentinel bounding it
+        let rest = (hash << HLL_PRECISION) | (1 << (HLL_PRECISION - 1));
+        let rank = rest.leading_zeros() as u8 + 1;
+        self.registers[index] = self.registers[index].max(rank);
+    }
+
+    /// Estimated number of distinct values added.
+    pub fn estimate(&self) -> usize {
+        let m = self.registers.len() as f64;
+        let sum: f64 = self
+            .registers
+            .iter()
+            .map(|rank| 2f64.powi(-i32::from(*rank)))
+            .sum();
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let estimate = alpha * m * m / sum;
+        let zeros = self.registers.iter().filter(|rank| **rank == 0).count();
+        // Linear counting is more accurate while many registers are still empty
+        let estimate = if estimate <= 2.5 * m && zeros > 0 {
+            m * (m / zeros as f64).ln()
+        } else {
+            estimate
+        };
+        estimate.round() as usize
+    }
+}
+
+/// Estimates the number of occurrences of each value. Estimates never undercount;
+/// they overcount by at most e / 1024 of the total added, except with a probability
+/// of e^-4 (about 2%).
+#[derive(Debug, Clone, PartialEq)]
+pub struct CountMinSketch {
+    counters: Vec<usize>,
+}
+
+impl Default for CountMinSketch {
+    fn default() -> Self {
+        Self {
+            counters: vec![0; CMS_WIDTH * CMS_DEPTH],
+        }
+    }
+}
+
+impl CountMinSketch {
+    /// Adds `count` occurrences of a value.
+    pub fn add(&mut self, value: &str, count: usize) {
+        for row in 0..CMS_DEPTH {
+            let index = Self::index(value, row);
+            self.counters[index] = self.counters[index].saturating_add(count);
+        }
+    }
+
+    /// Estimated number of occurrences of a value.
+    pub fn estimate(&self, value: &str) -> usize {
+        (0..CMS_DEPTH)
+            .map(|row| self.counters[Self::index(value, row)])
+            .min()
+            .unwrap_or(0)
+    }
+
+    /// Index of the counter of a value in a row.
+    fn index(value: &str, row: usize) -> usize {
+        row * CMS_WIDTH + (hash(value, row as u64 + 1) % CMS_WIDTH as u64) as usize
+    }
+}
+
+/// 64-bit FNV-1a of a value, seeded and finalized with the SplitMix64 mixer so that
+/// every bit depends on the whole value.
+fn hash(value: &str, seed: u64) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325 ^ seed.wrapping_mul(0x9e37_79b9_7f4a_7c15);
+    for byte in value.bytes() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    hash ^ (hash >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hyperloglog_estimates_distinct_values() {
+        let mut small = HyperLogLog::default();
+        for ip in ["2.0.1.0", "2.0.1.1", "2.0.1.0", "2.0.1.2"] {
+            small.insert(ip);
+        }
+        assert_eq!(small.estimate(), 3);
+
+        let mut large = HyperLogLog::default();
+        for i in 0..100_000 {
+            large.insert(&format!("10.{}.{}.{}", i / 65536, (i / 256) % 256, i % 256));
+            large.insert("2.0.1.0");
+        }
+        let error = (large.estimate() as f64 - 100_001.0).abs() / 100_001.0;
+        assert!(error < 0.05, "estimate {} is off", large.estimate());
+    }
+
+    #[test]
+    fn test_count_min_never_undercounts() {
+        let mut sketch = CountMinSketch::default();
+        sketch.add("CloggingPair", 5000);
+        sketch.add("KillMachineProcess", 3);
+        for i in 0..5000 {
+            sketch.add(&format!("Type{}", i), 1);
+        }
+        assert!(sketch.estimate("CloggingPair") >= 5000);
+        assert!(sketch.estimate("CloggingPair") < 5100);
+        assert!(sketch.estimate("KillMachineProcess") >= 3);
+        assert!(sketch.estimate("KillMachineProcess") < 100);
+    }
+}
@@ -0,0 +1,189 @@
+//! Splitting a trace into one event stream per machine.
+//!
+//! The events of each machine are written to `<ip>.jsonl` as normalized JSON lines,
+//! i.e. `Event`s as serialized by the parser rather than raw trace lines, so that the
+//! story of a single machine can be handed to the owner of the subsystem it hosts.
+
+use crate::parser::{for_each_event, stream_log_directory, Event, ParseOptions, ParsingError};
+use std::collections::btree_map::Entry;
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum SplitError {
+    #[error("I/O error writing split traces: {0}")]
+    Io(#[from] io::Error),
+    #[error("Failed to serialize event: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Parsing(#[from] ParsingError),
+}
+
+/// Path of the file holding the events of the machine at `ip`.
+pub fn machine_file(out_dir: &Path, ip: &str) -> PathBuf {
+    // IPv6 addresses contain colons, which some file systems reject
+    out_dir.join(format!("{}.jsonl", ip.replace(':', "_")))
+}
+
+/// Routes events to per-machine JSON lines files, see `Event::machine_ips`.
+/// Assassinations go to every machine of the targeted zone started so far.
+#[derive(Debug)]
+pub struct MachineSplitter {
+    out_dir: PathBuf,
+    /// Open file and number of events written, by machine IP.
+    files: BTreeMap<String, (BufWriter<File>, usize)>,
+    /// IPs of the machines of each zone.
+    zones: HashMap<String, Vec<String>>,
+}
+
+impl MachineSplitter {
+    /// Creates a splitter writing to `out_dir`, created if needed.
+    pub fn new<P: Into<PathBuf>>(out_dir: P) -> io::Result<Self> {
+        let out_dir = out_dir.into();
+        fs::create_dir_all(&out_dir)?;
+        Ok(Self {
+            out_dir,
+            files: BTreeMap::new(),
+            zones: HashMap::new(),
+        })
+    }
+
+    /// Writes an event to the files of the machines it involves.
+    pub fn add(&mut self, event: &Event) -> Result<(), SplitError> {
+        let mut ips: Vec<&str> = event.machine_ips();
+        match event {
+            Event::SimulatedMachineStart(data) => {
+                if let Some(zone_id) = &data.zone_id {
+                    let zone = self.zones.entry(zone_id.clone()).or_default();
+                    for ip in &ips {
+                        if !zone.iter().any(|known| known == ip) {
+                            zone.push(ip.to_string());
+                        }
+                    }
+                }
+            }
+            Event::Assassination(data) => {
+                if let Some(zone) = self.zones.get(&data.zone_id) {
+                    ips.extend(zone.iter().map(String::as_str));
+                }
+            }
+            _ => {}
+        }
+        ips.sort_unstable();
+        ips.dedup();
+        if ips.is_empty() {
+            return Ok(());
+        }
+
+        let line = serde_json::to_string(event)?;
+        for ip in ips {
+            let (writer, count) = match self.files.entry(ip.to_string()) {
+                Entry::Occupied(file) => file.into_mut(),
+                Entry::Vacant(entry) => {
+                    let file = File::create(machine_file(&self.out_dir, ip))?;
+                    entry.insert((BufWriter::new(file), 0))
+                }
+            };
+            writeln!(writer, "{}", line)?;
+            *count += 1;
+        }
+        Ok(())
+    }
+
+    /// Flushes the files and returns the number of events written by machine IP.
+    pub fn finish(self) -> Result<BTreeMap<String, usize>, SplitError> {
+        self.files
+            .into_iter()
+            .map(|(ip, (mut writer, count))| {
+                writer.flush()?;
+                Ok((ip, count))
+            })
+            .collect()
+    }
+}
+
+/// Splits a trace, or a directory of rolled trace files, into one JSON lines file per
+/// machine in `out_dir`, and returns the number of events written by machine IP.
+pub fn split_by_machine<P: AsRef<Path>>(
+    trace_path: P,
+    out_dir: &Path,
+    options: &ParseOptions,
+) -> Result<BTreeMap<String, usize>, SplitError> {
+    let mut splitter = MachineSplitter::new(out_dir)?;
+    if trace_path.as_ref().is_dir() {
+        for event in stream_log_directory(trace_path, options)? {
+            splitter.add(&event?)?;
+        }
+        return splitter.finish();
+    }
+    let mut written = Ok(());
+    for_each_event(trace_path, options, |event, _| {
+        if written.is_ok() {
+            written = splitter.add(&event);
+        }
+    })?;
+    written?;
+    splitter.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{
+        AssassinationData, CloggingPairData, ElapsedTimeData, SimulatedMachineStartData,
+    };
+
+    #[test]
+    fn test_split_by_machine() {
+        let events = vec![
+            Event::SimulatedMachineStart(SimulatedMachineStartData {
+                timestamp: "0".to_string(),
+                process_class: "storage".to_string(),
+                locality: String::new(),
+                machine_ips: Some("2.0.1.0".to_string()),
+                zone_id: Some("zone-a".to_string()),
+                process_id: None,
+                machine_id: Some("m0".to_string()),
+                dc_id: Some("0".to_string()),
+                data_hall: None,
+            }),
+            Event::CloggingPair(CloggingPairData {
+                timestamp: "10".to_string(),
+                from_id: "2.0.1.0".to_string(),
+                to_id: "2.0.1.1".to_string(),
+                seconds: "1".to_string(),
+            }),
+            Event::Assassination(AssassinationData {
+                timestamp: "20".to_string(),
+                target_machine: "zoneid=zone-a".to_string(),
+                zone_id: "zone-a".to_string(),
+                reboot: "1".to_string(),
+            }),
+            Event::ElapsedTime(ElapsedTimeData {
+                timestamp: "30".to_string(),
+                sim_time: "30".to_string(),
+                real_time: "1".to_string(),
+            }),
+        ];
+        let dir = tempfile::tempdir().unwrap();
+        let mut splitter = MachineSplitter::new(dir.path()).unwrap();
+        for event in &events {
+            splitter.add(event).unwrap();
+        }
+        let counts = splitter.finish().unwrap();
+        assert_eq!(
+            counts.into_iter().collect::<Vec<_>>(),
+            [("2.0.1.0".to_string(), 3), ("2.0.1.1".to_string(), 1)]
+        );
+
+        let content = fs::read_to_string(machine_file(dir.path(), "2.0.1.0")).unwrap();
+        let written: Vec<Event> = content
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(written, events[..3]);
+    }
+}
@@ -0,0 +1,305 @@
+//! Reconstruction of the cluster state at a point in simulated time.
+//!
+//! The state is derived from the intervals of the report: downtime intervals for
+//! processes, clog windows for the network and stall/throttle periods for disks.
+
+use crate::lifecycle::{downtime_intervals, DowntimeInterval};
+use crate::parser::{ip_from_address, ClogInterfaceData, CloggingPairData, SetDiskFailureData};
+use crate::report::SimulationReport;
+use crate::time_format::TimeFormatter;
+use colored::Colorize;
+use comfy_table::{presets::UTF8_FULL, Cell, ContentArrangement, Table};
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::fmt;
+
+/// What was going on in the cluster at a given time.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ClusterState {
+    /// Simulated time of the snapshot, in seconds.
+    pub time: f64,
+    /// IPs of the known machines without any process down, sorted.
+    pub machines_up: Vec<String>,
+    /// Processes down at `time`, sorted by kill time.
+    pub processes_down: Vec<DowntimeInterval>,
+    /// Time of the last coordinator change at or before `time`.
+    pub last_coordinators_change: Option<f64>,
+    /// Coordinators set by the last change that logged them.
+    pub coordinators: Option<String>,
+    /// CloggingPair windows covering `time`.
+    pub clogged_pairs: Vec<CloggingPairData>,
+    /// ClogInterface windows covering `time`.
+    pub clogged_interfaces: Vec<ClogInterfaceData>,
+    /// Disks stalled or throttled at `time`.
+    pub disk_faults: Vec<SetDiskFailureData>,
+}
+
+impl ClusterState {
+    /// Whether a fault of any kind was active.
+    pub fn is_degraded(&self) -> bool {
+        !self.processes_down.is_empty()
+            || !self.clogged_pairs.is_empty()
+            || !self.clogged_interfaces.is_empty()
+            || !self.disk_faults.is_empty()
+    }
+
+    /// Renders the state with times in the given format.
+    pub fn display_with<'a>(&'a self, time: &'a TimeFormatter) -> ClusterStateDisplay<'a> {
+        ClusterStateDisplay { state: self, time }
+    }
+}
+
+/// Reconstructs the cluster state at `time`. Raw clog events must have been kept,
+/// i.e. the report not built in streaming mode.
+pub fn state_at(report: &SimulationReport, time: f64) -> ClusterState {
+    let parse = |timestamp: &str| timestamp.parse::<f64>().unwrap_or(f64::NAN);
+    // Windows are half-open: a clog of 2s at 10s is over at 12s
+    let covers = |start: f64, seconds: f64| start <= time && time < start + seconds;
+
+    let processes_down: Vec<DowntimeInterval> = downtime_intervals(report)
+        .into_iter()
+        .filter(|interval| covers(interval.start, interval.end - interval.start))
+        .collect();
+    let down_ips: BTreeSet<&str> = processes_down
+        .iter()
+        .map(|interval| interval.machine.as_str())
+        .collect();
+    let machines_up: BTreeSet<String> = report
+        .machine_details
+        .values()
+        .flat_map(|machine| machine.ips())
+        .filter(|ip| !down_ips.contains(ip))
+        .map(str::to_string)
+        .collect();
+
+    // coordinators_changes is sorted by timestamp
+    let changes: Vec<_> = report
+        .coordinators_changes
+        .iter()
+        .take_while(|change| parse(&change.timestamp) <= time)
+        .collect();
+    let coordinators = changes
+        .iter()
+        .rev()
+        .map(|change| change.new_coordinators_key.as_str())
+        .find(|key| !key.is_empty())
+        .map(str::to_string);
+
+    ClusterState {
+        time,
+        machines_up: machines_up.into_iter().collect(),
+        processes_down,
+        last_coordinators_change: changes.last().map(|change| parse(&change.timestamp)),
+        coordinators,
+        clogged_pairs: report
+            .clogging_pairs
+            .iter()
+            .filter(|pair| covers(parse(&pair.timestamp), parse(&pair.seconds)))
+            .cloned()
+            .collect(),
+        clogged_interfaces: report
+            .clog_interfaces
+            .iter()
+            .filter(|interface| covers(parse(&interface.timestamp), parse(&interface.delay)))
+            .cloned()
+            .collect(),
+        disk_faults: report
+            .set_disk_failures
+            .iter()
+            .filter(|failure| {
+                let until = parse(&failure.stall_until).max(parse(&failure.throttle_until));
+                let start = parse(&failure.timestamp);
+                covers(start, until - start)
+            })
+            .cloned()
+            .collect(),
+    }
+}
+
+/// Helper rendering a `ClusterState` as text tables, see `ClusterState::display_with`.
+pub struct ClusterStateDisplay<'a> {
+    state: &'a ClusterState,
+    time: &'a TimeFormatter,
+}
+
+impl fmt::Display for ClusterStateDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (state, time) = (self.state, self.time);
+        writeln!(
+            f,
+            "{}",
+            format!("Cluster state at {}", time.timestamp(state.time)).bold()
+        )?;
+        writeln!(
+            f,
+            "  {} {}",
+            "Machines up:".green(),
+            state.machines_up.join(", ")
+        )?;
+        match (&state.coordinators, state.last_coordinators_change) {
+            (Some(key), _) => writeln!(f, "  {} {}", "Coordinators:".green(), key)?,
+            (None, Some(changed)) => writeln!(
+                f,
+                "  {} last changed at {} (not logged)",
+                "Coordinators:".green(),
+                time.timestamp(changed)
+            )?,
+            (None, None) => writeln!(f, "  {} unchanged since start", "Coordinators:".green())?,
+        }
+        if !state.is_degraded() {
+            return writeln!(f, "  {}", "No active faults".green());
+        }
+
+        let table = |header: Vec<String>| {
+            let mut table = Table::new();
+            table
+                .load_preset(UTF8_FULL)
+                .set_content_arrangement(ContentArrangement::Dynamic)
+                .set_header(header);
+            table
+        };
+        if !state.processes_down.is_empty() {
+            writeln!(f, "  {}:", "Processes Down".green())?;
+            let mut down = table(vec![
+                "Address".to_string(),
+                "DC ID".to_string(),
+                "Cause".to_string(),
+                time.header("Down Since"),
+                time.header("Back At"),
+            ]);
+            for interval in &state.processes_down {
+                down.add_row(vec![
+                    Cell::new(&interval.address),
+                    Cell::new(interval.dc_id.as_deref().unwrap_or("N/A")),
+                    Cell::new(format!("{:?}", interval.cause)),
+                    Cell::new(time.timestamp(interval.start)),
+                    Cell::new(if interval.restarted {
+                        time.timestamp(interval.end)
+                    } else {
+                        "never".to_string()
+                    }),
+                ]);
+            }
+            writeln!(f, "{}", down)?;
+        }
+        if !state.clogged_pairs.is_empty() || !state.clogged_interfaces.is_empty() {
+            writeln!(f, "  {}:", "Active Clogs".green())?;
+            let mut clogs = table(vec![
+                "Kind".to_string(),
+                "Target".to_string(),
+                time.header("Since"),
+                "Seconds".to_string(),
+            ]);
+            for pair in &state.clogged_pairs {
+                clogs.add_row(vec![
+                    Cell::new("Pair"),
+                    Cell::new(format!("{} -> {}", pair.from_id, pair.to_id)),
+                    Cell::new(time.timestamp_str(&pair.timestamp)),
+                    Cell::new(&pair.seconds),
+                ]);
+            }
+            for interface in &state.clogged_interfaces {
+                clogs.add_row(vec![
+                    Cell::new("Interface"),
+                    Cell::new(format!("{} ({})", interface.ip, interface.queue)),
+                    Cell::new(time.timestamp_str(&interface.timestamp)),
+                    Cell::new(&interface.delay),
+                ]);
+            }
+            writeln!(f, "{}", clogs)?;
+        }
+        if !state.disk_faults.is_empty() {
+            writeln!(f, "  {}:", "Disk Faults".green())?;
+            let mut disks = table(vec![
+                "Machine".to_string(),
+                time.header("Since"),
+                time.header("Stalled Until"),
+                time.header("Throttled Until"),
+            ]);
+            for failure in &state.disk_faults {
+                disks.add_row(vec![
+                    Cell::new(ip_from_address(&failure.machine)),
+                    Cell::new(time.timestamp_str(&failure.timestamp)),
+                    Cell::new(time.timestamp_str(&failure.stall_until)),
+                    Cell::new(time.timestamp_str(&failure.throttle_until)),
+                ]);
+            }
+            writeln!(f, "{}", disks)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{
+        CoordinatorsChangeData, ElapsedTimeData, Event, KillMachineProcessData, RebooterStartData,
+    };
+    use crate::report::create_simulation_report;
+
+    #[test]
+    fn test_state_at() {
+        let events = vec![
+            Event::KillMachineProcess(KillMachineProcessData {
+                timestamp: "10".to_string(),
+                raw_kill_type: "6".to_string(),
+                process: "name: Server address: 2.0.1.0:1 zone: z".to_string(),
+                starting_class: "storage".to_string(),
+                failed: "0".to_string(),
+                excluded: "0".to_string(),
+                cleared: "0".to_string(),
+                rebooting: "0".to_string(),
+            }),
+            Event::RebooterStart(RebooterStartData {
+                timestamp: "15".to_string(),
+                address: "2.0.1.0:1:tls".to_string(),
+                zone_id: None,
+                cycles: "2".to_string(),
+            }),
+            Event::CloggingPair(CloggingPairData {
+                timestamp: "12".to_string(),
+                from_id: "2.0.1.1".to_string(),
+                to_id: "2.0.1.2".to_string(),
+                seconds: "2".to_string(),
+            }),
+            Event::SetDiskFailure(SetDiskFailureData {
+                timestamp: "11".to_string(),
+                machine: "2.0.1.2:1".to_string(),
+                stall_interval: "1".to_string(),
+                stall_period: "1".to_string(),
+                stall_until: "12".to_string(),
+                throttle_period: "9".to_string(),
+                throttle_until: "20".to_string(),
+            }),
+            Event::CoordinatorsChange(CoordinatorsChangeData {
+                timestamp: "5".to_string(),
+                new_coordinators_key: "db:id@2.0.1.0:1,2.0.1.1:1".to_string(),
+            }),
+            Event::ElapsedTime(ElapsedTimeData {
+                timestamp: "30".to_string(),
+                sim_time: "30".to_string(),
+                real_time: "1".to_string(),
+            }),
+        ];
+        let report = create_simulation_report(&events);
+
+        let state = state_at(&report, 13.0);
+        assert_eq!(state.processes_down.len(), 1);
+        assert_eq!(state.clogged_pairs.len(), 1);
+        assert_eq!(state.disk_faults.len(), 1);
+        assert_eq!(state.last_coordinators_change, Some(5.0));
+        assert_eq!(
+            state.coordinators.as_deref(),
+            Some("db:id@2.0.1.0:1,2.0.1.1:1")
+        );
+
+        // The clog ended at 14, the process restarted at 15, the disk recovers at 20
+        let state = state_at(&report, 15.0);
+        assert!(state.processes_down.is_empty());
+        assert!(state.clogged_pairs.is_empty());
+        assert!(state.is_degraded());
+        assert!(!state_at(&report, 25.0).is_degraded());
+        assert_eq!(state_at(&report, 1.0).coordinators, None);
+    }
+}
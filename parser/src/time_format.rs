@@ -0,0 +1,314 @@
+//! Rendering of simulated times in the style chosen by the user.
+//!
+//! Every frontend (report Display, TUI, exports) formats times through a
+//! `TimeFormatter` so that switching modes changes all of them consistently.
+
+use crate::report::SimulationReport;
+use humantime::{format_rfc3339_millis, parse_rfc3339_weak};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, SystemTime};
+
+/// How simulated times are displayed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TimeFormat {
+    /// Simulated seconds since the start of the run, e.g. "54.523646".
+    #[default]
+    Seconds,
+    /// Humanized offsets since the start of the run, e.g. "54s 523ms".
+    Humanized,
+    /// Wall-clock date and time, anchored at the DateTime of the run's ProgramStart.
+    DateTime,
+}
+
+impl TimeFormat {
+    /// All formats, in the order the TUI toggle cycles through them.
+    pub const ALL: [TimeFormat; 3] = [
+        TimeFormat::Seconds,
+        TimeFormat::Humanized,
+        TimeFormat::DateTime,
+    ];
+
+    /// Returns the format following this one in `ALL`, wrapping around.
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|f| *f == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    /// Short human-readable name of the format.
+    pub fn label(self) -> &'static str {
+        match self {
+            TimeFormat::Seconds => "seconds",
+            TimeFormat::Humanized => "humanized",
+            TimeFormat::DateTime => "date/time",
+        }
+    }
+}
+
+/// Smallest unit shown by `format_duration`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DurationPrecision {
+    /// Whole seconds, e.g. "1m 2s".
+    Seconds,
+    /// Milliseconds, e.g. "1m 2s 500ms".
+    #[default]
+    Millis,
+    /// Microseconds, e.g. "1m 2s 500ms 250us".
+    Micros,
+}
+
+impl DurationPrecision {
+    /// Length of the unit in nanoseconds.
+    fn unit_nanos(self) -> u64 {
+        match self {
+            DurationPrecision::Seconds => 1_000_000_000,
+            DurationPrecision::Millis => 1_000_000,
+            DurationPrecision::Micros => 1_000,
+        }
+    }
+}
+
+/// Shown in place of times and durations that are NaN or infinite, e.g. computed
+/// from timestamps that failed to parse.
+pub const INVALID_TIME: &str = "N/A";
+
+/// Humanizes a duration in seconds, rounded to `precision`, e.g. "1m 2s 500ms".
+/// Negative durations keep their sign, and NaN, infinite or out of range values
+/// give `INVALID_TIME` instead of panicking like `Duration::from_secs_f64`.
+pub fn format_duration(seconds: f64, precision: DurationPrecision) -> String {
+    let unit_nanos = precision.unit_nanos();
+    let units = (seconds.abs() * 1e9 / unit_nanos as f64).round();
+    if !units.is_finite() || units >= (u64::MAX / unit_nanos) as f64 {
+        return INVALID_TIME.to_string();
+    }
+    let units = units as u64;
+    if units == 0 {
+        return "0s".to_string();
+    }
+    let formatted = humantime::format_duration(Duration::from_nanos(units * unit_nanos));
+    if seconds < 0.0 {
+        format!("-{}", formatted)
+    } else {
+        formatted.to_string()
+    }
+}
+
+/// Origin of the time axis when overlaying or bucketing several runs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TimeAlignment {
+    /// Absolute simulated time.
+    #[default]
+    SimTime,
+    /// Time since the run's first chaos event, so that runs with warm-up phases of
+    /// different lengths line up.
+    ChaosOnset,
+}
+
+impl TimeAlignment {
+    /// Simulated time of `report` that becomes zero under this alignment. Runs
+    /// without chaos keep their absolute times.
+    pub fn origin(self, report: &SimulationReport) -> f64 {
+        match self {
+            TimeAlignment::SimTime => 0.0,
+            TimeAlignment::ChaosOnset => report.chaos_onset().unwrap_or(0.0),
+        }
+    }
+
+    /// Short description of the time axis, e.g. for table headers.
+    pub fn label(self) -> &'static str {
+        match self {
+            TimeAlignment::SimTime => "simulated time",
+            TimeAlignment::ChaosOnset => "since chaos onset",
+        }
+    }
+}
+
+/// Formats simulated times of a given run.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimeFormatter {
+    format: TimeFormat,
+    /// Wall-clock time at simulated time zero, needed for `TimeFormat::DateTime`.
+    start: Option<SystemTime>,
+    /// Precision of humanized times and durations.
+    precision: DurationPrecision,
+}
+
+impl TimeFormatter {
+    /// Creates a formatter for the run described by `report`.
+    pub fn new(format: TimeFormat, report: &SimulationReport) -> Self {
+        let start = report
+            .start_date_time
+            .as_deref()
+            .and_then(|date_time| parse_rfc3339_weak(date_time).ok());
+        TimeFormatter {
+            format,
+            start,
+            precision: DurationPrecision::default(),
+        }
+    }
+
+    /// Sets the precision of humanized times and durations, milliseconds by default.
+    /// Seconds mode always keeps the microsecond precision of traces.
+    pub fn with_precision(mut self, precision: DurationPrecision) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    /// The format used by this formatter.
+    pub fn format(&self) -> TimeFormat {
+        self.format
+    }
+
+    /// Formats a point in simulated time. Falls back to seconds in DateTime mode when
+    /// the run has no start DateTime.
+    pub fn timestamp(&self, seconds: f64) -> String {
+        if !seconds.is_finite() {
+            return INVALID_TIME.to_string();
+        }
+        let seconds = seconds.max(0.0);
+        match (self.format, self.start) {
+            (TimeFormat::Humanized, _) => format_duration(seconds, self.precision),
+            (TimeFormat::DateTime, Some(start)) => {
+                format_rfc3339_millis(start + Duration::from_secs_f64(seconds)).to_string()
+            }
+            _ => format!("{:.6}", seconds),
+        }
+    }
+
+    /// Formats a timestamp kept as a string in event data, returning it unchanged if it
+    /// does not parse.
+    pub fn timestamp_str(&self, seconds: &str) -> String {
+        seconds
+            .parse::<f64>()
+            .map_or_else(|_| seconds.to_string(), |s| self.timestamp(s))
+    }
+
+    /// Formats a span of time, such as the total simulated time. Durations have no
+    /// wall-clock equivalent, so DateTime mode humanizes them.
+    pub fn duration(&self, seconds: f64) -> String {
+        match self.format {
+            TimeFormat::Seconds if !seconds.is_finite() => INVALID_TIME.to_string(),
+            TimeFormat::Seconds => format!("{:.6}s", seconds),
+            TimeFormat::Humanized | TimeFormat::DateTime => {
+                format_duration(seconds, self.precision)
+            }
+        }
+    }
+
+    /// Unit suffix for table headers, e.g. "Time (s)" in seconds mode and "Time" otherwise.
+    pub fn header(&self, label: &str) -> String {
+        match (self.format, self.start) {
+            (TimeFormat::Seconds, _) | (TimeFormat::DateTime, None) => format!("{} (s)", label),
+            _ => label.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn formatter(format: TimeFormat, start: Option<&str>) -> TimeFormatter {
+        TimeFormatter {
+            format,
+            start: start.map(|s| parse_rfc3339_weak(s).unwrap()),
+            precision: DurationPrecision::Millis,
+        }
+    }
+
+    #[test]
+    fn test_timestamp_formats() {
+        let seconds = formatter(TimeFormat::Seconds, None);
+        assert_eq!(seconds.timestamp(54.5236461), "54.523646");
+        assert_eq!(seconds.timestamp_str("oops"), "oops");
+        assert_eq!(seconds.header("Time"), "Time (s)");
+
+        let humanized = formatter(TimeFormat::Humanized, None);
+        assert_eq!(humanized.timestamp(114.5236), "1m 54s 524ms");
+        assert_eq!(humanized.timestamp(0.0), "0s");
+
+        let date_time = formatter(TimeFormat::DateTime, Some("2025-04-24T12:47:58Z"));
+        assert_eq!(date_time.timestamp(62.5), "2025-04-24T12:49:00.500Z");
+        assert_eq!(date_time.duration(62.5), "1m 2s 500ms");
+        assert_eq!(date_time.header("Time"), "Time");
+
+        // Without an anchor, DateTime falls back to seconds
+        let unanchored = formatter(TimeFormat::DateTime, None);
+        assert_eq!(unanchored.timestamp(1.0), "1.000000");
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(
+            format_duration(62.50025, DurationPrecision::Micros),
+            "1m 2s 500ms 250us"
+        );
+        assert_eq!(
+            format_duration(62.50025, DurationPrecision::Millis),
+            "1m 2s 500ms"
+        );
+        assert_eq!(format_duration(62.5, DurationPrecision::Seconds), "1m 3s");
+        assert_eq!(format_duration(0.0001, DurationPrecision::Millis), "0s");
+        assert_eq!(
+            format_duration(-1.5, DurationPrecision::Millis),
+            "-1s 500ms"
+        );
+        assert_eq!(
+            format_duration(f64::NAN, DurationPrecision::Millis),
+            INVALID_TIME
+        );
+        assert_eq!(
+            format_duration(1e300, DurationPrecision::Micros),
+            INVALID_TIME
+        );
+
+        // Invalid values must not panic in any mode
+        let date_time = formatter(TimeFormat::DateTime, Some("2025-04-24T12:47:58Z"));
+        assert_eq!(date_time.timestamp(f64::INFINITY), INVALID_TIME);
+        let seconds = formatter(TimeFormat::Seconds, None);
+        assert_eq!(seconds.duration(f64::NAN), INVALID_TIME);
+        let humanized =
+            formatter(TimeFormat::Humanized, None).with_precision(DurationPrecision::Seconds);
+        assert_eq!(humanized.timestamp(114.5236), "1m 55s");
+    }
+
+    #[test]
+    fn test_chaos_onset_alignment() {
+        use crate::parser::{CloggingPairData, CoordinatorsChangeData, Event, SetDiskFailureData};
+        use crate::report::create_simulation_report;
+
+        let events = vec![
+            Event::CoordinatorsChange(CoordinatorsChangeData {
+                timestamp: "1.0".to_string(),
+                new_coordinators_key: "key".to_string(),
+            }),
+            Event::CloggingPair(CloggingPairData {
+                timestamp: "12.5".to_string(),
+                from_id: "2.0.1.0".to_string(),
+                to_id: "2.0.1.1".to_string(),
+                seconds: "1.0".to_string(),
+            }),
+            Event::SetDiskFailure(SetDiskFailureData {
+                timestamp: "7.25".to_string(),
+                machine: "2.0.1.2:1".to_string(),
+                stall_interval: "1.0".to_string(),
+                stall_period: "1.0".to_string(),
+                stall_until: "8.25".to_string(),
+                throttle_period: "1.0".to_string(),
+                throttle_until: "8.25".to_string(),
+            }),
+        ];
+        let report = create_simulation_report(&events);
+        assert_eq!(report.chaos_onset(), Some(7.25));
+        assert_eq!(TimeAlignment::ChaosOnset.origin(&report), 7.25);
+        assert_eq!(TimeAlignment::SimTime.origin(&report), 0.0);
+
+        let quiet = create_simulation_report(&events[..1]);
+        assert_eq!(TimeAlignment::ChaosOnset.origin(&quiet), 0.0);
+    }
+
+    #[test]
+    fn test_time_format_cycles() {
+        assert_eq!(TimeFormat::Seconds.next(), TimeFormat::Humanized);
+        assert_eq!(TimeFormat::DateTime.next(), TimeFormat::Seconds);
+    }
+}
@@ -0,0 +1,661 @@
+//! Unified, chronological timeline of a run, shared by the TUI Timeline pane and the
+//! `timeline` subcommand.
+
+use crate::cluster::ClusterIndex;
+use crate::events::Events;
+use crate::export::escape_csv;
+use crate::parser::{
+    ip_from_address, AssassinationData, ClogInterfaceData, CloggingPairData, CorruptedBlockData,
+    DiskSwapData, Event, KillMachineProcessData, SetDiskFailureData,
+};
+use crate::report::SimulationReport;
+use crate::time_format::TimeFormatter;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::str::FromStr;
+
+/// A single row of the timeline.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TimelineEvent {
+    pub timestamp: f64,
+    /// Single-character marker distinguishing event kinds at a glance.
+    pub glyph: &'static str,
+    pub event_type: String,
+    pub details: String,
+    /// Every parsed field of the report entry behind the row, for detail views.
+    #[serde(skip)]
+    pub source: serde_json::Value,
+}
+
+/// Broad kind of a timeline row, for filtering rows by category.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TimelineCategory {
+    /// Process kills and zone assassinations.
+    Kills,
+    /// Starts and ends of clog windows.
+    Clogs,
+    /// Coordinator changes.
+    Coordinators,
+    /// Disk failures, swaps and corrupted blocks.
+    Disk,
+    /// Recoveries and annotations.
+    Other,
+}
+
+impl TimelineCategory {
+    pub const ALL: [TimelineCategory; 5] = [
+        TimelineCategory::Kills,
+        TimelineCategory::Clogs,
+        TimelineCategory::Coordinators,
+        TimelineCategory::Disk,
+        TimelineCategory::Other,
+    ];
+
+    /// Lowercase name, e.g. "kills".
+    pub fn name(&self) -> &'static str {
+        match self {
+            TimelineCategory::Kills => "kills",
+            TimelineCategory::Clogs => "clogs",
+            TimelineCategory::Coordinators => "coordinators",
+            TimelineCategory::Disk => "disk",
+            TimelineCategory::Other => "other",
+        }
+    }
+}
+
+impl TimelineEvent {
+    /// Category of the row, from its event type.
+    pub fn category(&self) -> TimelineCategory {
+        match self.event_type.as_str() {
+            "Reboot" | "Assassination" => TimelineCategory::Kills,
+            "Clog Start" | "Clog End" => TimelineCategory::Clogs,
+            "Coord Change" => TimelineCategory::Coordinators,
+            "Disk Failure" | "Disk Swap" | "Corrupt Block" => TimelineCategory::Disk,
+            _ => TimelineCategory::Other,
+        }
+    }
+}
+
+/// Collects the chaos, coordinator changes and annotations of a report into a single
+/// chronological list.
+pub fn timeline(report: &SimulationReport) -> Vec<TimelineEvent> {
+    let mut timeline_events: Vec<TimelineEvent> = Vec::new();
+
+    // 1. Coordinator Changes
+    for (i, event) in report.coordinators_changes.iter().enumerate() {
+        let mut details = "Triggering leader election".to_string();
+        if let Some(set) = report.coordinator_sets.get(i) {
+            for (sign, addresses) in [('+', set.added()), ('-', set.removed())] {
+                for address in addresses {
+                    details.push_str(&format!(" {}{}", sign, address));
+                }
+            }
+        }
+        timeline_events.extend(row(
+            &event.timestamp,
+            "♛",
+            "Coord Change",
+            details,
+            source(event),
+        ));
+    }
+
+    // 2. Killed Processes
+    timeline_events.extend(report.kill_machine_processes.iter().filter_map(kill_row));
+
+    // 3. Disk Swaps
+    timeline_events.extend(report.disk_swaps.iter().filter_map(disk_swap_row));
+
+    // 4. Assassinations
+    timeline_events.extend(report.assassinations.iter().filter_map(assassination_row));
+
+    // 5. Disk Faults
+    timeline_events.extend(report.set_disk_failures.iter().filter_map(disk_failure_row));
+    timeline_events.extend(
+        report
+            .corrupted_blocks
+            .iter()
+            .filter_map(corrupted_block_row),
+    );
+
+    // 6. Clog windows, as a start and a stop marker each
+    for event in &report.clogging_pairs {
+        timeline_events.extend(clogging_pair_rows(event));
+    }
+    for event in &report.clog_interfaces {
+        timeline_events.extend(clog_interface_rows(event));
+    }
+
+    // 7. Recoveries
+    for recovery in &report.recoveries {
+        let outcome = match recovery.duration {
+            Some(duration) => format!("recovered in {:.3}s", duration),
+            None => format!("stopped at {}", recovery.status),
+        };
+        let details = match &recovery.cause {
+            Some(cause) => format!("{}, after {}", outcome, cause.description),
+            None => outcome,
+        };
+        timeline_events.push(TimelineEvent {
+            timestamp: recovery.start,
+            glyph: "↻",
+            event_type: "Recovery".to_string(),
+            details,
+            source: source(recovery),
+        });
+    }
+
+    // 8. User annotations
+    for annotation in &report.annotations {
+        let mut details = annotation.text.clone();
+        if let Some(author) = &annotation.author {
+            details.push_str(&format!(" ({})", author));
+        }
+        timeline_events.push(TimelineEvent {
+            timestamp: annotation.timestamp,
+            glyph: "✎",
+            event_type: "Note".to_string(),
+            details,
+            source: source(annotation),
+        });
+    }
+
+    // Sort events chronologically
+    timeline_events.sort_by(|a, b| {
+        a.timestamp
+            .partial_cmp(&b.timestamp)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    timeline_events
+}
+
+/// The fields of a report entry, as the `source` of its rows.
+fn source<T: Serialize>(entry: &T) -> serde_json::Value {
+    serde_json::to_value(entry).unwrap_or_default()
+}
+
+/// A row at a timestamp kept as a string in event data, `None` when it does not parse.
+fn row(
+    timestamp_str: &str,
+    glyph: &'static str,
+    event_type: &str,
+    details: String,
+    source: serde_json::Value,
+) -> Option<TimelineEvent> {
+    let Ok(timestamp) = f64::from_str(timestamp_str) else {
+        // Log or handle parse error if needed
+        eprintln!(
+            "Warning: Could not parse timestamp '{}' for timeline",
+            timestamp_str
+        );
+        return None;
+    };
+    Some(TimelineEvent {
+        timestamp,
+        glyph,
+        event_type: event_type.to_string(),
+        details,
+        source,
+    })
+}
+
+fn kill_row(event: &KillMachineProcessData) -> Option<TimelineEvent> {
+    let ip_addr = event.ip_address().unwrap_or("?.?.?.?"); // Default if parsing fails
+    let details = format!("{:?} {}", event.kill_type(), ip_addr);
+    row(&event.timestamp, "✖", "Reboot", details, source(event))
+}
+
+fn disk_swap_row(event: &DiskSwapData) -> Option<TimelineEvent> {
+    let details = format!("IPs: {}", event.ips().join(", "));
+    row(&event.timestamp, "⇄", "Disk Swap", details, source(event))
+}
+
+fn assassination_row(event: &AssassinationData) -> Option<TimelineEvent> {
+    let action = if event.is_reboot() { "Reboot" } else { "Kill" };
+    let details = format!("{} zone {}", action, event.zone_id);
+    row(
+        &event.timestamp,
+        "†",
+        "Assassination",
+        details,
+        source(event),
+    )
+}
+
+fn disk_failure_row(event: &SetDiskFailureData) -> Option<TimelineEvent> {
+    let details = format!(
+        "{} stall {}s throttle {}s",
+        ip_from_address(&event.machine),
+        event.stall_period,
+        event.throttle_period
+    );
+    row(
+        &event.timestamp,
+        "▼",
+        "Disk Failure",
+        details,
+        source(event),
+    )
+}
+
+fn corrupted_block_row(event: &CorruptedBlockData) -> Option<TimelineEvent> {
+    let details = format!("{} {}", ip_from_address(&event.machine), event.filename);
+    row(&event.time, "▒", "Corrupt Block", details, source(event))
+}
+
+fn clogging_pair_rows(event: &CloggingPairData) -> Vec<TimelineEvent> {
+    let details = format!("{} -> {}", event.from_id, event.to_id);
+    clog_window_rows(&event.timestamp, &event.seconds, details, source(event))
+}
+
+fn clog_interface_rows(event: &ClogInterfaceData) -> Vec<TimelineEvent> {
+    let details = format!("{} ({})", event.ip, event.queue);
+    clog_window_rows(&event.timestamp, &event.delay, details, source(event))
+}
+
+/// The start and stop rows of a clog window, none when its times do not parse.
+fn clog_window_rows(
+    start_str: &str,
+    seconds_str: &str,
+    details: String,
+    source: serde_json::Value,
+) -> Vec<TimelineEvent> {
+    let (Ok(start), Ok(seconds)) = (start_str.parse::<f64>(), seconds_str.parse::<f64>()) else {
+        return Vec::new();
+    };
+    vec![
+        TimelineEvent {
+            timestamp: start,
+            glyph: "▶",
+            event_type: "Clog Start".to_string(),
+            details: details.clone(),
+            source: source.clone(),
+        },
+        TimelineEvent {
+            timestamp: start + seconds,
+            glyph: "◀",
+            event_type: "Clog End".to_string(),
+            details,
+            source,
+        },
+    ]
+}
+
+/// The rows of a chaos event targeting machines: a kill, a disk swap or fault, or a
+/// clog window. Other events have none.
+fn chaos_rows(event: &Event) -> Vec<TimelineEvent> {
+    match event {
+        Event::KillMachineProcess(data) => kill_row(data).into_iter().collect(),
+        Event::DiskSwap(data) => disk_swap_row(data).into_iter().collect(),
+        Event::Assassination(data) => assassination_row(data).into_iter().collect(),
+        Event::SetDiskFailure(data) => disk_failure_row(data).into_iter().collect(),
+        Event::CorruptedBlock(data) => corrupted_block_row(data).into_iter().collect(),
+        Event::CloggingPair(data) => clogging_pair_rows(data),
+        Event::ClogInterface(data) => clog_interface_rows(data),
+        _ => Vec::new(),
+    }
+}
+
+/// The events between `from` and `to` seconds, both inclusive, of events sorted by
+/// timestamp as `timeline` returns them.
+pub fn between(events: &[TimelineEvent], from: Option<f64>, to: Option<f64>) -> &[TimelineEvent] {
+    let start = from.map_or(0, |from| {
+        events.partition_point(|event| event.timestamp < from)
+    });
+    let end = to
+        .map_or(events.len(), |to| {
+            events.partition_point(|event| event.timestamp <= to)
+        })
+        .max(start);
+    &events[start..end]
+}
+
+/// Kind of chaos hitting a machine, see `machine_markers`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MarkerKind {
+    /// Clogged link or interface.
+    Clog,
+    /// Disk failure, swap or corrupted block.
+    Disk,
+    /// Process starting again after a kill or reboot.
+    Restart,
+    /// Process kill or assassination of the machine's zone.
+    Kill,
+}
+
+impl MarkerKind {
+    pub const ALL: [MarkerKind; 4] = [
+        MarkerKind::Clog,
+        MarkerKind::Disk,
+        MarkerKind::Restart,
+        MarkerKind::Kill,
+    ];
+
+    /// Single-character marker, the glyph of the matching Timeline rows.
+    pub fn glyph(&self) -> &'static str {
+        match self {
+            MarkerKind::Clog => "▶",
+            MarkerKind::Disk => "▼",
+            MarkerKind::Restart => "↺",
+            MarkerKind::Kill => "✖",
+        }
+    }
+
+    /// Lowercase name, e.g. "kill".
+    pub fn name(&self) -> &'static str {
+        match self {
+            MarkerKind::Clog => "clog",
+            MarkerKind::Disk => "disk",
+            MarkerKind::Restart => "restart",
+            MarkerKind::Kill => "kill",
+        }
+    }
+}
+
+/// A chaos event hitting a machine.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MachineMarker {
+    pub timestamp: f64,
+    pub kind: MarkerKind,
+}
+
+/// The chaos hitting each machine, keyed by IP and sorted by timestamp: clogs of its
+/// links and interfaces, disk faults, process restarts, and kills, including the
+/// assassinations of its zone.
+pub fn machine_markers(report: &SimulationReport) -> BTreeMap<String, Vec<MachineMarker>> {
+    let mut markers: BTreeMap<String, Vec<MachineMarker>> = BTreeMap::new();
+    let mut add = |ip: &str, timestamp: &str, kind: MarkerKind| {
+        if let Ok(timestamp) = timestamp.parse::<f64>() {
+            markers
+                .entry(ip_from_address(ip).to_string())
+                .or_default()
+                .push(MachineMarker { timestamp, kind });
+        }
+    };
+
+    for clog in &report.clogging_pairs {
+        add(&clog.from_id, &clog.timestamp, MarkerKind::Clog);
+        if clog.to_id != clog.from_id {
+            add(&clog.to_id, &clog.timestamp, MarkerKind::Clog);
+        }
+    }
+    for clog in &report.clog_interfaces {
+        add(&clog.ip, &clog.timestamp, MarkerKind::Clog);
+    }
+    for failure in &report.set_disk_failures {
+        add(&failure.machine, &failure.timestamp, MarkerKind::Disk);
+    }
+    for block in &report.corrupted_blocks {
+        add(&block.machine, &block.time, MarkerKind::Disk);
+    }
+    for swap in &report.disk_swaps {
+        let event = Event::from(swap.clone());
+        for ip in event.machine_ips() {
+            add(ip, &swap.timestamp, MarkerKind::Disk);
+        }
+    }
+    for restart in &report.process_restarts {
+        add(
+            restart.process_address(),
+            &restart.timestamp,
+            MarkerKind::Restart,
+        );
+    }
+    for kill in &report.kill_machine_processes {
+        if let Some(ip) = kill.ip_address() {
+            add(ip, &kill.timestamp, MarkerKind::Kill);
+        }
+    }
+    let cluster = ClusterIndex::new(report);
+    for assassination in &report.assassinations {
+        for machine in cluster.by_zone_id(&assassination.zone_id) {
+            let ips = machine.ip_address.as_deref().unwrap_or("");
+            for ip in ips.split([' ', ',']).filter(|ip| !ip.is_empty()) {
+                add(ip, &assassination.timestamp, MarkerKind::Kill);
+            }
+        }
+    }
+
+    for lane in markers.values_mut() {
+        lane.sort_by(|a, b| a.timestamp.total_cmp(&b.timestamp));
+    }
+    markers
+}
+
+/// The Timeline rows of the chaos affecting the machine at `ip` between `from` and `to`
+/// seconds, in chronological order: kills of its processes and assassinations of its
+/// zone, the clog windows its IP appears in, and its disk faults.
+///
+/// The events of the machine are looked up in the by-machine index of `events`, e.g.
+/// built from `SimulationReport::chaos_events`. Assassinations designate a zone rather
+/// than machines and are matched against the zone of the machine in `report`.
+pub fn machine_timeline(
+    report: &SimulationReport,
+    events: &Events,
+    ip: &str,
+    from: f64,
+    to: f64,
+) -> Vec<TimelineEvent> {
+    let ip = ip_from_address(ip);
+    let cluster = ClusterIndex::new(report);
+    let zone_id = cluster
+        .by_address(ip)
+        .and_then(|machine| machine.zone_id.as_deref());
+    let assassinations = report
+        .assassinations
+        .iter()
+        .filter(|event| Some(event.zone_id.as_str()) == zone_id)
+        .filter_map(assassination_row)
+        .filter(|event| (from..=to).contains(&event.timestamp));
+    let mut rows: Vec<TimelineEvent> = events
+        .for_machine_between(ip, from, to)
+        .flat_map(chaos_rows)
+        .chain(assassinations)
+        .collect();
+    rows.sort_by(|a, b| a.timestamp.total_cmp(&b.timestamp));
+    rows
+}
+
+/// Renders events as aligned plain-text columns, one event per line.
+pub fn to_text(events: &[TimelineEvent], time: &TimeFormatter) -> String {
+    let times: Vec<String> = events
+        .iter()
+        .map(|event| time.timestamp(event.timestamp))
+        .collect();
+    let time_width = times
+        .iter()
+        .map(String::len)
+        .chain(std::iter::once(time.header("Time").len()))
+        .max()
+        .unwrap_or(0);
+    let type_width = events
+        .iter()
+        .map(|event| event.event_type.len())
+        .max()
+        .unwrap_or(0)
+        .max("Event".len());
+
+    let mut text = String::new();
+    let _ = writeln!(
+        text,
+        "{:<time_width$}    {:<type_width$}  Details",
+        time.header("Time"),
+        "Event"
+    );
+    for (event, time_str) in events.iter().zip(times) {
+        let _ = writeln!(
+            text,
+            "{:<time_width$}  {} {:<type_width$}  {}",
+            time_str, event.glyph, event.event_type, event.details
+        );
+    }
+    text
+}
+
+/// Renders events as CSV, with a header row and times in seconds.
+pub fn to_csv(events: &[TimelineEvent]) -> String {
+    let mut out = String::from("timestamp,event_type,details\n");
+    for event in events {
+        let _ = writeln!(
+            out,
+            "{},{},{}",
+            event.timestamp,
+            escape_csv(&event.event_type),
+            escape_csv(&event.details)
+        );
+    }
+    out
+}
+
+/// Renders events as JSON lines, one object per event.
+pub fn to_jsonl(events: &[TimelineEvent]) -> String {
+    let mut out = String::new();
+    for event in events {
+        let _ = writeln!(
+            out,
+            "{}",
+            serde_json::to_string(event).expect("serializable timeline event")
+        );
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{CloggingPairData, CoordinatorsChangeData, KillMachineProcessData};
+    use crate::report::create_simulation_report;
+    use crate::time_format::TimeFormat;
+
+    #[test]
+    fn test_timeline_text() {
+        let events = vec![
+            Event::KillMachineProcess(KillMachineProcessData {
+                timestamp: "150.5".to_string(),
+                raw_kill_type: "6".to_string(),
+                process: "name: Server address: 2.0.1.0:1 zone: z".to_string(),
+                starting_class: "storage".to_string(),
+                failed: "0".to_string(),
+                excluded: "0".to_string(),
+                cleared: "0".to_string(),
+                rebooting: "0".to_string(),
+            }),
+            Event::CoordinatorsChange(CoordinatorsChangeData {
+                timestamp: "95.1".to_string(),
+                new_coordinators_key: "key".to_string(),
+            }),
+        ];
+        let report = create_simulation_report(&events);
+
+        let events = timeline(&report);
+        assert_eq!(events[0].event_type, "Coord Change");
+        assert_eq!(events[1].source["StartingClass"], "storage");
+        assert_eq!(events[0].category(), TimelineCategory::Coordinators);
+        assert_eq!(events[1].category(), TimelineCategory::Kills);
+        let events = between(&events, Some(100.0), Some(200.0));
+        assert_eq!(between(&timeline(&report), None, Some(95.1)).len(), 1);
+        assert!(between(&timeline(&report), Some(200.0), Some(100.0)).is_empty());
+        let time = TimeFormatter::new(TimeFormat::Seconds, &report);
+        assert_eq!(
+            to_text(events, &time),
+            "Time (s)      Event   Details\n\
+             150.500000  ✖ Reboot  Reboot 2.0.1.0\n"
+        );
+        assert_eq!(
+            to_csv(events),
+            "timestamp,event_type,details\n150.5,Reboot,Reboot 2.0.1.0\n"
+        );
+        assert_eq!(
+            to_jsonl(events),
+            "{\"timestamp\":150.5,\"glyph\":\"✖\",\"event_type\":\"Reboot\",\"details\":\"Reboot 2.0.1.0\"}\n"
+        );
+    }
+
+    #[test]
+    fn test_machine_markers() {
+        let events = vec![
+            Event::KillMachineProcess(KillMachineProcessData {
+                timestamp: "150.5".to_string(),
+                raw_kill_type: "6".to_string(),
+                process: "name: Server address: 2.0.1.0:1 zone: z".to_string(),
+                starting_class: "storage".to_string(),
+                failed: "0".to_string(),
+                excluded: "0".to_string(),
+                cleared: "0".to_string(),
+                rebooting: "0".to_string(),
+            }),
+            Event::CloggingPair(CloggingPairData {
+                timestamp: "20.0".to_string(),
+                from_id: "2.0.1.0:1".to_string(),
+                to_id: "2.0.1.1:1".to_string(),
+                seconds: "0.5".to_string(),
+            }),
+        ];
+        let report = create_simulation_report(&events);
+
+        let markers = machine_markers(&report);
+        assert_eq!(
+            markers["2.0.1.0"],
+            [
+                MachineMarker {
+                    timestamp: 20.0,
+                    kind: MarkerKind::Clog
+                },
+                MachineMarker {
+                    timestamp: 150.5,
+                    kind: MarkerKind::Kill
+                },
+            ]
+        );
+        assert_eq!(markers["2.0.1.1"].len(), 1);
+    }
+
+    #[test]
+    fn test_machine_timeline() {
+        let events = vec![
+            Event::KillMachineProcess(KillMachineProcessData {
+                timestamp: "150.5".to_string(),
+                raw_kill_type: "6".to_string(),
+                process: "name: Server address: 2.0.1.0:1 zone: z".to_string(),
+                starting_class: "storage".to_string(),
+                failed: "0".to_string(),
+                excluded: "0".to_string(),
+                cleared: "0".to_string(),
+                rebooting: "0".to_string(),
+            }),
+            Event::CloggingPair(CloggingPairData {
+                timestamp: "20.0".to_string(),
+                from_id: "2.0.1.10:1".to_string(),
+                to_id: "2.0.1.1:1".to_string(),
+                seconds: "0.5".to_string(),
+            }),
+            Event::Assassination(AssassinationData {
+                timestamp: "30.0".to_string(),
+                target_machine: "zoneid=y".to_string(),
+                zone_id: "y".to_string(),
+                reboot: "1".to_string(),
+            }),
+        ];
+        let report = create_simulation_report(&events);
+        let chaos = report.chaos_events();
+        assert_eq!(chaos.len(), 3);
+        assert!(chaos
+            .iter()
+            .any(|event| matches!(event, Event::Assassination(_))));
+        let index = Events::new(chaos);
+
+        let types = |ip: &str| -> Vec<String> {
+            machine_timeline(&report, &index, ip, f64::NEG_INFINITY, f64::INFINITY)
+                .into_iter()
+                .map(|event| event.event_type)
+                .collect()
+        };
+        assert_eq!(types("2.0.1.0"), ["Reboot"]);
+        assert_eq!(types("2.0.1.1:1"), ["Clog Start", "Clog End"]);
+        assert!(types("2.0.1.2").is_empty());
+        let in_window = machine_timeline(&report, &index, "2.0.1.1", 100.0, 200.0);
+        assert!(in_window.is_empty());
+    }
+}
@@ -0,0 +1,218 @@
+//! Triage bundle of a run: everything an on-call engineer needs about a failed
+//! simulation, written to a single directory by one command.
+//!
+//! The bundle holds the JSON, HTML and Markdown reports, the findings of the analysis
+//! passes, the timeline as a standalone SVG, the normalized events as JSON lines (see
+//! `split`), the run metadata embedded in exports and, when the trace logs it, the
+//! command reproducing the run.
+
+use crate::export::{trace_hash, ExportOptions};
+use crate::number_format::NumberFormat;
+use crate::parser::{for_each_event, stream_log_directory, Event, ParseOptions, ParsingError};
+use crate::report::{html, markdown, SimulationReport};
+use crate::time_format::{TimeFormat, TimeFormatter};
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum TriageError {
+    #[error("I/O error writing triage bundle: {0}")]
+    Io(#[from] io::Error),
+    #[error("Failed to serialize triage bundle: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Parsing(#[from] ParsingError),
+}
+
+/// Flags of the fdbserver command line taking the seed as their value.
+const SEED_FLAGS: &[&str] = &["-s", "--seed"];
+
+/// The fdbserver command line reproducing the run: the one logged by ProgramStart,
+/// with the seed of the run. `None` when the trace logs no command line or no seed.
+pub fn repro_command(report: &SimulationReport) -> Option<String> {
+    let command_line = report.command_line.as_deref()?;
+    let seed = report.seed.as_deref()?;
+    let mut args: Vec<&str> = Vec::new();
+    let mut words = command_line.split_whitespace();
+    while let Some(word) = words.next() {
+        if SEED_FLAGS.contains(&word) {
+            words.next();
+        } else if !SEED_FLAGS
+            .iter()
+            .any(|flag| word.starts_with(&format!("{}=", flag)))
+        {
+            args.push(word);
+        }
+    }
+    Some(format!("{} -s {}", args.join(" "), seed))
+}
+
+/// Writes the triage bundle of a trace, or a directory of rolled trace files, to
+/// `out_dir`, created if needed, and returns the paths of the files written.
+pub fn write_bundle(
+    report: &SimulationReport,
+    trace_path: &Path,
+    out_dir: &Path,
+    parse_options: &ParseOptions,
+    time_format: TimeFormat,
+    numbers: &NumberFormat,
+) -> Result<Vec<PathBuf>, TriageError> {
+    fs::create_dir_all(out_dir)?;
+    let time = TimeFormatter::new(time_format, report);
+    let mut written = Vec::new();
+    let mut write = |file_name: &str, content: &[u8]| -> io::Result<()> {
+        let path = out_dir.join(file_name);
+        fs::write(&path, content)?;
+        written.push(path);
+        Ok(())
+    };
+
+    write(
+        "report.json",
+        (serde_json::to_string_pretty(report)? + "\n").as_bytes(),
+    )?;
+    write(
+        "report.html",
+        html::to_html(report, &time, numbers).as_bytes(),
+    )?;
+    write(
+        "report.md",
+        markdown::to_markdown(report, &time, numbers).as_bytes(),
+    )?;
+    write(
+        "analysis.json",
+        (serde_json::to_string_pretty(&report.analyses)? + "\n").as_bytes(),
+    )?;
+    if let Some(svg) = html::timeline_svg_document(report, &time) {
+        write("timeline.svg", svg.as_bytes())?;
+    }
+    if let Some(command) = repro_command(report) {
+        write("repro.sh", format!("#!/bin/sh\n{}\n", command).as_bytes())?;
+    }
+    let metadata = ExportOptions {
+        trace_hash: trace_hash(trace_path).ok(),
+        ..ExportOptions::default()
+    }
+    .metadata(report);
+    write(
+        "meta.json",
+        (serde_json::to_string_pretty(&metadata)? + "\n").as_bytes(),
+    )?;
+
+    let events_path = out_dir.join("events.jsonl");
+    write_events(trace_path, &events_path, parse_options)?;
+    written.push(events_path);
+    Ok(written)
+}
+
+/// Writes the events of a trace, or a directory of rolled trace files, to `path` as
+/// normalized JSON lines.
+fn write_events(trace_path: &Path, path: &Path, options: &ParseOptions) -> Result<(), TriageError> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    let mut write = |event: &Event| -> Result<(), TriageError> {
+        serde_json::to_writer(&mut writer, event)?;
+        writeln!(writer)?;
+        Ok(())
+    };
+    if trace_path.is_dir() {
+        for event in stream_log_directory(trace_path, options)? {
+            write(&event?)?;
+        }
+    } else {
+        let mut written = Ok(());
+        for_each_event(trace_path, options, |event, _| {
+            if written.is_ok() {
+                written = write(&event);
+            }
+        })?;
+        written?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::{create_simulation_report, create_simulation_report_from_file};
+
+    #[test]
+    fn test_repro_command() {
+        let mut report = create_simulation_report(&[]);
+        assert_eq!(repro_command(&report), None);
+
+        report.command_line =
+            Some("fdbserver -r simulation -f /root/logical_db.toml -s 7 -b on".to_string());
+        assert_eq!(repro_command(&report), None);
+
+        report.seed = Some("292006968".to_string());
+        assert_eq!(
+            repro_command(&report).as_deref(),
+            Some("fdbserver -r simulation -f /root/logical_db.toml -b on -s 292006968")
+        );
+        report.command_line = Some("fdbserver -r simulation --seed=1 -f test.toml".to_string());
+        assert_eq!(
+            repro_command(&report).as_deref(),
+            Some("fdbserver -r simulation -f test.toml -s 292006968")
+        );
+    }
+
+    #[test]
+    fn test_write_bundle() {
+        let dir = tempfile::tempdir().unwrap();
+        let trace = dir.path().join("trace.json");
+        fs::write(
+            &trace,
+            concat!(
+                r#"{"Type": "ProgramStart", "Time": "0.000000", "Machine": "0.0.0.0:0", "RandomSeed": "42", "CommandLine": "fdbserver -r simulation -f test.toml -b on"}"#,
+                "\n",
+                r#"{"Type": "CloggingPair", "Time": "10.000000", "From": "2.0.1.0", "To": "2.0.1.1", "Seconds": "0.5"}"#,
+                "\n",
+            ),
+        )
+        .unwrap();
+        let options = ParseOptions::default();
+        let report = create_simulation_report_from_file(&trace, &options).unwrap();
+        let out_dir = dir.path().join("bundle");
+
+        let written = write_bundle(
+            &report,
+            &trace,
+            &out_dir,
+            &options,
+            TimeFormat::default(),
+            &NumberFormat::default(),
+        )
+        .unwrap();
+        let names: Vec<_> = written
+            .iter()
+            .map(|path| path.file_name().unwrap().to_str().unwrap())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "report.json",
+                "report.html",
+                "report.md",
+                "analysis.json",
+                "timeline.svg",
+                "repro.sh",
+                "meta.json",
+                "events.jsonl"
+            ]
+        );
+        assert_eq!(
+            fs::read_to_string(out_dir.join("repro.sh")).unwrap(),
+            "#!/bin/sh\nfdbserver -r simulation -f test.toml -b on -s 42\n"
+        );
+        let svg = fs::read_to_string(out_dir.join("timeline.svg")).unwrap();
+        assert!(svg.starts_with("<?xml"));
+        assert!(svg.contains("--fg: #1e1e1e"));
+        let events = fs::read_to_string(out_dir.join("events.jsonl")).unwrap();
+        assert_eq!(events.lines().count(), 2);
+        let meta = fs::read_to_string(out_dir.join("meta.json")).unwrap();
+        assert!(meta.contains(r#""seed": "42""#));
+    }
+}
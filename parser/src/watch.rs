@@ -0,0 +1,223 @@
+//! Following trace files while simulations are still writing them.
+//!
+//! `TraceTail` incrementally reads a single growing file. `TraceWatcher` builds on it
+//! with `notify` to emit the events of every new or growing trace file under a path,
+//! and is the building block for live views of running simulations.
+
+use crate::ensemble::is_trace_file;
+use crate::parser::{Event, MergedEvents};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::time::Duration;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum WatchError {
+    #[error("I/O error while following traces: {0}")]
+    Io(#[from] io::Error),
+    #[error("File system watcher error: {0}")]
+    Notify(#[from] notify::Error),
+}
+
+/// Incrementally reads events appended to a trace file that is still being written.
+#[derive(Debug)]
+pub struct TraceTail {
+    path: PathBuf,
+    offset: u64,
+    partial_line: Vec<u8>,
+}
+
+impl TraceTail {
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        Self {
+            path: path.into(),
+            offset: 0,
+            partial_line: Vec::new(),
+        }
+    }
+
+    /// Path of the followed file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Parses the lines completed since the last call. An incomplete trailing line is
+    /// kept until the simulator finishes writing it.
+    pub fn poll(&mut self) -> io::Result<Vec<Event>> {
+        let mut file = File::open(&self.path)?;
+        let len = file.metadata()?.len();
+        if len < self.offset {
+            // The file was truncated or replaced, start over
+            self.offset = 0;
+            self.partial_line.clear();
+        }
+        file.seek(SeekFrom::Start(self.offset))?;
+        self.offset += file.read_to_end(&mut self.partial_line)? as u64;
+
+        let Some(last_newline) = self.partial_line.iter().rposition(|b| *b == b'\n') else {
+            return Ok(Vec::new());
+        };
+        let remainder = self.partial_line.split_off(last_newline + 1);
+        let complete = std::mem::replace(&mut self.partial_line, remainder);
+        // Lines that are not events we track are skipped, like `parse_log_file` does
+        Ok(String::from_utf8_lossy(&complete)
+            .lines()
+            .filter_map(|line| Event::try_from(line).ok())
+            .collect())
+    }
+}
+
+/// An event read from one of the watched trace files.
+#[derive(Debug, Clone)]
+pub struct WatchedEvent {
+    /// Trace file the event was appended to.
+    pub path: PathBuf,
+    pub event: Event,
+}
+
+/// Watches a trace file, or a directory of `.json` or `.xml` trace files, for new events.
+pub struct TraceWatcher {
+    root: PathBuf,
+    _watcher: RecommendedWatcher,
+    changes: Receiver<notify::Result<notify::Event>>,
+    tails: BTreeMap<PathBuf, TraceTail>,
+}
+
+impl TraceWatcher {
+    /// Starts watching `path`. Events already in the files are returned by the first
+    /// call to `poll` or `next_events`.
+    pub fn new<P: AsRef<Path>>(path: P) -> Result<Self, WatchError> {
+        let root = path.as_ref().to_path_buf();
+        let (sender, changes) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(sender)?;
+        watcher.watch(&root, RecursiveMode::NonRecursive)?;
+        Ok(TraceWatcher {
+            root,
+            _watcher: watcher,
+            changes,
+            tails: BTreeMap::new(),
+        })
+    }
+
+    /// Reads the events appended to any watched trace file since the last call,
+    /// picking up trace files created in the meantime. Events of different files are
+    /// merged by timestamp.
+    pub fn poll(&mut self) -> Result<Vec<WatchedEvent>, WatchError> {
+        if self.root.is_dir() {
+            for entry in fs::read_dir(&self.root)? {
+                let path = entry?.path();
+                if is_trace_file(&path) && !self.tails.contains_key(&path) {
+                    self.tails.insert(path.clone(), TraceTail::new(path));
+                }
+            }
+        } else if !self.tails.contains_key(&self.root) {
+            self.tails
+                .insert(self.root.clone(), TraceTail::new(self.root.clone()));
+        }
+
+        // Each file is written in time order, merging keeps the batch in time order
+        let mut paths = Vec::with_capacity(self.tails.len());
+        let mut batches = Vec::with_capacity(self.tails.len());
+        for (path, tail) in &mut self.tails {
+            paths.push(path);
+            batches.push(tail.poll()?.into_iter().map(Ok));
+        }
+        let mut merged = MergedEvents::new(batches);
+        let mut events = Vec::new();
+        while let Some((source, Ok(event))) = merged.next_with_source() {
+            events.push(WatchedEvent {
+                path: paths[source].clone(),
+                event,
+            });
+        }
+        Ok(events)
+    }
+
+    /// Waits up to `timeout` for the file system to report changes, then returns the
+    /// new events. Files are polled on timeout as well, so changes missed by the
+    /// platform watcher are only delayed.
+    pub fn next_events(&mut self, timeout: Duration) -> Result<Vec<WatchedEvent>, WatchError> {
+        match self.changes.recv_timeout(timeout) {
+            Ok(change) => {
+                change?;
+                // Coalesce the burst of notifications a single write usually triggers
+                while let Ok(change) = self.changes.try_recv() {
+                    change?;
+                }
+            }
+            Err(RecvTimeoutError::Timeout) | Err(RecvTimeoutError::Disconnected) => {}
+        }
+        self.poll()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    const LINE: &str = r#"{"Type": "ProgramStart", "Time": "0.000000", "Machine": "0.0.0.0:0", "RandomSeed": "42"}"#;
+
+    #[test]
+    fn test_tail_waits_for_complete_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("trace.json");
+        let (head, tail) = LINE.split_at(20);
+        fs::write(&path, format!("{}\n{}", LINE, head)).unwrap();
+
+        let mut follower = TraceTail::new(&path);
+        assert_eq!(follower.poll().unwrap().len(), 1);
+        assert!(follower.poll().unwrap().is_empty());
+
+        let mut file = fs::OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(format!("{}\n", tail).as_bytes()).unwrap();
+        assert_eq!(follower.poll().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_watcher_picks_up_new_and_growing_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = dir.path().join("a.json");
+        fs::write(&first, format!("{}\n", LINE)).unwrap();
+        fs::write(dir.path().join("notes.txt"), format!("{}\n", LINE)).unwrap();
+
+        let mut watcher = TraceWatcher::new(dir.path()).unwrap();
+        let events = watcher.poll().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].path, first);
+
+        let second = dir.path().join("b.json");
+        fs::write(&second, format!("{}\n", LINE)).unwrap();
+        let mut file = fs::OpenOptions::new().append(true).open(&first).unwrap();
+        file.write_all(format!("{}\n", LINE).as_bytes()).unwrap();
+
+        let events = watcher.next_events(Duration::from_secs(1)).unwrap();
+        let paths: Vec<&PathBuf> = events.iter().map(|e| &e.path).collect();
+        assert_eq!(paths, vec![&first, &second]);
+    }
+
+    #[test]
+    fn test_watcher_merges_files_by_time() {
+        let dir = tempfile::tempdir().unwrap();
+        let at = |time: &str| LINE.replace("0.000000", time);
+        fs::write(
+            dir.path().join("a.json"),
+            format!("{}\n{}\n", at("1"), at("3")),
+        )
+        .unwrap();
+        fs::write(dir.path().join("b.json"), format!("{}\n", at("2"))).unwrap();
+
+        let mut watcher = TraceWatcher::new(dir.path()).unwrap();
+        let times: Vec<f64> = watcher
+            .poll()
+            .unwrap()
+            .iter()
+            .map(|e| e.event.timestamp())
+            .collect();
+        assert_eq!(times, [1.0, 2.0, 3.0]);
+    }
+}
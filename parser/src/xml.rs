@@ -0,0 +1,108 @@
+//! Reading of XML trace lines.
+//!
+//! fdbserver writes XML traces unless run with `--trace-format json`. Every event is
+//! a single `<Event Severity="10" Time="1.5" Type="..." .../>` line, whose attributes
+//! are converted into the object the JSON trace would hold, so both formats go
+//! through the same event parsing and traces of old runs can be read as they are.
+
+use serde_json::{Map, Value as JsonNode};
+
+/// Returns whether a trace line is XML rather than JSON.
+pub fn is_xml_line(line: &str) -> bool {
+    line.trim_start().starts_with('<')
+}
+
+/// Converts an `<Event .../>` line into the equivalent JSON trace object, attribute
+/// values being strings as in JSON traces. Returns `None` for other lines, such as
+/// the `<?xml ...?>` declaration and `<Trace>`, and for malformed elements.
+pub fn event_node(line: &str) -> Option<JsonNode> {
+    let mut rest = line.trim().strip_prefix("<Event")?;
+    if !rest.starts_with(|c: char| c.is_whitespace() || c == '/' || c == '>') {
+        return None;
+    }
+    let mut attributes = Map::new();
+    loop {
+        rest = rest.trim_start();
+        if rest.starts_with("/>") || rest.starts_with('>') {
+            return Some(JsonNode::Object(attributes));
+        }
+        let (name, value) = rest.split_once('=')?;
+        let name = name.trim();
+        if name.is_empty() || name.contains(char::is_whitespace) {
+            return None;
+        }
+        let value = value.trim_start();
+        let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+        let (raw, after) = value[1..].split_once(quote)?;
+        attributes.insert(name.to_string(), JsonNode::String(unescape(raw)?));
+        rest = after;
+    }
+}
+
+/// Replaces the predefined and numeric character references of an attribute value.
+fn unescape(raw: &str) -> Option<String> {
+    let mut value = String::with_capacity(raw.len());
+    let mut rest = raw;
+    while let Some(start) = rest.find('&') {
+        value.push_str(&rest[..start]);
+        let (entity, after) = rest[start + 1..].split_once(';')?;
+        value.push(match entity {
+            "amp" => '&',
+            "lt" => '<',
+            "gt" => '>',
+            "quot" => '"',
+            "apos" => '\'',
+            _ => {
+                let code = match entity.strip_prefix("#x") {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                    None => entity.strip_prefix('#')?.parse().ok()?,
+                };
+                char::from_u32(code)?
+            }
+        });
+        rest = after;
+    }
+    value.push_str(rest);
+    Some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{parse_log_file, Event};
+    use std::io::Write;
+
+    #[test]
+    fn test_xml_trace() {
+        let node = event_node(
+            r#"<Event Severity="10" Time="1.5" Type="Test" Error="a &lt;b&gt; &amp; &#x41;&#66;" Empty="" />"#,
+        )
+        .unwrap();
+        assert_eq!(node["Error"], "a <b> & AB");
+        assert_eq!(node["Empty"], "");
+        assert_eq!(event_node("<Trace>"), None);
+        assert_eq!(event_node(r#"<EventX A="1"/>"#), None);
+        assert_eq!(event_node(r#"<Event A="1/>"#), None);
+
+        let mut trace = tempfile::NamedTempFile::new().unwrap();
+        write!(
+            trace,
+            r#"<?xml version="1.0"?>
+<Trace>
+<Event Severity="10" Time="0.000000" Type="ProgramStart" Machine="0.0.0.0:0" ID="0000000000000000" RandomSeed="42" />
+<Event Severity="10" Time="12.500000" Type="CloggingPair" Machine="3.4.3.1:1" From="2.0.1.0" To="2.0.1.1" Seconds="3.5" />
+</Trace>
+"#
+        )
+        .unwrap();
+        let events = parse_log_file(trace.path()).unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(
+            events[1],
+            Event::try_from(
+                r#"{"Severity": "10", "Time": "12.500000", "Type": "CloggingPair", "Machine": "3.4.3.1:1", "From": "2.0.1.0", "To": "2.0.1.1", "Seconds": "3.5"}"#
+            )
+            .unwrap()
+        );
+    }
+}